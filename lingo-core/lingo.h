@@ -0,0 +1,44 @@
+/*
+ * The Lingo game engine for C. Link with the lingo_core library that is built with
+ * `cargo build -p lingo-core --release`.
+ *
+ * The words are lowercase UTF-8 with the letters of the language composed, like the word lists
+ * of the game. Strings returned by the functions are freed with lingo_string_free.
+ */
+
+#ifndef LINGO_H
+#define LINGO_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct LingoGame LingoGame;
+
+/* Start a game on word, words holds the words that can be guessed on separate lines. Returns
+ * NULL when the word isn't one of the words. */
+LingoGame *lingo_game_new(const char *words, const char *word, uint32_t max_guesses, bool hard);
+
+/* Verify a guess. Returns the pattern as a code like "GYXXG": G for a letter in the right place,
+ * Y for a letter in the wrong place and X for a letter that isn't in the word. Returns NULL when
+ * the guess isn't allowed, lingo_game_error tells why. */
+char *lingo_game_guess(LingoGame *game, const char *word);
+
+/* Why the last guess wasn't allowed, NULL when it was. Valid until the next guess. */
+const char *lingo_game_error(const LingoGame *game);
+
+/* The guesses and the end of the game as json. */
+char *lingo_game_state(const LingoGame *game);
+
+void lingo_game_free(LingoGame *game);
+
+void lingo_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,169 @@
+//! The game for other languages through the C ABI, declared in `lingo.h`. A game is created with
+//! `lingo_game_new` and freed with `lingo_game_free`, the strings the functions return are freed
+//! with `lingo_string_free`. All strings are UTF-8 and end with a nul.
+
+use crate::game::Game;
+use crate::rules;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+/// A game with the error of the last guess that wasn't allowed
+pub struct LingoGame {
+    game: Game,
+    error: Option<CString>,
+}
+
+/// The text of a C string, None for a null pointer or text that isn't UTF-8
+///
+/// # Safety
+///
+/// `text` is null or points to a nul terminated string
+unsafe fn text<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+    CStr::from_ptr(text).to_str().ok()
+}
+
+/// A C string the caller frees with `lingo_string_free`
+fn owned(text: String) -> *mut c_char {
+    // Nothing the game writes has a nul in it
+    CString::new(text).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Start a game on `word`, `words` holds the words that can be guessed on separate lines. Returns
+/// null when the word isn't one of the words or a string isn't valid.
+///
+/// # Safety
+///
+/// `words` and `word` are null or point to nul terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn lingo_game_new(
+    words: *const c_char,
+    word: *const c_char,
+    max_guesses: u32,
+    hard: bool,
+) -> *mut LingoGame {
+    let (Some(words), Some(word)) = (text(words), text(word)) else {
+        return ptr::null_mut();
+    };
+    let words = words.lines().map(|line| line.trim().to_lowercase());
+    match Game::new(words, &word.to_lowercase(), max_guesses, hard) {
+        Ok(game) => Box::into_raw(Box::new(LingoGame { game, error: None })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Verify a guess. Returns the pattern as a code like `GYXXG`, or null when the guess isn't
+/// allowed and `lingo_game_error` tells why.
+///
+/// # Safety
+///
+/// `game` comes from `lingo_game_new` and isn't freed, `word` is null or points to a nul
+/// terminated string
+#[no_mangle]
+pub unsafe extern "C" fn lingo_game_guess(
+    game: *mut LingoGame,
+    word: *const c_char,
+) -> *mut c_char {
+    let Some(game) = game.as_mut() else {
+        return ptr::null_mut();
+    };
+    let result = match text(word) {
+        None => Err("The guess is not valid UTF-8".to_string()),
+        Some(word) => game.game.guess(&word.to_lowercase()),
+    };
+    match result {
+        Ok(pattern) => {
+            game.error = None;
+            owned(rules::pattern_to_code(&pattern))
+        }
+        Err(err) => {
+            game.error = CString::new(err).ok();
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Why the last guess wasn't allowed, null when it was. The string belongs to the game and is only
+/// valid until the next guess.
+///
+/// # Safety
+///
+/// `game` comes from `lingo_game_new` and isn't freed
+#[no_mangle]
+pub unsafe extern "C" fn lingo_game_error(game: *const LingoGame) -> *const c_char {
+    game.as_ref()
+        .and_then(|game| game.error.as_ref())
+        .map_or(ptr::null(), |error| error.as_ptr())
+}
+
+/// The guesses and the end of the game as json
+///
+/// # Safety
+///
+/// `game` comes from `lingo_game_new` and isn't freed
+#[no_mangle]
+pub unsafe extern "C" fn lingo_game_state(game: *const LingoGame) -> *mut c_char {
+    game.as_ref()
+        .map_or(ptr::null_mut(), |game| owned(game.game.state_json()))
+}
+
+/// Free a game
+///
+/// # Safety
+///
+/// `game` is null or comes from `lingo_game_new` and isn't freed yet
+#[no_mangle]
+pub unsafe extern "C" fn lingo_game_free(game: *mut LingoGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Free a string returned by the game
+///
+/// # Safety
+///
+/// `string` is null or was returned by one of the functions and isn't freed yet
+#[no_mangle]
+pub unsafe extern "C" fn lingo_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_game_is_played_through_the_c_functions() {
+        let words = CString::new("kaart\ntabak\ntaken\n").unwrap();
+        let word = CString::new("tabak").unwrap();
+        unsafe {
+            let game = lingo_game_new(words.as_ptr(), word.as_ptr(), 5, false);
+            assert!(!game.is_null());
+
+            let guess = CString::new("zzzzz").unwrap();
+            assert!(lingo_game_guess(game, guess.as_ptr()).is_null());
+            assert_eq!(
+                CStr::from_ptr(lingo_game_error(game)).to_str(),
+                Ok("The word zzzzz is not in the dictionary")
+            );
+
+            let pattern = lingo_game_guess(game, word.as_ptr());
+            assert_eq!(CStr::from_ptr(pattern).to_str(), Ok("GGGGG"));
+            assert!(lingo_game_error(game).is_null());
+            lingo_string_free(pattern);
+
+            let state = lingo_game_state(game);
+            assert!(CStr::from_ptr(state)
+                .to_str()
+                .unwrap()
+                .contains(r#""won":true"#));
+            lingo_string_free(state);
+            lingo_game_free(game);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! A single game: the word, the guesses and their patterns. The words are given as they are
+//! played, lowercase and with the letters of the language composed.
+
+use crate::rules::{self, HardMode, Pattern};
+use crate::WORD_LENGTH;
+use serde::Serialize;
+
+pub struct Game {
+    /// The words that can be guessed, sorted
+    words: Vec<String>,
+    word: String,
+    max_guesses: u32,
+    /// The hints the guesses have to use when playing in hard mode
+    hard_mode: Option<HardMode>,
+    guesses: Vec<(String, Pattern)>,
+}
+
+/// A guess as it is shown on the board
+#[derive(Serialize)]
+pub struct GuessState {
+    pub word: String,
+    /// The pattern as a code, see `rules::pattern_to_code`
+    pub pattern: String,
+}
+
+/// Everything that can be shown of the game. The word is only told when the game is finished.
+#[derive(Serialize)]
+pub struct GameState {
+    pub guesses: Vec<GuessState>,
+    pub max_guesses: u32,
+    pub finished: bool,
+    pub won: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word: Option<String>,
+}
+
+impl Game {
+    /// A game on `word`, with `words` as the words that can be guessed
+    pub fn new(
+        words: impl IntoIterator<Item = String>,
+        word: &str,
+        max_guesses: u32,
+        hard: bool,
+    ) -> Result<Game, String> {
+        let mut words = words
+            .into_iter()
+            .filter(|word| word.chars().count() == WORD_LENGTH)
+            .collect::<Vec<_>>();
+        words.sort_unstable();
+        words.dedup();
+        if words
+            .binary_search_by(|candidate| candidate.as_str().cmp(word))
+            .is_err()
+        {
+            return Err(format!("The word {} is not in the word list", word));
+        }
+        Ok(Game {
+            words,
+            word: word.to_string(),
+            max_guesses,
+            hard_mode: hard.then(HardMode::default),
+            guesses: Vec::new(),
+        })
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.guesses
+            .last()
+            .is_some_and(|(guess, _)| *guess == self.word)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.is_won() || self.guesses.len() as u32 >= self.max_guesses
+    }
+
+    /// Verify a guess, the error tells why the guess isn't allowed
+    pub fn guess(&mut self, guess: &str) -> Result<Pattern, String> {
+        if self.is_finished() {
+            return Err("The game is finished".to_string());
+        }
+        if guess.chars().count() != WORD_LENGTH {
+            return Err(format!("The word {} is not {} letters", guess, WORD_LENGTH));
+        }
+        if self
+            .words
+            .binary_search_by(|word| word.as_str().cmp(guess))
+            .is_err()
+        {
+            return Err(format!("The word {} is not in the dictionary", guess));
+        }
+        if let Some(hard_mode) = &self.hard_mode {
+            hard_mode.check(guess)?;
+        }
+
+        let pattern = rules::score(guess, &self.word);
+        if let Some(hard_mode) = &mut self.hard_mode {
+            hard_mode.record(guess, &pattern);
+        }
+        self.guesses.push((guess.to_string(), pattern));
+        Ok(pattern)
+    }
+
+    pub fn state(&self) -> GameState {
+        GameState {
+            guesses: self
+                .guesses
+                .iter()
+                .map(|(word, pattern)| GuessState {
+                    word: word.clone(),
+                    pattern: rules::pattern_to_code(pattern),
+                })
+                .collect(),
+            max_guesses: self.max_guesses,
+            finished: self.is_finished(),
+            won: self.is_won(),
+            word: self.is_finished().then(|| self.word.clone()),
+        }
+    }
+
+    /// The state of the game as json
+    pub fn state_json(&self) -> String {
+        serde_json::to_string(&self.state()).expect("The state can always be written as json")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(hard: bool) -> Game {
+        let words = ["kaart", "tabak", "taken", "tafel", "stoel"];
+        Game::new(words.map(String::from), "tabak", 3, hard).unwrap()
+    }
+
+    #[test]
+    fn the_game_ends_when_the_word_is_found() {
+        let mut game = game(false);
+        assert!(game.guess("kaart").is_ok());
+        assert!(!game.is_finished());
+        assert_eq!(
+            rules::pattern_to_code(&game.guess("tabak").unwrap()),
+            "GGGGG"
+        );
+        assert!(game.is_won());
+        assert_eq!(game.guess("taken"), Err("The game is finished".to_string()));
+        assert_eq!(game.state().word.as_deref(), Some("tabak"));
+    }
+
+    #[test]
+    fn guesses_follow_the_rules() {
+        let mut game = game(true);
+        assert!(game.guess("zzzzz").is_err());
+        assert!(game.guess("kaart").is_ok());
+        // Hard mode keeps the revealed A on the second place
+        assert!(game.guess("stoel").is_err());
+        assert_eq!(game.state().guesses.len(), 1);
+        assert_eq!(game.state().word, None);
+    }
+}
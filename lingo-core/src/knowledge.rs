@@ -0,0 +1,181 @@
+//! What the verified guesses revealed about the word: the letters confirmed on a place, the letters
+//! that are known not to be on a place and how often the letters can be in the word. It is built
+//! up guess by guess, and the keyboard, the hard mode rules, the possible words and the solver all
+//! read it from here.
+
+use crate::rules::Feedback;
+use crate::word;
+use std::collections::BTreeMap;
+
+/// How often a letter can be in the word
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Count {
+    pub min: usize,
+    /// `None` when it isn't known how often the letter can be in the word
+    pub max: Option<usize>,
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Knowledge {
+    /// The letter that is confirmed on each place, the places are added by the guesses so a
+    /// phrase that is longer than a word fits too
+    confirmed: Vec<Option<char>>,
+    /// The letters that are known not to be on each place
+    excluded: Vec<Vec<char>>,
+    /// The counts of the letters that something is known about
+    counts: BTreeMap<char, Count>,
+}
+
+impl Knowledge {
+    /// Add what the feedback on a verified guess reveals. Every copy of a letter that is in the
+    /// word is marked, so a guess tells if a letter is in the word but not how often. That only
+    /// follows from the places it is confirmed on.
+    pub fn record(&mut self, guess: &str, pattern: &[Feedback]) {
+        self.add_places(pattern.len());
+        for (place, (chr, feedback)) in guess.chars().zip(pattern).enumerate() {
+            let count = self.counts.entry(chr).or_default();
+            match feedback {
+                Feedback::Correct => {
+                    self.confirmed[place] = Some(chr);
+                    count.min = count.min.max(1);
+                }
+                Feedback::WrongPlace => {
+                    count.min = count.min.max(1);
+                }
+                Feedback::Wrong => count.max = Some(0),
+            }
+            if *feedback != Feedback::Correct && !self.excluded[place].contains(&chr) {
+                self.excluded[place].push(chr);
+            }
+        }
+        for (chr, count) in self.counts.iter_mut() {
+            let places = self.confirmed.iter().filter(|place| **place == Some(*chr));
+            count.min = count.min.max(places.count());
+        }
+    }
+
+    /// Add a letter that is known to be on the place without a guess, like a letter that is given
+    /// away
+    pub fn reveal(&mut self, place: usize, letter: char) {
+        self.add_places(place + 1);
+        self.confirmed[place] = Some(letter);
+        let places = self.confirmed.iter().filter(|chr| **chr == Some(letter));
+        let count = self.counts.entry(letter).or_default();
+        count.min = count.min.max(places.count());
+    }
+
+    /// Make room for the places of a guess that is longer than the guesses so far
+    fn add_places(&mut self, places: usize) {
+        if self.confirmed.len() < places {
+            self.confirmed.resize(places, None);
+            self.excluded.resize(places, Vec::new());
+        }
+    }
+
+    /// The letter that is confirmed on the place
+    pub fn confirmed(&self, place: usize) -> Option<char> {
+        self.confirmed.get(place).copied().flatten()
+    }
+
+    /// The letters that are known not to be on the place
+    pub fn excluded(&self, place: usize) -> &[char] {
+        self.excluded.get(place).map_or(&[], Vec::as_slice)
+    }
+
+    /// How often the letter can be in the word, nothing is known about a letter that wasn't
+    /// guessed
+    pub fn count(&self, letter: char) -> Count {
+        self.counts.get(&letter).copied().unwrap_or_default()
+    }
+
+    /// The letters that something is known about, with their counts
+    pub fn letters(&self) -> impl Iterator<Item = (char, Count)> + '_ {
+        self.counts.iter().map(|(letter, count)| (*letter, *count))
+    }
+
+    /// The color of the letter on the keyboard, `None` for a letter nothing is known about
+    pub fn feedback(&self, letter: char) -> Option<Feedback> {
+        let count = self.counts.get(&letter)?;
+        Some(if self.confirmed.contains(&Some(letter)) {
+            Feedback::Correct
+        } else if count.max == Some(0) {
+            Feedback::Wrong
+        } else {
+            Feedback::WrongPlace
+        })
+    }
+
+    /// If the word can still be the word of the game
+    pub fn allows(&self, word: &str) -> bool {
+        let letters = word.chars().collect::<Vec<_>>();
+        let places = letters.iter().enumerate().all(|(place, chr)| {
+            self.confirmed(place)
+                .is_none_or(|confirmed| confirmed == *chr)
+                && !self.excluded(place).contains(chr)
+        });
+        places
+            && self.counts.iter().all(|(chr, count)| {
+                let times = letters.iter().filter(|letter| *letter == chr).count();
+                times >= count.min && count.max.is_none_or(|max| times <= max)
+            })
+    }
+
+    /// Check that the guess uses all the revealed hints, like the hard mode rules ask. The error
+    /// explains the first hint that isn't used.
+    pub fn check_hints(&self, guess: &str) -> Result<(), String> {
+        for (index, (place, chr)) in self.confirmed.iter().zip(guess.chars()).enumerate() {
+            if let Some(required) = place.filter(|required| *required != chr) {
+                return Err(format!(
+                    "Letter {} has to be {} in hard mode",
+                    index + 1,
+                    word::display(required)
+                ));
+            }
+        }
+        let missing = self
+            .counts
+            .iter()
+            .find(|(chr, count)| guess.chars().filter(|letter| letter == *chr).count() < count.min);
+        if let Some((missing, _)) = missing {
+            return Err(format!(
+                "The guess has to contain {} in hard mode",
+                word::display(*missing)
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::score;
+
+    #[test]
+    fn guesses_build_up_the_knowledge() {
+        let mut knowledge = Knowledge::default();
+        knowledge.record("kaart", &score("kaart", "tabak"));
+        assert_eq!(knowledge.confirmed(1), Some('a'));
+        assert_eq!(knowledge.excluded(0), ['k']);
+        assert_eq!(
+            knowledge.count('r'),
+            Count {
+                min: 0,
+                max: Some(0)
+            }
+        );
+        assert_eq!(knowledge.feedback('t'), Some(Feedback::WrongPlace));
+        assert_eq!(knowledge.feedback('z'), None);
+        assert!(knowledge.allows("tabak"));
+        assert!(!knowledge.allows("tafel"));
+
+        // The second A is confirmed, so there are at least two
+        knowledge.record("tabak", &score("tabak", "tabak"));
+        assert_eq!(knowledge.count('a').min, 2);
+        assert_eq!(knowledge.feedback('t'), Some(Feedback::Correct));
+        assert_eq!(
+            knowledge.check_hints("tafel"),
+            Err("Letter 3 has to be B in hard mode".to_string())
+        );
+    }
+}
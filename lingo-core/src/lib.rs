@@ -0,0 +1,15 @@
+//! The rules of Lingo without a screen: scoring guesses, the hard mode rules and a game that keeps
+//! track of the guesses. The terminal game is built on it, it compiles to WebAssembly for a
+//! browser version and other applications embed it through the C functions of `ffi`.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ffi;
+pub mod game;
+pub mod knowledge;
+pub mod rules;
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+pub mod word;
+
+/// The amount of letters of the words of the game
+pub const WORD_LENGTH: usize = 5;
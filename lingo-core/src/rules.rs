@@ -0,0 +1,180 @@
+//! Scoring guesses and the hard mode rules
+
+use crate::knowledge::Knowledge;
+use crate::WORD_LENGTH;
+use serde::{Deserialize, Serialize};
+
+/// The verdict on a single letter of a guess
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feedback {
+    /// The letter isn't in the target word
+    Wrong,
+    /// The letter is in the target word at a different place
+    WrongPlace,
+    /// The letter is in this place in the target word
+    Correct,
+}
+
+/// The verdicts on all the letters of a guess
+pub type Pattern = [Feedback; WORD_LENGTH];
+
+/// Score a guess against the target word
+pub fn score(guess: &str, word: &str) -> Pattern {
+    debug_assert!(
+        guess.chars().count() == WORD_LENGTH && word.chars().count() == WORD_LENGTH,
+        "Only words of the correct length can be scored"
+    );
+
+    let mut pattern = [Feedback::Wrong; WORD_LENGTH];
+    for ((feedback, chr), target) in pattern.iter_mut().zip(guess.chars()).zip(word.chars()) {
+        *feedback = verdict(chr, target, word);
+    }
+    pattern
+}
+
+/// Score a guess against a target of any length, like a phrase that is longer than a word
+pub fn score_tiles(guess: &str, word: &str) -> Vec<Feedback> {
+    debug_assert!(
+        guess.chars().count() == word.chars().count(),
+        "Only guesses as long as the target can be scored"
+    );
+
+    guess
+        .chars()
+        .zip(word.chars())
+        .map(|(chr, target)| verdict(chr, target, word))
+        .collect()
+}
+
+/// The verdict on a letter of the guess, `target` is the letter on the same place in the word
+fn verdict(chr: char, target: char, word: &str) -> Feedback {
+    if chr == target {
+        Feedback::Correct
+    } else if word.contains(chr) {
+        Feedback::WrongPlace
+    } else {
+        Feedback::Wrong
+    }
+}
+
+/// The pattern with a letter for each tile: `G` for a letter in the right place, `Y` for a letter
+/// in the wrong place and `X` for a letter that isn't in the word
+pub fn pattern_to_code(pattern: &Pattern) -> String {
+    pattern
+        .iter()
+        .map(|feedback| match feedback {
+            Feedback::Correct => 'G',
+            Feedback::WrongPlace => 'Y',
+            Feedback::Wrong => 'X',
+        })
+        .collect()
+}
+
+/// The pattern of a code with a letter for each tile
+pub fn pattern_from_code(code: &str) -> Option<Pattern> {
+    let mut pattern = [Feedback::Wrong; WORD_LENGTH];
+    if code.chars().count() != WORD_LENGTH {
+        return None;
+    }
+    for (feedback, letter) in pattern.iter_mut().zip(code.chars()) {
+        *feedback = match letter {
+            'G' => Feedback::Correct,
+            'Y' => Feedback::WrongPlace,
+            'X' => Feedback::Wrong,
+            _ => return None,
+        };
+    }
+    Some(pattern)
+}
+
+/// The hard mode rules: every revealed hint has to be used in the next guesses. A letter that is
+/// correct has to stay on its place and a letter that is in the word has to be in the guess.
+#[derive(Clone, Default)]
+pub struct HardMode {
+    /// The hints revealed by the verified guesses
+    knowledge: Knowledge,
+}
+
+impl HardMode {
+    /// Add the hints revealed by a verified guess
+    pub fn record(&mut self, guess: &str, pattern: &[Feedback]) {
+        self.knowledge.record(guess, pattern);
+    }
+
+    /// Check that the guess uses all the revealed hints, the error explains the first hint that
+    /// isn't used
+    pub fn check(&self, guess: &str) -> Result<(), String> {
+        self.knowledge.check_hints(guess)
+    }
+
+    pub fn allows(&self, guess: &str) -> bool {
+        self.check(guess).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hard_mode(guesses: &[(&str, &str)]) -> HardMode {
+        let mut hard_mode = HardMode::default();
+        for (guess, word) in guesses {
+            hard_mode.record(guess, &score(guess, word));
+        }
+        hard_mode
+    }
+
+    #[test]
+    fn everything_is_allowed_before_the_first_guess() {
+        let hard_mode = HardMode::default();
+        assert!(hard_mode.allows("staan"));
+        assert!(hard_mode.allows("kaart"));
+    }
+
+    #[test]
+    fn revealed_places_have_to_be_kept() {
+        let hard_mode = hard_mode(&[("staan", "stoel")]);
+        assert!(hard_mode.allows("stoel"));
+        assert!(hard_mode.allows("stijl"));
+        assert_eq!(
+            hard_mode.check("tsaar"),
+            Err("Letter 1 has to be S in hard mode".to_string())
+        );
+        assert_eq!(
+            hard_mode.check("sjaal"),
+            Err("Letter 2 has to be T in hard mode".to_string())
+        );
+    }
+
+    #[test]
+    fn revealed_letters_have_to_be_used() {
+        let hard_mode = hard_mode(&[("kaart", "tabak")]);
+        assert!(hard_mode.allows("tabak"));
+        // The K has been revealed to be in the word, but not on the first place
+        assert!(hard_mode.allows("taken"));
+        assert_eq!(
+            hard_mode.check("tafel"),
+            Err("The guess has to contain K in hard mode".to_string())
+        );
+    }
+
+    #[test]
+    fn hints_of_all_guesses_count() {
+        let hard_mode = hard_mode(&[("kaart", "stoel"), ("boten", "stoel")]);
+        // T from the first guess and O and E from the second, with the E on the fourth place
+        assert!(hard_mode.allows("stoel"));
+        assert!(hard_mode.allows("totem"));
+        assert!(!hard_mode.allows("ogent"));
+        assert!(!hard_mode.allows("kapel"));
+    }
+    #[test]
+    fn targets_longer_than_a_word_are_scored_on_every_tile() {
+        use Feedback::*;
+        assert_eq!(
+            score_tiles("het huis", "het hout"),
+            [Correct, Correct, Correct, Correct, Correct, WrongPlace, Wrong, Wrong]
+        );
+        assert_eq!(score_tiles("kaart", "tabak"), score("kaart", "tabak"));
+    }
+}
@@ -0,0 +1,42 @@
+//! The functions of the browser version. Build with
+//! `cargo build -p lingo-core --target wasm32-unknown-unknown` and generate the bindings with
+//! `wasm-bindgen`.
+
+use crate::game::Game;
+use crate::rules;
+use wasm_bindgen::prelude::*;
+
+/// A game that is played in the browser
+#[wasm_bindgen]
+pub struct WasmGame {
+    game: Game,
+}
+
+/// Start a game on `word`, `words` holds the words that can be guessed on separate lines
+#[wasm_bindgen]
+pub fn new_game(
+    words: &str,
+    word: &str,
+    max_guesses: u32,
+    hard: bool,
+) -> Result<WasmGame, JsError> {
+    let words = words.lines().map(|line| line.trim().to_lowercase());
+    Game::new(words, &word.to_lowercase(), max_guesses, hard)
+        .map(|game| WasmGame { game })
+        .map_err(|err| JsError::new(&err))
+}
+
+/// Verify a guess, returns the pattern as a code like `GYXXG`
+#[wasm_bindgen]
+pub fn guess(game: &mut WasmGame, word: &str) -> Result<String, JsError> {
+    game.game
+        .guess(&word.to_lowercase())
+        .map(|pattern| rules::pattern_to_code(&pattern))
+        .map_err(|err| JsError::new(&err))
+}
+
+/// The guesses and the end of the game as json
+#[wasm_bindgen]
+pub fn state_json(game: &WasmGame) -> String {
+    game.game.state_json()
+}
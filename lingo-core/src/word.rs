@@ -0,0 +1,18 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// The letter as it is shown on a tile. Letters without a single uppercase character, like ß, are
+/// shown as they are, and a letter that stands for a pair of letters, like the Dutch ĳ, shows both.
+pub fn display(letter: char) -> String {
+    let mut upper = letter.to_uppercase();
+    let upper = match (upper.next(), upper.next()) {
+        (Some(upper), None) => upper,
+        _ => letter,
+    };
+    // Only the ligatures come apart into more than one letter, accents come apart into marks
+    let letters = upper.to_string().nfkd().collect::<String>();
+    if letters.chars().count() > 1 && letters.chars().all(char::is_alphabetic) {
+        letters
+    } else {
+        upper.to_string()
+    }
+}
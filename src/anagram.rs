@@ -0,0 +1,222 @@
+//! Anagram training: the letters of a word are shown scrambled and the player gets a few attempts
+//! to put them in the right order. The board shows which letters are on their place already, like
+//! the feedback on a guess.
+
+use crate::dictionary::Dictionary;
+use crate::editor::GuessEditor;
+use crate::engine::{Event, Game, GameState, Transition};
+use crate::error::Error;
+use crate::input::{self, InputEvent, Poller};
+use crate::keymap::{Bindings, ESCAPE};
+use crate::language::Language;
+use crate::messages::Notice;
+use crate::pause;
+use crate::quick_settings::QuickSettings;
+use crate::rules;
+use crate::script::Scripts;
+use crate::strings::{fill, strings};
+use crate::theme::Theme;
+use crate::word;
+use crate::{init_ncurses, pick_words, render_game, Board, BoardState, GuessedLetter};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// The attempts at a word
+const ATTEMPTS: u32 = 3;
+/// The renders a rejected attempt stays on the screen
+const REJECTION_RENDERS: u32 = 3;
+
+/// The letters of the word in another order, unless every order is the same
+fn scramble(word: &str, rng: &mut impl Rng) -> String {
+    let mut letters = word.chars().collect_vec();
+    for _ in 0..20 {
+        letters.shuffle(rng);
+        let scrambled = letters.iter().collect::<String>();
+        if scrambled != word {
+            return scrambled;
+        }
+    }
+    word.to_string()
+}
+
+/// If the attempt uses exactly the letters of the word
+fn same_letters(attempt: &str, word: &str) -> bool {
+    attempt.chars().sorted().eq(word.chars().sorted())
+}
+
+/// Play an anagram, returns if it was solved and if the player wants another one
+fn play(
+    dictionary: &Dictionary,
+    language: &Language,
+    poller: &Poller,
+    quick: &mut QuickSettings,
+    word: &'static str,
+) -> Result<(bool, bool), Error> {
+    let scrambled = scramble(word, &mut rand::thread_rng());
+    let display = |text: &str| text.chars().map(word::display).join(" ");
+    let letters = scrambled.chars().unique().collect_vec();
+    let mut board_state = BoardState {
+        boards: vec![Board::new(ATTEMPTS)],
+        // Only the letters of the word are on the keyboard
+        keyboard: letters.clone(),
+        score: Some(fill(strings().anagram_letters, &[&display(&scrambled)])),
+        ..Default::default()
+    };
+    // An attempt that doesn't use the letters or isn't a word doesn't count
+    let scripts = Scripts::default();
+    let mut game = Game::new(
+        dictionary,
+        &scripts,
+        &letters,
+        vec![word],
+        ATTEMPTS,
+        None,
+        false,
+    )
+    .with_check(|text| {
+        let rejection = if !same_letters(text, word) {
+            fill(strings().anagram_other_letters, &[&display(&scrambled)])
+        } else if !dictionary.contains(text) {
+            fill(strings().not_in_dictionary, &[&text])
+        } else {
+            return None;
+        };
+        Some(Notice::warning(rejection))
+    });
+    let mut editor = GuessEditor::default();
+    // The pause menu opened with Ctrl-C asks to quit right away
+    let mut quitting = false;
+    loop {
+        let attempt = game.guesses() as usize;
+        match game.state() {
+            GameState::Typing => {
+                let text = editor.text();
+                for (index, letter) in board_state.boards[0].rows[attempt].iter_mut().enumerate() {
+                    *letter = match word::letter(&text, index) {
+                        None => GuessedLetter::NoLetter,
+                        Some(chr) => GuessedLetter::Letter(chr),
+                    };
+                }
+                board_state.cursor = editor.cursor().map(|tile| (attempt, tile));
+                render_game(&board_state);
+
+                match poller.poll() {
+                    event @ (InputEvent::Quit | InputEvent::Interrupt) => {
+                        quitting = event == InputEvent::Interrupt;
+                        game.handle_event(Event::Pause)?;
+                        continue;
+                    }
+                    InputEvent::Char(chr) => {
+                        if let Some(letter) = input::normalize(chr, &letters) {
+                            if !editor.join_digraph(language, letter) {
+                                editor.type_letter(letter);
+                            }
+                        }
+                    }
+                    InputEvent::Backspace => editor.backspace(),
+                    InputEvent::Delete => editor.delete(),
+                    InputEvent::ClearRow => editor.clear(),
+                    InputEvent::Left => editor.move_left(),
+                    InputEvent::Right => editor.move_right(),
+                    InputEvent::Home => editor.move_home(),
+                    InputEvent::End => editor.move_end(),
+                    InputEvent::Submit if editor.is_full() => {
+                        if let Transition::Rejected(notice) =
+                            game.handle_event(Event::Submit(text))?
+                        {
+                            board_state
+                                .messages
+                                .push(notice.expire_after(REJECTION_RENDERS));
+                            continue;
+                        }
+                    }
+                    _ => (),
+                }
+                board_state.messages.tick();
+            }
+            GameState::Scoring => {
+                if let Transition::Scored(scored) = game.handle_event(Event::Score)? {
+                    for (_, pattern) in scored.patterns {
+                        board_state.boards[0].rows[attempt] =
+                            rules::guessed_word(&scored.guess, &pattern);
+                    }
+                    editor.clear();
+                }
+            }
+            GameState::Menu => {
+                // Quitting after an attempt has to be confirmed
+                if pause::pause_or_quit(quick, attempt > 0, quitting, || render_game(&board_state))
+                {
+                    return Ok((false, false));
+                }
+                game.handle_event(Event::Resume)?;
+            }
+            GameState::Won | GameState::Lost => break,
+        }
+    }
+
+    // The word is shown until a key is pressed, escape stops the training
+    let solved = game.state() == GameState::Won;
+    let message = match solved {
+        true => strings().anagram_found,
+        false => strings().anagram_missed,
+    };
+    board_state.cursor = None;
+    board_state.messages.clear();
+    board_state
+        .messages
+        .push(Notice::info(fill(message, &[&display(word)])));
+    render_game(&board_state);
+    Ok((solved, ncurses::getch() != ESCAPE))
+}
+
+/// Play anagrams of the words until the player stops, returns the anagrams that were solved and
+/// played
+pub fn run(
+    dictionary: &Dictionary,
+    answers: &[&'static str],
+    language: &Language,
+    keys: &'static Bindings,
+    theme: &'static Theme,
+) -> Result<(u32, u32), Error> {
+    if answers.is_empty() {
+        eprintln!("There are no words to play");
+        return Ok((0, 0));
+    }
+    let _window_ender = init_ncurses(theme);
+    let poller = Poller::new(keys, -1);
+    // There are no possible words to show, only the theme can be changed
+    let mut quick = QuickSettings {
+        theme,
+        suggestions: None,
+    };
+    let mut solved = 0;
+    let mut played = 0;
+    loop {
+        let word = pick_words(answers, 1, None)[0];
+        let (found, next) = play(dictionary, language, &poller, &mut quick, word)?;
+        played += 1;
+        solved += found as u32;
+        if !next {
+            return Ok((solved, played));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn scrambled_words_keep_their_letters() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let scrambled = scramble("tabak", &mut rng);
+        assert_ne!(scrambled, "tabak");
+        assert!(same_letters(&scrambled, "tabak"));
+        assert!(!same_letters("tabek", "tabak"));
+        // A word with a single letter can only be written one way
+        assert_eq!(scramble("aaaaa", &mut rng), "aaaaa");
+    }
+}
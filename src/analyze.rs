@@ -0,0 +1,78 @@
+//! Analysis of the word list. The first guesses are ranked by the amount of words that are
+//! expected to be left after them, when every answer is equally likely.
+
+use crate::candidates::CandidateSet;
+use crate::language::Language;
+use crate::word;
+use itertools::Itertools;
+use rayon::prelude::*;
+
+/// How well a word does as the first guess
+pub struct Opener {
+    pub word: &'static str,
+    /// The average amount of answers that are left after the guess
+    pub expected: f64,
+    /// The most answers that can be left after the guess
+    pub worst: u32,
+    /// The amount of different patterns the guess can get
+    pub patterns: usize,
+}
+
+/// Every word as the first guess against the answers, the best first. Words with the same
+/// expectation stay in alphabetical order.
+pub fn openers(words: &[&'static str], answers: &[&'static str]) -> Vec<Opener> {
+    let candidates = CandidateSet::new(answers);
+    let total = answers.len().max(1) as f64;
+    let mut openers = words
+        .par_iter()
+        .map(|word| {
+            let sizes = candidates.bucket_sizes(word);
+            Opener {
+                word,
+                // A pattern with n answers is the pattern of each of them, so n answers are left
+                // for n of them
+                expected: sizes
+                    .iter()
+                    .map(|size| *size as f64 * *size as f64)
+                    .sum::<f64>()
+                    / total,
+                worst: sizes.iter().copied().max().unwrap_or_default(),
+                patterns: sizes.iter().filter(|size| **size > 0).count(),
+            }
+        })
+        .collect::<Vec<_>>();
+    openers.sort_by(|a, b| a.expected.total_cmp(&b.expected));
+    openers
+}
+
+/// The first guess that leaves the fewest answers on average
+pub fn best_opener(words: &[&'static str], answers: &[&'static str]) -> Option<&'static str> {
+    openers(words, answers).first().map(|opener| opener.word)
+}
+
+/// Print the `top` best first guesses as a table
+pub fn print_openers(
+    words: &[&'static str],
+    answers: &[&'static str],
+    language: &Language,
+    top: usize,
+) {
+    println!(
+        "The best first guesses of {} {} words against {} answers",
+        words.len(),
+        language.name,
+        answers.len()
+    );
+    println!();
+    println!("Rank  Word   Expected  Worst  Patterns");
+    for (rank, opener) in openers(words, answers).iter().take(top).enumerate() {
+        println!(
+            "{:>4}  {:<5}  {:>8.1}  {:>5}  {:>8}",
+            rank + 1,
+            opener.word.chars().map(word::display).join(""),
+            opener.expected,
+            opener.worst,
+            opener.patterns
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::rules::Feedback;
+use crate::strings::{fill, strings};
+use crate::word;
+use crate::{GuessedLetter, GuessedWord};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A single change in the game state as it should be announced to assistive tooling
+#[derive(Default)]
+pub struct Announcement {
+    /// The message shown below the board
+    pub message: Option<String>,
+    /// The feedback on the row that was just verified
+    pub row: Option<String>,
+    /// What is known about the letters of the alphabet
+    pub keyboard: Option<String>,
+}
+
+/// Writes announcements as plain lines of text to a file so assistive tooling can tail it. The
+/// file can also be a secondary file descriptor like `/dev/fd/3`.
+pub struct Announcer {
+    output: Option<File>,
+}
+
+impl Announcer {
+    /// An announcer that doesn't write anything
+    pub fn disabled() -> Self {
+        Announcer { output: None }
+    }
+
+    /// An announcer that appends to the file at the given path
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let output = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Announcer {
+            output: Some(output),
+        })
+    }
+
+    /// Write the announcement. The parts are always written in the same order, first the message,
+    /// then the row feedback and then the keyboard, so that tooling reading along gets the most
+    /// important information first.
+    pub fn announce(&mut self, announcement: &Announcement) {
+        let output = match &mut self.output {
+            None => return,
+            Some(output) => output,
+        };
+
+        let lines = [
+            &announcement.message,
+            &announcement.row,
+            &announcement.keyboard,
+        ];
+        let result = lines
+            .iter()
+            .filter_map(|line| line.as_ref())
+            .try_for_each(|line| writeln!(output, "{}", line))
+            .and_then(|_| output.flush());
+
+        // The announcements are an addition to the screen, when the stream breaks the game should
+        // continue without it
+        if result.is_err() {
+            self.output = None;
+        }
+    }
+}
+
+/// The marks around a letter that tell the feedback without color, for example [S] for a correct
+/// letter
+pub fn marks(feedback: Feedback) -> [char; 2] {
+    match feedback {
+        Feedback::Wrong => ['-', '-'],
+        Feedback::WrongPlace => ['(', ')'],
+        Feedback::Correct => ['[', ']'],
+    }
+}
+
+/// Describe the feedback on a verified row in words, for example "S correct, T wrong place, O
+/// wrong, E wrong, L wrong"
+pub fn describe_row(row: &GuessedWord) -> String {
+    row.iter()
+        .filter_map(|letter| match *letter {
+            GuessedLetter::NoLetter | GuessedLetter::Letter(_) | GuessedLetter::Separator => None,
+            GuessedLetter::Wrong(x) => Some(fill(strings().wrong, &[&word::display(x)])),
+            GuessedLetter::WrongPlace(x) => Some(fill(strings().wrong_place, &[&word::display(x)])),
+            GuessedLetter::Correct(x) => Some(fill(strings().correct, &[&word::display(x)])),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,163 @@
+//! The attract mode, like the screen of an arcade cabinet nobody is playing on. The solver plays
+//! demo games on random words, they are recorded as replays and played back in real time until a
+//! key is pressed.
+
+use crate::bench;
+use crate::protocol::Message;
+use crate::replay::{Recorder, Replay};
+use crate::rules;
+use crate::solver::Solver;
+use crate::strategy::Builtin;
+use crate::strings::{fill, strings};
+use crate::theme::Theme;
+use crate::word;
+use crate::{init_ncurses, pick_words, render_game};
+use std::time::Instant;
+
+/// The milliseconds the solver takes before it types a guess
+const THINKING: u64 = 900;
+/// The milliseconds between the typed letters
+const TYPING: u64 = 160;
+/// The milliseconds between a typed guess and its result
+const SUBMITTING: u64 = 400;
+/// The milliseconds the end of a game stays on the screen
+const LINGER: u64 = 3000;
+
+/// The replay of the solver finding the word, typed like a player would
+fn demo(
+    solver: &Solver,
+    opener: &'static str,
+    word: &str,
+    max_guesses: u32,
+    language: &str,
+) -> Replay {
+    let guesses = bench::play(solver, opener, word);
+    let guesses = &guesses[..guesses.len().min(max_guesses as usize)];
+    let mut recorder = Recorder::new();
+    let mut at = 0;
+    for guess in guesses {
+        at += THINKING;
+        for typed in 1..=guess.chars().count() {
+            at += TYPING;
+            let text = guess.chars().take(typed).collect();
+            recorder.record_at(
+                at,
+                Message::Typed {
+                    text,
+                    cursor: typed,
+                },
+            );
+        }
+        at += SUBMITTING;
+        recorder.record_at(
+            at,
+            Message::Result {
+                guess: Some(guess.to_string()),
+                pattern: rules::score(guess, word),
+            },
+        );
+    }
+
+    let won = guesses.last() == Some(&word);
+    let display = word.chars().map(word::display).collect::<String>();
+    let message = match won {
+        true => fill(strings().demo_found, &[&display, &guesses.len()]),
+        false => fill(strings().lost_word, &[&display]),
+    };
+    recorder.record_at(
+        at,
+        Message::Finished {
+            won,
+            word: Some(word.to_string()),
+            message: Some(message),
+        },
+    );
+    recorder.finish(language, word)
+}
+
+/// Show the replay as it happened, returns true when a key was pressed
+fn watch(replay: &Replay) -> bool {
+    let started = Instant::now();
+    let end = replay.events.last().map_or(0, |event| event.at) + LINGER;
+    let mut shown = None;
+    loop {
+        let clock = started.elapsed().as_millis() as u64;
+        if clock > end {
+            return false;
+        }
+        let position = replay
+            .events
+            .iter()
+            .take_while(|event| event.at <= clock)
+            .count();
+        if shown != Some(position) {
+            render_game(&replay.board_at(position));
+            let mut max_x = 0;
+            let mut max_y = 0;
+            ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+            let banner = strings().demo_banner;
+            ncurses::mvaddstr(max_y - 1, (max_x - banner.len() as i32) / 2, banner);
+            ncurses::refresh();
+            shown = Some(position);
+        }
+
+        match ncurses::getch() {
+            ncurses::ERR => (),
+            ncurses::KEY_RESIZE => shown = None,
+            _ => return true,
+        }
+    }
+}
+
+/// Play demo games until a key is pressed
+pub fn run(
+    language: &str,
+    words: &[&'static str],
+    answers: &[&'static str],
+    hard: bool,
+    strategy: Builtin,
+    max_guesses: u32,
+    theme: &Theme,
+) {
+    let Some((solver, opener)) =
+        bench::start(words, hard, strategy).filter(|_| !answers.is_empty())
+    else {
+        eprintln!("There are no words to play");
+        return;
+    };
+    let _window_ender = init_ncurses(theme);
+    ncurses::timeout(50);
+    loop {
+        let word = pick_words(answers, 1, None)[0];
+        if watch(&demo(&solver, opener, word, max_guesses, language)) {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demo_games_are_typed_like_a_player() {
+        let words = ["kaart", "tabak", "taken", "tafel", "stoel"];
+        let (solver, opener) = bench::start(&words, false, Builtin::Frequency).unwrap();
+        let replay = demo(&solver, opener, "stoel", 5, "nl");
+
+        assert!(!replay.header.guesses.is_empty());
+        assert!(replay
+            .events
+            .windows(2)
+            .all(|pair| pair[0].at <= pair[1].at));
+        let Some(Message::Finished { won, .. }) = replay.events.last().map(|event| &event.message)
+        else {
+            panic!("The demo game doesn't end");
+        };
+        assert!(won);
+
+        // The game is cut off at the guesses of a game
+        let replay = demo(&solver, opener, "stoel", 1, "nl");
+        assert_eq!(replay.header.guesses.len(), 1);
+    }
+}
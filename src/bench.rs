@@ -0,0 +1,148 @@
+//! Measuring how well the solver plays. The solver plays a game for every word that can be the word
+//! of the game, without a screen, and the results of all games are summarized. A game isn't cut
+//! off at the guesses of a game, so the failed words still show how far off the solver was.
+
+use crate::rules;
+use crate::solver::Solver;
+use crate::strategy::Builtin;
+use crate::word;
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::time::Instant;
+
+/// The amount of words shown as the worst cases
+const WORST_CASES: usize = 10;
+
+/// The word as it is shown to the player
+fn display(word: &str) -> String {
+    word.chars().map(word::display).collect()
+}
+
+/// Let the solver find the word, the guesses it made in order. Every game starts from the same
+/// solver and its first guess, those only depend on the word list. The last guess is the word,
+/// unless the solver ran out of candidates.
+pub fn play(start: &Solver, opener: &'static str, word: &str) -> Vec<&'static str> {
+    let mut solver = start.clone();
+    let mut guesses = vec![opener];
+    let mut guess = opener;
+    while guess != word {
+        solver.update(guess, &rules::score(guess, word));
+        // Every guess removes at least itself from the candidates
+        let Some(next) = solver.next_guess() else {
+            break;
+        };
+        guesses.push(next);
+        guess = next;
+    }
+    guesses
+}
+
+/// A solver for the word list and its first guess
+pub fn start(
+    words: &[&'static str],
+    hard: bool,
+    strategy: Builtin,
+) -> Option<(Solver, &'static str)> {
+    let solver = Solver::new(words, hard, strategy.strategy());
+    let opener = solver.next_guess()?;
+    Some((solver, opener))
+}
+
+/// Print the guesses of the solver for a single word
+pub fn show(words: &[&'static str], word: &str, hard: bool, strategy: Builtin, max_guesses: u32) {
+    let Some((solver, opener)) = start(words, hard, strategy) else {
+        return;
+    };
+    let guesses = play(&solver, opener, word);
+    for (number, guess) in guesses.iter().enumerate() {
+        println!("{:>3}  {}", number + 1, display(guess));
+    }
+    if guesses.len() > max_guesses as usize {
+        println!(
+            "Found {} in {} guesses, more than the {} of a game",
+            display(word),
+            guesses.len(),
+            max_guesses
+        );
+    } else {
+        println!("Found {} in {} guesses", display(word), guesses.len());
+    }
+}
+
+/// Let the solver play every answer and print the average guesses, how many words it didn't find
+/// within the guesses of a game and the words that took the most guesses
+pub fn run(
+    words: &[&'static str],
+    answers: &[&'static str],
+    hard: bool,
+    strategy: Builtin,
+    max_guesses: u32,
+) {
+    let started = Instant::now();
+    let Some((solver, opener)) = start(words, hard, strategy).filter(|_| !answers.is_empty())
+    else {
+        println!("There are no words to play");
+        return;
+    };
+    let games = answers
+        .par_iter()
+        .map(|word| (*word, play(&solver, opener, word).len()))
+        .collect::<Vec<_>>();
+    let elapsed = started.elapsed();
+
+    let solved = games
+        .iter()
+        .filter(|(_, guesses)| *guesses <= max_guesses as usize)
+        .collect_vec();
+    let failed = games.len() - solved.len();
+    println!(
+        "Played {} words with the {} strategy{} in {:.1}s",
+        games.len(),
+        strategy.name(),
+        if hard { " in hard mode" } else { "" },
+        elapsed.as_secs_f64()
+    );
+    println!(
+        "Failed {} words ({:.1}%) within {} guesses",
+        failed,
+        100.0 * failed as f64 / games.len() as f64,
+        max_guesses
+    );
+    if !solved.is_empty() {
+        println!(
+            "Average guesses of the solved words: {:.3}",
+            solved.iter().map(|(_, guesses)| guesses).sum::<usize>() as f64 / solved.len() as f64
+        );
+    }
+    println!(
+        "Average guesses of all words: {:.3}",
+        games.iter().map(|(_, guesses)| guesses).sum::<usize>() as f64 / games.len() as f64
+    );
+
+    println!();
+    println!("Guesses  Words");
+    for (guesses, words) in games
+        .iter()
+        .counts_by(|(_, guesses)| *guesses)
+        .iter()
+        .sorted()
+    {
+        let marker = if *guesses > max_guesses as usize {
+            "  failed"
+        } else {
+            ""
+        };
+        println!("{:>7}  {:>5}{}", guesses, words, marker);
+    }
+
+    println!();
+    println!("Worst cases");
+    // The sort is stable, words with the same guesses stay in alphabetical order
+    for (word, guesses) in games
+        .iter()
+        .sorted_by_key(|(_, guesses)| std::cmp::Reverse(*guesses))
+        .take(WORST_CASES)
+    {
+        println!("  {}  {} guesses", display(word), guesses);
+    }
+}
@@ -0,0 +1,233 @@
+//! The bingo card of an endless run, like the one of the TV show. Every solved word draws numbers
+//! from the bowl, the numbers that are on the card are filled in and a full row or column wins a
+//! prize. The card stays for the whole run, when it is full the next words fill a new card.
+
+use crate::render::{Renderer, Terminal};
+use crate::strings::{fill, strings};
+use crate::{borders, COLOR_PAIR_CORRECT_TILE};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// The rows and columns of a card
+const SIZE: usize = 4;
+/// The balls in the bowl are numbered from 1 up to this
+const HIGHEST: u8 = 40;
+/// The numbers drawn after a solved word
+const DRAWS: usize = 2;
+/// The points for a full row or column
+const LINE_PRIZE: u32 = 100;
+/// The height of a card on the screen
+pub const HEIGHT: i32 = 3 + 2 * SIZE as i32;
+/// The width of a card on the screen
+pub const WIDTH: i32 = 1 + 4 * SIZE as i32;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Card {
+    numbers: [[u8; SIZE]; SIZE],
+    /// The numbers that came out of the bowl, a bit for every number
+    drawn: u64,
+}
+
+/// A number that came out of the bowl
+pub struct Draw {
+    pub number: u8,
+    /// If the number is on the card
+    pub hit: bool,
+    /// The rows and columns that the number made full
+    pub lines: usize,
+}
+
+impl Card {
+    /// A card with random numbers, every column goes up like on a bingo card
+    pub fn new(rng: &mut impl Rng) -> Card {
+        let mut picked = rand::seq::index::sample(rng, HIGHEST as usize, SIZE * SIZE)
+            .into_iter()
+            .map(|index| index as u8 + 1)
+            .collect::<Vec<_>>();
+        picked.sort_unstable();
+        let mut numbers = [[0; SIZE]; SIZE];
+        for (index, number) in picked.into_iter().enumerate() {
+            numbers[index % SIZE][index / SIZE] = number;
+        }
+        Card { numbers, drawn: 0 }
+    }
+
+    fn is_drawn(&self, number: u8) -> bool {
+        self.drawn & (1 << number) != 0
+    }
+
+    /// The full rows and columns
+    fn full_lines(&self) -> usize {
+        let rows = (0..SIZE)
+            .filter(|row| (0..SIZE).all(|column| self.is_drawn(self.numbers[*row][column])))
+            .count();
+        let columns = (0..SIZE)
+            .filter(|column| (0..SIZE).all(|row| self.is_drawn(self.numbers[row][*column])))
+            .count();
+        rows + columns
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.full_lines() == 2 * SIZE
+    }
+
+    /// Take the number out of the bowl, returns the lines it made full
+    fn mark(&mut self, number: u8) -> usize {
+        let before = self.full_lines();
+        self.drawn |= 1 << number;
+        self.full_lines() - before
+    }
+
+    /// Draw a number out of the balls that are left in the bowl
+    pub fn draw(&mut self, rng: &mut impl Rng) -> Draw {
+        let left = (1..=HIGHEST)
+            .filter(|number| !self.is_drawn(*number))
+            .collect::<Vec<_>>();
+        // The bowl can't run out, the card is full before that
+        let number = *left.choose(rng).expect("There are balls left in the bowl");
+        let hit = self.numbers.iter().flatten().any(|other| *other == number);
+        let lines = self.mark(number);
+        Draw { number, hit, lines }
+    }
+}
+
+/// Draw the card with the top left corner at the position, the numbers that came out of the bowl
+/// are filled in
+pub fn render(screen: &mut impl Renderer, (x, y): (i32, i32), card: &Card, marked: bool) {
+    let borders = borders::borders();
+    let title = strings().bingo;
+    screen.print(y, x, &borders.top.across(SIZE), 0);
+    screen.print(y + 1, x, borders.vertical, 0);
+    screen.print(
+        y + 1,
+        x + (WIDTH - title.chars().count() as i32) / 2,
+        title,
+        0,
+    );
+    screen.print(y + 1, x + WIDTH - 1, borders.vertical, 0);
+    screen.print(y + 2, x, &borders.below_title.across(SIZE), 0);
+    for (index, row) in card.numbers.iter().enumerate() {
+        let line_y = y + 3 + 2 * index as i32;
+        for (column, number) in row.iter().enumerate() {
+            let tile_x = x + 4 * column as i32;
+            screen.print(line_y, tile_x, borders.vertical, 0);
+            let (mark, attribute) = match card.is_drawn(*number) {
+                true => (
+                    if marked { '*' } else { ' ' },
+                    ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_CORRECT_TILE),
+                ),
+                false => (' ', 0),
+            };
+            screen.print(
+                line_y,
+                tile_x + 1,
+                &format!("{}{:>2}", mark, number),
+                attribute,
+            );
+        }
+        screen.print(line_y, x + WIDTH - 1, borders.vertical, 0);
+        let line = match index + 1 == SIZE {
+            true => &borders.bottom,
+            false => &borders.between,
+        };
+        screen.print(line_y + 1, x, &line.across(SIZE), 0);
+    }
+}
+
+/// Show the card with the lines below it in the middle of the screen
+fn show(card: &Card, lines: &[String], marked: bool) {
+    let mut screen = Terminal;
+    let (max_x, max_y) = screen.size();
+    screen.clear();
+    let top = (max_y - HEIGHT - 2 * lines.len() as i32) / 2;
+    render(&mut screen, ((max_x - WIDTH) / 2, top), card, marked);
+    for (index, line) in lines.iter().enumerate() {
+        screen.print(
+            top + HEIGHT + 1 + 2 * index as i32,
+            (max_x - line.chars().count() as i32) / 2,
+            line,
+            0,
+        );
+    }
+    screen.refresh();
+}
+
+/// Draw the numbers for a solved word, every number is drawn with a key. Returns the points of the
+/// prizes of the lines that were made full. A full card is swapped for a new one.
+pub fn draw_numbers(card: &mut Card, marked: bool) -> u32 {
+    let strings = strings();
+    let mut rng = rand::thread_rng();
+    let mut message = strings.bingo_draw.to_string();
+    let mut lines = 0;
+    for _ in 0..DRAWS {
+        show(card, &[message.clone()], marked);
+        ncurses::getch();
+        // The balls roll around for a moment before one comes out
+        for _ in 0..8 {
+            let rolling = rng.gen_range(1..=HIGHEST);
+            show(card, &[format!("( {:>2} )", rolling)], marked);
+            ncurses::napms(80);
+        }
+        let draw = card.draw(&mut rng);
+        lines += draw.lines;
+        message = match draw.hit {
+            true => fill(strings.bingo_hit, &[&draw.number]),
+            false => fill(strings.bingo_miss, &[&draw.number]),
+        };
+        show(
+            card,
+            &[format!("( {:>2} )", draw.number), message.clone()],
+            marked,
+        );
+        ncurses::napms(600);
+        if card.is_full() {
+            break;
+        }
+    }
+
+    let prize = lines as u32 * LINE_PRIZE;
+    let mut summary = vec![message];
+    if prize > 0 {
+        summary.push(fill(strings.bingo_line, &[&prize]));
+    }
+    if card.is_full() {
+        summary.push(strings.bingo_full.to_string());
+    }
+    summary.push(strings.go_on.to_string());
+    show(card, &summary, marked);
+    ncurses::getch();
+    if card.is_full() {
+        *card = Card::new(&mut rng);
+    }
+    prize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_lines_win_prizes() {
+        let mut card = Card::new(&mut rand::thread_rng());
+        let numbers = card.numbers.iter().flatten().copied().collect::<Vec<_>>();
+        assert!((1..numbers.len()).all(|index| !numbers[index..].contains(&numbers[index - 1])));
+        assert!((1..SIZE).all(|row| card.numbers[row][0] > card.numbers[row - 1][0]));
+
+        // The first row is full with its last number, the last number also fills the first column
+        for column in 1..SIZE {
+            assert_eq!(card.mark(card.numbers[0][column]), 0);
+        }
+        for row in 1..SIZE {
+            assert_eq!(card.mark(card.numbers[row][0]), 0);
+        }
+        assert_eq!(card.mark(card.numbers[0][0]), 2);
+        assert!(!card.is_full());
+
+        // The bowl doesn't run out before the card is full
+        let mut lines = 2;
+        while !card.is_full() {
+            lines += card.draw(&mut rand::thread_rng()).lines;
+        }
+        assert_eq!(lines, 2 * SIZE);
+    }
+}
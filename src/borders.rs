@@ -0,0 +1,156 @@
+//! The lines around the tiles of the board. Box-drawing characters are used when the locale can
+//! show them, the config file can ask for the ASCII lines on terminals that draw them badly.
+
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// Which characters the lines are drawn with
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+    /// Box-drawing characters when the locale uses UTF-8, ASCII otherwise
+    #[default]
+    Auto,
+    Unicode,
+    Ascii,
+}
+
+/// A horizontal line: its ends, the line itself and where it meets the lines between the tiles
+pub struct Line {
+    left: char,
+    horizontal: char,
+    cross: char,
+    right: char,
+}
+
+impl Line {
+    /// The character the line is drawn with between the crossings
+    pub fn horizontal(&self) -> char {
+        self.horizontal
+    }
+
+    /// The line across a row of this many tiles
+    pub fn across(&self, tiles: usize) -> String {
+        let mut line = String::from(self.left);
+        for tile in 0..tiles {
+            if tile > 0 {
+                line.push(self.cross);
+            }
+            line.extend([self.horizontal; 3]);
+        }
+        line.push(self.right);
+        line
+    }
+}
+
+pub struct Borders {
+    /// The line above the title
+    pub top: Line,
+    /// The line between the title and the first row
+    pub below_title: Line,
+    /// The lines between the rows
+    pub between: Line,
+    pub bottom: Line,
+    pub vertical: &'static str,
+}
+
+pub const ASCII: Borders = Borders {
+    top: Line {
+        left: '+',
+        horizontal: '-',
+        cross: '-',
+        right: '+',
+    },
+    below_title: Line {
+        left: '+',
+        horizontal: '-',
+        cross: '+',
+        right: '+',
+    },
+    between: Line {
+        left: '+',
+        horizontal: '-',
+        cross: '+',
+        right: '+',
+    },
+    bottom: Line {
+        left: '+',
+        horizontal: '-',
+        cross: '+',
+        right: '+',
+    },
+    vertical: "|",
+};
+
+pub const UNICODE: Borders = Borders {
+    top: Line {
+        left: '┌',
+        horizontal: '─',
+        cross: '─',
+        right: '┐',
+    },
+    below_title: Line {
+        left: '├',
+        horizontal: '─',
+        cross: '┬',
+        right: '┤',
+    },
+    between: Line {
+        left: '├',
+        horizontal: '─',
+        cross: '┼',
+        right: '┤',
+    },
+    bottom: Line {
+        left: '└',
+        horizontal: '─',
+        cross: '┴',
+        right: '┘',
+    },
+    vertical: "│",
+};
+
+static BORDERS: OnceLock<&'static Borders> = OnceLock::new();
+
+/// Draw the boards in this style from now on, only the first call counts
+pub fn use_style(style: BorderStyle) {
+    let borders = match style {
+        BorderStyle::Auto if utf8_locale() => &UNICODE,
+        BorderStyle::Auto | BorderStyle::Ascii => &ASCII,
+        BorderStyle::Unicode => &UNICODE,
+    };
+    BORDERS.get_or_init(|| borders);
+}
+
+/// The characters the boards are drawn with
+pub fn borders() -> &'static Borders {
+    BORDERS.get_or_init(|| match utf8_locale() {
+        true => &UNICODE,
+        false => &ASCII,
+    })
+}
+
+/// If the locale uses UTF-8, the first of the variables that is set decides like setlocale does
+fn utf8_locale() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_meet_the_tiles() {
+        assert_eq!(ASCII.top.across(3), "+-----------+");
+        assert_eq!(ASCII.between.across(3), "+---+---+---+");
+        assert_eq!(UNICODE.below_title.across(2), "├───┬───┤");
+        assert_eq!(UNICODE.bottom.across(2), "└───┴───┘");
+    }
+}
@@ -0,0 +1,565 @@
+//! A bot that runs games in a chat channel on IRC or Discord. Anyone in the channel starts a game
+//! with `!lingo`, after that every message that is a single word of the right length is a guess
+//! and the bot replies with the colored grid. The games are played by the headless engine, the bot
+//! passes the messages of the `protocol` module between the chat and the engine.
+
+use crate::headless::Session;
+use crate::protocol::{self, Message};
+use crate::rules::{Feedback, Pattern};
+use crate::score::Score;
+use crate::share;
+use crate::{word, WORD_LENGTH};
+use clap::ValueEnum;
+use std::collections::BTreeMap;
+#[cfg(feature = "discord")]
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Cursor, ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, Scope};
+#[cfg(feature = "discord")]
+use std::time::Duration;
+
+/// The message that starts a game
+const START_COMMAND: &str = "!lingo";
+/// The message that shows the points of the players
+const SCORES_COMMAND: &str = "!scores";
+/// The API of Discord that the bot uses
+#[cfg(feature = "discord")]
+const DISCORD_API: &str = "https://discord.com/api/v10";
+/// The time between two looks at a Discord channel for new messages
+#[cfg(feature = "discord")]
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The chat the bot plays in
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Backend {
+    /// A channel on an IRC server, the server is given with --server
+    Irc,
+    /// A Discord channel by its id, the token of the bot is read from DISCORD_TOKEN
+    Discord,
+}
+
+/// A message in the channel of the bot
+struct ChatMessage {
+    author: String,
+    text: String,
+}
+
+/// A chat channel the bot plays in
+trait Chat {
+    /// Wait for the next message in the channel, `None` when the connection was closed
+    fn receive(&mut self) -> io::Result<Option<ChatMessage>>;
+    /// Send the text to the channel, it can have more than one line
+    fn say(&mut self, text: &str) -> io::Result<()>;
+}
+
+/// The lines that are sent to the engine, the input ends when the sender is dropped
+struct Lines {
+    receiver: Receiver<String>,
+    pending: Cursor<Vec<u8>>,
+}
+
+impl Read for Lines {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.position() as usize == self.pending.get_ref().len() {
+            match self.receiver.recv() {
+                Ok(line) => self.pending = Cursor::new(format!("{}\n", line).into_bytes()),
+                Err(_) => return Ok(0),
+            }
+        }
+        self.pending.read(buf)
+    }
+}
+
+/// The output of the engine, every line that is written is passed on as a message
+struct Answers {
+    sender: Sender<Message>,
+    line: Vec<u8>,
+}
+
+impl Write for Answers {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.line.extend_from_slice(buf);
+        while let Some(end) = self.line.iter().position(|byte| *byte == b'\n') {
+            let line = self.line.drain(..=end).collect::<Vec<_>>();
+            let message = protocol::decode(&String::from_utf8_lossy(&line))?;
+            self.sender
+                .send(message)
+                .map_err(|_| io::Error::from(ErrorKind::BrokenPipe))?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// What a guess did to the game
+enum Outcome {
+    Rejected(String),
+    Played,
+    Finished { won: bool, word: String },
+}
+
+/// A game of the headless engine, played on a thread of its own
+struct Game {
+    guesses: Sender<String>,
+    answers: Receiver<Message>,
+    language: String,
+    max_guesses: u32,
+    /// The guesses so far with their colors
+    rows: Vec<(String, Pattern)>,
+}
+
+impl Game {
+    /// Start the engine on the word, it plays until the game is dropped or finished
+    fn start<'scope, 'env>(
+        scope: &'scope Scope<'scope, 'env>,
+        session: &'env Session,
+        target: &'static str,
+    ) -> io::Result<Game> {
+        let (guesses, receiver) = mpsc::channel();
+        let (sender, answers) = mpsc::channel();
+        scope.spawn(move || {
+            let mut input = BufReader::new(Lines {
+                receiver,
+                pending: Cursor::default(),
+            });
+            let mut output = Answers {
+                sender,
+                line: Vec::new(),
+            };
+            // Errors only happen when the bot stopped listening, then no one is left to tell
+            let _ = session.play(&mut input, &mut output, target, &|_| {});
+        });
+        match answers.recv() {
+            Ok(Message::Start {
+                language,
+                guesses: max_guesses,
+                ..
+            }) => Ok(Game {
+                guesses,
+                answers,
+                language,
+                max_guesses,
+                rows: Vec::new(),
+            }),
+            _ => Err(io::Error::other("The engine did not start a game")),
+        }
+    }
+
+    /// The message of the engine after a guess
+    fn answer(&self) -> io::Result<Message> {
+        self.answers
+            .recv()
+            .map_err(|_| io::Error::other("The engine stopped"))
+    }
+
+    fn guess(&mut self, guess: &str) -> io::Result<Outcome> {
+        let line = protocol::encode(&Message::Guess {
+            word: guess.to_string(),
+        });
+        self.guesses
+            .send(line)
+            .map_err(|_| io::Error::other("The engine stopped"))?;
+        match self.answer()? {
+            Message::Rejected { message } => Ok(Outcome::Rejected(message)),
+            Message::Result { pattern, .. } => {
+                self.rows.push((guess.to_string(), pattern));
+                // The engine tells the game is over right after the guess that ended it
+                let won = pattern
+                    .iter()
+                    .all(|feedback| *feedback == Feedback::Correct);
+                if !won && self.rows.len() < self.max_guesses as usize {
+                    return Ok(Outcome::Played);
+                }
+                match self.answer()? {
+                    Message::Finished { won, word, .. } => Ok(Outcome::Finished {
+                        won,
+                        word: word.unwrap_or_default(),
+                    }),
+                    _ => Err(io::Error::other("The engine did not finish the game")),
+                }
+            }
+            _ => Err(io::Error::other("The engine sent an unexpected message")),
+        }
+    }
+
+    /// The grid of the guesses so far, with the words that were guessed next to the rows
+    fn grid(&self) -> String {
+        let mut text = format!(
+            "Lingo {} {}/{}",
+            self.language,
+            self.rows.len(),
+            self.max_guesses
+        );
+        for (guess, pattern) in &self.rows {
+            text.push_str(&format!("\n{} {}", share::tiles(pattern), display(guess)));
+        }
+        text
+    }
+}
+
+/// The word as it is shown to the players
+fn display(word: &str) -> String {
+    word.chars().map(word::display).collect()
+}
+
+/// The points of the players, the best first
+fn scoreboard(points: &BTreeMap<String, u32>) -> String {
+    if points.is_empty() {
+        return "No one solved a word yet".to_string();
+    }
+    let mut players = points.iter().collect::<Vec<_>>();
+    players.sort_by(|first, second| second.1.cmp(first.1));
+    let players = players
+        .iter()
+        .map(|(player, points)| format!("{} {}", player, points))
+        .collect::<Vec<_>>();
+    format!("Points: {}", players.join(", "))
+}
+
+/// Play games in the chat until the connection closes, each on a word of `pick`. A game is
+/// started with a command, a solved word gives the player that solved it the points of the game.
+fn play(
+    chat: &mut impl Chat,
+    session: &Session,
+    pick: impl Fn() -> &'static str,
+) -> io::Result<()> {
+    let language = session.language;
+    thread::scope(|scope| {
+        let mut game: Option<Game> = None;
+        let mut points: BTreeMap<String, u32> = BTreeMap::new();
+        while let Some(message) = chat.receive()? {
+            let text = message.text.trim();
+            if text == START_COMMAND {
+                match &game {
+                    Some(_) => chat.say(&format!(
+                        "A word is being played, send a word of {} letters to guess",
+                        WORD_LENGTH
+                    ))?,
+                    None => {
+                        let started = Game::start(scope, session, pick())?;
+                        chat.say(&format!(
+                            "Find the word of {} letters in {} guesses, send a word to guess",
+                            WORD_LENGTH, started.max_guesses
+                        ))?;
+                        game = Some(started);
+                    }
+                }
+                continue;
+            }
+            if text == SCORES_COMMAND {
+                chat.say(&scoreboard(&points))?;
+                continue;
+            }
+
+            let Some(current) = &mut game else {
+                continue;
+            };
+            // Only a single word of the right length is a guess, the rest is chatter
+            let guess = language.join_digraphs(&word::compose(&text.to_lowercase()));
+            if !word::is_playable(&guess) {
+                continue;
+            }
+            match current.guess(&guess)? {
+                Outcome::Rejected(reason) => chat.say(&reason)?,
+                Outcome::Played => chat.say(&current.grid())?,
+                Outcome::Finished { won, word } => {
+                    let mut reply = current.grid();
+                    if won {
+                        let score =
+                            Score::solved(current.rows.len() as u32, current.max_guesses, None);
+                        *points.entry(message.author.clone()).or_default() += score.total();
+                        reply.push_str(&format!(
+                            "\n{} found {} for {} points",
+                            message.author,
+                            display(&word),
+                            score.total()
+                        ));
+                    } else {
+                        reply.push_str(&format!("\nThe word was {}", display(&word)));
+                    }
+                    chat.say(&reply)?;
+                    game = None;
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// A line from an IRC server split in the nickname of the sender, the command and its parameters.
+/// The last parameter starts with a colon and can have spaces.
+fn parse_line(line: &str) -> (Option<&str>, &str, Vec<&str>) {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let (prefix, rest) = match line.strip_prefix(':') {
+        Some(rest) => match rest.split_once(' ') {
+            Some((prefix, rest)) => (Some(prefix), rest),
+            None => (Some(rest), ""),
+        },
+        None => (None, line),
+    };
+    let nick = prefix.map(|prefix| prefix.split('!').next().unwrap_or(prefix));
+    let (middle, trailing) = match rest.split_once(" :") {
+        Some((middle, trailing)) => (middle, Some(trailing)),
+        None => (rest, None),
+    };
+    let mut params = middle.split_whitespace().collect::<Vec<_>>();
+    let command = match params.is_empty() {
+        true => "",
+        false => params.remove(0),
+    };
+    params.extend(trailing);
+    (nick, command, params)
+}
+
+/// A channel on an IRC server
+struct Irc {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    channel: String,
+}
+
+impl Irc {
+    /// Connect to the server like host:6667, the channel is joined when the server welcomed the
+    /// bot
+    fn connect(server: &str, nick: &str, channel: &str) -> io::Result<Irc> {
+        let writer = TcpStream::connect(server)?;
+        let mut irc = Irc {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+            channel: channel.to_string(),
+        };
+        irc.send(&format!("NICK {}", nick))?;
+        irc.send(&format!("USER {} 0 * :Lingo bot", nick))?;
+        Ok(irc)
+    }
+
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        write!(self.writer, "{}\r\n", line)?;
+        self.writer.flush()
+    }
+}
+
+impl Chat for Irc {
+    fn receive(&mut self) -> io::Result<Option<ChatMessage>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let (nick, command, params) = parse_line(&line);
+            match command {
+                "PING" => self.send(&format!("PONG :{}", params.first().unwrap_or(&"")))?,
+                "001" => self.send(&format!("JOIN {}", self.channel))?,
+                "433" => return Err(io::Error::other("The nickname is already in use")),
+                "PRIVMSG"
+                    if params
+                        .first()
+                        .is_some_and(|target| target.eq_ignore_ascii_case(&self.channel)) =>
+                {
+                    return Ok(Some(ChatMessage {
+                        author: nick.unwrap_or_default().to_string(),
+                        text: params.get(1).unwrap_or(&"").to_string(),
+                    }));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn say(&mut self, text: &str) -> io::Result<()> {
+        // A message on IRC is a single line
+        for line in text.lines() {
+            self.send(&format!("PRIVMSG {} :{}", self.channel, line))?;
+        }
+        Ok(())
+    }
+}
+
+/// A Discord channel, the bot asks for the new messages every few seconds. It needs the message
+/// content intent to see what the players write.
+#[cfg(feature = "discord")]
+struct Discord {
+    token: String,
+    channel: String,
+    /// The id of the last message that was read
+    last: u64,
+    pending: VecDeque<ChatMessage>,
+}
+
+#[cfg(feature = "discord")]
+impl Discord {
+    fn connect(channel: &str) -> io::Result<Discord> {
+        let token = std::env::var("DISCORD_TOKEN")
+            .map_err(|_| io::Error::other("Set the token of the bot in DISCORD_TOKEN"))?;
+        let mut discord = Discord {
+            token,
+            channel: channel.to_string(),
+            last: 0,
+            pending: VecDeque::new(),
+        };
+        // The messages from before the bot started aren't played
+        discord.last = discord
+            .fetch("limit=1")?
+            .iter()
+            .filter_map(Discord::id)
+            .max()
+            .unwrap_or(0);
+        Ok(discord)
+    }
+
+    fn url(&self) -> String {
+        format!("{}/channels/{}/messages", DISCORD_API, self.channel)
+    }
+
+    fn id(message: &serde_json::Value) -> Option<u64> {
+        message["id"].as_str()?.parse().ok()
+    }
+
+    fn fetch(&self, query: &str) -> io::Result<Vec<serde_json::Value>> {
+        ureq::get(&format!("{}?{}", self.url(), query))
+            .set("Authorization", &format!("Bot {}", self.token))
+            .call()
+            .map_err(|err| io::Error::other(err.to_string()))?
+            .into_json()
+    }
+}
+
+#[cfg(feature = "discord")]
+impl Chat for Discord {
+    fn receive(&mut self) -> io::Result<Option<ChatMessage>> {
+        loop {
+            if let Some(message) = self.pending.pop_front() {
+                return Ok(Some(message));
+            }
+            thread::sleep(POLL_INTERVAL);
+            let mut messages = match self.fetch(&format!("after={}&limit=50", self.last)) {
+                Ok(messages) => messages,
+                Err(err) => {
+                    eprintln!("Could not read the channel: {}", err);
+                    continue;
+                }
+            };
+            messages.sort_by_key(Discord::id);
+            for message in messages {
+                self.last = self.last.max(Discord::id(&message).unwrap_or(0));
+                // The replies of the bot and other bots aren't guesses
+                if message["author"]["bot"].as_bool() == Some(true) {
+                    continue;
+                }
+                self.pending.push_back(ChatMessage {
+                    author: message["author"]["username"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    text: message["content"].as_str().unwrap_or_default().to_string(),
+                });
+            }
+        }
+    }
+
+    fn say(&mut self, text: &str) -> io::Result<()> {
+        ureq::post(&self.url())
+            .set("Authorization", &format!("Bot {}", self.token))
+            .send_json(serde_json::json!({ "content": text }))
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Run the bot in the channel until the connection closes
+pub fn run(
+    backend: Backend,
+    server: Option<&str>,
+    channel: &str,
+    nick: &str,
+    session: &Session,
+    pick: impl Fn() -> &'static str,
+) -> io::Result<()> {
+    match backend {
+        Backend::Irc => {
+            let server = server.ok_or_else(|| io::Error::other("An IRC bot needs a --server"))?;
+            play(&mut Irc::connect(server, nick, channel)?, session, pick)
+        }
+        #[cfg(feature = "discord")]
+        Backend::Discord => play(&mut Discord::connect(channel)?, session, pick),
+        #[cfg(not(feature = "discord"))]
+        Backend::Discord => Err(io::Error::other(
+            "This build has no Discord support, build it with the discord feature",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+    use crate::language;
+
+    /// A chat with the messages written beforehand
+    struct Script {
+        messages: Vec<(&'static str, &'static str)>,
+        said: Vec<String>,
+    }
+
+    impl Chat for Script {
+        fn receive(&mut self) -> io::Result<Option<ChatMessage>> {
+            if self.messages.is_empty() {
+                return Ok(None);
+            }
+            let (author, text) = self.messages.remove(0);
+            Ok(Some(ChatMessage {
+                author: author.to_string(),
+                text: text.to_string(),
+            }))
+        }
+
+        fn say(&mut self, text: &str) -> io::Result<()> {
+            self.said.push(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn players_guess_in_the_chat() {
+        let (nick, command, params) =
+            parse_line(":ann!ann@example.com PRIVMSG #lingo :hello there\r\n");
+        assert_eq!((nick, command), (Some("ann"), "PRIVMSG"));
+        assert_eq!(params, ["#lingo", "hello there"]);
+        assert_eq!(parse_line("PING :server").2, ["server"]);
+
+        let dictionary = Dictionary::load(&["kaart", "tabak", "taken", "tafel", "stoel"]);
+        let session = Session {
+            dictionary: &dictionary,
+            language: &language::DUTCH,
+            hard: false,
+            max_guesses: 5,
+        };
+        let mut chat = Script {
+            messages: vec![
+                ("bob", "taken"),
+                ("ann", "!lingo"),
+                ("bob", "good luck everyone"),
+                ("bob", "taken"),
+                ("ann", "xxxxx"),
+                ("ann", "TABAK"),
+                ("bob", "!scores"),
+            ],
+            said: Vec::new(),
+        };
+        play(&mut chat, &session, || "tabak").unwrap();
+
+        // Guesses before the game and chatter aren't answered
+        assert_eq!(chat.said.len(), 5);
+        assert!(chat.said[1].ends_with("🟩🟩🟨⬛⬛ TAKEN"));
+        assert!(chat.said[2].contains("not in the dictionary"));
+        assert!(chat.said[3].starts_with("Lingo nl 2/5"));
+        assert!(chat.said[3].ends_with("ann found TABAK for 110 points"));
+        assert_eq!(chat.said[4], "Points: ann 110");
+    }
+}
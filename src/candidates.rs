@@ -0,0 +1,160 @@
+use crate::masks::{Constraint, LetterIndex, WordMask};
+use crate::rules::{self, Feedback, Pattern};
+use crate::WORD_LENGTH;
+use lingo_core::knowledge::Knowledge;
+use std::collections::HashMap;
+
+/// The words that could still be the answer given the feedback on the guesses so far
+#[derive(Clone)]
+pub struct CandidateSet {
+    words: Vec<&'static str>,
+    index: LetterIndex,
+    /// The masks of the words, in the same order
+    masks: Vec<WordMask>,
+}
+
+impl CandidateSet {
+    pub fn new(words: &[&'static str]) -> CandidateSet {
+        let index = LetterIndex::new(words);
+        CandidateSet {
+            words: words.to_vec(),
+            masks: words.iter().map(|word| index.mask(word)).collect(),
+            index,
+        }
+    }
+
+    pub fn words(&self) -> &[&'static str] {
+        &self.words
+    }
+
+    /// Group the candidates by the pattern the guess would get if the candidate was the answer
+    pub fn partition(&self, guess: &str) -> HashMap<Pattern, Vec<&'static str>> {
+        let mut buckets = HashMap::<Pattern, Vec<&'static str>>::new();
+        for word in self.words.iter() {
+            buckets
+                .entry(rules::score(guess, word))
+                .or_default()
+                .push(word);
+        }
+        buckets
+    }
+
+    /// The amount of candidates for each pattern the guess can get, without grouping the words
+    pub fn bucket_sizes(&self, guess: &str) -> Vec<u32> {
+        let mut sizes = vec![0; 3usize.pow(WORD_LENGTH as u32)];
+        for word in self.words.iter() {
+            let pattern = rules::score(guess, word)
+                .iter()
+                .fold(0, |index, feedback| index * 3 + *feedback as usize);
+            sizes[pattern] += 1;
+        }
+        sizes
+    }
+
+    /// Only keep the candidates that can still be the word with what the guesses revealed. The
+    /// masks rule out most of the candidates, only the rest is checked letter by letter.
+    pub fn retain(&mut self, knowledge: &Knowledge) {
+        let constraint = Constraint::from_knowledge(&self.index, knowledge);
+        let mut kept = 0;
+        for index in 0..self.words.len() {
+            if constraint.matches(&self.masks[index]) && knowledge.allows(self.words[index]) {
+                self.words.swap(kept, index);
+                self.masks.swap(kept, index);
+                kept += 1;
+            }
+        }
+        self.words.truncate(kept);
+        self.masks.truncate(kept);
+    }
+
+    /// Pick the pattern for the guess that keeps the most candidates alive and only keep those
+    /// candidates, like Absurdle does. Between buckets of the same size the one that gives away
+    /// the least is picked. Returns one of the remaining candidates, the guess scored against it
+    /// gets the picked pattern.
+    pub fn dodge(&mut self, guess: &str) -> &'static str {
+        let revealed = |pattern: &Pattern| {
+            pattern
+                .iter()
+                .map(|feedback| match feedback {
+                    Feedback::Wrong => 0,
+                    Feedback::WrongPlace => 1,
+                    Feedback::Correct => 2,
+                })
+                .sum::<u32>()
+        };
+
+        let (_, bucket) = self
+            .partition(guess)
+            .into_iter()
+            .max_by(|(pattern_a, bucket_a), (pattern_b, bucket_b)| {
+                bucket_a
+                    .len()
+                    .cmp(&bucket_b.len())
+                    .then(revealed(pattern_b).cmp(&revealed(pattern_a)))
+                    // Keep the choice deterministic when everything else is equal
+                    .then(bucket_b.cmp(bucket_a))
+            })
+            .expect("There should always be a candidate left");
+        self.masks = bucket.iter().map(|word| self.index.mask(word)).collect();
+        self.words = bucket;
+        self.words[0]
+    }
+}
+
+/// How often each letter is on each place of the words, as the part of the words that has the
+/// letter there. The most common letters of a place come first.
+pub fn position_frequencies(words: &[&str]) -> Vec<Vec<(char, f64)>> {
+    // The words are all as long, a phrase can be longer than a word
+    let places = words
+        .first()
+        .map_or(WORD_LENGTH, |word| word.chars().count());
+    (0..places)
+        .map(|place| {
+            let mut counts = HashMap::<char, usize>::new();
+            for letter in words.iter().filter_map(|word| word.chars().nth(place)) {
+                *counts.entry(letter).or_default() += 1;
+            }
+            let mut frequencies = counts
+                .into_iter()
+                .map(|(letter, count)| (letter, count as f64 / words.len() as f64))
+                .collect::<Vec<_>>();
+            frequencies.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            frequencies
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_keep_the_words_with_the_same_patterns() {
+        let words = [
+            "kaart", "tabak", "taken", "tafel", "stoel", "kapel", "staat",
+        ];
+        let mut candidates = CandidateSet::new(&words);
+        let mut knowledge = Knowledge::default();
+        for guess in ["stoel", "kaart"] {
+            knowledge.record(guess, &rules::score(guess, "tabak"));
+            candidates.retain(&knowledge);
+        }
+        let expected = words
+            .into_iter()
+            .filter(|word| {
+                ["stoel", "kaart"]
+                    .iter()
+                    .all(|guess| rules::score(guess, word) == rules::score(guess, "tabak"))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(candidates.words(), expected);
+    }
+
+    #[test]
+    fn frequencies_are_counted_for_each_place() {
+        let frequencies = position_frequencies(&["kaart", "tabak", "taken", "tafel"]);
+        assert_eq!(frequencies[0], [('t', 0.75), ('k', 0.25)]);
+        assert_eq!(frequencies[1], [('a', 1.0)]);
+        assert_eq!(frequencies[2][0], ('a', 0.25));
+    }
+}
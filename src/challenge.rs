@@ -0,0 +1,151 @@
+//! Challenge codes, to send a friend a word to play with `lingo play CODE`. The code holds the
+//! language, the length of the word and a keyed hash of the word, not the word itself. The friend's
+//! game looks up the word in its own word list by the hash, so reading the code tells nothing
+//! about the word. A salt is picked so that only one word of the list has the hash, and a check
+//! character catches typos.
+
+use crate::codes::{decode, encode, fnv};
+use crate::language::{self, Language};
+use std::fmt;
+
+/// The key of the hash, the codes are only looked up by this game
+const KEY: &str = "rust-lingo challenge";
+
+/// The bits of the index of the language in a code
+const LANGUAGE_BITS: u32 = 3;
+/// The bits of the length of the word in a code
+const LENGTH_BITS: u32 = 4;
+/// The bits of the salt of the hash in a code
+const SALT_BITS: u32 = 8;
+/// The bits of the hash of the word in a code
+const HASH_BITS: u32 = 20;
+/// The bits of the check of the rest of the code
+const CHECK_BITS: u32 = 5;
+/// The characters of a code
+const CODE_LENGTH: usize =
+    ((LANGUAGE_BITS + LENGTH_BITS + SALT_BITS + HASH_BITS + CHECK_BITS) / 5) as usize;
+
+/// The hash of the word with the salt
+fn hash(salt: u64, word: &str) -> u64 {
+    fnv(&format!("{}|{}|{}", KEY, salt, word)) & ((1 << HASH_BITS) - 1)
+}
+
+/// The check of the value of a code without the check
+fn check(value: u64) -> u64 {
+    fnv(&value.to_string()) & ((1 << CHECK_BITS) - 1)
+}
+
+/// A word to play, sent as a code
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Challenge {
+    /// The index of the language in `LANGUAGES`
+    language: usize,
+    pub length: usize,
+    salt: u64,
+    hash: u64,
+}
+
+impl Challenge {
+    /// The challenge for the word out of `words`, the words of the list with the same length
+    pub fn new(
+        word: &str,
+        language: &'static Language,
+        words: &[&str],
+    ) -> Result<Challenge, String> {
+        let language = language::LANGUAGES
+            .iter()
+            .position(|other| other.code == language.code)
+            .ok_or_else(|| format!("There are no challenges in {}", language.name))?;
+        let length = word.chars().count();
+        if length >= 1 << LENGTH_BITS {
+            return Err(format!("The word {} is too long for a challenge", word));
+        }
+        // The first salt where no other word has the same hash
+        (0..1 << SALT_BITS)
+            .map(|salt| Challenge {
+                language,
+                length,
+                salt,
+                hash: hash(salt, word),
+            })
+            .find(|challenge| {
+                words
+                    .iter()
+                    .filter(|other| hash(challenge.salt, other) == challenge.hash)
+                    .all(|other| *other == word)
+            })
+            .ok_or_else(|| format!("No challenge can be made for {}", word))
+    }
+
+    /// Read a code as it was typed, the dash and the case don't matter
+    pub fn parse(text: &str) -> Result<Challenge, String> {
+        let text = text.replace(['-', ' '], "");
+        let invalid = || format!("{} is not a challenge code", text);
+        let value = decode(&text)
+            .filter(|_| text.len() == CODE_LENGTH)
+            .ok_or_else(invalid)?;
+        let (value, checked) = (value >> CHECK_BITS, value & ((1 << CHECK_BITS) - 1));
+        if check(value) != checked {
+            return Err(invalid());
+        }
+        let field = |shift: u32, bits: u32| (value >> shift) & ((1 << bits) - 1);
+        let language = field(LENGTH_BITS + SALT_BITS + HASH_BITS, LANGUAGE_BITS) as usize;
+        if language >= language::LANGUAGES.len() {
+            return Err(invalid());
+        }
+        Ok(Challenge {
+            language,
+            length: field(SALT_BITS + HASH_BITS, LENGTH_BITS) as usize,
+            salt: field(HASH_BITS, SALT_BITS),
+            hash: field(0, HASH_BITS),
+        })
+    }
+
+    pub fn language(&self) -> &'static Language {
+        language::LANGUAGES[self.language]
+    }
+
+    /// The word of the challenge out of the words of the list
+    pub fn find(&self, words: &[&'static str]) -> Option<&'static str> {
+        words
+            .iter()
+            .find(|word| word.chars().count() == self.length && hash(self.salt, word) == self.hash)
+            .copied()
+    }
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = ((self.language as u64) << (LENGTH_BITS + SALT_BITS + HASH_BITS))
+            | ((self.length as u64) << (SALT_BITS + HASH_BITS))
+            | (self.salt << HASH_BITS)
+            | self.hash;
+        let code = encode(value << CHECK_BITS | check(value), CODE_LENGTH);
+        write!(f, "{}-{}", &code[..4], &code[4..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_find_the_word_back() {
+        let words = ["kaart", "tabak", "taken", "tafel", "stoel"];
+        let challenge = Challenge::new("tabak", &language::DUTCH, &words).unwrap();
+        let code = challenge.to_string();
+        assert!(!code.to_lowercase().contains("tabak"));
+
+        let parsed = Challenge::parse(&code.to_lowercase()).unwrap();
+        assert_eq!(parsed, challenge);
+        assert_eq!(parsed.language().code, "nl");
+        assert_eq!(parsed.length, 5);
+        assert_eq!(parsed.find(&words), Some("tabak"));
+
+        // A typo is caught by the check
+        let mut typo = code.into_bytes();
+        typo[0] = if typo[0] == b'0' { b'1' } else { b'0' };
+        assert!(Challenge::parse(&String::from_utf8(typo).unwrap()).is_err());
+        assert!(Challenge::parse("ABC").is_err());
+    }
+}
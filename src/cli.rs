@@ -0,0 +1,536 @@
+use crate::bot::Backend;
+use crate::dict::Difficulty;
+use crate::obscurity::Obscurity;
+use crate::share::Privacy;
+use crate::stats::ExportFormat;
+use crate::strategy;
+use crate::tui::Ui;
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Parser, Subcommand};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// The characters a room code has at the least, every guess at it costs a connection
+const MIN_ROOM_CODE: usize = 10;
+
+/// Play lingo in the terminal
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Keep the statistics, settings and leaderboard of this player apart from the other players
+    /// on this machine. Without it a picker is shown when there are profiles.
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Write announcements of state changes as plain text lines to this file, assistive tooling
+    /// can tail it. Use /dev/fd/N to write to an already open file descriptor.
+    #[arg(long, value_name = "PATH")]
+    pub announce: Option<PathBuf>,
+
+    /// Play with the words from this file, one word on each line or compiled with `lingo dict
+    /// compile`, instead of the bundled list. Given more than once the words of all the files are
+    /// played with.
+    #[arg(long, value_name = "PATH")]
+    pub wordlist: Vec<PathBuf>,
+
+    /// The language of the word list, for example nl or en. Recognized from the words when a word
+    /// list is given without it.
+    #[arg(long, value_name = "CODE")]
+    pub language: Option<String>,
+
+    /// Write the events of the game as json lines to this Unix socket or named pipe, for stream
+    /// overlays. The events show the colors of the guesses but never the letters.
+    #[arg(long, value_name = "PATH")]
+    pub events: Option<PathBuf>,
+
+    /// Write a debug log to this file: the keys that were read, the changes in the state of the game
+    /// and how long drawing the screen took
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Give bonus points for solving the word quickly
+    #[arg(long)]
+    pub time_bonus: bool,
+
+    /// Give every row this many seconds, like the first round of the TV show. When the time runs
+    /// out a full row is guessed and a row that isn't full is lost.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["host", "join"]
+    )]
+    pub row_timer: Option<u64>,
+
+    /// Don't check for new versions of the subscribed word packs, only use the cached versions
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Play against a word that changes after every guess to stay out of reach for as long as
+    /// possible
+    #[arg(long, conflicts_with_all = ["versus", "host", "join"])]
+    pub evil: bool,
+
+    /// Every revealed hint has to be used in the next guesses, also for the computer opponent
+    #[arg(long, conflicts_with = "boards")]
+    pub hard: bool,
+
+    /// How uncommon the word can be, for word lists with a frequency column
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Obscurity::Normal,
+        conflicts_with_all = ["host", "join"]
+    )]
+    pub obscurity: Obscurity,
+
+    /// Only pick the word from this tier of the word list, every word can still be guessed
+    #[arg(long, value_enum, conflicts_with_all = ["evil", "host", "join", "word"])]
+    pub difficulty: Option<Difficulty>,
+
+    /// Kid mode: an easy word, as many guesses as it takes, the possible words are always shown and
+    /// the messages are friendlier
+    #[arg(
+        long,
+        conflicts_with_all = ["evil", "hard", "versus", "host", "join", "boards", "difficulty", "headless"]
+    )]
+    pub kids: bool,
+
+    /// Also play with the proper nouns of the word list, like the names of cities. These start with
+    /// a capital or are tagged `name` in a column after the word.
+    #[arg(long)]
+    pub allow_proper_nouns: bool,
+
+    /// Also play with the abbreviations of the word list. These are written in capitals or with
+    /// periods, or are tagged `abbr` in a column after the word.
+    #[arg(long)]
+    pub allow_abbreviations: bool,
+
+    /// The bundled offensive words of the language can be the word of the game
+    #[arg(long, conflicts_with_all = ["host", "join"])]
+    pub allow_offensive: bool,
+
+    /// Play without a screen, reading guesses from stdin and writing the results to stdout as
+    /// json lines
+    #[arg(long, conflicts_with_all = ["evil", "versus", "host", "join", "boards"])]
+    pub headless: bool,
+
+    /// Race a computer opponent on the same word
+    #[arg(long, conflicts_with_all = ["host", "join"])]
+    pub versus: bool,
+
+    /// Guess this many words at the same time, every guess counts for all words and every extra
+    /// word gives one extra guess
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = board_counts(),
+        conflicts_with_all = ["evil", "versus", "host", "join"]
+    )]
+    pub boards: u8,
+
+    /// How the computer opponent and the solver pick their guesses
+    #[arg(long, value_enum, default_value_t)]
+    pub strategy: strategy::Builtin,
+
+    /// The seconds the computer opponent takes for each guess
+    #[arg(long, value_name = "SECONDS", default_value_t = 12)]
+    pub versus_delay: u64,
+
+    /// Host a network game and wait for another player to join
+    #[arg(long, group = "network", conflicts_with = "join")]
+    pub host: bool,
+
+    /// Play the hosted game together on one board instead of racing, the players take turns on
+    /// the rows. The player that joins follows the host.
+    #[arg(long, requires = "host")]
+    pub coop: bool,
+
+    /// The port to host the network game on
+    #[arg(long, default_value_t = 4242)]
+    pub port: u16,
+
+    /// Join a network game hosted at this address, for example 192.168.1.2:4242
+    #[arg(long, group = "network", value_name = "ADDRESS")]
+    pub join: Option<String>,
+
+    /// Encrypt the network game end-to-end with a key agreed on with this code, both players need
+    /// the same code. A relay or forwarded port in between only sees opaque messages, and has to
+    /// guess the code to pose as one of the players.
+    #[arg(long, value_name = "CODE", requires = "network", value_parser = room_code)]
+    pub room: Option<String>,
+
+    /// Practice on this word. Keep in mind that the word ends up in the shell history.
+    #[arg(long, conflicts_with_all = ["evil", "host", "join", "boards", "starts_with", "contains"])]
+    pub word: Option<String>,
+
+    /// Practice on a word that starts with these letters
+    #[arg(long, value_name = "LETTERS", conflicts_with_all = ["evil", "host", "join"])]
+    pub starts_with: Option<String>,
+
+    /// Practice on a word that contains these letters somewhere, in any order
+    #[arg(long, value_name = "LETTERS", conflicts_with_all = ["evil", "host", "join"])]
+    pub contains: Option<String>,
+
+    /// Record the game to this file, watch it back with `lingo replay`
+    #[arg(long, value_name = "PATH", conflicts_with = "boards")]
+    pub record: Option<PathBuf>,
+
+    /// Look up the definitions of the words in this file after the game, a word and its
+    /// definition separated by a tab on each line
+    #[arg(long, value_name = "PATH")]
+    pub definitions: Option<PathBuf>,
+
+    /// Also show the definitions of the guessed words after the game, not only the hidden words
+    #[arg(long)]
+    pub define_guesses: bool,
+
+    /// Review the guesses after the game, each guess is compared with the best guess there was
+    #[arg(long)]
+    pub review: bool,
+
+    /// Play with a screen reader: the feedback on every guess is also written out below the board,
+    /// and marks around the letters tell it without the colors
+    #[arg(long)]
+    pub a11y: bool,
+
+    /// Zen mode: play without the panel of possible words and the colors on the keyboard. Tab
+    /// turns it on and off during a game, that choice is kept for the next games.
+    #[arg(long)]
+    pub no_assist: bool,
+
+    /// Learning mode: show how often the letters are on each place in the possible words, above
+    /// the words in the panel
+    #[arg(long, conflicts_with = "no_assist")]
+    pub learn: bool,
+
+    /// Phrase mode: play with the phrases of this file instead of words, two short words on each
+    /// line like "ik ga" or "het huis". The space is a fixed tile, the phrases with the space on
+    /// the same tile are the guesses.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["evil", "versus", "host", "join", "boards", "kids", "difficulty", "headless", "word", "starts_with", "contains"]
+    )]
+    pub phrases: Option<PathBuf>,
+
+    /// Suggest the first guess that leaves the fewest words on average at the start of a game
+    #[arg(long)]
+    pub suggest_opener: bool,
+
+    /// Run this Lua script with hooks into the game, after the scripts from the config file. Needs
+    /// a build with the scripting feature.
+    #[arg(long, value_name = "PATH")]
+    pub script: Vec<PathBuf>,
+
+    /// The interface the game is shown in, ratatui plays games on one board without an opponent
+    #[arg(long, value_enum, default_value_t)]
+    pub ui: Ui,
+
+    /// Print the result in a form that can be shared after the game
+    #[arg(long)]
+    pub share: bool,
+
+    /// How much of the game is shared, overrides the share_privacy from the config file
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    pub share_privacy: Option<Privacy>,
+}
+
+impl Cli {
+    /// If the word is given or picked from a filtered part of the word list
+    pub fn is_practice(&self) -> bool {
+        self.word.is_some() || self.starts_with.is_some() || self.contains.is_some()
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show the best scores and fastest solves
+    Leaderboard,
+    /// Work with the statistics of the games
+    Stats {
+        #[command(subcommand)]
+        command: StatsCommand,
+    },
+    /// Pick a color theme, with a preview of how it looks with color blindness
+    Theme,
+    /// Practice on the kinds of words that took the most guesses in earlier games
+    Drill,
+    /// Put the scrambled letters of a word in the right order, with a few attempts for each word
+    Anagram,
+    /// Guess two words that cross each other on a shared letter, solving one gives the shared
+    /// letter away in the other
+    Crossword,
+    /// Find a sum like 12+34 instead of a word, every guess has to be a sum that makes the same
+    /// number
+    Math,
+    /// Browse the finished games, with their boards and a review of the guesses
+    History,
+    /// Draw the emoji grid of a shared Wordle or Lingo result and work out the guesses behind it
+    ImportShare {
+        /// The file with the shared result, it is pasted on stdin when no file is given
+        file: Option<PathBuf>,
+        /// The answer of the puzzle
+        #[arg(long, conflicts_with = "answers")]
+        answer: Option<String>,
+        /// The daily answers, one on each line in the order of the puzzles. The answer is picked
+        /// with the number of the puzzle in the shared result.
+        #[arg(long, value_name = "PATH")]
+        answers: Option<PathBuf>,
+    },
+    /// Watch a recorded game
+    Replay {
+        /// The replay file
+        file: PathBuf,
+    },
+    /// Tools for word lists
+    Dict {
+        #[command(subcommand)]
+        command: DictCommand,
+    },
+    /// Play as two teams on one machine like the TV show: a solved word scores and draws balls for
+    /// bonus points, a word that isn't solved goes to the other team with one more letter
+    Team {
+        /// The amount of words, the teams start a word in turns
+        #[arg(long, default_value_t = 6, value_parser = clap::value_parser!(u8).range(1..=20))]
+        rounds: u8,
+    },
+    /// Play words one after the other until one isn't solved, every solved word draws numbers for a
+    /// bingo card that wins prizes for full rows and columns
+    Endless,
+    /// Play the puzzle of the week, its words are played back to back and their points are added
+    /// up. Everyone with the same word list gets the same words in the same week.
+    Weekly {
+        /// The amount of words of the week
+        #[arg(long, default_value_t = 7, value_parser = clap::value_parser!(u8).range(1..=20))]
+        words: u8,
+        /// Play the words of an earlier week, like 2026-W42
+        #[arg(long)]
+        week: Option<String>,
+    },
+    /// Make a code to send a friend, they play the same word with `lingo play CODE`
+    Challenge {
+        /// The word to challenge with, a random word when it isn't given. Keep in mind that the
+        /// word ends up in the shell history.
+        word: Option<String>,
+    },
+    /// Play the word of a challenge code from a friend
+    Play {
+        /// The challenge code
+        code: String,
+    },
+    /// Show how the solver finds a word, or measure how it does on every word with --bench
+    Solve {
+        /// The word to find
+        #[arg(required_unless_present = "bench")]
+        word: Option<String>,
+        /// Let the solver find every word and report the average guesses, the words it failed and
+        /// the worst cases
+        #[arg(long, conflicts_with = "word")]
+        bench: bool,
+    },
+    /// Let the solver play demo games on random words until a key is pressed, like an arcade
+    /// cabinet nobody is playing on
+    Demo,
+    /// Let the solver play many games and report how many it won and in how many guesses, to see
+    /// how the rules change the difficulty
+    Simulate {
+        /// The amount of games to play
+        #[arg(long, value_name = "N", default_value_t = 1000)]
+        games: usize,
+        /// How the solver picks its guesses
+        #[arg(long, value_enum, default_value_t)]
+        strategy: strategy::Builtin,
+        /// The guesses of a game, by default the guesses of a game with this many boards
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        guesses: Option<u32>,
+        /// Guess this many words at the same time
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 1,
+            value_parser = board_counts()
+        )]
+        boards: u8,
+        /// Every revealed hint has to be used in the next guesses
+        #[arg(long, conflicts_with = "boards")]
+        hard: bool,
+        /// Play against a word that dodges the guesses
+        #[arg(long, conflicts_with = "boards")]
+        evil: bool,
+        /// Pick the words with this seed, so the same words are played each time
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Host headless games for any number of clients over TCP, with the json lines protocol
+    Serve {
+        /// The port to listen on
+        #[arg(long, default_value_t = 4242)]
+        port: u16,
+        /// Let spectators watch the games on this port with `lingo watch`, they see the guesses
+        /// but not the words
+        #[arg(long, value_name = "PORT")]
+        watch_port: Option<u16>,
+    },
+    /// Run games in a chat channel: `!lingo` starts a game, every message with a word is a guess
+    /// and the bot replies with the colored grid
+    Bot {
+        #[arg(long, value_enum)]
+        backend: Backend,
+        /// The channel to play in, like #lingo on IRC or the id of a Discord channel
+        #[arg(long)]
+        channel: String,
+        /// The IRC server, like irc.libera.chat:6667
+        #[arg(long, required_if_eq("backend", "irc"))]
+        server: Option<String>,
+        /// The nickname of the bot on IRC
+        #[arg(long, default_value = "lingo-bot")]
+        nick: String,
+    },
+    /// Serve the engine over HTTP for web and mobile frontends, with POST /game,
+    /// POST /game/{id}/guess and GET /game/{id}. Needs a build with the http feature.
+    Http {
+        /// The port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Watch the games of a server that lets spectators in, like 192.168.1.2:4243
+    Watch {
+        /// The address and spectator port of the server
+        address: String,
+    },
+    /// Analyze the word list
+    Analyze {
+        #[command(subcommand)]
+        command: AnalyzeCommand,
+    },
+    /// Play the same words as a group in the same room, every player on their own machine
+    Group {
+        #[command(subcommand)]
+        command: GroupCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DictCommand {
+    /// Report duplicates, words of the wrong length, invalid characters and encoding problems.
+    /// Exits with an error when any are found.
+    Check {
+        /// The word list, one word on each line
+        file: PathBuf,
+        /// Print the report as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write the playable words of a list lowercased, sorted and without duplicates
+    Normalize {
+        /// The word list to read
+        input: PathBuf,
+        /// The file to write the normalized list to, it can be the same file
+        output: PathBuf,
+        /// Remove the accents from the letters
+        #[arg(long)]
+        strip_accents: bool,
+    },
+    /// Expand a Hunspell dictionary into a word list
+    ImportHunspell {
+        /// The .aff file with the affix rules
+        aff: PathBuf,
+        /// The .dic file with the stems
+        dic: PathBuf,
+        /// The file to write the word list to
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Only keep the words of this many letters
+        #[arg(long, default_value_t = crate::WORD_LENGTH)]
+        length: usize,
+    },
+    /// Compile word lists into a binary list that loads faster, play with it with --wordlist
+    Compile {
+        /// The word lists to compile
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// The file to write the compiled list to
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Combine word lists into one normalized list
+    Merge {
+        /// The word lists, the number of new words each one adds is shown
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// The file to write the combined list to
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StatsCommand {
+    /// Write the history of the games and the totals, for spreadsheets and notebooks
+    Export {
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+        /// The file to write to instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AnalyzeCommand {
+    /// Rank the first guesses by the amount of words they are expected to leave
+    Openers {
+        /// The amount of first guesses to show
+        #[arg(long, value_name = "N", default_value_t = 20)]
+        top: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GroupCommand {
+    /// Deal a new sequence of words and show the code the players join with
+    Host {
+        /// The amount of words in the sequence
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u8).range(1..=15))]
+        rounds: u8,
+    },
+    /// Play the words of a group, the result code to hand to the host is printed afterwards
+    Join {
+        /// The join code shown by the host
+        code: String,
+        /// The name the result is ranked under, letters and digits only
+        name: String,
+    },
+    /// Check the result codes of the players for typos and rank them
+    Results {
+        /// The join code of the group
+        code: String,
+        /// The result codes of the players
+        #[arg(required = true)]
+        results: Vec<String>,
+    },
+}
+
+/// The code of --room, it has to be long and varied enough that it can't be guessed
+fn room_code(code: &str) -> Result<String, String> {
+    let length = code.chars().count();
+    let different = code.chars().collect::<HashSet<_>>().len();
+    match length >= MIN_ROOM_CODE && different >= MIN_ROOM_CODE / 2 {
+        true => Ok(code.to_string()),
+        false => Err(format!(
+            "a room code has at least {} characters and {} different ones",
+            MIN_ROOM_CODE,
+            MIN_ROOM_CODE / 2
+        )),
+    }
+}
+
+/// The number of boards of --boards, a game guesses one, two or four words at the same time
+fn board_counts() -> impl TypedValueParser<Value = u8> {
+    PossibleValuesParser::new(["1", "2", "4"]).map(|boards| boards.parse().unwrap())
+}
@@ -0,0 +1,33 @@
+//! The short codes players type over from each other, in Crockford's base 32. The values in the
+//! codes are hashed with FNV-1a, which is the same on every machine.
+
+/// The characters of the codes, Crockford's base 32 leaves out the letters that look like digits
+const CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// FNV-1a, the codes have to be the same on every machine and every version of the game
+pub fn fnv(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The value in base 32, `length` characters long
+pub fn encode(value: u64, length: usize) -> String {
+    (0..length)
+        .rev()
+        .map(|index| CODE_ALPHABET[(value >> (5 * index)) as usize & 31] as char)
+        .collect()
+}
+
+/// The value of a base 32 text, the letters that look like digits are read as those digits
+pub fn decode(text: &str) -> Option<u64> {
+    text.chars().try_fold(0, |value, chr| {
+        let chr = match chr.to_ascii_uppercase() {
+            'I' | 'L' => '1',
+            'O' => '0',
+            chr => chr,
+        };
+        let digit = CODE_ALPHABET.iter().position(|code| *code as char == chr)?;
+        Some(value << 5 | digit as u64)
+    })
+}
@@ -0,0 +1,338 @@
+//! Compiled word lists. A large text list is composed, filtered and sorted every time it is loaded,
+//! `lingo dict compile` does that once and writes the result in a binary file that is mapped into
+//! memory when the game starts. Only the words of the length that is played are copied out of it,
+//! as they are.
+//!
+//! The file starts with a header, followed by the words of each length:
+//!
+//! ```text
+//! magic       8 bytes   LINGODIC
+//! version     u32
+//! language    u8 length and the ISO 639-1 code
+//! buckets     u32 amount, then for each bucket:
+//!   letters   u32       the length of the words in letters
+//!   words     u32       the amount of words
+//!   offset    u64       where the words start in the file
+//!   size      u64       the bytes of the words
+//! ```
+//!
+//! The words of a bucket are sorted lines of text, `word` or `word<TAB>count`, composed and with
+//! the letter pairs of the language joined, exactly as the game plays them. All numbers are little
+//! endian.
+
+use crate::dict::Entry;
+use crate::language::{self, Language};
+use crate::word;
+use itertools::Itertools;
+use memmap2::Mmap;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"LINGODIC";
+const VERSION: u32 = 1;
+
+/// A word list that was read for the game
+pub struct WordList {
+    /// The lines of the words of the list
+    pub text: &'static str,
+    /// The language a compiled list was compiled for
+    pub language: Option<&'static Language>,
+}
+
+/// Reads the numbers of the header one after the other
+struct Header<'a> {
+    bytes: &'a [u8],
+}
+
+impl Header<'_> {
+    fn take(&mut self, size: usize) -> io::Result<&[u8]> {
+        if self.bytes.len() < size {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "the compiled word list is cut short",
+            ));
+        }
+        let (taken, rest) = self.bytes.split_at(size);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+/// If the file is a compiled word list
+fn is_compiled(path: &Path) -> io::Result<bool> {
+    let mut magic = [0; MAGIC.len()];
+    match File::open(path)?.read_exact(&mut magic) {
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err),
+        Ok(()) => Ok(&magic == MAGIC),
+    }
+}
+
+/// The bytes of the words of `letters` letters of a compiled word list in `bytes`
+fn bucket(bytes: &[u8], letters: usize) -> io::Result<(&[u8], Option<&'static Language>)> {
+    let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, message.to_string());
+    let mut header = Header {
+        bytes: bytes.get(MAGIC.len()..).unwrap_or_default(),
+    };
+    if header.u32()? != VERSION {
+        return Err(invalid(
+            "the word list was compiled by another version, compile it again",
+        ));
+    }
+    let code_length = header.take(1)?[0] as usize;
+    let code = std::str::from_utf8(header.take(code_length)?)
+        .map_err(|_| invalid("the language of the compiled word list is not valid"))?;
+    let language = language::by_code(code);
+
+    for _ in 0..header.u32()? {
+        let bucket_letters = header.u32()? as usize;
+        let _words = header.u32()?;
+        let offset = header.u64()? as usize;
+        let size = header.u64()? as usize;
+        if bucket_letters != letters {
+            continue;
+        }
+        let text = offset
+            .checked_add(size)
+            .and_then(|end| bytes.get(offset..end))
+            .ok_or_else(|| invalid("the compiled word list is cut short"))?;
+        return Ok((text, language));
+    }
+    Ok((&[], language))
+}
+
+/// Read a word list for the game, the words of `letters` letters of a compiled list or the
+/// composed text of a text list. Both are kept for the rest of the game.
+pub fn read_word_list(path: &Path, letters: usize) -> io::Result<WordList> {
+    if !is_compiled(path)? {
+        let content = fs::read_to_string(path)?;
+        return Ok(WordList {
+            text: Box::leak(word::compose(&content).into_boxed_str()),
+            language: None,
+        });
+    }
+
+    // SAFETY: the map only lives while the header is read and the words are copied out of it, the
+    // game plays with the copy. The file must not be truncated in that moment, that ends the game
+    // with SIGBUS. A file that is rewritten in that moment can give a copy with other bytes, so the
+    // copy is checked to be UTF-8 and not the map.
+    let map = unsafe { Mmap::map(&File::open(path)?)? };
+    let (bytes, language) = bucket(&map, letters)?;
+    let text = String::from_utf8(bytes.to_vec()).map_err(|_| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            "the words of the compiled word list are not valid UTF-8",
+        )
+    })?;
+    Ok(WordList {
+        text: Box::leak(text.into_boxed_str()),
+        language,
+    })
+}
+
+/// The words of all lengths in the text lists `inputs`, as the game plays them, with their counts
+fn collect_entries(
+    inputs: &[PathBuf],
+    language: &Language,
+    allowed: &[word::Kind],
+) -> io::Result<BTreeMap<usize, Vec<Entry>>> {
+    let mut seen = HashSet::new();
+    let mut buckets = BTreeMap::<usize, Vec<_>>::new();
+    for input in inputs {
+        let content = fs::read(input)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", input.display(), err)))?;
+        let content = word::admit(&String::from_utf8_lossy(&content), allowed);
+        let content = language.join_digraphs(&word::compose(&content));
+        for line in content.lines() {
+            if word::kind(line) != word::Kind::Word {
+                continue;
+            }
+            let (entry, count) = word::split_entry(line);
+            let playable = !entry.is_empty()
+                && entry
+                    .chars()
+                    .all(|chr| chr.is_alphabetic() && chr.is_lowercase());
+            // The first count of a word is kept, like the game does
+            if playable && seen.insert(entry.to_string()) {
+                buckets.entry(word::length(entry)).or_default().push(Entry {
+                    word: entry.to_string(),
+                    count: count.and_then(|count| count.parse().ok()),
+                });
+            }
+        }
+    }
+    for entries in buckets.values_mut() {
+        entries.sort_by(|a, b| a.word.cmp(&b.word));
+    }
+    Ok(buckets)
+}
+
+/// Compile the text word lists `inputs` into one compiled list in `output`. Without a language it
+/// is recognized from the words.
+pub fn compile(
+    inputs: &[PathBuf],
+    output: &Path,
+    language: Option<&'static Language>,
+    allowed: &[word::Kind],
+) -> io::Result<()> {
+    let language = match language {
+        Some(language) => language,
+        None => {
+            let mut content = String::new();
+            for input in inputs {
+                content.push_str(&String::from_utf8_lossy(&fs::read(input)?));
+                content.push('\n');
+            }
+            let words = content
+                .lines()
+                .map(|line| word::split_entry(line).0)
+                .collect_vec();
+            language::detect(&words)
+        }
+    };
+    let buckets = collect_entries(inputs, language, allowed)?;
+
+    let texts = buckets
+        .iter()
+        .map(|(letters, entries)| {
+            let text = entries.iter().join("\n");
+            (*letters, entries.len(), text)
+        })
+        .collect_vec();
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.push(language.code.len() as u8);
+    bytes.extend_from_slice(language.code.as_bytes());
+    bytes.extend_from_slice(&(texts.len() as u32).to_le_bytes());
+    // The words follow right after the table of the buckets
+    let mut offset = bytes.len() + texts.len() * 24;
+    for (letters, words, text) in texts.iter() {
+        bytes.extend_from_slice(&(*letters as u32).to_le_bytes());
+        bytes.extend_from_slice(&(*words as u32).to_le_bytes());
+        bytes.extend_from_slice(&(offset as u64).to_le_bytes());
+        bytes.extend_from_slice(&(text.len() as u64).to_le_bytes());
+        offset += text.len();
+    }
+    for (_, _, text) in texts.iter() {
+        bytes.extend_from_slice(text.as_bytes());
+    }
+    fs::write(output, bytes)?;
+
+    println!(
+        "Compiled {} words in {} to {}",
+        texts.iter().map(|(_, words, _)| words).sum::<usize>(),
+        language.name,
+        output.display()
+    );
+    for (letters, words, _) in texts.iter() {
+        println!("  {} letters: {} words", letters, words);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file in the temporary directory that is removed again at the end of the test
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> TempFile {
+            TempFile(std::env::temp_dir().join(format!(
+                "lingo-compiled-{}-{}",
+                std::process::id(),
+                name
+            )))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    /// A compiled list of a few Dutch words, with the bytes of the file
+    fn compiled(name: &str) -> (TempFile, Vec<u8>) {
+        let input = TempFile::new(&format!("{}.txt", name));
+        fs::write(&input.0, "tafel\t12\nkaart\nstoel\nijzer\nboom\nKaart\n").unwrap();
+        let output = TempFile::new(&format!("{}.dic", name));
+        compile(
+            std::slice::from_ref(&input.0),
+            &output.0,
+            language::by_code("nl"),
+            &[word::Kind::Word],
+        )
+        .unwrap();
+        let bytes = fs::read(&output.0).unwrap();
+        (output, bytes)
+    }
+
+    fn error(path: &Path) -> String {
+        match read_word_list(path, 5) {
+            Ok(_) => panic!("{} was read", path.display()),
+            Err(err) => {
+                assert_eq!(err.kind(), ErrorKind::InvalidData);
+                err.to_string()
+            }
+        }
+    }
+
+    #[test]
+    fn compiled_lists_are_read_back() {
+        let (output, _) = compiled("round-trip");
+        let list = read_word_list(&output.0, 5).unwrap();
+        assert_eq!(list.language.map(|language| language.code), Some("nl"));
+        assert_eq!(list.text, "kaart\nstoel\ntafel\t12");
+        // The letter pair is one letter, so ijzer has four letters
+        assert_eq!(read_word_list(&output.0, 4).unwrap().text, "boom\nĳzer");
+        assert_eq!(read_word_list(&output.0, 6).unwrap().text, "");
+    }
+
+    #[test]
+    fn truncated_lists_are_refused() {
+        let (output, bytes) = compiled("truncated");
+        fs::write(&output.0, &bytes[..bytes.len() - 3]).unwrap();
+        assert!(error(&output.0).contains("cut short"));
+        fs::write(&output.0, &bytes[..MAGIC.len() + 6]).unwrap();
+        assert!(error(&output.0).contains("cut short"));
+    }
+
+    #[test]
+    fn corrupt_headers_are_refused() {
+        let (output, bytes) = compiled("corrupt");
+        let buckets = MAGIC.len() + 4 + 1 + 2;
+        // The offset of the second bucket, the one of five letters, points past the end
+        let mut corrupt = bytes.clone();
+        let offset = buckets + 4 + 24 + 8;
+        corrupt[offset..offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&output.0, &corrupt).unwrap();
+        assert!(error(&output.0).contains("cut short"));
+        // The language code is not text
+        let mut corrupt = bytes;
+        corrupt[buckets - 2] = 0xff;
+        fs::write(&output.0, &corrupt).unwrap();
+        assert!(error(&output.0).contains("language"));
+    }
+
+    #[test]
+    fn lists_of_another_version_are_refused() {
+        let (output, mut bytes) = compiled("version");
+        bytes[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        fs::write(&output.0, &bytes).unwrap();
+        assert!(error(&output.0).contains("another version"));
+    }
+}
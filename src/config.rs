@@ -0,0 +1,57 @@
+use crate::borders::BorderStyle;
+use crate::graphics::TileGraphics;
+use crate::keymap::Keymap;
+use crate::packs::PackSubscription;
+use crate::share::{Privacy, Webhook};
+use crate::sound::SoundConfig;
+use crate::storage;
+use serde::Deserialize;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// The settings from the config file, everything is optional
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The remote word packs the player subscribed to
+    pub packs: Vec<PackSubscription>,
+    /// How much of a game is shared by default
+    pub share_privacy: Privacy,
+    /// The webhooks the result of each game is posted to
+    pub webhooks: Vec<Webhook>,
+    /// A command that prints the definition of the word added to it, for example
+    /// `["dict", "-d", "fd-nld-eng"]`
+    pub definition_command: Vec<String>,
+    /// Don't accept the offensive words as guesses either, they are never the word of the game
+    pub reject_offensive_guesses: bool,
+    /// Lua scripts with hooks into the game, see the `script` module for the hooks
+    pub scripts: Vec<PathBuf>,
+    /// The lines of the board: `auto`, `unicode` or `ascii`
+    pub borders: BorderStyle,
+    /// The verified tiles as images: `off`, `auto`, `kitty` or `sixel`
+    pub tile_graphics: TileGraphics,
+    /// The keys of the actions during a game, see the `keymap` module
+    pub keys: Keymap,
+    /// The sound effects, see the `sound` module
+    pub sounds: SoundConfig,
+}
+
+impl Config {
+    /// Load the config file, if there is no config file the defaults are used
+    pub fn load() -> io::Result<Config> {
+        let path = storage::config_path(CONFIG_FILE)?;
+        match fs::read_to_string(&path) {
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err),
+            Ok(content) => toml::from_str(&content).map_err(|err| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), err),
+                )
+            }),
+        }
+    }
+}
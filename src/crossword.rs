@@ -0,0 +1,333 @@
+//! The crossword mode: two hidden words cross each other on a shared letter, one across and one
+//! down. Each word is guessed on its own board and solving one of them gives the shared letter
+//! away in the other. The grid next to the boards shows the letters that are known.
+
+use crate::dictionary::Dictionary;
+use crate::editor::GuessEditor;
+use crate::engine::{Event, Game, GameState, Transition};
+use crate::error::Error;
+use crate::input::{self, InputEvent, Poller};
+use crate::keymap::{Action, Bindings};
+use crate::language::Language;
+use crate::messages::Notice;
+use crate::pause;
+use crate::quick_settings::QuickSettings;
+use crate::render::Renderer;
+use crate::rules::{self, Feedback};
+use crate::script::Scripts;
+use crate::strings::{fill, strings};
+use crate::theme::Theme;
+use crate::word;
+use crate::{
+    init_ncurses, key_feedback, render_game, Board, BoardState, GuessedLetter,
+    COLOR_PAIR_CORRECT_TILE, GUESSES, WORD_LENGTH,
+};
+use itertools::Itertools;
+use lingo_core::knowledge::Knowledge;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// The width of the grid on the screen, a cell is three columns with one between the cells
+pub const WIDTH: i32 = 4 * WORD_LENGTH as i32 - 1;
+/// The renders a rejected guess stays on the screen
+const REJECTION_RENDERS: u32 = 3;
+/// The renders the given away letter is told for
+const REVEAL_RENDERS: u32 = 6;
+
+/// The words of a played crossword and which of them were solved
+pub type Outcome = ([&'static str; 2], [bool; 2]);
+
+/// The word across and the word down
+pub struct Crossword {
+    words: [&'static str; 2],
+    /// The place of the shared letter in each word
+    crossing: [usize; 2],
+    knowledge: [Knowledge; 2],
+}
+
+/// The letters of the crossword that are known, as they are drawn
+#[derive(Clone, Copy, Default)]
+pub struct Grid {
+    letters: [[Option<char>; WORD_LENGTH]; 2],
+    crossing: [usize; 2],
+    /// The word that is being guessed, the other board has no cursor
+    pub active: usize,
+}
+
+impl Crossword {
+    /// Two different words that share a letter, None when no two words share a letter
+    pub fn pick(answers: &[&'static str], rng: &mut impl Rng) -> Option<Crossword> {
+        let mut across_words = answers.to_vec();
+        across_words.shuffle(rng);
+        for across in across_words {
+            let crossings = answers
+                .iter()
+                .filter(|down| **down != across)
+                .flat_map(|down| {
+                    (0..WORD_LENGTH)
+                        .cartesian_product(0..WORD_LENGTH)
+                        .filter(|(place, down_place)| {
+                            word::letter(across, *place) == word::letter(down, *down_place)
+                        })
+                        .map(|(place, down_place)| (*down, [place, down_place]))
+                })
+                .collect_vec();
+            if let Some((down, crossing)) = crossings.choose(rng) {
+                return Some(Crossword {
+                    words: [across, down],
+                    crossing: *crossing,
+                    knowledge: Default::default(),
+                });
+            }
+        }
+        None
+    }
+
+    /// Add the feedback on a guess at one of the words. Solving the word gives the shared letter
+    /// away in the other word, returns that letter.
+    pub fn record(&mut self, track: usize, guess: &str, pattern: &[Feedback]) -> Option<char> {
+        self.knowledge[track].record(guess, pattern);
+        let other = 1 - track;
+        let shared = word::letter(self.words[other], self.crossing[other])?;
+        if guess != self.words[track]
+            || self.knowledge[other]
+                .confirmed(self.crossing[other])
+                .is_some()
+        {
+            return None;
+        }
+        self.knowledge[other].reveal(self.crossing[other], shared);
+        Some(shared)
+    }
+
+    /// The letters that are known, with the word that is being guessed
+    pub fn grid(&self, active: usize) -> Grid {
+        Grid {
+            letters: [0, 1]
+                .map(|track| std::array::from_fn(|place| self.knowledge[track].confirmed(place))),
+            crossing: self.crossing,
+            active,
+        }
+    }
+}
+
+/// The title of the board of a word, the word that is being guessed is marked
+pub fn title(grid: &Grid, track: usize) -> String {
+    let name = [strings().across_title, strings().down_title][track];
+    match grid.active == track {
+        true => format!("> {} <", name),
+        false => name.to_string(),
+    }
+}
+
+/// Draw the grid with the title above it, the word across goes through the shared letter of the
+/// word down
+pub fn render(screen: &mut impl Renderer, (x, y): (i32, i32), grid: &Grid) {
+    let title = strings().crossword_title;
+    screen.print(
+        y + 1,
+        x + (WIDTH - title.chars().count() as i32) / 2,
+        title,
+        0,
+    );
+    let [across_place, down_place] = grid.crossing;
+    for row in 0..WORD_LENGTH {
+        for column in 0..WORD_LENGTH {
+            let letter = match (row == down_place, column == across_place) {
+                (false, false) => continue,
+                (true, false) => grid.letters[0][column],
+                (false, true) => grid.letters[1][row],
+                (true, true) => grid.letters[0][column].or(grid.letters[1][row]),
+            };
+            let (text, attribute) = match letter {
+                Some(letter) => (
+                    format!(" {} ", word::display(letter)),
+                    ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_CORRECT_TILE),
+                ),
+                None => ("[ ]".to_string(), 0),
+            };
+            screen.print(
+                y + 3 + 2 * row as i32,
+                x + 4 * column as i32,
+                &text,
+                attribute,
+            );
+        }
+    }
+}
+
+/// Play a crossword, returns the words and which of them were solved
+pub fn run(
+    dictionary: &Dictionary,
+    answers: &[&'static str],
+    alphabet: &[char],
+    language: &Language,
+    keys: &'static Bindings,
+    theme: &'static Theme,
+) -> Result<Option<Outcome>, Error> {
+    let Some(mut crossword) = Crossword::pick(answers, &mut rand::thread_rng()) else {
+        eprintln!("There are no two words that share a letter");
+        return Ok(None);
+    };
+    let _window_ender = init_ncurses(theme);
+    let poller = Poller::new(keys, -1);
+    let mut board_state = BoardState {
+        boards: vec![Board::new(GUESSES), Board::new(GUESSES)],
+        keyboard: alphabet.to_vec(),
+        score: Some(fill(
+            strings().crossword_switch,
+            &[&keys.name(Action::TogglePanel)],
+        )),
+        crossword: Some(crossword.grid(0)),
+        ..Default::default()
+    };
+    // Each word is a game of its own
+    let scripts = Scripts::default();
+    let mut games = crossword.words.map(|word| {
+        Game::new(
+            dictionary,
+            &scripts,
+            alphabet,
+            vec![word],
+            GUESSES,
+            None,
+            false,
+        )
+    });
+    // There are no possible words to show, only the theme can be changed
+    let mut quick = QuickSettings {
+        theme,
+        suggestions: None,
+    };
+    let mut editor = GuessEditor::default();
+    let mut track = 0;
+    // The pause menu opened with Ctrl-C asks to quit right away
+    let mut quitting = false;
+    // A word is done when it is solved or its board is full
+    let done = |game: &Game| matches!(game.state(), GameState::Won | GameState::Lost);
+    loop {
+        let row = games[track].guesses() as usize;
+        match games[track].state() {
+            GameState::Typing => {
+                let text = editor.text();
+                for (index, letter) in board_state.boards[track].rows[row].iter_mut().enumerate() {
+                    *letter = match word::letter(&text, index) {
+                        None => GuessedLetter::NoLetter,
+                        Some(chr) => GuessedLetter::Letter(chr),
+                    };
+                }
+                board_state.cursor = editor.cursor().map(|tile| (row, tile));
+                board_state.key_feedback = key_feedback(&crossword.knowledge[track]);
+                board_state.crossword = Some(crossword.grid(track));
+                render_game(&board_state);
+
+                match poller.poll() {
+                    event @ (InputEvent::Quit | InputEvent::Interrupt) => {
+                        quitting = event == InputEvent::Interrupt;
+                        games[track].handle_event(Event::Pause)?;
+                        continue;
+                    }
+                    InputEvent::TogglePanel if !done(&games[1 - track]) => {
+                        board_state.boards[track].rows[row] = Default::default();
+                        editor.clear();
+                        track = 1 - track;
+                    }
+                    InputEvent::Char(chr) => {
+                        if let Some(letter) = input::normalize(chr, alphabet) {
+                            if !editor.join_digraph(language, letter) {
+                                editor.type_letter(letter);
+                            }
+                        }
+                    }
+                    InputEvent::Backspace => editor.backspace(),
+                    InputEvent::Delete => editor.delete(),
+                    InputEvent::ClearRow => editor.clear(),
+                    InputEvent::Left => editor.move_left(),
+                    InputEvent::Right => editor.move_right(),
+                    InputEvent::Home => editor.move_home(),
+                    InputEvent::End => editor.move_end(),
+                    InputEvent::Submit if editor.is_full() => {
+                        if let Transition::Rejected(notice) =
+                            games[track].handle_event(Event::Submit(text))?
+                        {
+                            board_state
+                                .messages
+                                .push(notice.expire_after(REJECTION_RENDERS));
+                            continue;
+                        }
+                    }
+                    _ => (),
+                }
+                board_state.messages.tick();
+            }
+            GameState::Scoring => {
+                let Transition::Scored(scored) = games[track].handle_event(Event::Score)? else {
+                    continue;
+                };
+                editor.clear();
+                for (_, pattern) in &scored.patterns {
+                    let board = &mut board_state.boards[track];
+                    board.rows[row] = rules::guessed_word(&scored.guess, pattern);
+                    board.solved = games[track].is_solved(0);
+                    if let Some(shared) = crossword.record(track, &scored.guess, pattern) {
+                        let shared = word::display(shared);
+                        board_state.messages.push(
+                            Notice::info(fill(strings().crossword_shared, &[&shared]))
+                                .expire_after(REVEAL_RENDERS),
+                        );
+                    }
+                }
+                if done(&games[0]) && done(&games[1]) {
+                    break;
+                } else if done(&games[track]) {
+                    track = 1 - track;
+                }
+            }
+            GameState::Menu => {
+                // Quitting after a guess has to be confirmed
+                let in_progress = games.iter().any(|game| game.guesses() > 0);
+                if pause::pause_or_quit(&mut quick, in_progress, quitting, || {
+                    render_game(&board_state)
+                }) {
+                    break;
+                }
+                games[track].handle_event(Event::Resume)?;
+            }
+            GameState::Won | GameState::Lost => break,
+        }
+    }
+    let solved = [0, 1].map(|track| board_state.boards[track].solved);
+    Ok(Some((crossword.words, solved)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn solving_a_word_gives_the_shared_letter_away() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut crossword = Crossword::pick(&["kaart", "stoel"], &mut rng).unwrap();
+        let [across, down] = crossword.words;
+        let [across_place, down_place] = crossword.crossing;
+        assert_eq!(
+            word::letter(across, across_place),
+            word::letter(down, down_place)
+        );
+
+        // A guess that doesn't solve the word gives nothing away
+        assert_eq!(
+            crossword.record(0, "tafel", &rules::score("tafel", across)),
+            None
+        );
+        assert_eq!(crossword.grid(0).letters[1], [None; WORD_LENGTH]);
+
+        let shared = crossword.record(0, across, &rules::score(across, across));
+        assert_eq!(shared, word::letter(down, down_place));
+        assert_eq!(crossword.grid(0).letters[1][down_place], shared);
+
+        // Two words without a shared letter don't make a crossword
+        assert!(Crossword::pick(&["kaart", "melon"], &mut rng).is_none());
+    }
+}
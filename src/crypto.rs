@@ -0,0 +1,211 @@
+//! End-to-end encryption of network games. The keys come from the room code with CPace, a
+//! password authenticated key exchange: both sides exchange ephemeral ristretto255 keys on a
+//! generator that is derived from the room code, and derive a key for each direction from the
+//! shared secret. Anything between the players, like a relay or a forwarded port, only sees
+//! public keys that tell nothing about the room code. Posing as one of the players takes a guess
+//! of the room code for each connection, the guesses can't be checked offline.
+//!
+//! Every payload is sealed with chacha20poly1305, the nonce is the number of the message so
+//! messages that are replayed, dropped or reordered don't open.
+
+#[cfg(not(feature = "encryption"))]
+use std::io;
+#[cfg(not(feature = "encryption"))]
+use std::net::TcpStream;
+
+/// The side of the connection, each side seals with its own key
+#[derive(Clone, Copy)]
+pub enum Role {
+    Host,
+    Join,
+}
+
+#[cfg(feature = "encryption")]
+mod keys {
+    use super::Role;
+    use chacha20poly1305::aead::{Aead, KeyInit};
+    use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+    use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+    use curve25519_dalek::scalar::Scalar;
+    use curve25519_dalek::traits::IsIdentity;
+    use hkdf::Hkdf;
+    use rand::rngs::OsRng;
+    use rand::RngCore;
+    use sha2::{Digest, Sha256, Sha512};
+    use std::io::{self, ErrorKind, Read, Write};
+    use std::net::TcpStream;
+
+    fn nonce(counter: u64) -> Nonce {
+        let mut nonce = [0; 12];
+        nonce[..8].copy_from_slice(&counter.to_le_bytes());
+        nonce.into()
+    }
+
+    /// Seals the payloads that are sent
+    pub struct Sealer {
+        cipher: ChaCha20Poly1305,
+        counter: u64,
+    }
+
+    impl Sealer {
+        pub fn seal(&mut self, payload: &[u8]) -> Vec<u8> {
+            let sealed = self
+                .cipher
+                .encrypt(&nonce(self.counter), payload)
+                .expect("Sealing a payload in memory can't fail");
+            self.counter += 1;
+            sealed
+        }
+    }
+
+    /// Opens the payloads that are received
+    pub struct Opener {
+        cipher: ChaCha20Poly1305,
+        counter: u64,
+    }
+
+    impl Opener {
+        pub fn open(&mut self, sealed: &[u8]) -> io::Result<Vec<u8>> {
+            let payload = self
+                .cipher
+                .decrypt(&nonce(self.counter), sealed)
+                .map_err(|_| {
+                    io::Error::new(ErrorKind::InvalidData, "A message could not be opened")
+                })?;
+            self.counter += 1;
+            Ok(payload)
+        }
+    }
+
+    /// The generator of the key exchange in the room, only someone with the room code knows it
+    fn generator(room: &str) -> RistrettoPoint {
+        let mut hash = Sha512::new();
+        hash.update(b"rust-lingo cpace");
+        hash.update((room.len() as u64).to_be_bytes());
+        hash.update(room.as_bytes());
+        RistrettoPoint::from_uniform_bytes(&hash.finalize().into())
+    }
+
+    fn refused() -> io::Error {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            "The other player sent an invalid key",
+        )
+    }
+
+    /// Exchange keys with the other side and derive the keys of both directions
+    pub fn handshake(
+        stream: &mut TcpStream,
+        room: &str,
+        role: Role,
+    ) -> io::Result<(Sealer, Opener)> {
+        let mut random = [0; 64];
+        OsRng.fill_bytes(&mut random);
+        let secret = Scalar::from_bytes_mod_order_wide(&random);
+        let public = (secret * generator(room)).compress();
+        stream.write_all(public.as_bytes())?;
+        stream.flush()?;
+        let mut other = [0; 32];
+        stream.read_exact(&mut other)?;
+        let other = CompressedRistretto(other);
+        let shared = other.decompress().ok_or_else(refused)? * secret;
+        if shared.is_identity() {
+            return Err(refused());
+        }
+
+        // Both public keys go into the keys, in the same order on both sides
+        let (host, join) = match role {
+            Role::Host => (public, other),
+            Role::Join => (other, public),
+        };
+        let transcript = [host.to_bytes(), join.to_bytes()].concat();
+        let hkdf = Hkdf::<Sha256>::new(Some(&transcript), shared.compress().as_bytes());
+        let cipher = |info: &[u8]| {
+            let mut key = Key::default();
+            hkdf.expand(info, &mut key)
+                .expect("The key length is valid for hkdf");
+            ChaCha20Poly1305::new(&key)
+        };
+        let (seal_info, open_info) = match role {
+            Role::Host => (b"rust-lingo host", b"rust-lingo join"),
+            Role::Join => (b"rust-lingo join", b"rust-lingo host"),
+        };
+
+        Ok((
+            Sealer {
+                cipher: cipher(seal_info),
+                counter: 0,
+            },
+            Opener {
+                cipher: cipher(open_info),
+                counter: 0,
+            },
+        ))
+    }
+}
+
+#[cfg(feature = "encryption")]
+pub use keys::{handshake, Opener, Sealer};
+
+/// Without the encryption feature there are never keys, the handshake always fails
+#[cfg(not(feature = "encryption"))]
+pub enum Sealer {}
+
+#[cfg(not(feature = "encryption"))]
+impl Sealer {
+    pub fn seal(&mut self, _payload: &[u8]) -> Vec<u8> {
+        match *self {}
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+pub enum Opener {}
+
+#[cfg(not(feature = "encryption"))]
+impl Opener {
+    pub fn open(&mut self, _sealed: &[u8]) -> io::Result<Vec<u8>> {
+        match *self {}
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+pub fn handshake(
+    _stream: &mut TcpStream,
+    _room: &str,
+    _role: Role,
+) -> io::Result<(Sealer, Opener)> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "This build can't encrypt, build with the encryption feature to play in a room",
+    ))
+}
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    /// Seal a payload on the host side and try to open it on the joining side
+    fn opens(host_room: &'static str, join_room: &'static str) -> bool {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let host = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (mut sealer, _) = handshake(&mut stream, host_room, Role::Host).unwrap();
+            sealer.seal(b"kaart")
+        });
+        let mut stream = TcpStream::connect(address).unwrap();
+        let (_, mut opener) = handshake(&mut stream, join_room, Role::Join).unwrap();
+        let sealed = host.join().unwrap();
+        opener
+            .open(&sealed)
+            .is_ok_and(|payload| payload == b"kaart")
+    }
+
+    #[test]
+    fn only_the_same_room_code_gives_the_same_keys() {
+        assert!(opens("lingo-room-42", "lingo-room-42"));
+        assert!(!opens("lingo-room-42", "lingo-room-43"));
+    }
+}
@@ -0,0 +1,204 @@
+//! The meaning of the words of a game, shown after the game so the player learns the words they
+//! didn't know. Definitions come from providers: a definition file with a word and its definition
+//! separated by a tab on each line, or a command that prints the definition of the word it is
+//! given. The first provider that knows the word is used.
+
+use crate::keymap::{ENTER, ESCAPE};
+use crate::language::Language;
+use crate::navigation::Navigator;
+use crate::storage;
+use crate::strings::{fill, strings};
+use crate::word;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::process::Command;
+
+/// Looks up the definition of a word
+pub trait DefinitionProvider {
+    fn define(&self, word: &str) -> Option<String>;
+}
+
+/// The definitions of a definition file, `word<TAB>definition` on each line
+pub struct DefinitionFile {
+    definitions: HashMap<String, String>,
+}
+
+impl DefinitionFile {
+    pub fn load(path: &Path, language: &Language) -> io::Result<DefinitionFile> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))?;
+        let definitions = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(word, definition)| {
+                // Written like the words of the word list so they can be found
+                let word = language.join_digraphs(&word::compose(&word.trim().to_lowercase()));
+                (word, definition.trim().to_string())
+            })
+            .filter(|(word, definition)| !word.is_empty() && !definition.is_empty())
+            .collect();
+        Ok(DefinitionFile { definitions })
+    }
+}
+
+impl DefinitionProvider for DefinitionFile {
+    fn define(&self, word: &str) -> Option<String> {
+        self.definitions.get(word).cloned()
+    }
+}
+
+/// A command that prints the definition of the word that is added to its arguments
+pub struct DefinitionCommand {
+    command: Vec<String>,
+    language: &'static Language,
+}
+
+impl DefinitionProvider for DefinitionCommand {
+    fn define(&self, word: &str) -> Option<String> {
+        let (program, args) = self.command.split_first()?;
+        let output = Command::new(program)
+            .args(args)
+            .arg(word)
+            .env("LINGO_LANGUAGE", self.language.code)
+            .output()
+            .ok()?;
+        let definition = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !definition.is_empty()).then_some(definition)
+    }
+}
+
+/// All the providers of definitions, in the order they are asked
+#[derive(Default)]
+pub struct Definitions {
+    providers: Vec<Box<dyn DefinitionProvider>>,
+}
+
+impl Definitions {
+    /// The providers of the language: the definition file given on the command line, the file of
+    /// the language in the config directory and the command of the config file
+    pub fn load(
+        file: Option<&Path>,
+        command: &[String],
+        language: &'static Language,
+    ) -> io::Result<Definitions> {
+        let mut definitions = Definitions::default();
+        if let Some(file) = file {
+            definitions
+                .providers
+                .push(Box::new(DefinitionFile::load(file, language)?));
+        }
+        let path = storage::config_path(&format!("definitions-{}.txt", language.code))?;
+        match DefinitionFile::load(&path, language) {
+            Err(err) if err.kind() == ErrorKind::NotFound => (),
+            Err(err) => return Err(err),
+            Ok(file) => definitions.providers.push(Box::new(file)),
+        }
+        if !command.is_empty() {
+            definitions.providers.push(Box::new(DefinitionCommand {
+                command: command.to_vec(),
+                language,
+            }));
+        }
+        Ok(definitions)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    pub fn define(&self, word: &str) -> Option<String> {
+        self.providers
+            .iter()
+            .find_map(|provider| provider.define(word))
+    }
+}
+
+/// Split the text in lines that fit in `width` columns, at the spaces between the words
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for part in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + part.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(part);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Show the definitions of the words in a panel that scrolls with the arrow keys and page up and
+/// down, until it is closed. Words without a definition are left out, if none of the words has one
+/// nothing is shown.
+pub fn show(definitions: &Definitions, words: &[&str]) {
+    let found = words
+        .iter()
+        .filter_map(|word| {
+            definitions.define(word).map(|definition| {
+                (
+                    word.chars().map(word::display).collect::<String>(),
+                    definition,
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    if found.is_empty() {
+        return;
+    }
+
+    ncurses::timeout(-1);
+    let mut top = 0;
+    let mut navigator = Navigator::default();
+    loop {
+        let mut max_x = 0;
+        let mut max_y = 0;
+        ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+        let width = (max_x - 4).max(10) as usize;
+        let lines = found
+            .iter()
+            .flat_map(|(word, definition)| {
+                std::iter::once(word.clone())
+                    .chain(
+                        wrap(definition, width - 2)
+                            .into_iter()
+                            .map(|line| format!("  {}", line)),
+                    )
+                    .chain(std::iter::once(String::new()))
+            })
+            .collect::<Vec<_>>();
+        // The title and the help take two lines each
+        let height = (max_y - 4).max(1) as usize;
+        let last_top = lines.len().saturating_sub(height);
+        top = top.min(last_top);
+
+        ncurses::clear();
+        ncurses::mvaddstr(0, 2, strings().definitions_title);
+        for (row, line) in lines.iter().skip(top).take(height).enumerate() {
+            ncurses::mvaddstr(2 + row as i32, 2, line);
+        }
+        let help = if last_top > 0 {
+            fill(strings().definitions_scroll, &[&(top + 1), &(last_top + 1)])
+        } else {
+            strings().definitions_close.to_string()
+        };
+        ncurses::mvaddstr(max_y - 1, 2, &help);
+        ncurses::refresh();
+
+        let input = ncurses::getch();
+        if let Some(movement) = navigator.read(input) {
+            top = movement.apply(top, last_top, height);
+        } else if input == ' ' as i32 {
+            top = (top + height).min(last_top);
+        } else if [ESCAPE, 'q' as i32].contains(&input) || ENTER.contains(&input) {
+            return;
+        }
+    }
+}
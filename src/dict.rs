@@ -0,0 +1,592 @@
+//! Tools for the maintainers of word lists. The game takes what it can from a list and skips the
+//! rest without saying so, these tools tell what was skipped and why.
+
+use crate::language::{self, Language};
+use crate::{storage, word, WORD_LENGTH};
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// The problems of each kind that are shown as text, the json output has all of them
+const SHOWN_PROBLEMS: usize = 10;
+
+/// A problem on a line of a word list
+#[derive(Serialize)]
+pub struct Problem {
+    /// The number of the line, starting at 1
+    pub line: usize,
+    pub word: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// The problems found in a word list
+#[derive(Serialize)]
+pub struct Report {
+    pub language: &'static str,
+    /// The amount of different words the game can play
+    pub playable: usize,
+    pub duplicates: Vec<Problem>,
+    pub wrong_length: Vec<Problem>,
+    pub invalid_characters: Vec<Problem>,
+    pub encoding: Vec<Problem>,
+}
+
+impl Report {
+    fn kinds(&self) -> [(String, &[Problem]); 4] {
+        [
+            ("duplicates".to_string(), &self.duplicates),
+            (
+                format!("words that aren't {} letters", WORD_LENGTH),
+                &self.wrong_length,
+            ),
+            (
+                "words with invalid characters".to_string(),
+                &self.invalid_characters,
+            ),
+            ("encoding problems".to_string(), &self.encoding),
+        ]
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.kinds().iter().all(|(_, problems)| problems.is_empty())
+    }
+
+    /// The report as text for a person, only the first problems of each kind are listed
+    pub fn text(&self) -> String {
+        let mut text = format!(
+            "{} playable words, checked as {}",
+            self.playable, self.language
+        );
+        for (kind, problems) in self.kinds() {
+            if problems.is_empty() {
+                continue;
+            }
+            text.push_str(&format!("\n{} {}", problems.len(), kind));
+            for problem in problems.iter().take(SHOWN_PROBLEMS) {
+                let parts = [Some(&problem.word), problem.detail.as_ref()];
+                text.push_str(&format!(
+                    "\n  line {}: {}",
+                    problem.line,
+                    parts
+                        .into_iter()
+                        .flatten()
+                        .filter(|part| !part.is_empty())
+                        .join(", ")
+                ));
+            }
+            if problems.len() > SHOWN_PROBLEMS {
+                text.push_str(&format!("\n  and {} more", problems.len() - SHOWN_PROBLEMS));
+            }
+        }
+        text
+    }
+}
+
+/// Check the content of a word list. The words are checked the way the game loads them, so
+/// decomposed accents and the letter pairs of the language that are written on one tile count
+/// as a single letter. Without a language it is recognized from the words.
+pub fn check(content: &[u8], language: Option<&'static Language>) -> Report {
+    let mut encoding = Vec::new();
+    let lines = content
+        .split(|byte| *byte == b'\n')
+        .enumerate()
+        .map(|(index, line)| (index + 1, String::from_utf8_lossy(line)))
+        .collect::<Vec<_>>();
+
+    // Problems with the file as a whole are reported once, on the first line they show up on
+    if content.starts_with("\u{feff}".as_bytes()) {
+        encoding.push(Problem {
+            line: 1,
+            word: String::new(),
+            detail: Some("the file starts with a byte order mark".to_string()),
+        });
+    }
+    if let Some((line, _)) = lines.iter().find(|(_, text)| text.ends_with('\r')) {
+        encoding.push(Problem {
+            line: *line,
+            word: String::new(),
+            detail: Some("the lines end with a carriage return".to_string()),
+        });
+    }
+
+    let words = lines
+        .iter()
+        .map(|(line, text)| {
+            (
+                *line,
+                word::split_entry(text.trim_start_matches('\u{feff}')),
+            )
+        })
+        .filter(|(_, (text, _))| !text.is_empty())
+        .collect::<Vec<_>>();
+    let language = language.unwrap_or_else(|| {
+        language::detect(&words.iter().map(|(_, (text, _))| *text).collect::<Vec<_>>())
+    });
+
+    let mut invalid_characters = Vec::new();
+    let mut wrong_length = Vec::new();
+    let mut duplicates = Vec::new();
+    let mut seen = HashMap::new();
+    for (line, (text, count)) in words {
+        if let Some(count) = count.filter(|count| count.parse::<u64>().is_err()) {
+            invalid_characters.push(Problem {
+                line,
+                word: text.to_string(),
+                detail: Some(format!("the frequency {} isn't a number", count)),
+            });
+            continue;
+        }
+        if text.contains('\u{fffd}') {
+            encoding.push(Problem {
+                line,
+                word: text.to_string(),
+                detail: Some("not valid UTF-8".to_string()),
+            });
+            continue;
+        }
+        let composed = word::compose(text);
+        if composed != text {
+            encoding.push(Problem {
+                line,
+                word: text.to_string(),
+                detail: Some("the accents are decomposed".to_string()),
+            });
+        }
+
+        let invalid = composed
+            .chars()
+            .filter(|chr| !chr.is_alphabetic() || !chr.is_lowercase())
+            .collect::<String>();
+        if !invalid.is_empty() {
+            invalid_characters.push(Problem {
+                line,
+                word: text.to_string(),
+                detail: Some(format!("has {}", invalid)),
+            });
+            continue;
+        }
+
+        let joined = language.join_digraphs(&composed);
+        if word::length(&joined) != WORD_LENGTH {
+            wrong_length.push(Problem {
+                line,
+                word: text.to_string(),
+                detail: None,
+            });
+            continue;
+        }
+
+        match seen.get(&joined) {
+            Some(first) => duplicates.push(Problem {
+                line,
+                word: text.to_string(),
+                detail: Some(format!("also on line {}", first)),
+            }),
+            None => {
+                seen.insert(joined, line);
+            }
+        }
+    }
+
+    Report {
+        language: language.code,
+        playable: seen.len(),
+        duplicates,
+        wrong_length,
+        invalid_characters,
+        encoding,
+    }
+}
+
+/// Check the word list in the file and print the report, returns if the list is clean
+pub fn check_file(
+    path: &Path,
+    language: Option<&'static Language>,
+    json: bool,
+) -> io::Result<bool> {
+    let report = check(&fs::read(path)?, language);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}: {}", path.display(), report.text());
+    }
+    Ok(report.is_clean())
+}
+
+/// The word without its accents, letters like ß that aren't a letter with an accent are kept
+fn strip_accents(word: &str) -> String {
+    word.nfd()
+        .filter(|chr| !is_combining_mark(*chr))
+        .nfc()
+        .collect()
+}
+
+/// A word of a word list with the count of its frequency column
+pub struct Entry {
+    pub word: String,
+    pub count: Option<u64>,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.count {
+            None => write!(f, "{}", self.word),
+            Some(count) => write!(f, "{}\t{}", self.word, count),
+        }
+    }
+}
+
+/// The words of a word list as the game wants them: lowercase, without duplicates or words of
+/// another length, and sorted. With `strip` the accents are removed, a word that only differs in
+/// its accents from another word is a duplicate then. The frequency of the first of the duplicates
+/// is kept.
+pub fn normalize(content: &str, language: Option<&'static Language>, strip: bool) -> Vec<Entry> {
+    let entries = content
+        .lines()
+        .map(|line| word::split_entry(line.trim_start_matches('\u{feff}')))
+        .filter(|(word, _)| !word.is_empty())
+        .map(|(word, count)| {
+            let word = word::compose(&word.to_lowercase());
+            Entry {
+                word: if strip { strip_accents(&word) } else { word },
+                count: count.and_then(|count| count.parse().ok()),
+            }
+        })
+        .collect::<Vec<_>>();
+    let language = language.unwrap_or_else(|| {
+        language::detect(
+            &entries
+                .iter()
+                .map(|entry| entry.word.as_str())
+                .collect::<Vec<_>>(),
+        )
+    });
+
+    entries
+        .into_iter()
+        .filter(|entry| word::is_playable(&language.join_digraphs(&entry.word)))
+        // The sort is stable so the first of the duplicates stays first
+        .sorted_by(|a, b| a.word.cmp(&b.word))
+        .dedup_by(|a, b| a.word == b.word)
+        .collect()
+}
+
+/// Normalize the word list in the file `input` and write it to `output`
+pub fn normalize_file(
+    input: &Path,
+    output: &Path,
+    language: Option<&'static Language>,
+    strip: bool,
+) -> io::Result<()> {
+    let content = fs::read(input)?;
+    let content = String::from_utf8_lossy(&content);
+    let lines = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count();
+    let words = normalize(&content, language, strip);
+    let mut text = words.iter().join("\n");
+    text.push('\n');
+    fs::write(output, text)?;
+    println!(
+        "Wrote {} words to {}, {} lines were left out",
+        words.len(),
+        output.display(),
+        lines.saturating_sub(words.len())
+    );
+    Ok(())
+}
+
+/// Which word list each word came from, a word that is in more than one list belongs to the first
+#[derive(Default)]
+pub struct Provenance {
+    lists: Vec<PathBuf>,
+    sources: HashMap<String, usize>,
+}
+
+impl Provenance {
+    /// Add the words of the next list, returns the amount of words that weren't in the earlier
+    /// lists
+    pub fn add<W: Into<String>>(
+        &mut self,
+        list: &Path,
+        words: impl IntoIterator<Item = W>,
+    ) -> usize {
+        let index = self.lists.len();
+        self.lists.push(list.to_path_buf());
+        let before = self.sources.len();
+        for word in words {
+            self.sources.entry(word.into()).or_insert(index);
+        }
+        self.sources.len() - before
+    }
+
+    /// The list the word came from
+    pub fn source(&self, word: &str) -> Option<&Path> {
+        self.sources
+            .get(word)
+            .map(|index| self.lists[*index].as_path())
+    }
+
+    /// All the words of the lists, sorted
+    pub fn words(&self) -> Vec<&str> {
+        self.sources.keys().map(String::as_str).sorted().collect()
+    }
+}
+
+/// Combine the normalized word lists in the files `inputs` and write them to `output`
+pub fn merge_files(
+    inputs: &[PathBuf],
+    output: &Path,
+    language: Option<&'static Language>,
+) -> io::Result<()> {
+    let mut provenance = Provenance::default();
+    let mut counts = HashMap::new();
+    for input in inputs {
+        let content = fs::read(input)?;
+        let entries = normalize(&String::from_utf8_lossy(&content), language, false);
+        let added = provenance.add(input, entries.iter().map(|entry| entry.word.clone()));
+        println!(
+            "{}: {} words, {} new",
+            input.display(),
+            entries.len(),
+            added
+        );
+        for entry in entries {
+            counts.entry(entry.word).or_insert(entry.count);
+        }
+    }
+
+    let words = provenance.words();
+    let mut text = words
+        .iter()
+        .map(|word| Entry {
+            word: word.to_string(),
+            count: counts[*word],
+        })
+        .join("\n");
+    text.push('\n');
+    fs::write(output, text)?;
+    println!("Wrote {} words to {}", words.len(), output.display());
+    Ok(())
+}
+
+/// The words the player adds to the word list, in the config directory
+const CUSTOM_WORDS_FILE: &str = "custom-words.txt";
+/// The words the player removes from the word list, in the config directory
+const BANNED_WORDS_FILE: &str = "banned-words.txt";
+
+/// The words the player added to and removed from the word list, one word on each line. Lines
+/// starting with a # are comments.
+#[derive(Default)]
+pub struct PersonalWords {
+    pub added: Vec<&'static str>,
+    pub banned: HashSet<&'static str>,
+}
+
+/// The words in a file of the config directory, written like the words of the word list. A file
+/// that doesn't exist has no words.
+fn load_personal_file(file_name: &str, language: &Language) -> io::Result<Vec<&'static str>> {
+    let path = storage::config_path(file_name)?;
+    let content = match fs::read_to_string(&path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(io::Error::new(
+                err.kind(),
+                format!("{}: {}", path.display(), err),
+            ))
+        }
+        Ok(content) => content,
+    };
+
+    // The words have to live as long as the bundled words
+    let content = language.join_digraphs(&word::compose(&content.to_lowercase()));
+    let content: &'static str = Box::leak(content.into_boxed_str());
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect())
+}
+
+impl PersonalWords {
+    pub fn load(language: &Language) -> io::Result<PersonalWords> {
+        Ok(PersonalWords {
+            added: load_personal_file(CUSTOM_WORDS_FILE, language)?,
+            banned: load_personal_file(BANNED_WORDS_FILE, language)?
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+/// The bundled offensive words of the language, written like the words of the word list
+pub fn offensive_words(language: &Language) -> HashSet<&'static str> {
+    let content = language.join_digraphs(language.offensive);
+    let content: &'static str = Box::leak(content.into_boxed_str());
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// The tier of the dictionary the word of the game comes from
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// How hard the words are, from the easiest to the hardest. With frequencies the common words are
+/// the easiest, words without a count come last. Otherwise, and between words with the same count,
+/// words with the common letters of the list are easier than words with the rare ones.
+fn rank_by_difficulty(
+    words: &[&'static str],
+    frequencies: &HashMap<&str, u64>,
+) -> Vec<&'static str> {
+    let mut letters = HashMap::<char, usize>::new();
+    for letter in words.iter().flat_map(|word| word.chars()) {
+        *letters.entry(letter).or_default() += 1;
+    }
+    let total = letters.values().sum::<usize>() as f64;
+    // The information in the letters of the word, rare letters carry more
+    let rarity = |word: &str| {
+        word.chars()
+            .map(|letter| -(letters[&letter] as f64 / total).ln())
+            .sum::<f64>()
+    };
+
+    let mut ranked = words.to_vec();
+    ranked.sort_by(|a, b| {
+        let count = |word| frequencies.get(word).copied().unwrap_or(0);
+        count(b)
+            .cmp(&count(a))
+            .then_with(|| rarity(a).total_cmp(&rarity(b)))
+    });
+    ranked
+}
+
+/// The words of the tier, the words are split into three tiers of the same size by their
+/// difficulty
+pub fn tier(
+    words: &[&'static str],
+    frequencies: &HashMap<&str, u64>,
+    difficulty: Difficulty,
+) -> HashSet<&'static str> {
+    let ranked = rank_by_difficulty(words, frequencies);
+    let size = ranked.len().div_ceil(3);
+    let index = match difficulty {
+        Difficulty::Easy => 0,
+        Difficulty::Medium => 1,
+        Difficulty::Hard => 2,
+    };
+    ranked
+        .chunks(size.max(1))
+        .nth(index)
+        .unwrap_or_default()
+        .iter()
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A word list with one of each problem, the comments have the line numbers
+    fn list() -> Vec<u8> {
+        let mut list = Vec::new();
+        list.extend_from_slice("\u{feff}kaart\n".as_bytes()); // 1
+        list.extend_from_slice(b"tabak\t12\r\n"); // 2
+        list.extend_from_slice(b"kaart\n"); // 3
+        list.extend_from_slice(b"boom\n"); // 4
+        list.extend_from_slice(b"Tafel\n"); // 5
+        list.extend_from_slice(b"st\xffel\n"); // 6
+        list.extend_from_slice("cafe\u{301}s\n".as_bytes()); // 7
+        list.extend_from_slice(b"ijzers\n"); // 8
+        list.extend_from_slice(b"stoel\tvaak\n"); // 9
+        list
+    }
+
+    fn lines(problems: &[Problem]) -> Vec<usize> {
+        problems.iter().map(|problem| problem.line).collect()
+    }
+
+    #[test]
+    fn problems_are_reported_on_their_lines() {
+        let report = check(&list(), Some(&language::DUTCH));
+        assert_eq!(lines(&report.duplicates), [3]);
+        assert_eq!(lines(&report.wrong_length), [4]);
+        assert_eq!(lines(&report.invalid_characters), [5, 9]);
+        assert_eq!(lines(&report.encoding), [1, 2, 6, 7]);
+        // The letter pair ij is one letter in Dutch, cafés is counted despite its accent
+        assert_eq!(report.playable, 4);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn clean_lists_have_no_problems() {
+        let report = check(b"kaart\ntabak\t12\nijzers\n", Some(&language::DUTCH));
+        assert!(report.is_clean());
+        assert_eq!(report.playable, 3);
+        assert_eq!(report.text(), "3 playable words, checked as nl");
+    }
+
+    #[test]
+    fn the_json_report_has_every_problem() {
+        let report = check(&list(), Some(&language::DUTCH));
+        let problem = |line: usize, word: &str, detail: &str| json!({ "line": line, "word": word, "detail": detail });
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "language": "nl",
+                "playable": 4,
+                "duplicates": [problem(3, "kaart", "also on line 1")],
+                "wrong_length": [{ "line": 4, "word": "boom" }],
+                "invalid_characters": [
+                    problem(5, "Tafel", "has T"),
+                    problem(9, "stoel", "the frequency vaak isn't a number"),
+                ],
+                "encoding": [
+                    problem(1, "", "the file starts with a byte order mark"),
+                    problem(2, "", "the lines end with a carriage return"),
+                    problem(6, "st\u{fffd}el", "not valid UTF-8"),
+                    problem(7, "cafe\u{301}s", "the accents are decomposed"),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn normalized_lists_are_sorted_without_duplicates() {
+        let words = normalize(
+            "Tabak\t3\nkaart\t5\nboom\ntabak\t7\ncafe\u{301}s\ncafes\t2\n",
+            Some(&language::DUTCH),
+            true,
+        );
+        let words = words.iter().map(Entry::to_string).collect::<Vec<_>>();
+        assert_eq!(words, ["cafes", "kaart\t5", "tabak\t3"]);
+    }
+
+    #[test]
+    fn merged_words_come_from_the_first_list() {
+        let mut provenance = Provenance::default();
+        assert_eq!(provenance.add(Path::new("a.txt"), ["kaart", "tabak"]), 2);
+        assert_eq!(provenance.add(Path::new("b.txt"), ["tabak", "stoel"]), 1);
+        assert_eq!(provenance.words(), ["kaart", "stoel", "tabak"]);
+        assert_eq!(provenance.source("tabak"), Some(Path::new("a.txt")));
+        assert_eq!(provenance.source("stoel"), Some(Path::new("b.txt")));
+        assert_eq!(provenance.source("appel"), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! The words of the word lists indexed by their length. The lists are scanned once when they are
+//! loaded and the words of every length go in their own bucket, a bucket is only sorted when words
+//! of that length are first asked for. A game only pays for the length it is played with, and
+//! another length doesn't scan the lists again.
+
+use crate::error::Error;
+use crate::masks::{LetterIndex, WordMask};
+use crate::word;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// The words of a single length
+#[derive(Default)]
+struct Bucket {
+    /// The words in the order of the lists, with duplicates
+    words: Vec<&'static str>,
+    /// The words sorted and without duplicates
+    sorted: OnceLock<Vec<&'static str>>,
+    /// The letters of the sorted words and their masks, in the same order
+    masks: OnceLock<(LetterIndex, Vec<WordMask>)>,
+}
+
+/// The words that can be played, by length
+#[derive(Default)]
+pub struct Dictionary {
+    buckets: HashMap<usize, Bucket>,
+    /// The letters of a length without words
+    empty: OnceLock<LetterIndex>,
+}
+
+impl Dictionary {
+    /// Index the words of the word lists. A word list has a word on each line, maybe with a
+    /// frequency column. Names and abbreviations are only played when they are allowed, they are
+    /// rewritten as words before this.
+    pub fn load(word_strings: &[&'static str]) -> Dictionary {
+        let mut dictionary = Dictionary::default();
+        for line in word_strings
+            .iter()
+            .flat_map(|word_string| word_string.lines())
+            .filter(|line| word::kind(line) == word::Kind::Word)
+        {
+            let word = word::split_entry(line).0;
+            if !word.is_empty() && word::has_only_letters(word) {
+                dictionary
+                    .buckets
+                    .entry(word::length(word))
+                    .or_default()
+                    .words
+                    .push(word);
+            }
+        }
+        dictionary
+    }
+
+    /// The words of `length` letters, sorted and without duplicates. lingo dict check reports the
+    /// duplicates of a list.
+    pub fn words(&self, length: usize) -> &[&'static str] {
+        match self.buckets.get(&length) {
+            None => &[],
+            Some(bucket) => bucket.sorted.get_or_init(|| {
+                let mut words = bucket.words.clone();
+                words.sort_unstable();
+                words.dedup();
+                words
+            }),
+        }
+    }
+
+    /// The words of `length` letters like `words`, a game can't be played without them
+    pub fn playable(&self, length: usize) -> Result<&[&'static str], Error> {
+        match self.words(length) {
+            [] => Err(Error::NoWords { length }),
+            words => Ok(words),
+        }
+    }
+
+    /// The letters of the words of `length` letters and the masks of the words, in the order of
+    /// `words`. The masks are made when they are first asked for.
+    pub fn masks(&self, length: usize) -> (&LetterIndex, &[WordMask]) {
+        let (index, masks) = match self.buckets.get(&length) {
+            None => return (self.empty.get_or_init(|| LetterIndex::new(&[])), &[]),
+            Some(bucket) => bucket.masks.get_or_init(|| {
+                let words = self.words(length);
+                let index = LetterIndex::new(words);
+                let masks = words.iter().map(|word| index.mask(word)).collect();
+                (index, masks)
+            }),
+        };
+        (index, masks)
+    }
+
+    /// The places of the words of `length` letters that start with `prefix`, in `words`. The
+    /// words with the same start are next to each other in the sorted bucket, so the range is found
+    /// with two binary searches.
+    pub fn starting_with(&self, length: usize, prefix: &str) -> Range<usize> {
+        let words = self.words(length);
+        let start = words.partition_point(|word| *word < prefix);
+        let end = start + words[start..].partition_point(|word| word.starts_with(prefix));
+        start..end
+    }
+
+    /// Replace the words of `length` letters, the words of the other lengths are kept
+    pub fn replace(&mut self, length: usize, words: Vec<&'static str>) {
+        self.buckets.insert(
+            length,
+            Bucket {
+                words,
+                ..Bucket::default()
+            },
+        );
+    }
+
+    /// If the word can be played, looked up in the sorted bucket of its length
+    pub fn contains(&self, word: &str) -> bool {
+        self.words(word::length(word)).binary_search(&word).is_ok()
+    }
+}
+
+impl FromIterator<&'static str> for Dictionary {
+    fn from_iter<I: IntoIterator<Item = &'static str>>(words: I) -> Dictionary {
+        let mut dictionary = Dictionary::default();
+        for word in words {
+            dictionary
+                .buckets
+                .entry(word::length(word))
+                .or_default()
+                .words
+                .push(word);
+        }
+        dictionary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replacing_a_length_keeps_the_others() {
+        let mut dictionary = Dictionary::load(&["kaart\nboom\nstoel\t12\nappels\nboom"]);
+        assert_eq!(dictionary.words(4), ["boom"]);
+        assert_eq!(dictionary.words(5), ["kaart", "stoel"]);
+        dictionary.replace(5, vec!["tabak", "kaart"]);
+        assert_eq!(dictionary.words(5), ["kaart", "tabak"]);
+        assert!(!dictionary.contains("stoel"));
+        assert_eq!(dictionary.words(4), ["boom"]);
+        assert_eq!(dictionary.words(6), ["appels"]);
+    }
+}
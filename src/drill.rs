@@ -0,0 +1,267 @@
+//! Practice drills on the kinds of words that went wrong before. The games in the history of the
+//! statistics are grouped into categories, like words with a double letter or words with the same
+//! ending, and the categories that needed the most guesses are drilled. Drills are kept apart from
+//! the normal statistics so practising doesn't change them.
+
+use crate::announce::Announcer;
+use crate::dictionary::Dictionary;
+use crate::events::EventSink;
+use crate::play::play_game;
+use crate::stats::{Played, Stats};
+use crate::{storage, GameOptions, Stopped, GUESSES, WORD_LENGTH};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+
+const DRILLS_FILE: &str = "drills.json";
+/// The amount of games a category needs in the history before it can be called weak
+const MIN_GAMES: usize = 3;
+/// The amount of weak categories that are drilled
+const MAX_CATEGORIES: usize = 3;
+/// The amount of letters, counted from the least used, that are rare
+const RARE_LETTERS: usize = 6;
+
+/// A kind of word that can be drilled
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Category {
+    /// Words with a letter in it more than once
+    DoubleLetter,
+    /// Words ending with these two letters
+    Ending(String),
+    /// Words with one of the least used letters of the word list
+    RareLetter,
+}
+
+impl Category {
+    /// The key of the category in the drill statistics
+    fn key(&self) -> String {
+        match self {
+            Category::DoubleLetter => "double-letter".to_string(),
+            Category::Ending(ending) => format!("ending-{}", ending),
+            Category::RareLetter => "rare-letter".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::DoubleLetter => write!(f, "words with a double letter"),
+            Category::Ending(ending) => write!(f, "words ending in -{}", ending),
+            Category::RareLetter => write!(f, "words with a rare letter"),
+        }
+    }
+}
+
+/// Sorts words into categories
+pub struct Categorizer {
+    rare_letters: Vec<char>,
+}
+
+impl Categorizer {
+    /// The rare letters are the least used letters in the word list
+    pub fn new(words: &[&str]) -> Categorizer {
+        let mut counts = HashMap::<char, usize>::new();
+        for chr in words.iter().flat_map(|word| word.chars()) {
+            *counts.entry(chr).or_default() += 1;
+        }
+        let mut letters = counts.into_iter().collect::<Vec<_>>();
+        letters.sort_by_key(|(chr, count)| (*count, *chr));
+
+        Categorizer {
+            rare_letters: letters
+                .into_iter()
+                .take(RARE_LETTERS)
+                .map(|(chr, _)| chr)
+                .collect(),
+        }
+    }
+
+    /// All the categories a word belongs to
+    pub fn categories(&self, word: &str) -> Vec<Category> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let mut categories = Vec::new();
+        if chars
+            .iter()
+            .enumerate()
+            .any(|(index, chr)| chars[index + 1..].contains(chr))
+        {
+            categories.push(Category::DoubleLetter);
+        }
+        if chars.len() >= 2 {
+            categories.push(Category::Ending(chars[chars.len() - 2..].iter().collect()));
+        }
+        if chars.iter().any(|chr| self.rare_letters.contains(chr)) {
+            categories.push(Category::RareLetter);
+        }
+        categories
+    }
+}
+
+/// How many guesses a game took, a lost game counts as one guess more than allowed
+fn cost(played: &Played) -> f64 {
+    if played.won {
+        played.guesses as f64
+    } else {
+        (GUESSES + 1) as f64
+    }
+}
+
+/// The average cost of the games in each category
+fn category_costs(categorizer: &Categorizer, history: &[Played]) -> HashMap<Category, Vec<f64>> {
+    let mut costs = HashMap::<Category, Vec<f64>>::new();
+    for played in history {
+        for category in categorizer.categories(&played.word) {
+            costs.entry(category).or_default().push(cost(played));
+        }
+    }
+    costs
+}
+
+fn average(costs: &[f64]) -> f64 {
+    costs.iter().sum::<f64>() / costs.len() as f64
+}
+
+/// The categories that took more guesses than the average game, the weakest first
+pub fn weak_categories(categorizer: &Categorizer, history: &[Played]) -> Vec<Category> {
+    if history.is_empty() {
+        return Vec::new();
+    }
+    let overall = history.iter().map(cost).sum::<f64>() / history.len() as f64;
+
+    let mut weak = category_costs(categorizer, history)
+        .into_iter()
+        .filter(|(_, costs)| costs.len() >= MIN_GAMES)
+        .map(|(category, costs)| (category, average(&costs)))
+        .filter(|(_, cost)| *cost > overall)
+        .collect::<Vec<_>>();
+    // Sort on the key as well so equally weak categories always come out in the same order
+    weak.sort_by(|(a, a_cost), (b, b_cost)| {
+        b_cost.total_cmp(a_cost).then_with(|| a.key().cmp(&b.key()))
+    });
+    weak.into_iter()
+        .take(MAX_CATEGORIES)
+        .map(|(category, _)| category)
+        .collect()
+}
+
+/// The results of the drills in a single category
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Record {
+    pub played: u32,
+    pub won: u32,
+    /// The guesses of all drills, lost drills count as one guess more than allowed
+    pub guesses: u32,
+}
+
+/// The drill statistics by category, kept apart from the normal statistics
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DrillStats {
+    pub categories: BTreeMap<String, Record>,
+}
+
+impl DrillStats {
+    pub fn load() -> io::Result<DrillStats> {
+        storage::load_json(DRILLS_FILE)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save_json(DRILLS_FILE, self)
+    }
+}
+
+/// Play a single drill on a word from one of the weak categories and report the progress in that
+/// category afterwards
+pub fn drill(
+    dictionary: &Dictionary,
+    answers: &[&'static str],
+    alphabet: Vec<char>,
+    mut announcer: Announcer,
+    events: &EventSink,
+    options: &GameOptions,
+) {
+    let stats = Stats::load().unwrap_or_else(|err| {
+        eprintln!("Could not load the statistics: {}", err);
+        Stats::default()
+    });
+
+    let categorizer = Categorizer::new(dictionary.words(WORD_LENGTH));
+    let mut categories = weak_categories(&categorizer, &stats.history);
+    if categories.is_empty() {
+        eprintln!(
+            "Not enough games played to find weak spots, drilling words with a double letter"
+        );
+        categories.push(Category::DoubleLetter);
+    }
+
+    let mut rng = rand::thread_rng();
+    let category = categories.choose(&mut rng).unwrap().clone();
+    let candidates = answers
+        .iter()
+        .filter(|word| categorizer.categories(word).contains(&category))
+        .copied()
+        .collect::<Vec<_>>();
+    let word = match candidates.choose(&mut rng) {
+        None => {
+            eprintln!("There are no {} in the word list", category);
+            return;
+        }
+        Some(word) => *word,
+    };
+
+    let result = match play_game(
+        dictionary,
+        alphabet,
+        vec![word],
+        &mut announcer,
+        events,
+        None,
+        options,
+    ) {
+        Err(Stopped::Failed(err)) => {
+            eprintln!("The drill stopped: {}", err);
+            return;
+        }
+        // The drill was quit before it ended
+        Err(_) => return,
+        Ok(result) => result,
+    };
+
+    let mut drills = DrillStats::load().unwrap_or_else(|err| {
+        eprintln!(
+            "Could not load the drill statistics, starting over: {}",
+            err
+        );
+        DrillStats::default()
+    });
+    let record = drills.categories.entry(category.key()).or_default();
+    record.played += 1;
+    if result.won {
+        record.won += 1;
+    }
+    record.guesses += cost(&Played {
+        word: word.to_string(),
+        won: result.won,
+        guesses: result.guesses,
+        ..Default::default()
+    }) as u32;
+
+    println!(
+        "Drilled {}: won {} of {}, {:.1} guesses on average",
+        category,
+        record.won,
+        record.played,
+        record.guesses as f64 / record.played as f64
+    );
+    if let Some(costs) = category_costs(&categorizer, &stats.history).get(&category) {
+        println!("In normal games: {:.1} guesses on average", average(costs));
+    }
+
+    if let Err(err) = drills.save() {
+        eprintln!("Could not save the drill statistics: {}", err);
+    }
+}
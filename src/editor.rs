@@ -0,0 +1,296 @@
+use crate::language::Language;
+use crate::word::SEPARATOR;
+use crate::WORD_LENGTH;
+
+/// The guess that is being typed on the current row, with a cursor that can be moved through it.
+/// The letters are always contiguous, there are no empty places before the last letter. In phrase
+/// mode the space between the words is a fixed tile that is skipped, it is only in the text.
+pub struct GuessEditor {
+    letters: Vec<char>,
+    /// The place in the row the next letter is typed, between 0 and the amount of letters
+    cursor: usize,
+    /// If typed letters replace the letter under the cursor instead of being inserted before it
+    overwrite: bool,
+    /// The tiles on the row, the letters and the space of a phrase
+    tiles: usize,
+    /// The tile of the space between the words of a phrase
+    separator: Option<usize>,
+}
+
+impl Default for GuessEditor {
+    fn default() -> GuessEditor {
+        GuessEditor::new(WORD_LENGTH, None)
+    }
+}
+
+impl GuessEditor {
+    /// An editor for a row of tiles with the space of a phrase on a tile, or for a word without it
+    pub fn new(tiles: usize, separator: Option<usize>) -> GuessEditor {
+        GuessEditor {
+            letters: Vec::new(),
+            cursor: 0,
+            overwrite: false,
+            tiles,
+            separator,
+        }
+    }
+
+    /// The letters typed so far, with the space of a phrase once there are letters after it
+    pub fn text(&self) -> String {
+        let space = |index| (Some(index) == self.separator).then_some(SEPARATOR);
+        self.letters
+            .iter()
+            .enumerate()
+            .flat_map(|(index, letter)| space(index).into_iter().chain([*letter]))
+            .collect()
+    }
+
+    /// The letters that fit on the row, the space of a phrase takes a tile
+    pub fn capacity(&self) -> usize {
+        self.tiles - self.separator.is_some() as usize
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.letters.len() == self.capacity()
+    }
+
+    /// The tile the cursor is on, `None` if the cursor is past the last tile of a full row
+    pub fn cursor(&self) -> Option<usize> {
+        let skipped = self.separator.is_some_and(|place| self.cursor >= place) as usize;
+        (self.cursor < self.capacity()).then_some(self.cursor + skipped)
+    }
+
+    /// Type a letter at the cursor, depending on the mode it is inserted or replaces the letter
+    /// under the cursor
+    pub fn type_letter(&mut self, letter: char) {
+        if self.overwrite && self.cursor < self.letters.len() {
+            self.letters[self.cursor] = letter;
+            self.cursor += 1;
+        } else if self.letters.len() < self.capacity() {
+            self.letters.insert(self.cursor, letter);
+            self.cursor += 1;
+        }
+    }
+
+    /// Combine the letter before the cursor with the typed key if they form a compose sequence of
+    /// the language, the composed letter is only used if it is in the alphabet
+    pub fn compose(&mut self, language: &Language, key: char, alphabet: &[char]) {
+        if self.cursor == 0 {
+            return;
+        }
+        let before = &mut self.letters[self.cursor - 1];
+        if let Some(composed) = language.compose(*before, key) {
+            if alphabet.contains(&composed) {
+                *before = composed;
+            }
+        }
+    }
+
+    /// Join the typed key with the letter before the cursor when the pair is written on a single
+    /// tile in the language, returns if they were joined
+    pub fn join_digraph(&mut self, language: &Language, key: char) -> bool {
+        let Some(before) = self
+            .cursor
+            .checked_sub(1)
+            .map(|index| &mut self.letters[index])
+        else {
+            return false;
+        };
+        match language.digraph(*before, key) {
+            Some(joined) => {
+                *before = joined;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the letter before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.letters.remove(self.cursor);
+        }
+    }
+
+    /// Remove the letter under the cursor, at the end of the row this works like a backspace
+    pub fn delete(&mut self) {
+        if self.cursor < self.letters.len() {
+            self.letters.remove(self.cursor);
+        } else {
+            self.backspace();
+        }
+    }
+
+    /// Replace the letters of the row, the cursor goes to the end
+    pub fn set_text(&mut self, text: &str) {
+        let capacity = self.capacity();
+        self.letters = text
+            .chars()
+            .filter(|chr| *chr != SEPARATOR)
+            .take(capacity)
+            .collect();
+        self.cursor = self.letters.len();
+    }
+
+    /// Remove all the letters of the row
+    pub fn clear(&mut self) {
+        self.letters.clear();
+        self.cursor = 0;
+    }
+
+    /// Remove the letters before the cursor, like deleting the word before the cursor in a shell.
+    /// A guess is a single word so the letters after the cursor are kept to be fixed up.
+    pub fn delete_word(&mut self) {
+        self.letters.drain(..self.cursor);
+        self.cursor = 0;
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.letters.len());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.letters.len();
+    }
+
+    /// Put the cursor on a place in the row, like where it is on a row typed somewhere else
+    pub fn move_to(&mut self, cursor: usize) {
+        let skipped = self.separator.is_some_and(|place| cursor > place) as usize;
+        self.cursor = (cursor - skipped).min(self.letters.len());
+    }
+
+    /// Switch between inserting and overwriting letters
+    pub fn toggle_overwrite(&mut self) {
+        self.overwrite = !self.overwrite;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language;
+
+    fn typed(text: &str) -> GuessEditor {
+        let mut editor = GuessEditor::default();
+        for letter in text.chars() {
+            editor.type_letter(letter);
+        }
+        editor
+    }
+
+    #[test]
+    fn the_cursor_stays_within_the_letters() {
+        let mut editor = typed("kaa");
+        assert_eq!(editor.cursor(), Some(3));
+        editor.move_right();
+        assert_eq!(editor.cursor(), Some(3));
+        editor.move_home();
+        editor.move_left();
+        assert_eq!(editor.cursor(), Some(0));
+        editor.move_right();
+        assert_eq!(editor.cursor(), Some(1));
+        editor.move_to(4);
+        assert_eq!(editor.cursor(), Some(3));
+        editor.move_end();
+        assert_eq!(editor.cursor(), Some(3));
+
+        // On a full row the cursor is past the last tile
+        let editor = typed("kaart");
+        assert!(editor.is_full());
+        assert_eq!(editor.cursor(), None);
+    }
+
+    #[test]
+    fn letters_are_inserted_or_overwritten_at_the_cursor() {
+        let mut editor = typed("kart");
+        editor.move_to(2);
+        editor.type_letter('a');
+        assert_eq!(
+            (editor.text().as_str(), editor.cursor()),
+            ("kaart", Some(3))
+        );
+        // A full row takes no more letters
+        editor.type_letter('s');
+        assert_eq!(editor.text(), "kaart");
+
+        editor.toggle_overwrite();
+        editor.move_home();
+        editor.type_letter('w');
+        assert_eq!(
+            (editor.text().as_str(), editor.cursor()),
+            ("waart", Some(1))
+        );
+        // Overwriting at the end of a row that isn't full adds the letter
+        let mut editor = typed("taa");
+        editor.toggle_overwrite();
+        editor.type_letter('r');
+        assert_eq!(editor.text(), "taar");
+    }
+
+    #[test]
+    fn the_letter_before_the_cursor_is_composed() {
+        let mut editor = typed("ca");
+        editor.compose(&language::DUTCH, '\'', &['c', 'a']);
+        assert_eq!(editor.text(), "ca");
+        editor.compose(&language::DUTCH, '\'', &['c', 'a', 'á']);
+        assert_eq!(editor.text(), "cá");
+        editor.move_home();
+        editor.compose(&language::DUTCH, '\'', &['c', 'a', 'á']);
+        assert_eq!(editor.text(), "cá");
+    }
+
+    #[test]
+    fn digraphs_are_joined_on_a_single_tile() {
+        let mut editor = typed("bi");
+        assert!(!editor.join_digraph(&language::DUTCH, 'k'));
+        assert!(editor.join_digraph(&language::DUTCH, 'j'));
+        assert_eq!((editor.text().as_str(), editor.cursor()), ("bĳ", Some(2)));
+
+        // The letter before the cursor is joined, not the last one
+        let mut editor = typed("ik");
+        editor.move_to(1);
+        assert!(editor.join_digraph(&language::DUTCH, 'j'));
+        assert_eq!(editor.text(), "ĳk");
+        editor.move_home();
+        assert!(!editor.join_digraph(&language::DUTCH, 'j'));
+    }
+    #[test]
+    fn words_are_deleted_before_the_cursor() {
+        // At the start of the row there is nothing before the cursor
+        let mut editor = typed("kaart");
+        editor.move_home();
+        editor.delete_word();
+        assert_eq!(
+            (editor.text().as_str(), editor.cursor()),
+            ("kaart", Some(0))
+        );
+
+        let mut editor = typed("kaart");
+        editor.move_to(2);
+        editor.delete_word();
+        assert_eq!((editor.text().as_str(), editor.cursor()), ("art", Some(0)));
+
+        let mut editor = typed("kaa");
+        editor.delete_word();
+        assert_eq!((editor.text().as_str(), editor.cursor()), ("", Some(0)));
+    }
+
+    #[test]
+    fn clearing_removes_every_letter() {
+        for cursor in [0, 2, 5] {
+            let mut editor = typed("kaart");
+            editor.move_to(cursor);
+            editor.clear();
+            assert_eq!((editor.text().as_str(), editor.cursor()), ("", Some(0)));
+        }
+    }
+}
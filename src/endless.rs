@@ -0,0 +1,104 @@
+//! Endless runs, words are played one after the other until the lives run out or the run is quit.
+//! A word that isn't solved costs a life, every solved word draws numbers for the bingo card of
+//! the run.
+
+use crate::announce::Announcer;
+use crate::bingo::{self, Card};
+use crate::dictionary::Dictionary;
+use crate::events::EventSink;
+use crate::play::play_game;
+use crate::{init_ncurses, pick_words, GameOptions, Stopped};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The words that can be missed in a run
+pub const LIVES: u32 = 3;
+
+/// How far a run got, the runs are kept in the statistics
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Run {
+    /// The words that were solved
+    pub solved: u32,
+    /// The points of the solved words
+    pub points: u32,
+    /// The points of the prizes of the bingo card
+    pub prizes: u32,
+    /// The lives that were left, a run that wasn't quit ends without lives
+    pub lives: u32,
+    /// The words that weren't solved
+    pub missed: Vec<String>,
+    /// When the run ended, in seconds since the Unix epoch
+    pub at: u64,
+    pub language: String,
+}
+
+/// Play words until the lives run out, the words aren't played twice until all of them were
+pub fn play(
+    dictionary: &Dictionary,
+    answers: &[&'static str],
+    alphabet: &[char],
+    announcer: &mut Announcer,
+    events: &EventSink,
+    options: &GameOptions,
+) -> Result<Run, String> {
+    let mut card = Card::new(&mut rand::thread_rng());
+    let mut run = Run {
+        lives: LIVES,
+        language: options.language.code.to_string(),
+        ..Default::default()
+    };
+    let mut played = Vec::new();
+    loop {
+        let left = answers
+            .iter()
+            .filter(|word| !played.contains(*word))
+            .copied()
+            .collect::<Vec<_>>();
+        if left.is_empty() {
+            played.clear();
+            continue;
+        }
+        let word = pick_words(&left, 1, None)[0];
+        played.push(word);
+
+        let game_options = GameOptions {
+            bingo: Some(card),
+            turn: None,
+            lives: Some(run.lives),
+            ..*options
+        };
+        let result = match play_game(
+            dictionary,
+            alphabet.to_vec(),
+            vec![word],
+            announcer,
+            events,
+            None,
+            &game_options,
+        ) {
+            Ok(result) => result,
+            Err(Stopped::Failed(err)) => return Err(err.to_string()),
+            Err(_) => break,
+        };
+        // The word was shown at the end of the game
+        if !result.won {
+            run.lives -= 1;
+            run.missed.push(word.to_string());
+            match run.lives {
+                0 => break,
+                _ => continue,
+            }
+        }
+        run.solved += 1;
+        run.points += result.score.total();
+
+        let _window_ender = init_ncurses(options.theme);
+        ncurses::timeout(-1);
+        run.prizes += bingo::draw_numbers(&mut card, options.a11y);
+    }
+    run.at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    Ok(run)
+}
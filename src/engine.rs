@@ -0,0 +1,430 @@
+//! The rules of a game as a state machine, apart from the screen. The screen sends what the player
+//! does to `Game::handle_event` and shows what the transition it gets back tells. What a guess
+//! does to the game can be tested this way without a terminal.
+
+use crate::candidates::CandidateSet;
+use crate::dictionary::Dictionary;
+use crate::error::Error;
+use crate::messages::Notice;
+use crate::rules::{self, Feedback, HardMode, Pattern};
+use crate::script::Scripts;
+use crate::strings::{fill, strings};
+use crate::word;
+use lingo_core::knowledge::Knowledge;
+
+/// Where a game is
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameState {
+    /// The player is typing a guess
+    Typing,
+    /// A guess was accepted, it is verified on the boards next
+    Scoring,
+    Won,
+    Lost,
+    /// The game is paused
+    Menu,
+}
+
+/// What happens during a game
+#[derive(Debug)]
+pub enum Event {
+    /// The player submits a full row
+    Submit(String),
+    /// Verify the accepted guess on the boards
+    Score,
+    /// The time for the row ran out before a guess was accepted
+    TimeUp,
+    Pause,
+    Resume,
+    /// The opponent found the word first
+    OpponentSolved,
+}
+
+/// What an event changed
+#[derive(Debug)]
+pub enum Transition {
+    /// The event means nothing in the state the game is in
+    Ignored,
+    /// The guess isn't allowed, the notice tells why
+    Rejected(Notice),
+    /// The guess is allowed, it is scored next
+    Accepted,
+    Scored(Scored),
+    /// The row is lost without a guess, the next row is typed
+    Burned,
+    Paused,
+    Resumed,
+    /// The game was lost without a guess of the player
+    Ended,
+}
+
+/// The feedback on a guess on the boards that weren't solved before it
+#[derive(Debug)]
+pub struct Scored {
+    pub guess: String,
+    /// The index of each board with its feedback, a phrase can have more tiles than a word
+    pub patterns: Vec<(usize, Vec<Feedback>)>,
+    /// The words that were found with the guess
+    pub found: Vec<&'static str>,
+}
+
+/// Gives the notice for a guess that isn't allowed
+type Check<'a> = Box<dyn Fn(&str) -> Option<Notice> + 'a>;
+
+pub struct Game<'a> {
+    dictionary: &'a Dictionary,
+    scripts: &'a Scripts,
+    state: GameState,
+    /// The hidden word of every board
+    targets: Vec<&'static str>,
+    max_guesses: u32,
+    /// The letters that can be guessed
+    alphabet: Vec<char>,
+    /// What is known about the letters, the knowledge is different for every board
+    knowledge: Vec<Knowledge>,
+    solved: Vec<bool>,
+    /// The guess that is scored next
+    accepted: Option<String>,
+    /// The verified guesses
+    guessed: Vec<String>,
+    /// The rows that were lost because their time ran out
+    burned: u32,
+    /// The patterns of the verified guesses, only kept for games with a single board of words
+    patterns: Vec<Pattern>,
+    /// In evil mode the word changes to dodge every guess for as long as possible
+    evil: Option<CandidateSet>,
+    hard_mode: Option<HardMode>,
+    /// The rules of a mode for the guesses, they replace the lookup in the dictionary
+    check: Option<Check<'a>>,
+}
+
+impl<'a> Game<'a> {
+    /// Start a game with a board for every word of `targets`, the letters of `alphabet` start
+    /// without any knowledge about them. An evil game moves the word between the `evil` answers.
+    pub fn new(
+        dictionary: &'a Dictionary,
+        scripts: &'a Scripts,
+        alphabet: &[char],
+        targets: Vec<&'static str>,
+        max_guesses: u32,
+        evil: Option<&[&'static str]>,
+        hard: bool,
+    ) -> Game<'a> {
+        Game {
+            dictionary,
+            scripts,
+            state: GameState::Typing,
+            alphabet: alphabet.to_vec(),
+            knowledge: vec![Knowledge::default(); targets.len()],
+            solved: vec![false; targets.len()],
+            targets,
+            max_guesses,
+            accepted: None,
+            guessed: Vec::new(),
+            burned: 0,
+            patterns: Vec::new(),
+            evil: evil.map(CandidateSet::new),
+            hard_mode: hard.then(HardMode::default),
+            check: None,
+        }
+    }
+
+    /// Check the guesses with the rules of a mode instead of looking them up in the dictionary,
+    /// `check` gives the notice for a guess that isn't allowed
+    pub fn with_check(mut self, check: impl Fn(&str) -> Option<Notice> + 'a) -> Game<'a> {
+        self.check = Some(Box::new(check));
+        self
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Move the game on with what happened, the transition tells what changed
+    pub fn handle_event(&mut self, event: Event) -> Result<Transition, Error> {
+        let transition = match (self.state, event) {
+            (GameState::Typing, Event::Submit(guess)) => self.submit(guess),
+            (GameState::Scoring, Event::Score) => return self.score(),
+            (GameState::Typing, Event::TimeUp) => {
+                self.burned += 1;
+                if self.guesses() == self.max_guesses {
+                    self.state = GameState::Lost;
+                    Transition::Ended
+                } else {
+                    Transition::Burned
+                }
+            }
+            (GameState::Typing, Event::Pause) => {
+                self.state = GameState::Menu;
+                Transition::Paused
+            }
+            (GameState::Menu, Event::Resume) => {
+                self.state = GameState::Typing;
+                Transition::Resumed
+            }
+            (GameState::Typing | GameState::Menu, Event::OpponentSolved) => {
+                self.state = GameState::Lost;
+                Transition::Ended
+            }
+            _ => Transition::Ignored,
+        };
+        Ok(transition)
+    }
+
+    /// Accept the guess when it is allowed. A word that isn't in the dictionary or against the
+    /// rules of the mode, a guess that doesn't use every revealed hint in hard mode and a guess
+    /// against the house rules of the scripts are disallowed.
+    fn submit(&mut self, guess: String) -> Transition {
+        let unknown = match &self.check {
+            Some(check) => check(&guess),
+            None => (!self.dictionary.contains(&guess))
+                .then(|| Notice::error(fill(strings().not_in_dictionary, &[&guess]))),
+        };
+        let rejection = if unknown.is_some() {
+            unknown
+        } else if let Some(Err(message)) = self
+            .hard_mode
+            .as_ref()
+            .map(|hard_mode| hard_mode.check(&guess))
+        {
+            Some(Notice::warning(message))
+        } else {
+            self.scripts
+                .on_guess(&guess, self.guesses() + 1)
+                .map(Notice::warning)
+        };
+        match rejection {
+            Some(notice) => Transition::Rejected(notice),
+            None => {
+                self.accepted = Some(guess);
+                self.state = GameState::Scoring;
+                Transition::Accepted
+            }
+        }
+    }
+
+    /// Verify the accepted guess on every board that is still being played
+    fn score(&mut self) -> Result<Transition, Error> {
+        let Some(guess) = self.accepted.take() else {
+            return Ok(Transition::Ignored);
+        };
+
+        // In evil mode pick the word that keeps the most words possible, any of those words gives
+        // the same pattern
+        if let Some(candidates) = &mut self.evil {
+            self.targets[0] = candidates.dodge(&guess);
+        }
+
+        // Every letter of the guess has to be in the alphabet, the space of a phrase isn't a letter
+        if let Some(chr) = guess
+            .chars()
+            .find(|chr| *chr != word::SEPARATOR && !self.alphabet.contains(chr))
+        {
+            return Err(Error::UnknownLetter(chr));
+        }
+        let mut patterns = Vec::new();
+        let mut found = Vec::new();
+        for (index, target) in self.targets.iter().enumerate() {
+            if self.solved[index] {
+                continue;
+            }
+            let pattern = rules::score_tiles(&guess, target);
+            self.knowledge[index].record(&guess, &pattern);
+            self.solved[index] = guess == *target;
+            if self.solved[index] {
+                found.push(*target);
+            }
+            patterns.push((index, pattern));
+        }
+        // Hard mode and the replays only follow a game with a single board, the replays and the
+        // review only have room for words
+        if let (1, Some((_, pattern))) = (self.targets.len(), patterns.first()) {
+            if let Ok(pattern) = Pattern::try_from(&pattern[..]) {
+                self.patterns.push(pattern);
+            }
+            if let Some(hard_mode) = &mut self.hard_mode {
+                hard_mode.record(&guess, pattern);
+            }
+        }
+        self.guessed.push(guess.clone());
+
+        // The game ends when every word is found or when the guesses run out
+        self.state = if self.solved.iter().all(|solved| *solved) {
+            GameState::Won
+        } else if self.guesses() == self.max_guesses {
+            GameState::Lost
+        } else {
+            GameState::Typing
+        };
+        Ok(Transition::Scored(Scored {
+            guess,
+            patterns,
+            found,
+        }))
+    }
+
+    /// The amount of rows that were used, by verified guesses and by rows that ran out of time
+    pub fn guesses(&self) -> u32 {
+        self.guessed.len() as u32 + self.burned
+    }
+
+    pub fn targets(&self) -> &[&'static str] {
+        &self.targets
+    }
+
+    pub fn guessed(&self) -> &[String] {
+        &self.guessed
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    pub fn is_solved(&self, board: usize) -> bool {
+        self.solved[board]
+    }
+
+    /// What is known about the letters on a board
+    pub fn knowledge(&self, board: usize) -> &Knowledge {
+        &self.knowledge[board]
+    }
+
+    /// If the word can be guessed, in hard mode it has to use the revealed hints
+    pub fn allows(&self, word: &str) -> bool {
+        self.hard_mode
+            .as_ref()
+            .is_none_or(|hard_mode| hard_mode.allows(word))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Feedback;
+
+    #[test]
+    fn guesses_move_the_game_to_its_end() {
+        let dictionary: Dictionary = ["kaart", "tabak", "taken"].into_iter().collect();
+        let scripts = Scripts::default();
+        let alphabet = "kartbenz".chars().collect::<Vec<_>>();
+        let mut game = Game::new(
+            &dictionary,
+            &scripts,
+            &alphabet,
+            vec!["tabak"],
+            3,
+            None,
+            false,
+        );
+
+        let submit = |game: &mut Game, guess: &str| {
+            game.handle_event(Event::Submit(guess.to_string())).unwrap()
+        };
+        assert!(matches!(
+            submit(&mut game, "zzzzz"),
+            Transition::Rejected(_)
+        ));
+        assert!(matches!(submit(&mut game, "kaart"), Transition::Accepted));
+        assert_eq!(game.state(), GameState::Scoring);
+        assert!(matches!(submit(&mut game, "tabak"), Transition::Ignored));
+        assert!(matches!(
+            game.handle_event(Event::Score).unwrap(),
+            Transition::Scored(_)
+        ));
+        assert_eq!((game.state(), game.guesses()), (GameState::Typing, 1));
+        assert_eq!(game.knowledge(0).excluded(0), ['k']);
+        assert_eq!(game.knowledge(0).feedback('k'), Some(Feedback::WrongPlace));
+
+        // Nothing is guessed while the game is paused
+        game.handle_event(Event::Pause).unwrap();
+        assert!(matches!(submit(&mut game, "tabak"), Transition::Ignored));
+        game.handle_event(Event::Resume).unwrap();
+
+        submit(&mut game, "tabak");
+        match game.handle_event(Event::Score).unwrap() {
+            Transition::Scored(scored) => assert_eq!(scored.found, vec!["tabak"]),
+            transition => panic!("The guess wasn't scored: {:?}", transition),
+        }
+        assert_eq!(game.state(), GameState::Won);
+    }
+
+    #[test]
+    fn rows_that_run_out_of_time_are_lost() {
+        let dictionary: Dictionary = ["kaart", "tabak"].into_iter().collect();
+        let scripts = Scripts::default();
+        let alphabet = "kartb".chars().collect::<Vec<_>>();
+        let mut game = Game::new(
+            &dictionary,
+            &scripts,
+            &alphabet,
+            vec!["tabak"],
+            2,
+            None,
+            false,
+        );
+
+        assert!(matches!(
+            game.handle_event(Event::TimeUp).unwrap(),
+            Transition::Burned
+        ));
+        assert_eq!((game.state(), game.guesses()), (GameState::Typing, 1));
+        assert!(matches!(
+            game.handle_event(Event::TimeUp).unwrap(),
+            Transition::Ended
+        ));
+        assert_eq!(game.state(), GameState::Lost);
+    }
+
+    #[test]
+    fn modes_check_the_guesses_with_their_own_rules() {
+        let dictionary = Dictionary::default();
+        let scripts = Scripts::default();
+        let alphabet = "12345+".chars().collect::<Vec<_>>();
+        let mut game = Game::new(
+            &dictionary,
+            &scripts,
+            &alphabet,
+            vec!["12+34"],
+            2,
+            None,
+            false,
+        )
+        .with_check(|guess| (!guess.contains('+')).then(|| Notice::warning("No sum")));
+
+        assert!(matches!(
+            game.handle_event(Event::Submit("12345".to_string()))
+                .unwrap(),
+            Transition::Rejected(_)
+        ));
+        // The guess doesn't have to be in the dictionary
+        assert!(matches!(
+            game.handle_event(Event::Submit("21+43".to_string()))
+                .unwrap(),
+            Transition::Accepted
+        ));
+    }
+    #[test]
+    fn evil_games_only_move_between_the_answers() {
+        // The offensive "taken" can be guessed but is never an answer. Without the answers evil
+        // mode would dodge the guess by moving to it.
+        let dictionary: Dictionary = ["kaart", "taken"].into_iter().collect();
+        let answers = ["kaart"];
+        let scripts = Scripts::default();
+        let alphabet = "kartben".chars().collect::<Vec<_>>();
+        let mut game = Game::new(
+            &dictionary,
+            &scripts,
+            &alphabet,
+            vec!["kaart"],
+            3,
+            Some(&answers),
+            false,
+        );
+
+        game.handle_event(Event::Submit("kaart".to_string()))
+            .unwrap();
+        game.handle_event(Event::Score).unwrap();
+        assert_eq!(game.targets(), ["kaart"]);
+        assert_eq!(game.state(), GameState::Won);
+    }
+}
@@ -0,0 +1,33 @@
+//! The errors that stop a game. A game runs with the terminal taken over by ncurses, so these are
+//! returned up to where the terminal is restored and printed there instead of panicking on the
+//! screen.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The word lists have no words of the length that is played
+    NoWords { length: usize },
+    /// A guess has a letter that isn't in the alphabet of the word lists
+    UnknownLetter(char),
+    /// The terminal can't be drawn on or read from
+    Terminal(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoWords { length } => {
+                write!(f, "The word lists have no words of {} letters", length)
+            }
+            Error::UnknownLetter(letter) => write!(
+                f,
+                "The letter {} isn't in the alphabet of the word lists",
+                letter
+            ),
+            Error::Terminal(err) => write!(f, "The terminal could not be used: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,116 @@
+//! Game events as json lines for overlays, like the ones streamers show on top of the game. The
+//! events only hold what viewers of the stream may see: the colors of the guesses and the shared
+//! result, never the letters or the word.
+
+use crate::rules::Feedback;
+use crate::share::Share;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// How long the game waits on exit for the last events to be written
+const FLUSH_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// A game started
+    RoundStarted {
+        language: &'a str,
+        boards: usize,
+        max_guesses: u32,
+    },
+    /// A guess was verified on one of the boards
+    GuessVerified {
+        /// The number of the guess, starting at 1
+        guess: u32,
+        /// The index of the board, starting at 0
+        board: usize,
+        pattern: &'a [Feedback],
+    },
+    /// The game ended, with the result as it would be shared without the word
+    GameFinished { result: &'a Share },
+}
+
+/// Writes events to a Unix socket, named pipe or file. Opening a named pipe waits for a reader, so
+/// the writing happens on its own thread and the game never waits on it.
+pub struct EventSink {
+    sender: Option<Sender<String>>,
+    /// Closed by the writing thread when it is done
+    done: Option<Receiver<()>>,
+}
+
+/// Open the events output, a Unix socket is connected to and anything else is appended to
+fn open(path: &Path) -> io::Result<Box<dyn Write>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::UnixStream;
+
+        let is_socket = std::fs::metadata(path).is_ok_and(|meta| meta.file_type().is_socket());
+        if is_socket {
+            return Ok(Box::new(UnixStream::connect(path)?));
+        }
+    }
+    Ok(Box::new(
+        OpenOptions::new().create(true).append(true).open(path)?,
+    ))
+}
+
+impl EventSink {
+    /// A sink that doesn't write anything
+    pub fn disabled() -> Self {
+        EventSink {
+            sender: None,
+            done: None,
+        }
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let (sender, lines) = mpsc::channel::<String>();
+        let (finished, done) = mpsc::channel::<()>();
+        thread::spawn(move || {
+            // The overlay is an addition to the game, when it can't be written the events are
+            // dropped
+            let mut output = match open(&path) {
+                Ok(output) => output,
+                Err(_) => return,
+            };
+            for line in lines {
+                if writeln!(output, "{}", line)
+                    .and_then(|_| output.flush())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            // Dropping the sender tells the game everything has been written
+            drop(finished);
+        });
+
+        EventSink {
+            sender: Some(sender),
+            done: Some(done),
+        }
+    }
+
+    pub fn emit(&self, event: &Event) {
+        if let (Some(sender), Ok(line)) = (&self.sender, serde_json::to_string(event)) {
+            let _ = sender.send(line);
+        }
+    }
+}
+
+impl Drop for EventSink {
+    fn drop(&mut self) {
+        // Let the thread write the last events, without waiting forever on a pipe nobody reads
+        self.sender = None;
+        if let Some(done) = &self.done {
+            let _ = done.recv_timeout(FLUSH_TIMEOUT);
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! The verified tiles as images, rounded squares in the colors of the theme. Terminals with the
+//! kitty graphics protocol get the square under the letter, terminals with sixel get an image with
+//! the letter drawn in. The images are drawn after ncurses has refreshed the screen, everywhere
+//! else the tiles stay text.
+
+use crate::rules::Feedback;
+use crate::theme::Rgb;
+use base64::Engine;
+use itertools::Itertools;
+use serde::Deserialize;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
+
+/// If the tiles are drawn as images, from the config file
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TileGraphics {
+    #[default]
+    Off,
+    /// Images on the terminals that are known to show them
+    Auto,
+    Kitty,
+    Sixel,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+}
+
+/// The size of a cell when the terminal doesn't tell it
+const CELL: (usize, usize) = (10, 20);
+
+/// The letters of the sixel images, five pixels wide and seven high
+const FONT: [[u8; 7]; 26] = [
+    [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+    [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e],
+    [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e],
+    [0x1e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1e],
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f],
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10],
+    [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f],
+    [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+    [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c],
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f],
+    [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11],
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+    [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+    [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10],
+    [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d],
+    [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11],
+    [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e],
+    [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04],
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a],
+    [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11],
+    [0x11, 0x11, 0x0a, 0x04, 0x04, 0x04, 0x04],
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f],
+];
+
+/// The pixels of an image
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Pixel {
+    Clear,
+    Tile,
+    Letter,
+}
+
+/// A tile that is drawn on the next refresh
+struct Queued {
+    y: i32,
+    x: i32,
+    feedback: Feedback,
+    text: String,
+}
+
+#[derive(Default)]
+struct State {
+    /// The colors of the correct, wrong place and wrong tiles, without them there are no images
+    colors: Option<[Rgb; 3]>,
+    /// The colors the kitty images were sent in
+    sent: Option<[Rgb; 3]>,
+    queued: Vec<Queued>,
+}
+
+static PROTOCOL: OnceLock<Option<Protocol>> = OnceLock::new();
+static STATE: Mutex<State> = Mutex::new(State {
+    colors: None,
+    sent: None,
+    queued: Vec::new(),
+});
+
+/// Draw the tiles like this from now on, only the first call counts
+pub fn use_graphics(graphics: TileGraphics) {
+    let protocol = match graphics {
+        TileGraphics::Off => None,
+        TileGraphics::Auto => detect(|name| std::env::var(name).ok()),
+        TileGraphics::Kitty => Some(Protocol::Kitty),
+        TileGraphics::Sixel => Some(Protocol::Sixel),
+    };
+    PROTOCOL.get_or_init(|| protocol);
+}
+
+/// The protocol of the terminal from its variables. Inside tmux or screen the images would have to
+/// be passed through, so those get text.
+fn detect(var: impl Fn(&str) -> Option<String>) -> Option<Protocol> {
+    let term = var("TERM").unwrap_or_default();
+    let program = var("TERM_PROGRAM").unwrap_or_default();
+    if term.starts_with("screen") || term.starts_with("tmux") {
+        None
+    } else if var("KITTY_WINDOW_ID").is_some()
+        || term == "xterm-kitty"
+        || ["WezTerm", "ghostty"].contains(&program.as_str())
+    {
+        Some(Protocol::Kitty)
+    } else if program == "iTerm.app"
+        || ["foot", "mlterm"].iter().any(|name| term.starts_with(name))
+        || term.contains("sixel")
+    {
+        Some(Protocol::Sixel)
+    } else {
+        None
+    }
+}
+
+/// Draw the tiles in these colors, `None` for a theme that doesn't fill the tiles
+pub fn use_colors(colors: Option<[Rgb; 3]>) {
+    STATE.lock().expect("The state is never poisoned").colors = colors;
+}
+
+/// Queue the image of a tile on the three cells from the row and column. Returns the protocol it
+/// is drawn with, `None` when the tile has to be text.
+pub fn queue(y: i32, x: i32, feedback: Feedback, text: &str) -> Option<Protocol> {
+    let protocol = (*PROTOCOL.get()?)?;
+    let mut state = STATE.lock().expect("The state is never poisoned");
+    state.colors?;
+    // The sixel images carry the letter, the font only has A to Z
+    if protocol == Protocol::Sixel && glyphs(text).is_none() {
+        return None;
+    }
+    state.queued.push(Queued {
+        y,
+        x,
+        feedback,
+        text: text.to_string(),
+    });
+    Some(protocol)
+}
+
+/// Forget the tiles of the screen that is being cleared
+pub fn clear() {
+    STATE
+        .lock()
+        .expect("The state is never poisoned")
+        .queued
+        .clear();
+}
+
+/// Draw the queued tiles over what ncurses drew
+pub fn flush() {
+    let Some(Some(protocol)) = PROTOCOL.get() else {
+        return;
+    };
+    let mut state = STATE.lock().expect("The state is never poisoned");
+    let mut out = Vec::new();
+    match protocol {
+        Protocol::Kitty => kitty(&mut state, &mut out),
+        Protocol::Sixel => sixel(&state, &mut out),
+    }
+    state.queued.clear();
+    let mut stdout = io::stdout();
+    if let Err(err) = stdout.write_all(&out).and_then(|_| stdout.flush()) {
+        log::warn!("Could not draw the tiles: {}", err);
+    }
+}
+
+fn index(feedback: Feedback) -> usize {
+    match feedback {
+        Feedback::Correct => 0,
+        Feedback::WrongPlace => 1,
+        Feedback::Wrong => 2,
+    }
+}
+
+/// The width and height in pixels of a cell
+fn cell_size() -> (usize, usize) {
+    #[cfg(unix)]
+    {
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        // Safe, the size is only written to
+        let read = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+        if read == 0 && size.ws_col > 0 && size.ws_row > 0 && size.ws_xpixel > 0 {
+            return (
+                (size.ws_xpixel / size.ws_col) as usize,
+                (size.ws_ypixel / size.ws_row) as usize,
+            );
+        }
+    }
+    CELL
+}
+
+/// The letters of the text in the font, `None` when the font doesn't have one of them
+fn glyphs(text: &str) -> Option<Vec<[u8; 7]>> {
+    text.chars()
+        .map(|letter| match letter {
+            'A'..='Z' => Some(FONT[letter as usize - 'A' as usize]),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A rounded square of the size with the text in the middle, `None` when the font doesn't have a
+/// letter of the text
+fn pixels(width: usize, height: usize, text: &str) -> Option<Vec<Pixel>> {
+    let glyphs = glyphs(text)?;
+    let scale = (height * 6 / 10 / 7).max(1);
+    let text_width = (glyphs.len() * 6).saturating_sub(1) * scale;
+    let left = width.saturating_sub(text_width) / 2;
+    let top = height.saturating_sub(7 * scale) / 2;
+    let radius = width.min(height) / 4;
+
+    let mut image = vec![Pixel::Clear; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            // The distance into the rounded corner, if the pixel is in one
+            let dx = radius.saturating_sub(x.min(width - 1 - x));
+            let dy = radius.saturating_sub(y.min(height - 1 - y));
+            if dx * dx + dy * dy > radius * radius {
+                continue;
+            }
+            let (column, row) = (
+                (x.wrapping_sub(left)) / scale,
+                (y.wrapping_sub(top)) / scale,
+            );
+            let letter = x >= left && y >= top && row < 7 && column % 6 < 5;
+            let lit = letter
+                && glyphs
+                    .get(column / 6)
+                    .is_some_and(|glyph| glyph[row] & (0x10 >> (column % 6)) != 0);
+            image[y * width + x] = if lit { Pixel::Letter } else { Pixel::Tile };
+        }
+    }
+    Some(image)
+}
+
+/// Move the cursor to the cell, the escape codes count from 1
+fn goto(out: &mut Vec<u8>, y: i32, x: i32) {
+    let _ = write!(out, "\x1b[{};{}H", y + 1, x + 1);
+}
+
+/// The kitty images are sent once in the colors, after that they are only placed. The placements
+/// of the last refresh are removed first. The images go under the text, so the letters that
+/// ncurses drew stay on top.
+fn kitty(state: &mut State, out: &mut Vec<u8>) {
+    let Some(colors) = state.colors else {
+        return;
+    };
+    if state.sent != Some(colors) {
+        let (width, height) = cell_size();
+        let (width, height) = (3 * width, height);
+        let image = pixels(width, height, "").expect("There are no letters to miss");
+        for (id, color) in colors.iter().enumerate() {
+            let rgba = image
+                .iter()
+                .flat_map(|pixel| match pixel {
+                    Pixel::Clear => [0, 0, 0, 0],
+                    _ => [color.0, color.1, color.2, 255],
+                })
+                .collect::<Vec<u8>>();
+            let data = base64::engine::general_purpose::STANDARD.encode(rgba);
+            let chunks = data.as_bytes().chunks(4096).collect::<Vec<_>>();
+            for (number, chunk) in chunks.iter().enumerate() {
+                let more = (number + 1 < chunks.len()) as u8;
+                let _ = match number {
+                    0 => write!(
+                        out,
+                        "\x1b_Ga=t,q=2,f=32,i={},s={},v={},m={};",
+                        id + 1,
+                        width,
+                        height,
+                        more
+                    ),
+                    _ => write!(out, "\x1b_Gm={};", more),
+                };
+                out.extend_from_slice(chunk);
+                out.extend_from_slice(b"\x1b\\");
+            }
+        }
+        state.sent = Some(colors);
+    }
+
+    out.extend_from_slice(b"\x1b7\x1b_Ga=d,d=a,q=2\x1b\\");
+    for tile in &state.queued {
+        goto(out, tile.y, tile.x);
+        let id = index(tile.feedback) + 1;
+        let _ = write!(out, "\x1b_Ga=p,i={},c=3,r=1,z=-1,C=1,q=2\x1b\\", id);
+    }
+    out.extend_from_slice(b"\x1b8");
+}
+
+/// The sixel images are sent on every refresh, they take the place of the cells
+fn sixel(state: &State, out: &mut Vec<u8>) {
+    let Some(colors) = state.colors else {
+        return;
+    };
+    let (width, height) = cell_size();
+    let (width, height) = (3 * width, height);
+    let percent = |value: u8| value as u32 * 100 / 255;
+    out.extend_from_slice(b"\x1b7");
+    for tile in &state.queued {
+        let Some(image) = pixels(width, height, &tile.text) else {
+            continue;
+        };
+        let color = colors[index(tile.feedback)];
+        // Black letters on the colors and white on the gray, like the filled text tiles
+        let letter = match tile.feedback {
+            Feedback::Wrong => 100,
+            _ => 0,
+        };
+        goto(out, tile.y, tile.x);
+        let _ = write!(
+            out,
+            "\x1bP0;1q\"1;1;{};{}#1;2;{};{};{}#2;2;{};{};{}",
+            width,
+            height,
+            percent(color.0),
+            percent(color.1),
+            percent(color.2),
+            letter,
+            letter,
+            letter
+        );
+        for band in (0..height).step_by(6) {
+            for (number, pixel) in [(1, Pixel::Tile), (2, Pixel::Letter)] {
+                let _ = write!(out, "#{}", number);
+                let sixels = (0..width).map(|x| {
+                    (0..6)
+                        .filter(|row| band + row < height)
+                        .filter(|row| image[(band + row) * width + x] == pixel)
+                        .fold(0, |bits, row| bits | 1 << row)
+                });
+                // Runs of the same sixel are written once with their length
+                for (count, bits) in sixels.dedup_with_count() {
+                    let chr = (63 + bits) as u8 as char;
+                    let _ = match count {
+                        1..=3 => write!(out, "{}", chr.to_string().repeat(count)),
+                        _ => write!(out, "!{}{}", count, chr),
+                    };
+                }
+                out.push(b'$');
+            }
+            out.push(b'-');
+        }
+        out.extend_from_slice(b"\x1b\\");
+    }
+    out.extend_from_slice(b"\x1b8");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_are_rounded_squares_with_the_letter() {
+        let var = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            detect(var(&[("TERM", "xterm-kitty")])),
+            Some(Protocol::Kitty)
+        );
+        assert_eq!(detect(var(&[("TERM", "foot")])), Some(Protocol::Sixel));
+        assert_eq!(
+            detect(var(&[("TERM", "tmux-256color"), ("KITTY_WINDOW_ID", "1")])),
+            None
+        );
+        assert_eq!(detect(var(&[("TERM", "xterm-256color")])), None);
+
+        let image = pixels(30, 20, "T").unwrap();
+        assert_eq!(image[0], Pixel::Clear);
+        assert_eq!(image[20 * 30 - 1], Pixel::Clear);
+        assert_eq!(image[30 + 15], Pixel::Tile);
+        // The middle of the T, in the row below its bar
+        assert_eq!(image[10 * 30 + 14], Pixel::Letter);
+        assert!(pixels(30, 20, "Ä").is_none());
+    }
+}
@@ -0,0 +1,405 @@
+//! Group play for people in the same room. The host deals a sequence of words and shows a join
+//! code, every player enters the code on their own machine and plays the same words offline.
+//! Afterwards each player hands the host a result code, the host checks the codes and ranks the
+//! players.
+//!
+//! The join code holds the seed the words are dealt from, the amount of rounds and a checksum of
+//! the word list so players with a different list are stopped before they play other words. A
+//! result code ends with a checksum over the join code and the dealt words, which catches typos
+//! and codes of another group. Everything in it is known to the players, so it doesn't stop a
+//! player from making up a result, the group has to trust each other.
+
+use crate::announce::Announcer;
+use crate::codes::{decode, encode, fnv};
+use crate::dict;
+use crate::dictionary::Dictionary;
+use crate::events::EventSink;
+use crate::language::Language;
+use crate::play::play_game;
+use crate::{pick_words, GameOptions, Stopped, GUESSES, WORD_LENGTH};
+use itertools::Itertools;
+use rand::Rng;
+use std::fmt;
+
+/// The bits of the seed in a join code
+const SEED_BITS: u32 = 24;
+/// The bits of the amount of rounds in a join code
+const ROUNDS_BITS: u32 = 4;
+/// The bits of the word list checksum in a join code
+const CHECKSUM_BITS: u32 = 12;
+/// The characters of the typo checksum of a result code
+const TYPO_CHECK_LENGTH: usize = 4;
+
+/// The letters of the large join code, each row of a glyph is three pixels wide
+const FONT: [(char, [&str; 5]); 33] = [
+    ('0', ["###", "# #", "# #", "# #", "###"]),
+    ('1', [" # ", "## ", " # ", " # ", "###"]),
+    ('2', ["###", "  #", "###", "#  ", "###"]),
+    ('3', ["###", "  #", "###", "  #", "###"]),
+    ('4', ["# #", "# #", "###", "  #", "  #"]),
+    ('5', ["###", "#  ", "###", "  #", "###"]),
+    ('6', ["###", "#  ", "###", "# #", "###"]),
+    ('7', ["###", "  #", "  #", "  #", "  #"]),
+    ('8', ["###", "# #", "###", "# #", "###"]),
+    ('9', ["###", "# #", "###", "  #", "###"]),
+    ('A', [" # ", "# #", "###", "# #", "# #"]),
+    ('B', ["## ", "# #", "## ", "# #", "## "]),
+    ('C', [" ##", "#  ", "#  ", "#  ", " ##"]),
+    ('D', ["## ", "# #", "# #", "# #", "## "]),
+    ('E', ["###", "#  ", "## ", "#  ", "###"]),
+    ('F', ["###", "#  ", "## ", "#  ", "#  "]),
+    ('G', [" ##", "#  ", "# #", "# #", " ##"]),
+    ('H', ["# #", "# #", "###", "# #", "# #"]),
+    ('J', ["  #", "  #", "  #", "# #", " # "]),
+    ('K', ["# #", "# #", "## ", "# #", "# #"]),
+    ('M', ["# #", "###", "###", "# #", "# #"]),
+    ('N', ["## ", "# #", "# #", "# #", "# #"]),
+    ('P', ["## ", "# #", "## ", "#  ", "#  "]),
+    ('Q', [" # ", "# #", "# #", "## ", " ##"]),
+    ('R', ["## ", "# #", "## ", "# #", "# #"]),
+    ('S', [" ##", "#  ", " # ", "  #", "## "]),
+    ('T', ["###", " # ", " # ", " # ", " # "]),
+    ('V', ["# #", "# #", "# #", "# #", " # "]),
+    ('W', ["# #", "# #", "###", "###", "# #"]),
+    ('X', ["# #", "# #", " # ", "# #", "# #"]),
+    ('Y', ["# #", "# #", " # ", " # ", " # "]),
+    ('Z', ["###", "  #", " # ", "#  ", "###"]),
+    ('-', ["   ", "   ", "###", "   ", "   "]),
+];
+
+/// A checksum of the word list, the same lists have the same checksum
+fn checksum(words: &[&str]) -> u64 {
+    fnv(&words.join("\n")) & ((1 << CHECKSUM_BITS) - 1)
+}
+
+/// The code a group plays with
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct GroupCode {
+    seed: u64,
+    rounds: u8,
+    checksum: u64,
+}
+
+impl GroupCode {
+    /// A new group with a random seed
+    pub fn new(rounds: u8, words: &[&str]) -> GroupCode {
+        GroupCode {
+            seed: rand::thread_rng().gen_range(0..1 << SEED_BITS),
+            rounds,
+            checksum: checksum(words),
+        }
+    }
+
+    /// Read a join code as it was typed, the dash and the case don't matter
+    pub fn parse(text: &str, words: &[&str]) -> Result<GroupCode, String> {
+        let text = text.replace(['-', ' '], "");
+        let value = decode(&text)
+            .filter(|_| text.len() * 5 == (SEED_BITS + ROUNDS_BITS + CHECKSUM_BITS) as usize)
+            .ok_or_else(|| format!("{} is not a join code", text))?;
+        let code = GroupCode {
+            seed: value >> (ROUNDS_BITS + CHECKSUM_BITS),
+            rounds: (value >> CHECKSUM_BITS) as u8 & ((1 << ROUNDS_BITS) - 1),
+            checksum: value & ((1 << CHECKSUM_BITS) - 1),
+        };
+        if code.rounds == 0 {
+            return Err(format!("{} is not a join code", text));
+        }
+        if code.checksum != checksum(words) {
+            return Err(
+                "The group plays with another word list, use the same list as the host".to_string(),
+            );
+        }
+        Ok(code)
+    }
+
+    /// The words of the rounds from the words that can be the word of a game, the same for
+    /// everyone with the code
+    pub fn deal(&self, answers: &[&'static str]) -> Vec<&'static str> {
+        (0..u64::from(self.rounds))
+            .map(|round| pick_words(answers, 1, Some(self.seed << ROUNDS_BITS | round))[0])
+            .collect()
+    }
+
+    /// The join code written large, for everyone in the room to read from the screen of the host
+    pub fn banner(&self) -> String {
+        let code = self.to_string();
+        (0..5)
+            .map(|row| {
+                code.chars()
+                    .filter_map(|chr| FONT.iter().find(|(glyph, _)| *glyph == chr))
+                    .map(|(_, rows)| rows[row].replace('#', "##").replace(' ', "  "))
+                    .join("  ")
+            })
+            .join("\n")
+    }
+}
+
+impl fmt::Display for GroupCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = (self.seed << (ROUNDS_BITS + CHECKSUM_BITS))
+            | (u64::from(self.rounds) << CHECKSUM_BITS)
+            | self.checksum;
+        let code = encode(value, 8);
+        write!(f, "{}-{}", &code[..4], &code[4..])
+    }
+}
+
+/// The result of a player in a group, the guesses of each round or `None` for a lost round
+pub struct GroupResult {
+    pub name: String,
+    pub guesses: Vec<Option<u32>>,
+}
+
+impl GroupResult {
+    /// The rounds as text, the amount of guesses or an X for a lost round
+    fn rounds(&self) -> String {
+        self.guesses
+            .iter()
+            .map(|guesses| guesses.map_or('X', |guesses| char::from_digit(guesses, 10).unwrap()))
+            .collect()
+    }
+
+    /// The checksum at the end of the result code, it catches typos but anyone can work it out
+    fn typo_check(&self, code: &GroupCode, dealt: &[&str]) -> String {
+        let checked = format!(
+            "{}|{}|{}|{}",
+            code,
+            self.name,
+            self.rounds(),
+            dealt.join(",")
+        );
+        encode(fnv(&checked), TYPO_CHECK_LENGTH)
+    }
+
+    /// The code the player hands to the host
+    pub fn code(&self, code: &GroupCode, dealt: &[&str]) -> String {
+        format!(
+            "{}-{}-{}",
+            self.name,
+            self.rounds(),
+            self.typo_check(code, dealt)
+        )
+    }
+
+    /// Check a result code of the group
+    pub fn parse(text: &str, code: &GroupCode, dealt: &[&str]) -> Result<GroupResult, String> {
+        let invalid = || format!("{} is not a result code of this group", text);
+        let (name, rounds, typo_check) =
+            text.trim().split('-').collect_tuple().ok_or_else(invalid)?;
+        if rounds.chars().count() != usize::from(code.rounds) {
+            return Err(invalid());
+        }
+        let guesses = rounds
+            .chars()
+            .map(|round| match round.to_ascii_uppercase() {
+                'X' => Some(None),
+                round => round
+                    .to_digit(10)
+                    .filter(|guesses| (1..=GUESSES).contains(guesses))
+                    .map(Some),
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        let result = GroupResult {
+            name: name.to_string(),
+            guesses,
+        };
+        if decode(typo_check) != decode(&result.typo_check(code, dealt)) {
+            return Err(format!(
+                "The result code of {} has a typo or is of another group",
+                name
+            ));
+        }
+        Ok(result)
+    }
+
+    /// The amount of rounds that were won
+    fn won(&self) -> usize {
+        self.guesses.iter().flatten().count()
+    }
+
+    /// The guesses of all the rounds, a lost round counts as one more guess than allowed
+    fn total(&self) -> u32 {
+        self.guesses
+            .iter()
+            .map(|guesses| guesses.unwrap_or(GUESSES + 1))
+            .sum()
+    }
+}
+
+/// The words that can be dealt, the offensive words are never dealt to a group
+fn answers(words: &[&'static str], language: &Language) -> Vec<&'static str> {
+    let offensive = dict::offensive_words(language);
+    words
+        .iter()
+        .filter(|word| !offensive.contains(*word))
+        .copied()
+        .collect()
+}
+
+/// Show the join code of a new group
+pub fn host(rounds: u8, words: &[&str]) {
+    let code = GroupCode::new(rounds, words);
+    println!("{}\n", code.banner());
+    println!("Join code {}, {} rounds", code, rounds);
+    println!("Every player runs: lingo group join {} <NAME>", code);
+    println!(
+        "Afterwards check the result codes with: lingo group results {} <RESULT>...",
+        code
+    );
+}
+
+/// Play the rounds of the group and print the result code
+pub fn join(
+    code: &GroupCode,
+    name: &str,
+    dictionary: &Dictionary,
+    alphabet: Vec<char>,
+    announcer: &mut Announcer,
+    events: &EventSink,
+    options: &GameOptions,
+) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|chr| chr.is_alphanumeric()) {
+        return Err("The name can only have letters and digits".to_string());
+    }
+
+    let dealt = code.deal(&answers(dictionary.words(WORD_LENGTH), options.language));
+    let mut result = GroupResult {
+        name: name.to_string(),
+        guesses: Vec::new(),
+    };
+    for word in dealt.iter() {
+        let game = play_game(
+            dictionary,
+            alphabet.clone(),
+            vec![*word],
+            announcer,
+            events,
+            None,
+            options,
+        );
+        match game {
+            Ok(game) => result.guesses.push(game.won.then_some(game.guesses)),
+            Err(Stopped::Failed(err)) => return Err(err.to_string()),
+            // Quitting gives up the rest of the rounds
+            Err(_) => break,
+        }
+    }
+    result.guesses.resize(dealt.len(), None);
+
+    println!("Words found {}/{}", result.won(), dealt.len());
+    println!("Your result code: {}", result.code(code, &dealt));
+    Ok(())
+}
+
+/// Check the result codes of the players and rank them
+pub fn results(code: &GroupCode, texts: &[String], words: &[&'static str], language: &Language) {
+    let dealt = code.deal(&answers(words, language));
+    let mut results = texts
+        .iter()
+        .filter_map(|text| {
+            GroupResult::parse(text, code, &dealt)
+                .map_err(|err| eprintln!("{}", err))
+                .ok()
+        })
+        .collect_vec();
+    results.sort_by(|a, b| {
+        b.won()
+            .cmp(&a.won())
+            .then_with(|| a.total().cmp(&b.total()))
+    });
+
+    println!("Group {}, the words were {}", code, dealt.join(", "));
+    let width = results
+        .iter()
+        .map(|result| result.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (rank, result) in results.iter().enumerate() {
+        println!(
+            "{:>2}. {:<width$}  {}  {} won, {} guesses",
+            rank + 1,
+            result.name,
+            result.rounds(),
+            result.won(),
+            result.total(),
+            width = width
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 6] = ["kaart", "tabak", "taken", "tafel", "stoel", "appel"];
+
+    /// A group with a fixed seed, so the checksums in the tests are always the same
+    fn group(rounds: u8) -> GroupCode {
+        GroupCode {
+            seed: 0x2a_5c_3e,
+            rounds,
+            checksum: checksum(&WORDS),
+        }
+    }
+
+    fn dealt(code: &GroupCode) -> Vec<&'static str> {
+        code.deal(&WORDS)
+    }
+
+    #[test]
+    fn join_codes_are_read_back() {
+        let code = GroupCode::new(7, &WORDS);
+        let text = code.to_string();
+        assert!(GroupCode::parse(&text, &WORDS) == Ok(code));
+        assert!(GroupCode::parse(&text.to_lowercase().replace('-', " "), &WORDS) == Ok(code));
+        assert!(GroupCode::parse(&text, &WORDS[1..]).is_err());
+        assert!(GroupCode::parse(&text[..6], &WORDS).is_err());
+    }
+
+    #[test]
+    fn everyone_in_the_group_gets_the_same_words() {
+        let code = group(5);
+        let words = dealt(&code);
+        assert_eq!(words.len(), 5);
+        assert_eq!(words, dealt(&code));
+        let joined = GroupCode::parse(&code.to_string(), &WORDS).unwrap();
+        assert_eq!(words, dealt(&joined));
+    }
+
+    #[test]
+    fn result_codes_are_read_back() {
+        let code = group(3);
+        let words = dealt(&code);
+        let result = GroupResult {
+            name: "Anna".to_string(),
+            guesses: vec![Some(3), None, Some(5)],
+        };
+        let text = result.code(&code, &words);
+        assert!(text.starts_with("Anna-3X5-"));
+        let parsed = GroupResult::parse(&text, &code, &words).unwrap();
+        assert_eq!(parsed.name, result.name);
+        assert_eq!(parsed.guesses, result.guesses);
+    }
+
+    #[test]
+    fn typos_in_result_codes_are_caught() {
+        let code = group(3);
+        let words = dealt(&code);
+        let result = GroupResult {
+            name: "Anna".to_string(),
+            guesses: vec![Some(3), None, Some(5)],
+        };
+        let text = result.code(&code, &words);
+        let typo = text.replace("-3X5-", "-3X4-");
+        assert!(GroupResult::parse(&typo, &code, &words).is_err());
+        assert!(GroupResult::parse(&text.replace("Anna", "Anne"), &code, &words).is_err());
+        assert!(GroupResult::parse("Anna-3X5", &code, &words).is_err());
+
+        let other = GroupCode {
+            seed: code.seed ^ 1,
+            ..code
+        };
+        assert!(GroupResult::parse(&text, &other, &dealt(&other)).is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! Games without a screen, played with the messages of the `protocol` module. Other programs, like
+//! bots and test scripts, play the game this way on stdin and stdout or over the network. A server
+//! plays a session of games with every connection, a new game starts when the last one ended.
+//! Spectators connect to a port of their own and see the guesses of every game, but not the words.
+
+use crate::dictionary::Dictionary;
+use crate::language::Language;
+use crate::protocol::{self, Message};
+use crate::rules::{self, HardMode, Pattern};
+use crate::{word, WORD_LENGTH};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// The longest line a client can send, a guess fits in it many times over. A longer line ends the
+/// game so a client can't fill the memory of the server.
+const MAX_LINE: u64 = 4096;
+
+fn send(output: &mut impl Write, message: &Message) -> io::Result<()> {
+    writeln!(output, "{}", protocol::encode(message))?;
+    output.flush()
+}
+
+/// The rules of the headless games
+pub struct Session<'a> {
+    pub dictionary: &'a Dictionary,
+    pub language: &'static Language,
+    pub hard: bool,
+    pub max_guesses: u32,
+}
+
+/// A headless game that is being played
+pub struct Game {
+    pub target: String,
+    hard_mode: Option<HardMode>,
+    /// The guesses that were accepted, with their colors
+    pub rows: Vec<(String, Pattern)>,
+    /// If the word was found, `None` while the game goes on
+    pub won: Option<bool>,
+}
+
+impl Session<'_> {
+    pub fn start(&self, target: &str) -> Game {
+        Game {
+            target: target.to_string(),
+            hard_mode: self.hard.then(HardMode::default),
+            rows: Vec::new(),
+            won: None,
+        }
+    }
+
+    /// Check the guess and score it against the word, the guess is written like the words of the
+    /// word list. The reason a guess isn't accepted is returned as the error.
+    pub fn guess(&self, game: &mut Game, guess: &str) -> Result<Pattern, String> {
+        if game.won.is_some() {
+            return Err("The game has ended".to_string());
+        }
+        if word::length(guess) != WORD_LENGTH {
+            return Err(format!("The word {} is not {} letters", guess, WORD_LENGTH));
+        }
+        if !self.dictionary.contains(guess) {
+            return Err(format!("The word {} is not in the dictionary", guess));
+        }
+        if let Some(hard_mode) = &game.hard_mode {
+            hard_mode.check(guess)?;
+        }
+
+        let pattern = rules::score(guess, &game.target);
+        if let Some(hard_mode) = &mut game.hard_mode {
+            hard_mode.record(guess, &pattern);
+        }
+        game.rows.push((guess.to_string(), pattern));
+        if guess == game.target {
+            game.won = Some(true);
+        } else if game.rows.len() == self.max_guesses as usize {
+            game.won = Some(false);
+        }
+        Ok(pattern)
+    }
+
+    /// The guess as it was typed, written like the words of the word list
+    pub fn compose(&self, guess: &str) -> String {
+        self.language
+            .join_digraphs(&word::compose(&guess.trim().to_lowercase()))
+    }
+
+    /// Play a game on the word, reading a guess from every line of the input. The game ends when
+    /// the word is found, the guesses run out or the input is closed, returns if the game ended.
+    /// The spectators are told about the game without the word.
+    pub fn play(
+        &self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+        target: &str,
+        watched: &impl Fn(Message),
+    ) -> io::Result<bool> {
+        let mut game = self.start(target);
+        let start = Message::Start {
+            language: self.language.code.to_string(),
+            letters: WORD_LENGTH,
+            guesses: self.max_guesses,
+        };
+        send(output, &start)?;
+        watched(start);
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if (&mut *input).take(MAX_LINE).read_line(&mut line)? == 0 {
+                return Ok(false);
+            }
+            if line.len() as u64 == MAX_LINE && !line.ends_with('\n') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("A line is longer than {} bytes", MAX_LINE),
+                ));
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let guess = match protocol::decode(&line) {
+                Ok(Message::Guess { word: guess }) => self.compose(&guess),
+                Ok(_) => {
+                    send(
+                        output,
+                        &Message::Rejected {
+                            message: "Only guesses can be sent".to_string(),
+                        },
+                    )?;
+                    continue;
+                }
+                Err(err) => {
+                    send(
+                        output,
+                        &Message::Rejected {
+                            message: format!("The line is not a message: {}", err),
+                        },
+                    )?;
+                    continue;
+                }
+            };
+
+            let pattern = match self.guess(&mut game, &guess) {
+                Ok(pattern) => pattern,
+                Err(message) => {
+                    send(output, &Message::Rejected { message })?;
+                    continue;
+                }
+            };
+            send(
+                output,
+                &Message::Result {
+                    guess: None,
+                    pattern,
+                },
+            )?;
+            watched(Message::Result {
+                guess: Some(guess.clone()),
+                pattern,
+            });
+
+            if let Some(won) = game.won {
+                send(
+                    output,
+                    &Message::Finished {
+                        won,
+                        word: Some(target.to_string()),
+                        message: None,
+                    },
+                )?;
+                watched(Message::Finished {
+                    won,
+                    word: None,
+                    message: None,
+                });
+                return Ok(true);
+            }
+        }
+    }
+}
+
+/// The spectators of a server and the games they are shown
+#[derive(Default)]
+struct Spectators {
+    streams: Vec<TcpStream>,
+    /// The messages of the games that are being played, for the spectators that join later
+    games: BTreeMap<usize, Vec<Message>>,
+}
+
+impl Spectators {
+    /// Send the message to every spectator, the ones that are gone are dropped
+    fn broadcast(&mut self, player: usize, message: Message) {
+        let watched = Message::Watched {
+            player,
+            message: Box::new(message.clone()),
+        };
+        self.streams
+            .retain_mut(|stream| send(stream, &watched).is_ok());
+        match message {
+            Message::Start { .. } => {
+                self.games.insert(player, vec![message]);
+            }
+            Message::Finished { .. } => {
+                self.games.remove(&player);
+            }
+            _ => self.games.entry(player).or_default().push(message),
+        }
+    }
+
+    /// Add a spectator, it is shown the games that are being played so far. A spectator that
+    /// doesn't keep up is dropped instead of holding up the games.
+    fn join(&mut self, mut stream: TcpStream) {
+        if stream
+            .set_write_timeout(Some(Duration::from_secs(1)))
+            .is_err()
+        {
+            return;
+        }
+        for (player, messages) in &self.games {
+            for message in messages {
+                let watched = Message::Watched {
+                    player: *player,
+                    message: Box::new(message.clone()),
+                };
+                if send(&mut stream, &watched).is_err() {
+                    return;
+                }
+            }
+        }
+        self.streams.push(stream);
+    }
+
+    /// The player disconnected, a game that wasn't finished ends for the spectators
+    fn leave(&mut self, player: usize) {
+        if self.games.contains_key(&player) {
+            self.broadcast(
+                player,
+                Message::Finished {
+                    won: false,
+                    word: None,
+                    message: Some("The player left".to_string()),
+                },
+            );
+        }
+    }
+}
+
+/// Play games with the client until it disconnects, each on a word of `pick`
+fn serve_client(
+    session: &Session,
+    stream: TcpStream,
+    pick: &impl Fn() -> &'static str,
+    watched: &impl Fn(Message),
+) -> io::Result<()> {
+    let mut input = BufReader::new(stream.try_clone()?);
+    let mut output = stream;
+    while session.play(&mut input, &mut output, pick(), watched)? {}
+    Ok(())
+}
+
+/// Let spectators connect on the port, they only receive the games and never send anything
+fn serve_spectators(listener: TcpListener, spectators: &Mutex<Spectators>) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                eprintln!("A spectator connected");
+                spectators.lock().unwrap().join(stream);
+            }
+            Err(err) => eprintln!("Could not accept a spectator: {}", err),
+        }
+    }
+}
+
+/// Host headless games on the port for any number of clients at the same time. With a port for
+/// spectators they can watch all the games.
+pub fn serve(
+    port: u16,
+    watch_port: Option<u16>,
+    session: &Session,
+    pick: impl Fn() -> &'static str + Sync,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    eprintln!("Serving games on port {}", port);
+    let spectator_listener = watch_port
+        .map(|port| TcpListener::bind(("0.0.0.0", port)))
+        .transpose()?;
+    if let Some(port) = watch_port {
+        eprintln!("Spectators can watch on port {}", port);
+    }
+    let spectators = Mutex::new(Spectators::default());
+    let players = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        if let Some(listener) = spectator_listener {
+            let spectators = &spectators;
+            scope.spawn(move || serve_spectators(listener, spectators));
+        }
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    eprintln!("Could not accept a client: {}", err);
+                    continue;
+                }
+            };
+            let pick = &pick;
+            let spectators = &spectators;
+            let player = players.fetch_add(1, Ordering::Relaxed) + 1;
+            scope.spawn(move || {
+                let address = stream
+                    .peer_addr()
+                    .map_or_else(|_| "A client".to_string(), |address| address.to_string());
+                eprintln!("{} connected as player {}", address, player);
+                let watched =
+                    |message: Message| spectators.lock().unwrap().broadcast(player, message);
+                match serve_client(session, stream, pick, &watched) {
+                    Ok(()) => eprintln!("{} disconnected", address),
+                    Err(err) => eprintln!("{} disconnected: {}", address, err),
+                }
+                spectators.lock().unwrap().leave(player);
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language;
+
+    #[test]
+    fn long_lines_end_the_game() {
+        let dictionary = Dictionary::load(&["kaart", "tabak", "stoel"]);
+        let session = Session {
+            dictionary: &dictionary,
+            language: &language::DUTCH,
+            hard: false,
+            max_guesses: 5,
+        };
+        let line = format!("{}\n", "x".repeat(MAX_LINE as usize * 4));
+        let mut output = Vec::new();
+        let result = session.play(&mut line.as_bytes(), &mut output, "tabak", &|_| {});
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        let guess = "{\"type\":\"guess\",\"word\":\"tabak\"}\n";
+        let mut output = Vec::new();
+        assert!(session
+            .play(&mut guess.as_bytes(), &mut output, "tabak", &|_| {})
+            .unwrap());
+    }
+}
@@ -0,0 +1,161 @@
+//! Browse the finished games of the history, with their boards and a review of their guesses
+
+use crate::keymap::{ENTER, ESCAPE};
+use crate::language::Language;
+use crate::messages::Notice;
+use crate::navigation::Navigator;
+use crate::stats::Played;
+use crate::strings::{fill, strings};
+use crate::theme::Theme;
+use crate::{render_game, review, rules, word, Board, BoardState, GUESSES};
+
+/// The word as it is shown on the board
+fn display(word: &str) -> String {
+    word.chars().map(word::display).collect()
+}
+
+/// The day of the time in seconds since the Unix epoch, like 2024-03-01
+pub fn date(seconds: u64) -> String {
+    // Count the days in eras of 400 years that start on the first of March, so the leap day is the
+    // last day of a year
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+/// The line of a game in the list
+fn describe(played: &Played) -> String {
+    let when = match played.at {
+        0 => strings().unknown_date.to_string(),
+        at => date(at),
+    };
+    let outcome = if played.won {
+        fill(strings().won_in, &[&played.guesses])
+    } else {
+        strings().lost.to_string()
+    };
+    let language = match played.language.as_str() {
+        "" => "??",
+        code => code,
+    };
+    format!(
+        "{}  {}  {}  {}{}",
+        when,
+        language,
+        display(&played.word),
+        outcome,
+        if played.hard {
+            format!("  {}", strings().hard)
+        } else {
+            String::new()
+        }
+    )
+}
+
+/// Show the board of a game as it looked at the end, returns if the guesses should be reviewed
+fn show_board(played: &Played) -> bool {
+    let max_guesses = match played.max_guesses {
+        0 => GUESSES,
+        max_guesses => max_guesses,
+    };
+    let mut board = Board::new(max_guesses.max(played.guessed.len() as u32));
+    for (row, (guess, pattern)) in played.guessed.iter().zip(&played.patterns).enumerate() {
+        board.rows[row] = rules::guessed_word(guess, pattern);
+    }
+    board.solved = played.won;
+    let mut board_state = BoardState {
+        boards: vec![board],
+        hide_possible_words: true,
+        ..Default::default()
+    };
+    board_state.messages.push(Notice::info(fill(
+        strings().history_board,
+        &[&describe(played)],
+    )));
+    render_game(&board_state);
+    ncurses::getch() == 'r' as i32
+}
+
+/// Browse the games of the history, newest first. Only the games in `language` can be reviewed,
+/// the review needs their word list.
+pub fn browse(
+    history: &[Played],
+    words: &[&'static str],
+    language: &'static Language,
+    theme: &Theme,
+) {
+    let _window_ender = crate::init_ncurses(theme);
+    ncurses::timeout(-1);
+
+    let games = history.iter().rev().collect::<Vec<_>>();
+    let mut selected: usize = 0;
+    let mut navigator = Navigator::default();
+    let mut message = None;
+    loop {
+        let mut max_x = 0;
+        let mut max_y = 0;
+        ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+        // The list scrolls to keep the selected game on the screen
+        let rows = (max_y - 5).max(1) as usize;
+        let first = selected.saturating_sub(rows - 1);
+
+        ncurses::clear();
+        ncurses::mvaddstr(0, 2, strings().history_title);
+        if games.is_empty() {
+            ncurses::mvaddstr(2, 2, strings().history_empty);
+        }
+        for (row, played) in games.iter().enumerate().skip(first).take(rows) {
+            if row == selected {
+                ncurses::attron(ncurses::A_REVERSE());
+            }
+            ncurses::mvaddstr(2 + (row - first) as i32, 2, &describe(played));
+            ncurses::attroff(ncurses::A_REVERSE());
+        }
+        let footer = message.take().unwrap_or(strings().history_help);
+        ncurses::mvaddstr(max_y - 2, 2, footer);
+        ncurses::refresh();
+
+        let input = ncurses::getch();
+        let Some(played) = games.get(selected) else {
+            return;
+        };
+        let mut review = input == 'r' as i32;
+        if input == 'q' as i32 || input == ESCAPE {
+            return;
+        } else if let Some(movement) = navigator.read(input) {
+            selected = movement.apply(selected, games.len() - 1, rows);
+        } else if ENTER.contains(&input) {
+            review = show_board(played);
+        }
+
+        if review {
+            if played.guessed.is_empty() {
+                message = Some(strings().history_not_kept);
+            } else if played.language != language.code {
+                message = Some(strings().history_other_language);
+            } else {
+                ncurses::clear();
+                ncurses::mvaddstr(0, 2, strings().reviewing);
+                ncurses::refresh();
+                review::show(&review::review(
+                    words,
+                    &played.guessed,
+                    &played.patterns,
+                    played.hard,
+                ));
+            }
+        }
+    }
+}
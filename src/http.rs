@@ -0,0 +1,292 @@
+//! The headless engine over HTTP, for web and mobile frontends. The games are kept on the server
+//! and played with json bodies:
+//!
+//! ```text
+//! POST /game                              201 {"id":"5f0c8d2e9a7b4c11","language":"nl",...}
+//! POST /game/{id}/guess {"word":"stoel"}  200 {"pattern":"XXGXG","rows":[...],...}
+//! GET  /game/{id}                         200 {"id":"5f0c8d2e9a7b4c11","rows":[...],...}
+//! ```
+//!
+//! A guess that isn't accepted gets 422 with the reason in `error`. The word is only in the game
+//! once it has ended. Patterns are written like in the `protocol` module.
+
+use crate::headless::Session;
+use std::io;
+
+#[cfg(any(feature = "http", test))]
+use api::Games;
+
+/// The longest request body that is read, a guess fits in it many times over. Longer bodies are
+/// refused so a client can't fill the memory of the server.
+#[cfg(feature = "http")]
+const MAX_BODY: u64 = 4096;
+
+/// The games on the server and their json, apart from the server so they can be tested without it
+#[cfg(any(feature = "http", test))]
+mod api {
+    use crate::headless::{Game, Session};
+    use crate::WORD_LENGTH;
+    use lingo_core::rules::pattern_to_code;
+    use serde::{Deserialize, Serialize};
+    use std::collections::{HashMap, VecDeque};
+
+    /// The most games that are kept, the oldest game is forgotten for a new one
+    const MAX_GAMES: usize = 10000;
+
+    #[derive(Deserialize)]
+    struct GuessRequest {
+        word: String,
+    }
+
+    #[derive(Serialize)]
+    struct Row {
+        guess: String,
+        pattern: String,
+    }
+
+    /// A game as the frontends get it
+    #[derive(Serialize)]
+    struct GameView {
+        id: String,
+        language: String,
+        letters: usize,
+        guesses: u32,
+        rows: Vec<Row>,
+        finished: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        won: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        word: Option<String>,
+    }
+
+    /// The answer to a guess, the game with the pattern of the guess
+    #[derive(Serialize)]
+    struct GuessView {
+        pattern: String,
+        #[serde(flatten)]
+        game: GameView,
+    }
+
+    /// The status and the json body of an answer
+    type Reply = (u16, serde_json::Value);
+
+    pub fn error(status: u16, message: String) -> Reply {
+        (status, serde_json::json!({ "error": message }))
+    }
+
+    fn reply(status: u16, body: impl Serialize) -> Reply {
+        let body = serde_json::to_value(body).expect("The answers can always be written as json");
+        (status, body)
+    }
+
+    /// The games that are being played on the server
+    pub struct Games<'a> {
+        session: &'a Session<'a>,
+        games: HashMap<String, Game>,
+        /// The ids of the games from the oldest to the newest
+        order: VecDeque<String>,
+    }
+
+    impl<'a> Games<'a> {
+        pub fn new(session: &'a Session<'a>) -> Games<'a> {
+            Games {
+                session,
+                games: HashMap::new(),
+                order: VecDeque::new(),
+            }
+        }
+
+        fn view(&self, id: &str, game: &Game) -> GameView {
+            GameView {
+                id: id.to_string(),
+                language: self.session.language.code.to_string(),
+                letters: WORD_LENGTH,
+                guesses: self.session.max_guesses,
+                rows: game
+                    .rows
+                    .iter()
+                    .map(|(guess, pattern)| Row {
+                        guess: guess.clone(),
+                        pattern: pattern_to_code(pattern),
+                    })
+                    .collect(),
+                finished: game.won.is_some(),
+                won: game.won,
+                word: game.won.map(|_| game.target.clone()),
+            }
+        }
+
+        /// Answer the request, the path can have a query that is left out
+        pub fn handle(
+            &mut self,
+            method: &str,
+            path: &str,
+            body: &str,
+            pick: &impl Fn() -> &'static str,
+        ) -> Reply {
+            let path = path.split('?').next().unwrap_or_default();
+            let parts = path.trim_matches('/').split('/').collect::<Vec<_>>();
+            match (method, parts.as_slice()) {
+                ("POST", ["game"]) => self.create(pick()),
+                ("GET", ["game", id]) => match self.games.get(*id) {
+                    Some(game) => reply(200, self.view(id, game)),
+                    None => error(404, format!("There is no game {}", id)),
+                },
+                ("POST", ["game", id, "guess"]) => self.guess(id, body),
+                (_, ["game"] | ["game", _] | ["game", _, "guess"]) => {
+                    error(405, format!("{} can't be used on {}", method, path))
+                }
+                _ => error(404, format!("There is nothing at {}", path)),
+            }
+        }
+
+        fn create(&mut self, target: &str) -> Reply {
+            let id = format!("{:016x}", rand::random::<u64>());
+            if self.order.len() == MAX_GAMES {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.games.remove(&oldest);
+                }
+            }
+            let game = self.session.start(target);
+            let view = self.view(&id, &game);
+            self.games.insert(id.clone(), game);
+            self.order.push_back(id);
+            reply(201, view)
+        }
+
+        fn guess(&mut self, id: &str, body: &str) -> Reply {
+            let request: GuessRequest = match serde_json::from_str(body) {
+                Ok(request) => request,
+                Err(err) => return error(400, format!("The body is not a guess: {}", err)),
+            };
+            let Some(game) = self.games.get_mut(id) else {
+                return error(404, format!("There is no game {}", id));
+            };
+            let guess = self.session.compose(&request.word);
+            match self.session.guess(game, &guess) {
+                Ok(pattern) => {
+                    let game = &self.games[id];
+                    reply(
+                        200,
+                        GuessView {
+                            pattern: pattern_to_code(&pattern),
+                            game: self.view(id, game),
+                        },
+                    )
+                }
+                Err(message) => error(422, message),
+            }
+        }
+    }
+}
+
+/// Serve the API on the port until the program is stopped, every new game is on a word of `pick`
+#[cfg(feature = "http")]
+pub fn serve(port: u16, session: &Session, pick: impl Fn() -> &'static str) -> io::Result<()> {
+    use api::error;
+    use std::io::Read;
+    use tiny_http::{Header, Method, Response, Server};
+
+    let server =
+        Server::http(("0.0.0.0", port)).map_err(|err| io::Error::other(err.to_string()))?;
+    eprintln!("Serving the HTTP API on port {}", port);
+    let header = |line: &str| line.parse::<Header>().expect("The headers are valid");
+    let mut games = Games::new(session);
+    for mut request in server.incoming_requests() {
+        let mut body = Vec::new();
+        let read = request
+            .as_reader()
+            .take(MAX_BODY + 1)
+            .read_to_end(&mut body);
+        let (status, value) = if let Err(err) = read {
+            error(400, format!("The body could not be read: {}", err))
+        } else if body.len() as u64 > MAX_BODY {
+            error(413, format!("The body is longer than {} bytes", MAX_BODY))
+        } else if *request.method() == Method::Options {
+            // Browsers ask before they send json to another origin
+            (204, serde_json::Value::Null)
+        } else {
+            let method = request.method().to_string();
+            games.handle(
+                &method,
+                request.url(),
+                &String::from_utf8_lossy(&body),
+                &pick,
+            )
+        };
+        let content = match value {
+            serde_json::Value::Null => String::new(),
+            value => value.to_string(),
+        };
+        let response = Response::from_string(content)
+            .with_status_code(status)
+            .with_header(header("Content-Type: application/json"))
+            .with_header(header("Access-Control-Allow-Origin: *"))
+            .with_header(header("Access-Control-Allow-Methods: GET, POST"))
+            .with_header(header("Access-Control-Allow-Headers: Content-Type"));
+        if let Err(err) = request.respond(response) {
+            eprintln!("Could not answer a request: {}", err);
+        }
+    }
+    Ok(())
+}
+
+/// Without the `http` feature there is no server
+#[cfg(not(feature = "http"))]
+pub fn serve(_port: u16, _session: &Session, _pick: impl Fn() -> &'static str) -> io::Result<()> {
+    Err(io::Error::other(
+        "This build has no HTTP support, build it with the http feature",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+    use crate::language;
+
+    #[test]
+    fn games_are_played_with_requests() {
+        let dictionary = Dictionary::load(&["kaart", "tabak", "taken", "tafel", "stoel"]);
+        let session = Session {
+            dictionary: &dictionary,
+            language: &language::DUTCH,
+            hard: false,
+            max_guesses: 5,
+        };
+        let mut games = Games::new(&session);
+        let pick = || "tabak";
+        let (status, game) = games.handle("POST", "/game", "", &pick);
+        assert_eq!(status, 201);
+        assert_eq!(game["guesses"], 5);
+        assert!(game.get("word").is_none());
+        let id = game["id"].as_str().unwrap();
+        let guess = format!("/game/{}/guess", id);
+
+        let (status, answer) = games.handle("POST", &guess, r#"{"word":"Taken"}"#, &pick);
+        assert_eq!(status, 200);
+        assert_eq!(answer["pattern"], "GGYXX");
+        assert_eq!(answer["finished"], false);
+        assert_eq!(
+            games.handle("POST", &guess, r#"{"word":"xxxxx"}"#, &pick).0,
+            422
+        );
+        assert_eq!(games.handle("POST", &guess, "taken", &pick).0, 400);
+
+        games.handle("POST", &guess, r#"{"word":"tabak"}"#, &pick);
+        let (status, game) = games.handle("GET", &format!("/game/{}", id), "", &pick);
+        assert_eq!(status, 200);
+        assert_eq!(game["rows"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            (&game["won"], &game["word"]),
+            (&true.into(), &"tabak".into())
+        );
+        assert_eq!(
+            games.handle("POST", &guess, r#"{"word":"kaart"}"#, &pick).0,
+            422
+        );
+
+        assert_eq!(games.handle("GET", "/game/unknown", "", &pick).0, 404);
+        assert_eq!(games.handle("DELETE", &guess, "", &pick).0, 405);
+    }
+}
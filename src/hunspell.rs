@@ -0,0 +1,440 @@
+//! Expanding a Hunspell dictionary into a flat word list. A Hunspell dictionary is a list of stems
+//! in the .dic file with flags that refer to the prefix and suffix rules in the .aff file, every
+//! rule that applies to a stem makes another word.
+//!
+//! Only single affixes and the combination of a prefix with a suffix are expanded, the affixes
+//! that continue with another affix and compounds are left out. Those make long words, the lists
+//! of the game are about short ones.
+
+use crate::language::Language;
+use crate::word;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+/// How the flags of a stem are written, set with FLAG in the .aff file
+#[derive(Clone, Copy)]
+enum FlagType {
+    /// A single character for each flag, the default
+    Char,
+    /// Two characters for each flag
+    Long,
+    /// Numbers separated by commas
+    Number,
+}
+
+impl FlagType {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => flags
+                .chars()
+                .chunks(2)
+                .into_iter()
+                .map(|chunk| chunk.collect())
+                .collect(),
+            FlagType::Number => flags
+                .split(',')
+                .map(|flag| flag.trim().to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A character of the condition of an affix
+enum Condition {
+    Any,
+    /// One of the characters, or any character but these when negated
+    Set {
+        chars: Vec<char>,
+        negated: bool,
+    },
+}
+
+impl Condition {
+    fn matches(&self, chr: char) -> bool {
+        match self {
+            Condition::Any => true,
+            Condition::Set { chars, negated } => chars.contains(&chr) != *negated,
+        }
+    }
+
+    /// Parse a condition like `[^aeiou]y`, a condition of `.` holds for every word
+    fn parse(text: &str) -> Vec<Condition> {
+        let mut conditions = Vec::new();
+        let mut chars = text.chars();
+        while let Some(chr) = chars.next() {
+            conditions.push(match chr {
+                '.' => Condition::Any,
+                '[' => {
+                    let set = chars
+                        .by_ref()
+                        .take_while(|chr| *chr != ']')
+                        .collect::<String>();
+                    match set.strip_prefix('^') {
+                        Some(set) => Condition::Set {
+                            chars: set.chars().collect(),
+                            negated: true,
+                        },
+                        None => Condition::Set {
+                            chars: set.chars().collect(),
+                            negated: false,
+                        },
+                    }
+                }
+                chr => Condition::Set {
+                    chars: vec![chr],
+                    negated: false,
+                },
+            });
+        }
+        // A single dot is the same as no condition
+        if let [Condition::Any] = conditions[..] {
+            conditions.clear();
+        }
+        conditions
+    }
+}
+
+/// A rule of a prefix or suffix flag
+struct Rule {
+    /// The characters removed from the stem
+    strip: String,
+    /// The characters added to the stem
+    add: String,
+    /// The characters at the start of the stem for a prefix, or the end for a suffix
+    condition: Vec<Condition>,
+}
+
+/// The rules of a prefix or suffix flag
+struct Affix {
+    suffix: bool,
+    /// If the affix can be combined with an affix of the other kind
+    cross_product: bool,
+    rules: Vec<Rule>,
+}
+
+impl Affix {
+    /// The words the affix makes from the stem
+    fn apply(&self, stem: &str) -> Vec<String> {
+        let chars = stem.chars().collect_vec();
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                if rule.condition.len() > chars.len() {
+                    return None;
+                }
+                let (matches, kept) = if self.suffix {
+                    let end = &chars[chars.len() - rule.condition.len()..];
+                    let kept = stem.strip_suffix(rule.strip.as_str())?;
+                    (end, format!("{}{}", kept, rule.add))
+                } else {
+                    let start = &chars[..rule.condition.len()];
+                    let kept = stem.strip_prefix(rule.strip.as_str())?;
+                    (start, format!("{}{}", rule.add, kept))
+                };
+                rule.condition
+                    .iter()
+                    .zip(matches)
+                    .all(|(condition, chr)| condition.matches(*chr))
+                    .then_some(kept)
+            })
+            .collect()
+    }
+}
+
+/// The rules of the .aff file that are needed to expand the stems
+struct Rules {
+    flag_type: FlagType,
+    affixes: HashMap<String, Affix>,
+    /// The flags of stems that aren't words on their own
+    not_words: HashSet<String>,
+    /// The sets of flags of AF, the stems name them by their number instead of listing the flags.
+    /// `None` when the .aff file has no aliases.
+    aliases: Option<Vec<String>>,
+}
+
+/// Read a file of a Hunspell dictionary, the .aff file says which character set both files use
+fn read(path: &Path, latin1: bool) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    Ok(if latin1 {
+        bytes.iter().map(|byte| char::from(*byte)).collect()
+    } else {
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+impl Rules {
+    fn parse(text: &str) -> Rules {
+        let mut rules = Rules {
+            flag_type: FlagType::Char,
+            affixes: HashMap::new(),
+            not_words: HashSet::new(),
+            aliases: None,
+        };
+        for line in text.lines() {
+            let fields = line.split_whitespace().collect_vec();
+            match fields[..] {
+                ["FLAG", "long", ..] => rules.flag_type = FlagType::Long,
+                ["FLAG", "num", ..] => rules.flag_type = FlagType::Number,
+                ["NEEDAFFIX" | "ONLYINCOMPOUND" | "FORBIDDENWORD", flag, ..] => {
+                    rules.not_words.insert(flag.to_string());
+                }
+                ["AF", flags, ..] => match &mut rules.aliases {
+                    // The first line has the amount of aliases
+                    None => rules.aliases = Some(Vec::new()),
+                    Some(aliases) => aliases.push(flags.to_string()),
+                },
+                // The header of an affix: PFX flag cross_product count
+                [kind @ ("PFX" | "SFX"), flag, cross_product, count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    rules.affixes.insert(
+                        flag.to_string(),
+                        Affix {
+                            suffix: kind == "SFX",
+                            cross_product: cross_product == "Y",
+                            rules: Vec::new(),
+                        },
+                    );
+                }
+                // A rule of an affix: PFX flag strip add condition
+                ["PFX" | "SFX", flag, strip, add, ..] => {
+                    if let Some(affix) = rules.affixes.get_mut(flag) {
+                        let zero = |text: &str| if text == "0" { "" } else { text }.to_string();
+                        // The affixes that continue with other flags are added without them
+                        let add = add.split('/').next().unwrap_or_default();
+                        affix.rules.push(Rule {
+                            strip: zero(strip),
+                            add: zero(add),
+                            condition: Condition::parse(fields.get(4).copied().unwrap_or(".")),
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+        rules
+    }
+
+    /// The flags of a stem as they are written in the .dic file, `None` for a number that isn't
+    /// one of the aliases
+    fn flags(&self, flags: &str) -> Option<Vec<String>> {
+        match &self.aliases {
+            Some(aliases) if !flags.is_empty() => {
+                let alias = aliases.get(flags.parse::<usize>().ok()?.checked_sub(1)?)?;
+                Some(self.flag_type.split(alias))
+            }
+            _ => Some(self.flag_type.split(flags)),
+        }
+    }
+
+    /// All the words that are made from a stem with its flags
+    fn expand(&self, stem: &str, flags: &[String]) -> Vec<String> {
+        let mut words = Vec::new();
+        if !flags.iter().any(|flag| self.not_words.contains(flag)) {
+            words.push(stem.to_string());
+        }
+
+        let (suffixes, prefixes): (Vec<&Affix>, Vec<&Affix>) = flags
+            .iter()
+            .filter_map(|flag| self.affixes.get(flag))
+            .partition(|affix| affix.suffix);
+        for suffix in suffixes.iter() {
+            for suffixed in suffix.apply(stem) {
+                // A prefix and a suffix are only combined when both allow it
+                for prefix in prefixes.iter() {
+                    if suffix.cross_product && prefix.cross_product {
+                        words.extend(prefix.apply(&suffixed));
+                    }
+                }
+                words.push(suffixed);
+            }
+        }
+        for prefix in prefixes.iter() {
+            words.extend(prefix.apply(stem));
+        }
+        words
+    }
+}
+
+/// Expand the Hunspell dictionary into the words of `length` letters that the game can play,
+/// sorted and without duplicates. With a language the letter pairs that are written on one tile
+/// count as one letter.
+pub fn import(
+    aff: &Path,
+    dic: &Path,
+    length: usize,
+    language: Option<&Language>,
+) -> io::Result<Vec<String>> {
+    let aff_bytes = fs::read(aff)?;
+    let aff_text = String::from_utf8_lossy(&aff_bytes);
+    let latin1 = aff_text.lines().any(|line| {
+        matches!(
+            line.split_whitespace().collect_vec()[..],
+            ["SET", "ISO8859-1" | "ISO8859-15"]
+        )
+    });
+    let rules = Rules::parse(&read(aff, latin1)?);
+    let dic_text = read(dic, latin1)?;
+
+    let mut lines = dic_text.lines();
+    // The first line is the amount of stems
+    if lines
+        .next()
+        .and_then(|line| line.trim().parse::<usize>().ok())
+        .is_none()
+    {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("{} doesn't start with the amount of words", dic.display()),
+        ));
+    }
+
+    let mut expanded = Vec::new();
+    // Morphological fields follow after whitespace
+    for entry in lines.filter_map(|line| line.split_whitespace().next()) {
+        // A slash that is part of the word is escaped
+        let entry = entry.replace("\\/", "\u{0}");
+        let (stem, flags) = entry.split_once('/').unwrap_or((&entry, ""));
+        let Some(flags) = rules.flags(flags) else {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{} has the flag alias {} that isn't in the .aff file",
+                    dic.display(),
+                    flags
+                ),
+            ));
+        };
+        expanded.extend(rules.expand(&stem.replace('\u{0}', "/"), &flags));
+    }
+
+    let words = expanded
+        .into_iter()
+        .map(|word| word::compose(&word))
+        .filter(|word| {
+            let tiles =
+                language.map_or_else(|| word.clone(), |language| language.join_digraphs(word));
+            word::length(&tiles) == length
+                && word
+                    .chars()
+                    .all(|chr| chr.is_alphabetic() && chr.is_lowercase())
+        })
+        .sorted()
+        .dedup()
+        .collect();
+    Ok(words)
+}
+
+/// Import the Hunspell dictionary and write the word list to `output`
+pub fn import_file(
+    aff: &Path,
+    dic: &Path,
+    output: &Path,
+    length: usize,
+    language: Option<&Language>,
+) -> io::Result<()> {
+    let words = import(aff, dic, length, language)?;
+    let mut text = words.join("\n");
+    text.push('\n');
+    fs::write(output, text)?;
+    println!("Wrote {} words to {}", words.len(), output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The words of the stems of a .dic file without its first line
+    fn expand(aff: &str, dic: &str) -> Vec<String> {
+        let rules = Rules::parse(aff);
+        dic.lines()
+            .flat_map(|entry| {
+                let (stem, flags) = entry.split_once('/').unwrap_or((entry, ""));
+                rules.expand(stem, &rules.flags(flags).unwrap())
+            })
+            .sorted()
+            .collect()
+    }
+
+    const AFF: &str = "\
+PFX O Y 1
+PFX O 0 on .
+
+SFX E Y 3
+SFX E 0 en [^e]
+SFX E 0 n e
+SFX E y ies [^aeiou]y
+
+SFX T N 1
+SFX T 0 tje .
+
+NEEDAFFIX X
+";
+
+    #[test]
+    fn conditions_match_the_end_of_the_stem() {
+        let conditions = Condition::parse("[^aeiou]y");
+        assert!(conditions[0].matches('r') && !conditions[0].matches('a'));
+        assert!(conditions[1].matches('y') && !conditions[1].matches('r'));
+        assert!(Condition::parse(".").is_empty());
+
+        assert_eq!(expand(AFF, "boom/E"), ["boom", "boomen"]);
+        assert_eq!(expand(AFF, "rate/E"), ["rate", "raten"]);
+        // The y is stripped before the ies is added, but only after a consonant
+        assert_eq!(expand(AFF, "pony/E"), ["ponies", "pony", "ponyen"]);
+        assert_eq!(expand(AFF, "tray/E"), ["tray", "trayen"]);
+    }
+
+    #[test]
+    fn prefixes_and_suffixes_are_combined_when_both_allow_it() {
+        assert_eq!(
+            expand(AFF, "wijs/OE"),
+            ["onwijs", "onwijsen", "wijs", "wijsen"]
+        );
+        assert_eq!(expand(AFF, "wijs/OT"), ["onwijs", "wijs", "wijstje"]);
+    }
+
+    #[test]
+    fn stems_that_need_an_affix_are_left_out() {
+        assert_eq!(expand(AFF, "kleed/XT"), ["kleedtje"]);
+    }
+
+    #[test]
+    fn flags_can_be_long_or_numbers() {
+        let long = "FLAG long\nSFX Aa Y 1\nSFX Aa 0 en .\nSFX Bb Y 1\nSFX Bb 0 s .\n";
+        assert_eq!(expand(long, "boom/AaBb"), ["boom", "boomen", "booms"]);
+
+        let number = "FLAG num\nSFX 12 Y 1\nSFX 12 0 en .\nSFX 3 Y 1\nSFX 3 0 s .\n";
+        assert_eq!(expand(number, "boom/12,3"), ["boom", "boomen", "booms"]);
+    }
+
+    #[test]
+    fn stems_can_name_an_alias_of_their_flags() {
+        let aff = format!("AF 2\nAF E\nAF OE\n{}", AFF);
+        assert_eq!(expand(&aff, "boom/1"), ["boom", "boomen"]);
+        assert_eq!(
+            expand(&aff, "wijs/2"),
+            ["onwijs", "onwijsen", "wijs", "wijsen"]
+        );
+        assert_eq!(Rules::parse(&aff).flags("3"), None);
+    }
+
+    #[test]
+    fn latin1_files_are_read_as_latin1() {
+        let path = |name: &str| {
+            std::env::temp_dir().join(format!("lingo-hunspell-{}-{}", std::process::id(), name))
+        };
+        let (aff, dic) = (path("nl.aff"), path("nl.dic"));
+        fs::write(&aff, b"SET ISO8859-1\nSFX E Y 1\nSFX E 0 s .\n").unwrap();
+        // "caf\xe9" is "café" in Latin-1, it isn't valid UTF-8
+        fs::write(&dic, b"1\ncaf\xe9/E\n").unwrap();
+        let words = import(&aff, &dic, 5, None);
+        let _ = (fs::remove_file(&aff), fs::remove_file(&dic));
+        assert_eq!(words.unwrap(), ["cafés"]);
+    }
+}
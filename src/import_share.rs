@@ -0,0 +1,202 @@
+//! Importing the emoji grid of a shared Wordle or Lingo result. The grid is drawn as a board, and
+//! with the answer of the puzzle the guesses behind every row are worked out: the words of the
+//! list that get the colors of the row when they are scored against the answer. The letters all
+//! those guesses agree on are shown on the board.
+
+use crate::keymap::ESCAPE;
+use crate::messages::Notice;
+use crate::navigation::Navigator;
+use crate::rules::{self, Feedback, Pattern};
+use crate::share;
+use crate::theme::Theme;
+use crate::{render_game, word, Board, BoardState, GuessedLetter, GuessedWord, WORD_LENGTH};
+
+/// The guesses that are listed for a row when they are printed
+const LISTED_GUESSES: usize = 10;
+
+/// A grid read from a shared result
+pub struct Imported {
+    /// The number of the puzzle from the first line, like the 1234 of "Wordle 1,234 3/6"
+    pub puzzle: Option<usize>,
+    pub grid: Vec<Pattern>,
+}
+
+/// The feedback of a tile, the high contrast colors are read too
+fn tile(chr: char) -> Option<Feedback> {
+    match chr {
+        '⬛' | '⬜' => Some(Feedback::Wrong),
+        '🟨' | '🟦' => Some(Feedback::WrongPlace),
+        '🟩' | '🟧' => Some(Feedback::Correct),
+        _ => None,
+    }
+}
+
+/// Read the grid out of the pasted text, the lines around the grid are left out
+pub fn parse(text: &str) -> Result<Imported, String> {
+    let mut puzzle = None;
+    let mut grid = Vec::new();
+    for line in text.lines() {
+        // Some chat apps add variation selectors to the emoji
+        let tiles = line
+            .chars()
+            .filter(|chr| !chr.is_whitespace() && *chr != '\u{fe0f}')
+            .map(tile)
+            .collect::<Option<Vec<_>>>();
+        match tiles {
+            Some(tiles) if !tiles.is_empty() => {
+                let pattern = Pattern::try_from(tiles.as_slice()).map_err(|_| {
+                    format!(
+                        "A row of the grid has {} tiles, the game plays words of {} letters",
+                        tiles.len(),
+                        WORD_LENGTH
+                    )
+                })?;
+                grid.push(pattern);
+            }
+            // The header goes before the grid, like "Wordle 1,234 3/6"
+            _ if grid.is_empty() && puzzle.is_none() => {
+                puzzle = line
+                    .split_whitespace()
+                    .nth(1)
+                    .and_then(|number| number.replace([',', '.', '#'], "").parse().ok());
+            }
+            _ => {}
+        }
+    }
+    if grid.is_empty() {
+        return Err("There is no grid in the text".to_string());
+    }
+    Ok(Imported { puzzle, grid })
+}
+
+/// The guesses that get the pattern when they are scored against the answer
+pub fn guesses(words: &[&'static str], answer: &str, pattern: &Pattern) -> Vec<&'static str> {
+    words
+        .iter()
+        .filter(|guess| rules::score(guess, answer) == *pattern)
+        .copied()
+        .collect()
+}
+
+/// The row of the board, with the letters that all the guesses have on that place
+fn row(pattern: &Pattern, guesses: &[&str]) -> GuessedWord {
+    let mut row = GuessedWord::default();
+    for (index, (letter, feedback)) in row.iter_mut().zip(pattern).enumerate() {
+        let mut letters = guesses.iter().filter_map(|guess| guess.chars().nth(index));
+        let first = letters.next();
+        let chr = match first {
+            Some(first) if letters.all(|chr| chr == first) => first,
+            _ => ' ',
+        };
+        *letter = match feedback {
+            Feedback::Wrong => GuessedLetter::Wrong(chr),
+            Feedback::WrongPlace => GuessedLetter::WrongPlace(chr),
+            Feedback::Correct => GuessedLetter::Correct(chr),
+        };
+    }
+    row
+}
+
+/// The word as it is shown to the player
+fn display(word: &str) -> String {
+    word.chars().map(word::display).collect()
+}
+
+/// Show the grid as a board. With the answer the guesses of the picked row are shown next to it,
+/// the rows are picked with the arrow keys.
+pub fn show(imported: &Imported, answer: Option<&str>, words: &[&'static str], theme: &Theme) {
+    let rows = imported
+        .grid
+        .iter()
+        .map(|pattern| answer.map_or_else(Vec::new, |answer| guesses(words, answer, pattern)))
+        .collect::<Vec<_>>();
+
+    let _window_ender = crate::init_ncurses(theme);
+    ncurses::timeout(-1);
+    let mut board = Board::new(imported.grid.len() as u32);
+    for (index, pattern) in imported.grid.iter().enumerate() {
+        board.rows[index] = row(pattern, &rows[index]);
+    }
+    board.solved = imported.grid.last().is_some_and(|pattern| {
+        pattern
+            .iter()
+            .all(|feedback| *feedback == Feedback::Correct)
+    });
+
+    let mut selected = 0;
+    let mut navigator = Navigator::default();
+    loop {
+        let mut board_state = BoardState {
+            boards: vec![board.clone()],
+            possible_words: rows[selected].clone(),
+            hide_possible_words: answer.is_none(),
+            ..Default::default()
+        };
+        let message = match answer {
+            None => {
+                "Give the answer with --answer to work out the guesses. Press q to quit".to_string()
+            }
+            Some(_) => format!(
+                "Row {}: {} possible guesses. Up and down to pick a row, q to quit",
+                selected + 1,
+                rows[selected].len()
+            ),
+        };
+        board_state.messages.push(Notice::info(message));
+        render_game(&board_state);
+
+        let input = ncurses::getch();
+        if input == 'q' as i32 || input == ESCAPE {
+            return;
+        } else if let Some(movement) = navigator.read(input) {
+            selected = movement.apply(selected, rows.len() - 1, rows.len());
+        }
+    }
+}
+
+/// Print the grid with the guesses of every row, when the result isn't shown on a terminal
+pub fn print(imported: &Imported, answer: Option<&str>, words: &[&'static str]) {
+    for pattern in &imported.grid {
+        let tiles = share::tiles(pattern);
+        let Some(answer) = answer else {
+            println!("{}", tiles);
+            continue;
+        };
+        let guesses = guesses(words, answer, pattern);
+        let listed = guesses
+            .iter()
+            .take(LISTED_GUESSES)
+            .map(|guess| display(guess))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let more = match guesses.len().saturating_sub(LISTED_GUESSES) {
+            0 => String::new(),
+            more => format!(" and {} more", more),
+        };
+        let count = match guesses.len() {
+            1 => "1 guess".to_string(),
+            count => format!("{} guesses", count),
+        };
+        println!("{}  {}: {}{}", tiles, count, listed, more);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grids_are_read_from_shared_results() {
+        let text = "Wordle 1,234 3/6\n\n⬛🟨⬛⬛🟩\n🟩🟩⬛🟨🟩\n🟩🟩🟩🟩🟩\n";
+        let imported = parse(text).unwrap();
+        assert_eq!(imported.puzzle, Some(1234));
+        assert_eq!(imported.grid.len(), 3);
+        assert_eq!(imported.grid[1][3], Feedback::WrongPlace);
+        assert!(parse("Lingo nl 2/5\n🟩🟩🟩🟩\n").is_err());
+        assert!(parse("no grid").is_err());
+
+        let words = ["kaart", "tabak", "taken", "tafel", "stoel"];
+        let pattern = rules::score("taken", "tabak");
+        assert_eq!(guesses(&words, "tabak", &pattern), ["taken"]);
+    }
+}
@@ -0,0 +1,362 @@
+//! Turning the keys ncurses reads into letters and into the events of a game. Keys outside ASCII
+//! come in as the bytes of their UTF-8 encoding, and letters are normalized so typing with Caps
+//! Lock on or with accents that aren't in the word list still works.
+
+use crate::keymap::{Action, Bindings, ESCAPE};
+use defer::defer;
+use itertools::Itertools;
+use std::io::{self, Write};
+
+/// Ctrl-C as ncurses reads it in raw mode
+const CTRL_C: i32 = 0x03;
+
+/// What the player did during a game, apart from the key codes of the terminal
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum InputEvent {
+    /// A typed character, it is only a letter of the guess after `normalize`
+    Char(char),
+    /// Text that was pasted at once
+    Paste(String),
+    Backspace,
+    /// Remove the letter under the cursor
+    Delete,
+    Submit,
+    /// Pause the game
+    Quit,
+    /// Ctrl-C, the game is paused with the question to quit
+    Interrupt,
+    Hint,
+    Help,
+    TogglePanel,
+    /// Open the settings that can be changed during a game
+    Settings,
+    Left,
+    Right,
+    Home,
+    End,
+    /// Switch between inserting and overwriting letters
+    Insert,
+    /// Clear the whole row
+    ClearRow,
+    /// Remove the letters before the cursor
+    DeleteWord,
+    PageUp,
+    PageDown,
+    /// The mouse was pressed at this place on the screen
+    Mouse {
+        x: i32,
+        y: i32,
+    },
+    /// The terminal changed size
+    Resize,
+    /// The timeout passed without input
+    Tick,
+    /// A key that does nothing in a game
+    Other,
+}
+
+impl InputEvent {
+    /// If the event changes the letters or the cursor on the row that is typed
+    pub fn edits_row(&self) -> bool {
+        matches!(
+            self,
+            InputEvent::Char(_)
+                | InputEvent::Paste(_)
+                | InputEvent::Backspace
+                | InputEvent::Delete
+                | InputEvent::Submit
+                | InputEvent::Left
+                | InputEvent::Right
+                | InputEvent::Home
+                | InputEvent::End
+                | InputEvent::Insert
+                | InputEvent::ClearRow
+                | InputEvent::DeleteWord
+                | InputEvent::Mouse { .. }
+        )
+    }
+}
+
+/// Reads the input of a game from ncurses and turns it into events
+pub struct Poller {
+    /// The keys of the actions from the config file
+    keys: &'static Bindings,
+    /// How long to wait for input before a tick in milliseconds, -1 to wait forever
+    timeout: i32,
+}
+
+impl Poller {
+    pub fn new(keys: &'static Bindings, timeout: i32) -> Poller {
+        ncurses::timeout(timeout);
+        Poller { keys, timeout }
+    }
+
+    /// Wait for the next event
+    pub fn poll(&self) -> InputEvent {
+        let input = ncurses::getch();
+        if input == ncurses::ERR {
+            return InputEvent::Tick;
+        } else if input == ncurses::KEY_RESIZE {
+            return InputEvent::Resize;
+        } else if input == ncurses::KEY_MOUSE {
+            return read_mouse();
+        } else if let Some(pasted) = read_paste(input, self.timeout) {
+            return InputEvent::Paste(pasted);
+        } else if input == CTRL_C {
+            // ncurses reads Ctrl-C as a key instead of the interrupt signal
+            return InputEvent::Interrupt;
+        }
+
+        // The keys of the actions from the config file go before the other keys
+        if let Some(action) = self.keys.action(input) {
+            return match action {
+                Action::Quit => InputEvent::Quit,
+                Action::Submit => InputEvent::Submit,
+                Action::Backspace => InputEvent::Backspace,
+                Action::Hint => InputEvent::Hint,
+                Action::Help => InputEvent::Help,
+                Action::TogglePanel => InputEvent::TogglePanel,
+            };
+        }
+        match input {
+            ncurses::KEY_DC => InputEvent::Delete,
+            // Ctrl-S
+            0x13 => InputEvent::Settings,
+            ncurses::KEY_LEFT => InputEvent::Left,
+            ncurses::KEY_RIGHT => InputEvent::Right,
+            ncurses::KEY_HOME => InputEvent::Home,
+            ncurses::KEY_END => InputEvent::End,
+            ncurses::KEY_IC => InputEvent::Insert,
+            // Ctrl-U and Ctrl-W like in a shell
+            0x15 => InputEvent::ClearRow,
+            0x17 => InputEvent::DeleteWord,
+            // Ctrl-B and Ctrl-F like in vim
+            ncurses::KEY_PPAGE | 0x02 => InputEvent::PageUp,
+            ncurses::KEY_NPAGE | 0x06 => InputEvent::PageDown,
+            _ => read_char(input, self.timeout).map_or(InputEvent::Other, InputEvent::Char),
+        }
+    }
+}
+
+/// The press of the mouse ncurses read
+fn read_mouse() -> InputEvent {
+    let mut event = ncurses::MEVENT {
+        id: 0,
+        x: 0,
+        y: 0,
+        z: 0,
+        bstate: 0,
+    };
+    if ncurses::getmouse(&mut event) != ncurses::OK
+        || event.bstate & ncurses::BUTTON1_PRESSED as ncurses::mmask_t == 0
+    {
+        return InputEvent::Other;
+    }
+    InputEvent::Mouse {
+        x: event.x,
+        y: event.y,
+    }
+}
+
+/// Accented letters with the letter without the accent
+const ACCENTS: [(char, char); 27] = [
+    ('à', 'a'),
+    ('á', 'a'),
+    ('â', 'a'),
+    ('ã', 'a'),
+    ('ä', 'a'),
+    ('å', 'a'),
+    ('ç', 'c'),
+    ('è', 'e'),
+    ('é', 'e'),
+    ('ê', 'e'),
+    ('ë', 'e'),
+    ('ì', 'i'),
+    ('í', 'i'),
+    ('î', 'i'),
+    ('ï', 'i'),
+    ('ñ', 'n'),
+    ('ò', 'o'),
+    ('ó', 'o'),
+    ('ô', 'o'),
+    ('õ', 'o'),
+    ('ö', 'o'),
+    ('ù', 'u'),
+    ('ú', 'u'),
+    ('û', 'u'),
+    ('ü', 'u'),
+    ('ý', 'y'),
+    ('ÿ', 'y'),
+];
+
+/// The letter of the alphabet a typed character stands for. Uppercase letters are lowercased and
+/// an accented letter that isn't in the alphabet loses its accent.
+pub fn normalize(chr: char, alphabet: &[char]) -> Option<char> {
+    let chr = chr.to_lowercase().next()?;
+    if alphabet.contains(&chr) {
+        return Some(chr);
+    }
+    ACCENTS
+        .iter()
+        .find(|(accented, _)| *accented == chr)
+        .map(|(_, base)| *base)
+        .filter(|base| alphabet.contains(base))
+}
+
+/// The keys as text, bytes of characters outside ASCII are decoded together
+fn decode(keys: &[i32]) -> String {
+    let bytes = keys
+        .iter()
+        .filter_map(|key| u8::try_from(*key).ok())
+        .collect_vec();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// The character that starts with the key `first`, the rest of the bytes of a character outside
+/// ASCII are read. Returns `None` for keys that aren't characters, like the arrow keys.
+pub fn read_char(first: i32, timeout: i32) -> Option<char> {
+    let length = match u8::try_from(first).ok()? {
+        byte if byte < 0x80 => return char::from_u32(first as u32),
+        byte if byte >> 5 == 0b110 => 2,
+        byte if byte >> 4 == 0b1110 => 3,
+        byte if byte >> 3 == 0b11110 => 4,
+        _ => return None,
+    };
+
+    // The other bytes are sent together with the first one
+    ncurses::timeout(50);
+    let mut keys = vec![first];
+    while keys.len() < length {
+        let key = ncurses::getch();
+        if key == ncurses::ERR {
+            break;
+        }
+        keys.push(key);
+    }
+    ncurses::timeout(timeout);
+
+    decode(&keys)
+        .chars()
+        .next()
+        .filter(|chr| *chr != '\u{fffd}')
+}
+
+/// Ask the terminal to mark pasted text so it can be told apart from typing, the terminal goes back
+/// to normal when the returned value is dropped
+pub fn enable_bracketed_paste() -> impl Drop {
+    print!("\x1b[?2004h");
+    let _ = io::stdout().flush();
+    defer(|| {
+        print!("\x1b[?2004l");
+        let _ = io::stdout().flush();
+    })
+}
+
+/// The marker the terminal sends before pasted text, after the escape key
+const PASTE_START: &str = "[200~";
+/// The marker the terminal sends after pasted text
+const PASTE_END: &str = "\x1b[201~";
+
+/// The keys of the characters of a marker
+fn marker_keys(marker: &str) -> Vec<i32> {
+    marker.chars().map(|chr| chr as i32).collect()
+}
+
+/// The pasted text when the keys after `first` start with the bracketed paste marker, `None` when
+/// the keys were typed. Keys that come in faster than anyone can type aren't pasted on their own,
+/// the keys of a fast typist or a bit of lag come in like that too.
+fn pasted(first: i32, keys: &[i32]) -> Option<String> {
+    let start = marker_keys(PASTE_START);
+    if first != ESCAPE || !keys.starts_with(&start) {
+        return None;
+    }
+    let end = marker_keys(PASTE_END);
+    let content = &keys[start.len()..];
+    Some(decode(content.strip_suffix(&end[..]).unwrap_or(content)))
+}
+
+/// Read text that the terminal put between the bracketed paste markers, `first` is the escape
+/// that starts the marker. Returns `None` when `first` is a key on its own, the keys that were
+/// read ahead are given back to ncurses then. `timeout` is the input timeout to go back to.
+pub fn read_paste(first: i32, timeout: i32) -> Option<String> {
+    if first != ESCAPE {
+        return None;
+    }
+
+    // Read what is already waiting
+    ncurses::timeout(0);
+    let mut keys = Vec::new();
+    loop {
+        let key = ncurses::getch();
+        if key == ncurses::ERR {
+            break;
+        }
+        keys.push(key);
+    }
+
+    if keys.starts_with(&marker_keys(PASTE_START)) {
+        // A large paste may still be coming in, wait for the end marker a little while
+        ncurses::timeout(50);
+        let end = marker_keys(PASTE_END);
+        while !keys.ends_with(&end) {
+            let key = ncurses::getch();
+            if key == ncurses::ERR {
+                break;
+            }
+            keys.push(key);
+        }
+    }
+    let pasted = pasted(first, &keys);
+    if pasted.is_none() {
+        // An escape sequence or keys typed after escape, give the keys back to be handled one by one
+        for key in keys.iter().rev() {
+            ncurses::ungetch(*key);
+        }
+    }
+
+    ncurses::timeout(timeout);
+    pasted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(text: &str) -> Vec<i32> {
+        text.bytes().map(i32::from).collect()
+    }
+
+    #[test]
+    fn the_bytes_of_a_character_are_decoded_together() {
+        assert_eq!(decode(&keys("kaart")), "kaart");
+        assert_eq!(decode(&keys("café")), "café");
+        // Keys that aren't bytes, like the arrow keys, are left out
+        assert_eq!(decode(&[ncurses::KEY_LEFT, 'a' as i32]), "a");
+    }
+
+    #[test]
+    fn only_text_between_the_paste_markers_is_pasted() {
+        assert_eq!(
+            pasted(ESCAPE, &keys("[200~kaart\x1b[201~")),
+            Some("kaart".to_string())
+        );
+        assert_eq!(
+            pasted(ESCAPE, &keys("[200~café\x1b[201~")),
+            Some("café".to_string())
+        );
+        // The end marker may not have come in yet
+        assert_eq!(
+            pasted(ESCAPE, &keys("[200~kaart")),
+            Some("kaart".to_string())
+        );
+    }
+
+    #[test]
+    fn typing_fast_is_not_pasting() {
+        assert_eq!(pasted('k' as i32, &keys("aarten")), None);
+        assert_eq!(pasted('[' as i32, &keys("200~kaart")), None);
+        // An arrow key as an escape sequence
+        assert_eq!(pasted(ESCAPE, &keys("[D")), None);
+        assert_eq!(pasted(ESCAPE, &[]), None);
+    }
+}
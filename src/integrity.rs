@@ -0,0 +1,150 @@
+//! Which help a game offers depends on how it is played. Games against another player and the
+//! puzzle of the week, whose scores are compared between players, are competitive and played
+//! without any assistance. The features that help the player all ask the policy here instead of
+//! looking at the options of the game themselves.
+
+use crate::strings::strings;
+
+/// How a game is played
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// A normal game, alone or against the computer
+    Casual,
+    /// A game on a known word or a part of the word list
+    Practice,
+    /// A drill on the weak spots of the player
+    Drill,
+    /// A game against another player over the network
+    Network,
+    /// The words of a group that are played by everyone in the room
+    Group,
+    /// Two teams taking turns on the words on one machine
+    Team,
+    /// Words one after the other until one isn't solved
+    Endless,
+    /// The words of the week, played back to back
+    Weekly,
+    /// An easy word with as many guesses as it takes
+    Kids,
+}
+
+impl Mode {
+    #[cfg(test)]
+    const ALL: [Mode; 9] = [
+        Mode::Casual,
+        Mode::Practice,
+        Mode::Drill,
+        Mode::Network,
+        Mode::Group,
+        Mode::Team,
+        Mode::Endless,
+        Mode::Weekly,
+        Mode::Kids,
+    ];
+
+    /// The name of the mode as it is shown in the status line
+    pub fn name(self) -> &'static str {
+        let strings = strings();
+        match self {
+            Mode::Casual => strings.casual,
+            Mode::Practice => strings.practice,
+            Mode::Drill => strings.drill,
+            Mode::Network => strings.network,
+            Mode::Group => strings.group,
+            Mode::Team => strings.team,
+            Mode::Endless => strings.endless,
+            Mode::Weekly => strings.weekly,
+            Mode::Kids => strings.kids,
+        }
+    }
+
+    /// If the game is a competition between players, these are played without assistance
+    pub fn is_competitive(self) -> bool {
+        match self {
+            Mode::Casual | Mode::Practice | Mode::Drill | Mode::Endless | Mode::Kids => false,
+            Mode::Network | Mode::Group | Mode::Team | Mode::Weekly => true,
+        }
+    }
+}
+
+/// The help the player gets during a game
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Assistance {
+    /// The panel with the words that are still possible
+    pub suggestions: bool,
+    /// The colors on the keyboard that show what is known about the letters
+    pub keyboard_hints: bool,
+    /// If the player can turn the assistance on and off during the game
+    pub adjustable: bool,
+}
+
+impl Assistance {
+    pub fn for_mode(mode: Mode) -> Assistance {
+        if mode.is_competitive() {
+            Assistance::none()
+        } else {
+            Assistance {
+                suggestions: true,
+                keyboard_hints: true,
+                adjustable: true,
+            }
+        }
+    }
+
+    /// The assistance in zen mode, what can be turned off is turned off
+    pub fn zen(self) -> Assistance {
+        Assistance {
+            suggestions: false,
+            keyboard_hints: false,
+            ..self
+        }
+    }
+
+    fn none() -> Assistance {
+        Assistance {
+            suggestions: false,
+            keyboard_hints: false,
+            adjustable: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn competitive_modes_have_no_assistance() {
+        // The scores of the weekly tournament are compared, so it counts as competitive
+        assert!(Mode::Weekly.is_competitive());
+        for mode in Mode::ALL.into_iter().filter(|mode| mode.is_competitive()) {
+            assert_eq!(Assistance::for_mode(mode), Assistance::none(), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn network_games_are_competitive() {
+        assert!(Mode::Network.is_competitive());
+    }
+
+    #[test]
+    fn other_modes_have_suggestions() {
+        for mode in Mode::ALL.into_iter().filter(|mode| !mode.is_competitive()) {
+            let assistance = Assistance::for_mode(mode);
+            assert!(assistance.suggestions, "{:?}", mode);
+            assert!(assistance.keyboard_hints, "{:?}", mode);
+            assert!(assistance.adjustable, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn zen_mode_can_be_left_again() {
+        let zen = Assistance::for_mode(Mode::Casual).zen();
+        assert!(!zen.suggestions && !zen.keyboard_hints);
+        assert!(zen.adjustable);
+        assert_eq!(
+            Assistance::for_mode(Mode::Network).zen(),
+            Assistance::none()
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! The keyboard at the bottom of the screen. It shows what is known about each letter, and its keys
+//! and the enter and backspace buttons can be clicked with the mouse.
+
+use crate::announce;
+use crate::render::Renderer;
+use crate::rules::Feedback;
+use crate::strings::strings;
+use crate::word;
+use crate::{COLOR_PAIR_CORRECT, COLOR_PAIR_WRONG_PLACE};
+use std::collections::HashMap;
+
+/// The letters of a qwerty keyboard, the letters of the alphabet that aren't on it get a row of
+/// their own
+const ROWS: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyAction {
+    Letter(char),
+    Enter,
+    Backspace,
+}
+
+/// A key on the screen, the label is drawn with a space on both sides
+pub struct Key {
+    action: KeyAction,
+    label: String,
+    x: i32,
+    y: i32,
+}
+
+impl Key {
+    fn width(&self) -> i32 {
+        self.label.chars().count() as i32 + 2
+    }
+}
+
+/// The amount of rows the keyboard takes for the alphabet
+pub fn height(alphabet: &[char]) -> i32 {
+    rows(alphabet).len() as i32
+}
+
+/// The actions of the keys on each row
+pub fn rows(alphabet: &[char]) -> Vec<Vec<KeyAction>> {
+    let letters = |row: &str| {
+        row.chars()
+            .filter(|letter| alphabet.contains(letter))
+            .map(KeyAction::Letter)
+            .collect::<Vec<_>>()
+    };
+    let mut rows = vec![letters(ROWS[0]), letters(ROWS[1])];
+    // Like on a phone, enter and backspace go around the last row of letters
+    let mut last = vec![KeyAction::Enter];
+    last.extend(letters(ROWS[2]));
+    last.push(KeyAction::Backspace);
+    rows.push(last);
+
+    let mut extra = alphabet
+        .iter()
+        .filter(|letter| !ROWS.iter().any(|row| row.contains(**letter)))
+        .copied()
+        .collect::<Vec<_>>();
+    extra.sort_unstable();
+    if !extra.is_empty() {
+        rows.push(extra.into_iter().map(KeyAction::Letter).collect());
+    }
+    rows
+}
+
+/// Place the keys on the bottom rows of a screen of this size, every row is centered
+pub fn layout(alphabet: &[char], max_x: i32, max_y: i32) -> Vec<Key> {
+    let rows = rows(alphabet);
+    let top = max_y - rows.len() as i32;
+    let mut keys = Vec::new();
+    for (index, row) in rows.into_iter().enumerate() {
+        let row = row
+            .into_iter()
+            .map(|action| Key {
+                action,
+                label: match action {
+                    KeyAction::Letter(letter) => word::display(letter),
+                    KeyAction::Enter => strings().enter_key.to_string(),
+                    KeyAction::Backspace => strings().back_key.to_string(),
+                },
+                x: 0,
+                y: top + index as i32,
+            })
+            .collect::<Vec<_>>();
+        // The keys have a space between them
+        let width = row.iter().map(|key| key.width() + 1).sum::<i32>() - 1;
+        let mut x = (max_x - width) / 2;
+        for mut key in row {
+            key.x = x;
+            x += key.width() + 1;
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// Draw the keys, colored by what is known about their letters. The keys are also marked like the
+/// tiles when `marked`.
+pub fn render(
+    screen: &mut impl Renderer,
+    keys: &[Key],
+    feedback: &HashMap<char, Feedback>,
+    marked: bool,
+) {
+    for key in keys {
+        let known = match key.action {
+            KeyAction::Letter(letter) => feedback.get(&letter).copied(),
+            KeyAction::Enter | KeyAction::Backspace => None,
+        };
+        let attribute = match known {
+            None => ncurses::A_REVERSE(),
+            Some(Feedback::Wrong) => ncurses::A_DIM(),
+            Some(Feedback::WrongPlace) => {
+                ncurses::A_BOLD()
+                    | ncurses::A_REVERSE()
+                    | ncurses::COLOR_PAIR(COLOR_PAIR_WRONG_PLACE)
+            }
+            Some(Feedback::Correct) => {
+                ncurses::A_BOLD() | ncurses::A_REVERSE() | ncurses::COLOR_PAIR(COLOR_PAIR_CORRECT)
+            }
+        };
+        let [open, close] = match known {
+            Some(known) if marked => announce::marks(known),
+            _ => [' ', ' '],
+        };
+        screen.print(
+            key.y,
+            key.x,
+            &format!("{}{}{}", open, key.label, close),
+            attribute,
+        );
+    }
+}
+
+/// Turn on the mouse events for clicks on the keys
+pub fn enable_mouse() {
+    ncurses::mousemask(ncurses::BUTTON1_PRESSED as ncurses::mmask_t, None);
+    // Report the press right away instead of waiting to see if it becomes a click
+    ncurses::mouseinterval(0);
+}
+
+/// The key at this place on the screen, for the keyboard of the alphabet on the screen as it is
+/// now
+pub fn key_at(alphabet: &[char], x: i32, y: i32) -> Option<KeyAction> {
+    let mut max_x = 0;
+    let mut max_y = 0;
+    ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+    layout(alphabet, max_x, max_y)
+        .into_iter()
+        .find(|key| key.y == y && (key.x..key.x + key.width()).contains(&x))
+        .map(|key| key.action)
+}
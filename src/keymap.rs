@@ -0,0 +1,206 @@
+//! The keys of the actions during a game, from the `[keys]` table of the config file. Every action
+//! takes a list of key names, like `quit = ["esc", "ctrl-q"]`. A name is a single character,
+//! `ctrl-` with a letter, `f1` to `f12` or one of `esc`, `enter`, `backspace`, `delete` and `tab`.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The code of the escape key
+pub const ESCAPE: i32 = 27;
+/// The codes of the enter key, terminals send either of these
+#[cfg(not(windows))]
+pub const ENTER: &[i32] = &[ncurses::KEY_ENTER, '\n' as i32];
+/// The codes of the enter key, PDCurses reads a carriage return in the Windows console
+#[cfg(windows)]
+pub const ENTER: &[i32] = &[ncurses::KEY_ENTER, '\n' as i32, '\r' as i32];
+/// The codes of the backspace key, terminals send either of these
+#[cfg(not(windows))]
+pub const BACKSPACE: &[i32] = &[ncurses::KEY_BACKSPACE, 127];
+/// The codes of the backspace key, the Windows console sends Ctrl-H
+#[cfg(windows)]
+pub const BACKSPACE: &[i32] = &[ncurses::KEY_BACKSPACE, 127, 8];
+
+/// What a key does during a game
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    /// Pause the game, it can be quit from the pause menu
+    Quit,
+    Submit,
+    Backspace,
+    /// Suggest one of the words that are still possible
+    Hint,
+    /// Show the keys of the actions
+    Help,
+    /// Turn zen mode on and off
+    TogglePanel,
+}
+
+impl Action {
+    /// The name of the action in the config file
+    fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Submit => "submit",
+            Action::Backspace => "backspace",
+            Action::Hint => "hint",
+            Action::Help => "help",
+            Action::TogglePanel => "toggle_panel",
+        }
+    }
+}
+
+/// The key names of each action, as they are in the config file
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Keymap {
+    pub quit: Vec<String>,
+    pub submit: Vec<String>,
+    pub backspace: Vec<String>,
+    pub hint: Vec<String>,
+    pub help: Vec<String>,
+    pub toggle_panel: Vec<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        let keys = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        Keymap {
+            quit: keys(&["esc"]),
+            submit: keys(&["enter"]),
+            backspace: keys(&["backspace"]),
+            hint: keys(&["?"]),
+            help: keys(&["f1"]),
+            toggle_panel: keys(&["tab"]),
+        }
+    }
+}
+
+impl Keymap {
+    fn actions(&self) -> [(Action, &[String]); 6] {
+        [
+            (Action::Quit, &self.quit),
+            (Action::Submit, &self.submit),
+            (Action::Backspace, &self.backspace),
+            (Action::Hint, &self.hint),
+            (Action::Help, &self.help),
+            (Action::TogglePanel, &self.toggle_panel),
+        ]
+    }
+
+    /// The table from the keys ncurses reads to the actions, a key can only have one action
+    pub fn bindings(&self) -> Result<Bindings, String> {
+        let mut actions = HashMap::new();
+        let mut names = HashMap::new();
+        for (action, keys) in self.actions() {
+            for name in keys {
+                let codes = codes(name).ok_or_else(|| format!("There is no key {}", name))?;
+                for code in codes {
+                    if let Some(other) = actions
+                        .insert(code, action)
+                        .filter(|&other| other != action)
+                    {
+                        return Err(format!(
+                            "The key {} is used for both {} and {}",
+                            name,
+                            other.name(),
+                            action.name()
+                        ));
+                    }
+                }
+            }
+            if let Some(name) = keys.first() {
+                names.insert(action, name.clone());
+            }
+        }
+        Ok(Bindings { actions, names })
+    }
+}
+
+/// The key codes ncurses reads for the key with this name
+fn codes(name: &str) -> Option<Vec<i32>> {
+    let lowercase = name.to_lowercase();
+    let codes = match lowercase.as_str() {
+        "esc" | "escape" => vec![ESCAPE],
+        "enter" | "return" => ENTER.to_vec(),
+        "backspace" => BACKSPACE.to_vec(),
+        "delete" => vec![ncurses::KEY_DC],
+        "tab" => vec!['\t' as i32],
+        _ => {
+            if let Some(letter) = lowercase.strip_prefix("ctrl-") {
+                let mut letters = letter.chars();
+                match (letters.next(), letters.next()) {
+                    (Some(letter @ 'a'..='z'), None) => vec![letter as i32 - 'a' as i32 + 1],
+                    _ => return None,
+                }
+            } else if let Some(number) = lowercase
+                .strip_prefix('f')
+                .and_then(|number| number.parse::<i32>().ok())
+                .filter(|number| (1..=12).contains(number))
+            {
+                vec![ncurses::KEY_F0 + number]
+            } else {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(chr), None) if chr.is_ascii_graphic() => vec![chr as i32],
+                    _ => return None,
+                }
+            }
+        }
+    };
+    Some(codes)
+}
+
+/// The actions of the keys during a game
+pub struct Bindings {
+    actions: HashMap<i32, Action>,
+    /// The first key of each action, to tell the player
+    names: HashMap<Action, String>,
+}
+
+impl Bindings {
+    /// The action of the key ncurses read
+    pub fn action(&self, input: i32) -> Option<Action> {
+        self.actions.get(&input).copied()
+    }
+
+    /// The name of the key for the action, a dash when it has no key
+    pub fn name(&self, action: Action) -> &str {
+        self.names.get(&action).map_or("-", String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_bound_once() {
+        let bindings = Keymap::default().bindings().unwrap();
+        assert_eq!(bindings.action('\n' as i32), Some(Action::Submit));
+        assert_eq!(bindings.action('a' as i32), None);
+        for code in BACKSPACE {
+            assert_eq!(bindings.action(*code), Some(Action::Backspace));
+        }
+
+        let keymap = Keymap {
+            quit: vec!["ctrl-q".to_string()],
+            hint: vec!["F2".to_string()],
+            ..Default::default()
+        };
+        let bindings = keymap.bindings().unwrap();
+        assert_eq!(bindings.action(0x11), Some(Action::Quit));
+        assert_eq!(bindings.action(27), None);
+        assert_eq!(bindings.name(Action::Hint), "F2");
+
+        let keymap = Keymap {
+            hint: vec!["tab".to_string()],
+            ..Default::default()
+        };
+        assert!(keymap.bindings().is_err());
+        let keymap = Keymap {
+            help: vec!["ctrl-1".to_string()],
+            ..Default::default()
+        };
+        assert!(keymap.bindings().is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// A language the game can be played in
+pub struct Language {
+    /// The ISO 639-1 code of the language
+    pub code: &'static str,
+    /// The name of the language in English
+    pub name: &'static str,
+    /// The most common pairs of letters in words of the language, the most common first, used to
+    /// recognize the language of a word list
+    pub bigrams: &'static [&'static str],
+    /// The letters that aren't on a standard keyboard with the keys that are typed for them, the
+    /// letter is typed first and then the accent
+    pub compose: &'static [(&'static str, char)],
+    /// The pairs of letters that are written on a single tile, with the letter that stands for
+    /// them in the words
+    pub digraphs: &'static [(&'static str, char)],
+    /// The offensive words of the language, one on each line, these are never the word of the game
+    pub offensive: &'static str,
+}
+
+pub const DUTCH: Language = Language {
+    code: "nl",
+    name: "Dutch",
+    bigrams: &[
+        "en", "de", "er", "te", "an", "ee", "in", "ge", "ie", "el", "aa", "ve", "nd", "st", "oo",
+        "re", "ij", "et", "ch", "ar", "ng", "or", "ke", "le", "ri", "ui", "ei", "ze", "we", "ra",
+    ],
+    compose: &[
+        ("a'", 'á'),
+        ("a`", 'à'),
+        ("a\"", 'ä'),
+        ("c,", 'ç'),
+        ("e'", 'é'),
+        ("e`", 'è'),
+        ("e^", 'ê'),
+        ("e\"", 'ë'),
+        ("i'", 'í'),
+        ("i\"", 'ï'),
+        ("n~", 'ñ'),
+        ("o'", 'ó'),
+        ("o^", 'ô'),
+        ("o\"", 'ö'),
+        ("u'", 'ú'),
+        ("u\"", 'ü'),
+    ],
+    digraphs: &[("ij", 'ĳ')],
+    offensive: include_str!("../offensive-nl.txt"),
+};
+
+pub const ENGLISH: Language = Language {
+    code: "en",
+    name: "English",
+    bigrams: &[
+        "th", "he", "in", "er", "an", "re", "on", "at", "en", "nd", "ti", "es", "or", "te", "of",
+        "ed", "is", "it", "al", "ar", "st", "to", "nt", "ng", "se", "ha", "as", "ou", "io", "le",
+    ],
+    compose: &[],
+    digraphs: &[],
+    offensive: include_str!("../offensive-en.txt"),
+};
+
+/// All the languages the game knows
+pub const LANGUAGES: [&Language; 2] = [&DUTCH, &ENGLISH];
+
+/// The language with the ISO 639-1 code
+pub fn by_code(code: &str) -> Option<&'static Language> {
+    LANGUAGES
+        .iter()
+        .find(|language| language.code.eq_ignore_ascii_case(code))
+        .copied()
+}
+
+/// Guess the language of a word list. The most common pairs of letters in the list are ranked and
+/// the language with ranks closest to its own profile wins, a pair that isn't in the profile of a
+/// language counts as far away as possible.
+pub fn detect(words: &[&str]) -> &'static Language {
+    let mut counts = HashMap::<(char, char), usize>::new();
+    for word in words {
+        for bigram in word.chars().tuple_windows::<(_, _)>() {
+            *counts.entry(bigram).or_default() += 1;
+        }
+    }
+    let mut ranked = counts.into_iter().collect::<Vec<_>>();
+    ranked.sort_by_key(|(bigram, count)| (Reverse(*count), *bigram));
+
+    LANGUAGES
+        .iter()
+        .min_by_key(|language| {
+            ranked
+                .iter()
+                .take(language.bigrams.len())
+                .enumerate()
+                .map(|(rank, ((first, second), _))| {
+                    language
+                        .bigrams
+                        .iter()
+                        .position(|bigram| bigram.chars().eq([*first, *second]))
+                        .map_or(language.bigrams.len(), |position| rank.abs_diff(position))
+                })
+                .sum::<usize>()
+        })
+        .copied()
+        .unwrap()
+}
+
+/// Ask the player to confirm the detected language or to pick another one. Without a terminal to
+/// ask on the detected language is used.
+pub fn confirm(detected: &'static Language) -> io::Result<&'static Language> {
+    if !io::stdin().is_terminal() {
+        eprintln!(
+            "The word list looks {}, playing in {}",
+            detected.name, detected.name
+        );
+        return Ok(detected);
+    }
+
+    let codes = LANGUAGES.iter().map(|language| language.code).join(", ");
+    loop {
+        eprint!(
+            "The word list looks {}. Press enter to play in {} or type another language ({}): ",
+            detected.name, detected.name, codes
+        );
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        match answer.trim() {
+            "" => return Ok(detected),
+            code => match by_code(code) {
+                Some(language) => return Ok(language),
+                None => eprintln!("There is no language {}", code),
+            },
+        }
+    }
+}
+
+impl Language {
+    /// The letter that is composed by typing `key` after `letter`, if there is one
+    pub fn compose(&self, letter: char, key: char) -> Option<char> {
+        self.compose.iter().find_map(|(sequence, composed)| {
+            let mut chars = sequence.chars();
+            (chars.next() == Some(letter) && chars.next() == Some(key) && chars.next().is_none())
+                .then_some(*composed)
+        })
+    }
+
+    /// The letter that stands for the pair of `letter` and `key` when they are written on a single
+    /// tile, if they are
+    pub fn digraph(&self, letter: char, key: char) -> Option<char> {
+        self.digraphs
+            .iter()
+            .find_map(|(pair, joined)| pair.chars().eq([letter, key]).then_some(*joined))
+    }
+
+    /// The text with every pair of letters that is written on a single tile joined into its letter
+    pub fn join_digraphs(&self, text: &str) -> String {
+        self.digraphs
+            .iter()
+            .fold(text.to_string(), |text, (pair, joined)| {
+                text.replace(pair, &joined.to_string())
+            })
+    }
+
+    /// Explain how to type each letter in the alphabet that has to be composed
+    pub fn compose_hints(&self, alphabet: &[char]) -> Vec<String> {
+        self.compose
+            .iter()
+            .filter(|(_, composed)| alphabet.contains(composed))
+            .map(|(sequence, composed)| format!("{} = {}", composed, sequence))
+            .collect()
+    }
+}
@@ -0,0 +1,153 @@
+use crate::storage;
+use crate::strings::{fill, strings};
+use crate::theme::Theme;
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io;
+
+const LEADERBOARD_FILE: &str = "leaderboard.json";
+/// The amount of entries kept in each list
+const MAX_ENTRIES: usize = 10;
+
+/// A single solved game on the leaderboard
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub word: String,
+    pub score: u32,
+    pub guesses: u32,
+    /// The time it took to solve the word in seconds
+    pub seconds: f64,
+}
+
+/// The best games for a single language and word length
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Board {
+    /// Sorted from the highest score down
+    pub best_scores: Vec<Entry>,
+    /// Sorted from the fastest solve up
+    pub fastest: Vec<Entry>,
+}
+
+/// The local leaderboard, kept between runs
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Leaderboard {
+    /// The boards by language and word length, see `key`
+    boards: BTreeMap<String, Board>,
+}
+
+/// The key of the board for a language and word length
+fn key(language: &str, word_length: usize) -> String {
+    format!("{}-{}", language, word_length)
+}
+
+impl Leaderboard {
+    pub fn load() -> io::Result<Leaderboard> {
+        storage::load_json(LEADERBOARD_FILE)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save_json(LEADERBOARD_FILE, self)
+    }
+
+    pub fn board(&self, language: &str, word_length: usize) -> Option<&Board> {
+        self.boards.get(&key(language, word_length))
+    }
+
+    /// Add a game to the leaderboard, only solved games can get on the leaderboard
+    pub fn record(&mut self, language: &str, word_length: usize, result: &GameResult) {
+        // Games on more boards are played by different rules, only single word games compare
+        if !result.won || result.words.len() != 1 {
+            return;
+        }
+
+        let entry = Entry {
+            word: result.words[0].to_string(),
+            score: result.score.total(),
+            guesses: result.guesses,
+            seconds: result.elapsed.as_secs_f64(),
+        };
+        let board = self.boards.entry(key(language, word_length)).or_default();
+
+        board.best_scores.push(entry.clone());
+        board.best_scores.sort_by_key(|entry| Reverse(entry.score));
+        board.best_scores.truncate(MAX_ENTRIES);
+
+        board.fastest.push(entry);
+        board
+            .fastest
+            .sort_by(|a, b| a.seconds.total_cmp(&b.seconds));
+        board.fastest.truncate(MAX_ENTRIES);
+    }
+}
+
+/// Show the leaderboard for a language and word length until a key is pressed
+pub fn render_leaderboard(
+    leaderboard: &Leaderboard,
+    language: &str,
+    word_length: usize,
+    theme: &Theme,
+) {
+    let _window_ender = crate::init_ncurses(theme);
+
+    let mut max_x = 0;
+    let mut max_y = 0;
+    ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+
+    let title = fill(
+        strings().leaderboard_title,
+        &[&language.to_uppercase(), &word_length],
+    );
+    let columns_width: i32 = 2 * 30 + 4;
+    let win_x = (max_x - columns_width) / 2;
+    let win_y = (max_y - (MAX_ENTRIES as i32 + 6)) / 2;
+
+    ncurses::mvaddstr(win_y, (max_x - title.len() as i32) / 2, &title);
+
+    let print_column = |x: i32, heading: &str, entries: &[Entry]| {
+        ncurses::attron(ncurses::A_BOLD());
+        ncurses::mvaddstr(win_y + 2, x, heading);
+        ncurses::attroff(ncurses::A_BOLD());
+        if entries.is_empty() {
+            ncurses::mvaddstr(win_y + 4, x, strings().no_solved_games);
+        }
+        for (index, entry) in entries.iter().enumerate() {
+            let seconds = entry.seconds.round() as u64;
+            let line = format!(
+                "{:>2}. {} {:>4} {} {} {:>2}:{:02}",
+                index + 1,
+                entry.word,
+                entry.score,
+                entry.guesses,
+                strings().guesses,
+                seconds / 60,
+                seconds % 60
+            );
+            ncurses::mvaddstr(win_y + 4 + index as i32, x, &line);
+        }
+    };
+
+    let (best_scores, fastest) = match leaderboard.board(language, word_length) {
+        None => (&[][..], &[][..]),
+        Some(board) => (&board.best_scores[..], &board.fastest[..]),
+    };
+    print_column(win_x, strings().best_scores, best_scores);
+    print_column(
+        win_x + columns_width / 2 + 2,
+        strings().fastest_solves,
+        fastest,
+    );
+
+    let footer = strings().press_to_quit;
+    ncurses::mvaddstr(
+        win_y + MAX_ENTRIES as i32 + 5,
+        (max_x - footer.len() as i32) / 2,
+        footer,
+    );
+
+    ncurses::refresh();
+    ncurses::getch();
+}
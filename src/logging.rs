@@ -0,0 +1,58 @@
+//! Debug logging to the file of --log-file. During a game the terminal belongs to ncurses, so the
+//! log is where to look for what happened: the keys that were read, the changes in the state of
+//! the game and how long drawing the screen took. Without --log-file nothing is logged.
+
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Writes every record as a line with the seconds since the start of the log
+struct FileLogger {
+    file: Mutex<File>,
+    start: Instant,
+}
+
+impl Log for FileLogger {
+    /// Only the records of the game, not the ones of the crates it uses
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{:10.3} {:5} {}: {}\n",
+            self.start.elapsed().as_secs_f64(),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Ok(mut file) = self.file.lock() {
+            // A line that can't be written is lost, the game goes on
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Log to the end of this file from now on
+pub fn init(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let logger = Box::leak(Box::new(FileLogger {
+        file: Mutex::new(file),
+        start: Instant::now(),
+    }));
+    log::set_logger(logger).map_err(|err| io::Error::other(err.to_string()))?;
+    log::set_max_level(LevelFilter::Debug);
+    Ok(())
+}
@@ -1,18 +1,136 @@
+mod anagram;
+mod analyze;
+mod announce;
+mod attract;
+mod bench;
+mod bingo;
+mod borders;
+mod bot;
+mod candidates;
+mod challenge;
+mod cli;
+mod codes;
+mod compiled;
+mod config;
+mod crossword;
+mod crypto;
+mod definitions;
+mod dict;
+mod dictionary;
+mod drill;
+mod editor;
+mod endless;
+mod engine;
+mod error;
+mod events;
+mod graphics;
+mod group;
+mod headless;
+mod history;
+mod http;
+mod hunspell;
+mod import_share;
+mod input;
+mod integrity;
+mod keyboard;
+mod keymap;
+mod language;
+mod leaderboard;
+mod logging;
+mod masks;
+mod math;
+mod messages;
+mod navigation;
+mod net;
+mod obscurity;
+mod opponent;
+mod packs;
+mod pause;
+mod phrases;
+mod play;
+mod profile;
+mod protocol;
+mod quick_settings;
+mod render;
+mod replay;
+mod review;
+mod rules;
+mod score;
+mod script;
+mod settings;
+mod share;
+mod simulate;
+mod solver;
+mod sound;
+mod stats;
+mod storage;
+mod strategy;
+mod strings;
+mod team;
+mod theme;
+mod tui;
+mod watch;
+mod weekly;
+mod word;
+
+use announce::Announcer;
+use borders::Line;
+use clap::Parser;
+use config::Config;
 use defer::defer;
+use definitions::Definitions;
+use dictionary::Dictionary;
+use error::Error;
+use events::{Event, EventSink};
+use integrity::Mode;
 use itertools::Itertools;
-use ncurses;
-use rand::Rng;
-use std::collections::HashMap;
+use keymap::Bindings;
+use language::Language;
+use leaderboard::Leaderboard;
+use lingo_core::knowledge::Knowledge;
+use masks::Constraint;
+use messages::{MessageQueue, Severity};
+use opponent::{ComputerOpponent, Opponent};
+use rand::{Rng, SeedableRng};
+use render::{Renderer, Terminal};
+use replay::Replay;
+use rules::{Feedback, Pattern};
+use score::Score;
+use script::Scripts;
+use settings::Settings;
+use share::Privacy;
+use stats::Stats;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, IsTerminal};
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use strings::{fill, strings};
+use theme::Theme;
+use tui::Ui;
 
-const WORD_LENGTH: usize = 5;
+const WORD_LENGTH: usize = lingo_core::WORD_LENGTH;
+/// The most tiles on a row, for a phrase of two words with the space between them
+const MAX_TILES: usize = 2 * WORD_LENGTH + 1;
 const GUESSES: u32 = 5;
+/// The max guesses of a game that goes on until the word is found
+const UNLIMITED: u32 = u32::MAX;
 
 // Ids used by ncurses to identify colors
 const COLOR_PAIR_CORRECT: i16 = 1;
 const COLOR_PAIR_WRONG_PLACE: i16 = 2;
+const COLOR_PAIR_WARNING: i16 = 3;
+const COLOR_PAIR_ERROR: i16 = 4;
+// The tiles on the board, these are filled with the color or only color the letter depending on
+// the theme
+const COLOR_PAIR_WRONG_TILE: i16 = 5;
+const COLOR_PAIR_WRONG_PLACE_TILE: i16 = 6;
+const COLOR_PAIR_CORRECT_TILE: i16 = 7;
 
+#[derive(Clone, Copy, Default)]
 enum GuessedLetter {
     /// No letter has been entered on this spot yet
+    #[default]
     NoLetter,
     /// A letter has been entered but it hasn't been verified yet
     Letter(char),
@@ -22,409 +140,2236 @@ enum GuessedLetter {
     WrongPlace(char),
     /// The letter has been verified and is in this place in the target word
     Correct(char),
+    /// The space between the words of a phrase, a fixed tile that isn't guessed
+    Separator,
+}
+
+impl GuessedLetter {
+    /// The tile of a letter that is being typed
+    fn typed(letter: Option<char>) -> GuessedLetter {
+        match letter {
+            None => GuessedLetter::NoLetter,
+            Some(word::SEPARATOR) => GuessedLetter::Separator,
+            Some(x) => GuessedLetter::Letter(x),
+        }
+    }
+
+    /// The verdict on the letter, if it has been verified
+    fn feedback(self) -> Option<Feedback> {
+        match self {
+            GuessedLetter::NoLetter | GuessedLetter::Letter(_) | GuessedLetter::Separator => None,
+            GuessedLetter::Wrong(_) => Some(Feedback::Wrong),
+            GuessedLetter::WrongPlace(_) => Some(Feedback::WrongPlace),
+            GuessedLetter::Correct(_) => Some(Feedback::Correct),
+        }
+    }
+}
+
+/// The tiles of a row of a board, a tile for each letter of the word. A phrase has a tile for
+/// each letter and one for the space, so its rows can be longer.
+#[derive(Clone, Copy)]
+struct GuessedWord {
+    tiles: [GuessedLetter; MAX_TILES],
+    /// How many of the tiles are on the row
+    length: usize,
+}
+
+impl GuessedWord {
+    /// A row of empty tiles
+    fn empty(length: usize) -> GuessedWord {
+        assert!(length <= MAX_TILES, "A row can't have {} tiles", length);
+        GuessedWord {
+            tiles: [GuessedLetter::NoLetter; MAX_TILES],
+            length,
+        }
+    }
 }
 
-impl Default for GuessedLetter {
-    fn default() -> Self {
-        GuessedLetter::NoLetter
+impl Default for GuessedWord {
+    fn default() -> GuessedWord {
+        GuessedWord::empty(WORD_LENGTH)
     }
 }
 
-type GuessedWord = [GuessedLetter; WORD_LENGTH];
+impl Deref for GuessedWord {
+    type Target = [GuessedLetter];
+
+    fn deref(&self) -> &[GuessedLetter] {
+        &self.tiles[..self.length]
+    }
+}
+
+impl DerefMut for GuessedWord {
+    fn deref_mut(&mut self) -> &mut [GuessedLetter] {
+        &mut self.tiles[..self.length]
+    }
+}
+
+/// How the verified tiles of a board are drawn
+#[derive(Clone, Copy, Default)]
+struct Tiles {
+    /// Only the colors are shown, not the letters
+    masked: bool,
+    /// Marks around the letters tell the feedback without the colors
+    marked: bool,
+}
+
+/// One of the boards the player guesses on, in a multi-board game every guess goes on each board
+/// that isn't solved yet
+#[derive(Clone)]
+struct Board {
+    rows: Vec<GuessedWord>,
+    /// If the hidden word of this board has been found
+    solved: bool,
+}
+
+impl Board {
+    fn new(guesses: u32) -> Board {
+        Board::with_tiles(guesses, WORD_LENGTH)
+    }
+
+    /// A board with rows that are longer or shorter than a word, for a phrase
+    fn with_tiles(guesses: u32, tiles: usize) -> Board {
+        Board {
+            rows: vec![GuessedWord::empty(tiles); guesses as usize],
+            solved: false,
+        }
+    }
+
+    /// The amount of tiles on each row
+    fn tiles(&self) -> usize {
+        self.rows.first().map_or(WORD_LENGTH, |row| row.len())
+    }
+}
 
 #[derive(Default)]
 struct BoardState {
-    board: [GuessedWord; GUESSES as usize],
-    message: Option<String>,
+    boards: Vec<Board>,
+    messages: MessageQueue,
+    /// The line with the score, shown between the board and the message
+    score: Option<String>,
+    /// The part of the time for the row that is left, it is shown as a bar behind the score
+    row_timer: Option<f64>,
+    /// The bingo card of an endless run, next to the boards
+    bingo: Option<bingo::Card>,
+    /// The grid of a crossword, next to the boards. The cursor is on the board of the word that
+    /// is being guessed.
+    crossword: Option<crossword::Grid>,
+    /// The row and tile of the cursor while a guess is being typed
+    cursor: Option<(usize, usize)>,
     possible_words: Vec<&'static str>,
+    /// How often the letters are on each place in the possible words, shown above the words in
+    /// learning mode
+    frequencies: Vec<Vec<(char, f64)>>,
+    /// How many of the possible words are scrolled past in the panel
+    possible_scroll: usize,
+    /// If the possible words are left out, they are still kept up to date
+    hide_possible_words: bool,
+    /// The letters of the keyboard below the board, without letters there is no keyboard
+    keyboard: Vec<char>,
+    /// What is known about the letters on the keyboard
+    key_feedback: HashMap<char, Feedback>,
+    /// How to type the letters of the alphabet that aren't on the keyboard
+    compose_hints: Vec<String>,
+    /// The board of the opponent racing the player
+    opponent_board: Option<[GuessedWord; GUESSES as usize]>,
+    opponent_name: String,
+    /// If the letters on the board of the opponent are hidden
+    opponent_masked: bool,
+    /// If the tiles and keys are marked, so the feedback isn't told by the colors alone
+    marked: bool,
+    /// What the status line above the boards tells, without it there is no status line
+    info: Option<GameInfo>,
+    /// The row that is shaken and how far its letters are moved to the right
+    shake: Option<(usize, i32)>,
+}
+
+impl BoardState {
+    /// The amount of tiles on the rows of the boards
+    fn tiles(&self) -> usize {
+        self.boards.first().map_or(WORD_LENGTH, Board::tiles)
+    }
+}
+
+/// What the status line above the boards tells about the game
+struct GameInfo {
+    language: &'static str,
+    /// The letters to find, for a phrase the space isn't counted
+    letters: usize,
+    mode: Mode,
+    hard: bool,
+    evil: bool,
+    /// If there is a bonus for solving the word quickly
+    timed: bool,
+    /// The guess that is being made, starting at 1
+    guess: usize,
+    max_guesses: u32,
+    /// The games won in a row before this one, for games that count in the statistics
+    streak: Option<u32>,
+    /// The words that can still be missed in an endless run
+    lives: Option<u32>,
+}
+
+impl GameInfo {
+    fn status(&self) -> String {
+        let strings = strings();
+        let mut parts = vec![
+            self.language.to_uppercase(),
+            fill(strings.letters, &[&self.letters]),
+            self.mode.name().to_string(),
+        ];
+        for (on, name) in [
+            (self.hard, strings.hard),
+            (self.evil, strings.evil),
+            (self.timed, strings.timed),
+        ] {
+            if on {
+                parts.push(name.to_string());
+            }
+        }
+        if let Some(streak) = self.streak {
+            parts.push(fill(strings.streak, &[&streak]));
+        }
+        if let Some(lives) = self.lives {
+            parts.push(fill(strings.lives, &[&lives]));
+        }
+        parts.join(" \u{b7} ")
+    }
+
+    /// The title of a single board, the guess counter
+    fn title(&self) -> String {
+        match self.max_guesses {
+            UNLIMITED => fill(strings().guess_title_unlimited, &[&self.guess]),
+            _ => fill(
+                strings().guess_title,
+                &[
+                    &self.guess.min(self.max_guesses as usize),
+                    &self.max_guesses,
+                ],
+            ),
+        }
+    }
+}
+
+/// The options for a single game
+struct GameOptions<'a> {
+    /// Give bonus points for solving the word quickly
+    time_bonus: bool,
+    /// The time for each row, a full row is guessed when it runs out and a row that isn't full is
+    /// lost
+    row_timer: Option<Duration>,
+    /// Don't fix the word, after each guess keep as many of these answers possible as the rules
+    /// allow
+    evil: Option<&'a [&'static str]>,
+    /// Every revealed hint has to be used in the next guesses
+    hard: bool,
+    language: &'static Language,
+    /// The amount of words that are guessed at the same time
+    boards: usize,
+    theme: &'static Theme,
+    /// How the game is played, this decides the assistance the player gets
+    mode: Mode,
+    /// Where the words are looked up after the game
+    definitions: &'static Definitions,
+    /// Also look up the guessed words after the game
+    define_guesses: bool,
+    /// The first guess that is suggested when the game starts
+    opener: Option<&'static str>,
+    /// Review the guesses after the game
+    review: bool,
+    /// The games won in a row, shown for games that count in the statistics
+    streak: Option<u32>,
+    /// The words that can still be missed in an endless run
+    lives: Option<u32>,
+    /// Show how often the letters are on each place in the possible words
+    learn: bool,
+    /// The hooks of the Lua scripts
+    scripts: &'static Scripts,
+    /// Start in zen mode, without the assistance that can be turned off
+    zen: bool,
+    /// Write the feedback out and mark the tiles, for screen readers
+    a11y: bool,
+    /// The keys of the actions
+    keys: &'static Bindings,
+    /// The turn of a team in a team game
+    turn: Option<team::Turn>,
+    /// The bingo card of an endless run
+    bingo: Option<bingo::Card>,
+}
+
+/// Why a game stopped before its end
+enum Stopped {
+    Quit,
+    /// The player wants to start over with new words
+    Restart,
+    /// The game can't go on, the error is for after the terminal is restored
+    Failed(Error),
+}
+
+/// The outcome of a game that was played to the end
+pub struct GameResult {
+    /// The hidden words, one for each board
+    pub words: Vec<&'static str>,
+    pub won: bool,
+    /// The amount of verified guesses
+    pub guesses: u32,
+    /// The amount of guesses that were allowed
+    pub max_guesses: u32,
+    /// The verified guesses
+    pub guessed: Vec<String>,
+    /// The patterns of the verified guesses, only kept for games with a single board
+    pub patterns: Vec<Pattern>,
+    /// The recording of the game, only kept for games with a single board
+    pub replay: Option<Replay>,
+    pub score: Score,
+    pub elapsed: Duration,
 }
 
 fn main() {
-    // This should be the only object that actually has bytes in it instead of references to bytes
-    let word_string = include_str!("../word-list-nl.txt");
-
-    // Collect the possible words into a vector of references
-    let mut words: Vec<&str> = word_string
-        // The dictionary should have a valid word on each line
-        .lines()
-        // Only take words of the correct length
-        .filter(|word| word.len() == WORD_LENGTH)
-        // Remove words that cannot be entered on the keyboard, the lists that are currently used
-        // also contain city names
-        .filter(|word| word.chars().all(|chr| ('a'..='z').contains(&chr)))
-        .collect();
-
-    // Sort the word list and make the list non-mutable afterwards
+    let cli = cli::Cli::parse();
+    if let Some(path) = &cli.log_file {
+        if let Err(err) = logging::init(path) {
+            eprintln!("Could not open the log file {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    }
+    use_profile(&cli);
+    let config = Config::load().unwrap_or_else(|err| {
+        eprintln!("Could not load the config file: {}", err);
+        std::process::exit(1);
+    });
+    borders::use_style(config.borders);
+    graphics::use_graphics(config.tile_graphics);
+    sound::use_sounds(&config.sounds);
+    strings::select(cli.language.as_deref());
+    let mut settings = Settings::load().unwrap_or_else(|err| {
+        eprintln!("Could not load the settings, using the defaults: {}", err);
+        Settings::default()
+    });
+    let theme = Theme::from_settings(&settings);
+    let picked_language = cli.language.as_ref().map(|code| {
+        language::by_code(code).unwrap_or_else(|| {
+            eprintln!(
+                "There is no language {}, pick one of {}",
+                code,
+                language::LANGUAGES
+                    .iter()
+                    .map(|language| language.code)
+                    .join(", ")
+            );
+            std::process::exit(1);
+        })
+    });
+
+    // The commands that don't play with the word list
+    match &cli.command {
+        Some(cli::Command::Theme) => return pick_theme(&mut settings),
+        Some(cli::Command::Leaderboard) => return show_leaderboard(picked_language, theme),
+        Some(cli::Command::Stats { command }) => return stats_command(command),
+        Some(cli::Command::Dict { command }) => {
+            return dict_command(&cli, command, picked_language)
+        }
+        Some(cli::Command::Replay { file }) => return show_replay(file, theme),
+        Some(cli::Command::Watch { address }) => return watch_games(address, theme),
+        _ => (),
+    }
+
+    let lists = load_lists(&cli, &config, picked_language);
+    let words = lists.words();
+    let language = lists.language;
+
+    match &cli.command {
+        Some(command @ (cli::Command::Serve { .. } | cli::Command::Http { .. })) => {
+            return serve(&cli, command, &lists)
+        }
+        Some(cli::Command::Bot {
+            backend,
+            channel,
+            server,
+            nick,
+        }) => {
+            let pick = || lists.pick(&cli);
+            let played = bot::run(
+                *backend,
+                server.as_deref(),
+                channel,
+                nick,
+                &lists.session(&cli),
+                pick,
+            );
+            if let Err(err) = played {
+                eprintln!("Could not run the bot in {}: {}", channel, err);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(cli::Command::History) => {
+            let stats = Stats::load().unwrap_or_else(|err| {
+                eprintln!("Could not load the statistics: {}", err);
+                std::process::exit(1);
+            });
+            return history::browse(&stats.history, words, language, theme);
+        }
+        Some(cli::Command::ImportShare {
+            file,
+            answer,
+            answers,
+        }) => {
+            return import_shared(
+                file.as_deref(),
+                answer.as_deref(),
+                answers.as_deref(),
+                words,
+                language,
+                theme,
+            )
+        }
+        Some(cli::Command::Analyze { command }) => match command {
+            cli::AnalyzeCommand::Openers { top } => {
+                return analyze::print_openers(words, &lists.answers, language, *top)
+            }
+        },
+        Some(cli::Command::Solve { word, bench }) => {
+            return solve(&cli, &lists, word.as_deref(), *bench)
+        }
+        Some(cli::Command::Demo) => {
+            return attract::run(
+                language.code,
+                words,
+                &lists.answers,
+                cli.hard,
+                cli.strategy,
+                max_guesses(1),
+                theme,
+            )
+        }
+        Some(command @ cli::Command::Simulate { .. }) => return simulate(command, &lists),
+        Some(cli::Command::Challenge { word }) => {
+            return create_challenge(&cli, &lists, word.as_deref())
+        }
+        _ => (),
+    }
+
+    let setup = Setup::load(&cli, &config, &settings, &lists, theme);
+    match &cli.command {
+        Some(cli::Command::Math) => play_math(&setup),
+        Some(cli::Command::Crossword) => play_crossword(&lists, &setup),
+        Some(cli::Command::Anagram) => play_anagrams(&lists, &setup),
+        Some(cli::Command::Drill) => play_drill(&cli, &lists, setup),
+        Some(cli::Command::Group { command }) => group_command(&cli, command, &lists, setup),
+        Some(cli::Command::Team { rounds }) => play_team(&cli, *rounds, &lists, setup),
+        Some(cli::Command::Endless) => play_endless(&cli, &lists, setup),
+        Some(cli::Command::Weekly { words: count, week }) => {
+            play_weekly(&cli, *count as usize, week.as_deref(), &lists, setup)
+        }
+        _ => play(&cli, &config, &lists, setup),
+    }
+}
+
+/// Pick the profile of the player, it decides where the data of the player is loaded from
+fn use_profile(cli: &cli::Cli) {
+    match &cli.profile {
+        Some(name) if !profile::valid_name(name) => {
+            eprintln!("A profile name can only have letters, digits, - and _");
+            std::process::exit(1);
+        }
+        Some(name) => storage::use_profile(name.clone()),
+        // Only a game that is played on the screen asks who is playing
+        None if cli.command.is_none() && !cli.headless => {
+            let profiles = profile::list().unwrap_or_else(|err| {
+                eprintln!("Could not list the profiles: {}", err);
+                Vec::new()
+            });
+            if !profiles.is_empty() {
+                if let Some(name) = profile::pick(&profiles, &theme::CLASSIC) {
+                    storage::use_profile(name);
+                }
+            }
+        }
+        None => (),
+    }
+}
+
+fn pick_theme(settings: &mut Settings) {
+    theme::theme_settings(settings);
+    if let Err(err) = settings.save() {
+        eprintln!("Could not save the settings: {}", err);
+    }
+}
+
+fn show_leaderboard(language: Option<&'static Language>, theme: &Theme) {
+    let leaderboard = Leaderboard::load().unwrap_or_else(|err| {
+        eprintln!("Could not load the leaderboard: {}", err);
+        std::process::exit(1);
+    });
+    let language = language.unwrap_or(&language::DUTCH);
+    leaderboard::render_leaderboard(&leaderboard, language.code, WORD_LENGTH, theme);
+}
+
+fn stats_command(command: &cli::StatsCommand) {
+    let cli::StatsCommand::Export { format, output } = command;
+    let stats = Stats::load().unwrap_or_else(|err| {
+        eprintln!("Could not load the statistics: {}", err);
+        std::process::exit(1);
+    });
+    let exported = match output {
+        None => stats.export(*format, &mut io::stdout().lock()),
+        Some(path) => std::fs::File::create(path).and_then(|file| {
+            let mut writer = io::BufWriter::new(file);
+            stats.export(*format, &mut writer)?;
+            io::Write::flush(&mut writer)
+        }),
+    };
+    if let Err(err) = exported {
+        eprintln!("Could not export the statistics: {}", err);
+        std::process::exit(1);
+    }
+}
+
+/// Check, convert and combine word lists
+fn dict_command(
+    cli: &cli::Cli,
+    command: &cli::DictCommand,
+    picked_language: Option<&'static Language>,
+) {
+    match command {
+        cli::DictCommand::Check { file, json } => {
+            match dict::check_file(file, picked_language, *json) {
+                Ok(true) => (),
+                Ok(false) => std::process::exit(1),
+                Err(err) => {
+                    eprintln!("Could not check {}: {}", file.display(), err);
+                    std::process::exit(2);
+                }
+            }
+        }
+        cli::DictCommand::ImportHunspell {
+            aff,
+            dic,
+            output,
+            length,
+        } => {
+            if let Err(err) = hunspell::import_file(aff, dic, output, *length, picked_language) {
+                eprintln!("Could not import the Hunspell dictionary: {}", err);
+                std::process::exit(1);
+            }
+        }
+        cli::DictCommand::Compile { inputs, output } => {
+            let allowed = allowed_kinds(cli);
+            if let Err(err) = compiled::compile(inputs, output, picked_language, &allowed) {
+                eprintln!("Could not compile the word lists: {}", err);
+                std::process::exit(1);
+            }
+        }
+        cli::DictCommand::Merge { inputs, output } => {
+            if let Err(err) = dict::merge_files(inputs, output, picked_language) {
+                eprintln!("Could not merge the word lists: {}", err);
+                std::process::exit(1);
+            }
+        }
+        cli::DictCommand::Normalize {
+            input,
+            output,
+            strip_accents,
+        } => {
+            if let Err(err) = dict::normalize_file(input, output, picked_language, *strip_accents) {
+                eprintln!("Could not normalize {}: {}", input.display(), err);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+fn show_replay(file: &Path, theme: &Theme) {
+    let replay = replay::Replay::load(file).unwrap_or_else(|err| {
+        eprintln!("Could not load the replay {}: {}", file.display(), err);
+        std::process::exit(1);
+    });
+    replay::play_replay(&replay, theme);
+}
+
+fn watch_games(address: &str, theme: &Theme) {
+    if let Err(err) = watch::watch(address, theme) {
+        eprintln!("Could not watch the games on {}: {}", address, err);
+        std::process::exit(1);
+    }
+}
+
+/// The word lists as they are played, with what is known about their words
+struct Lists {
+    /// The words of every length, the words of the length of the game are the ones that are
+    /// played
+    dictionary: Dictionary,
+    /// The phrases of phrase mode, they are played instead of the words
+    phrases: Option<Vec<&'static str>>,
+    /// The words that can be the word of the game
+    answers: Vec<&'static str>,
+    /// The letters of the words
+    alphabet: Vec<char>,
+    language: &'static Language,
+    frequencies: HashMap<&'static str, u64>,
+    /// Which list each word is from, when there is more than one
+    provenance: Option<dict::Provenance>,
+}
+
+impl Lists {
+    /// The words of the length of the game, or the phrases in phrase mode
+    fn words(&self) -> &[&'static str] {
+        self.phrases
+            .as_deref()
+            .unwrap_or_else(|| self.dictionary.words(WORD_LENGTH))
+    }
+
+    /// Pick the word of a game from the tier of the difficulty, the common words are preferred
+    fn pick(&self, cli: &cli::Cli) -> &'static str {
+        let pool = match cli.difficulty {
+            None => self.answers.clone(),
+            Some(difficulty) => {
+                let tier = dict::tier(&self.answers, &self.frequencies, difficulty);
+                self.answers
+                    .iter()
+                    .filter(|word| tier.contains(*word))
+                    .copied()
+                    .collect()
+            }
+        };
+        obscurity::pick(&pool, 1, &self.frequencies, cli.obscurity)[0]
+    }
+
+    /// The games of a server are played on these lists
+    fn session(&self, cli: &cli::Cli) -> headless::Session<'_> {
+        headless::Session {
+            dictionary: &self.dictionary,
+            language: self.language,
+            hard: cli.hard,
+            max_guesses: max_guesses(1),
+        }
+    }
+}
+
+/// Read the word lists with the packs and the personal words, and recognize their language when
+/// it isn't given
+fn load_lists(
+    cli: &cli::Cli,
+    config: &Config,
+    picked_language: Option<&'static Language>,
+) -> Lists {
+    // These should be the only objects that actually have bytes in them instead of references to
+    // bytes, supplied word lists are kept for the rest of the game
+    let word_lists = cli
+        .wordlist
+        .iter()
+        .map(|path| {
+            compiled::read_word_list(path, WORD_LENGTH).unwrap_or_else(|err| {
+                eprintln!("Could not read the word list {}: {}", path.display(), err);
+                std::process::exit(1);
+            })
+        })
+        .collect_vec();
+    let mut word_strings: Vec<&'static str> = if word_lists.is_empty() {
+        vec![include_str!("../word-list-nl.txt")]
+    } else {
+        word_lists.iter().map(|list| list.text).collect()
+    };
+    // Compiled word lists know their language
+    let compiled_languages = word_lists
+        .iter()
+        .map(|list| list.language.map(|language| language.code))
+        .unique()
+        .collect_vec();
+    let compiled_language = match compiled_languages[..] {
+        [Some(code)] => language::by_code(code),
+        _ => None,
+    };
+
+    // The lists contain names, like cities, and abbreviations, these are left out unless they are
+    // allowed
+    let allowed = allowed_kinds(cli);
+    if !allowed.is_empty() {
+        for word_string in word_strings.iter_mut() {
+            *word_string = Box::leak(word::admit(word_string, &allowed).into_boxed_str());
+        }
+    }
+
+    // The bundled word list is Dutch, the language of a supplied word list is recognized from its
+    // words when it isn't given
+    let language = match (
+        picked_language.or(compiled_language),
+        cli.wordlist.is_empty(),
+    ) {
+        (Some(language), _) => language,
+        (None, true) => &language::DUTCH,
+        (None, false) => {
+            let listed = word_strings
+                .iter()
+                .flat_map(|word_string| word_string.lines())
+                .filter(|line| word::kind(line) == word::Kind::Word)
+                .map(|line| word::split_entry(line).0)
+                .collect_vec();
+            language::confirm(language::detect(&listed)).unwrap_or_else(|err| {
+                eprintln!("Could not ask for the language: {}", err);
+                std::process::exit(1);
+            })
+        }
+    };
+
+    // Letter pairs that are written on a single tile, like the Dutch ij, are joined into a single
+    // letter so the words are counted in tiles. This happens before the lists are indexed so they
+    // are only scanned once.
+    if !language.digraphs.is_empty() {
+        // Compiled lists already have them joined
+        for word_string in word_strings.iter_mut().filter(|word_string| {
+            language
+                .digraphs
+                .iter()
+                .any(|(pair, _)| word_string.contains(pair))
+        }) {
+            *word_string = Box::leak(language.join_digraphs(word_string).into_boxed_str());
+        }
+    }
+
+    // The words are indexed by their length, the game only plays with the words of one length
+    let mut dictionary = Dictionary::load(&word_strings);
+    let mut words = match dictionary.playable(WORD_LENGTH) {
+        Ok(words) => words.to_vec(),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let frequencies = collect_frequencies(&word_strings);
+
+    // With more than one word list, keep which list each word is from to tell where the word of
+    // the game came from
+    let provenance = (cli.wordlist.len() > 1).then(|| {
+        let mut provenance = dict::Provenance::default();
+        for (path, word_string) in cli.wordlist.iter().zip(&word_strings) {
+            provenance.add(
+                path,
+                Dictionary::load(&[word_string]).words(WORD_LENGTH).to_vec(),
+            );
+        }
+        provenance
+    });
+
+    // Merge in the subscribed word packs, these may overlap with the bundled list so duplicates
+    // are removed afterwards
+    if !config.packs.is_empty() {
+        let pack_words = packs::load_packs(&config.packs, !cli.offline, language);
+        words.extend(
+            pack_words
+                .words
+                .into_iter()
+                .filter(|word| word::is_playable(word)),
+        );
+        words.sort_unstable();
+        words.dedup();
+        words.retain(|word| !pack_words.denied.contains(word));
+    }
+
+    // The words the player added go in and the words the player banned go out last, the bans also
+    // hold for the words of the packs
+    let personal = dict::PersonalWords::load(language).unwrap_or_else(|err| {
+        eprintln!("Could not load the personal word lists: {}", err);
+        dict::PersonalWords::default()
+    });
+    words.extend(
+        personal
+            .added
+            .into_iter()
+            .filter(|word| word::is_playable(word)),
+    );
     words.sort_unstable();
-    let words = words;
+    words.dedup();
+    words.retain(|word| !personal.banned.contains(word));
 
-    // Since the words vector should be sorted now should duplicate words be after each other.
-    // unique from itertools could also be used but this is faster since the word list should be
-    // sorted.
-    if words.iter().tuple_windows::<(_, _)>().any(|(a, b)| a == b) {
-        panic!("Word list contains duplicates");
+    // Offensive words are never the word of the game, and aren't guesses either when the config
+    // file says so
+    let offensive = if cli.allow_offensive {
+        HashSet::new()
+    } else {
+        dict::offensive_words(language)
+    };
+    if config.reject_offensive_guesses {
+        words.retain(|word| !offensive.contains(word));
+    }
+    if words.is_empty() {
+        eprintln!("All the words of the word list are banned");
+        std::process::exit(1);
     }
 
+    // In phrase mode the phrases take the place of the words, as the words of the game and as the
+    // guesses. They are kept by their length like the words, a phrase can be longer than a word.
+    let phrases = cli.phrases.as_ref().map(|path| {
+        if cli.command.is_some() {
+            eprintln!("Phrase mode plays a game, it can't be combined with a command");
+            std::process::exit(1);
+        }
+        phrases::load(path, language).unwrap_or_else(|err| {
+            eprintln!("Could not load the phrases: {}", err);
+            std::process::exit(1);
+        })
+    });
+    let dictionary = match &phrases {
+        Some(phrases) => phrases.iter().copied().collect(),
+        None => {
+            // The words of the length of the game are the ones that were merged and filtered
+            // above, the other lengths stay as the lists have them. Every guess is looked up in
+            // them.
+            dictionary.replace(WORD_LENGTH, words);
+            dictionary
+        }
+    };
+    let words = phrases
+        .as_deref()
+        .unwrap_or_else(|| dictionary.words(WORD_LENGTH));
+    let answers = words
+        .iter()
+        .filter(|word| !offensive.contains(*word))
+        .copied()
+        .collect_vec();
+
     // Extract the alphabet from the dictionary
-    let alphabet;
-    {
-        alphabet = words
+    let alphabet = words
+        .iter()
+        .flat_map(|word| word.chars())
+        .filter(|chr| *chr != word::SEPARATOR)
+        .unique()
+        .collect_vec();
+    // TODO Sort alphabet?
+
+    Lists {
+        dictionary,
+        phrases,
+        answers,
+        alphabet,
+        language,
+        frequencies,
+        provenance,
+    }
+}
+
+/// Serve games to other programs, over a socket or over HTTP
+fn serve(cli: &cli::Cli, command: &cli::Command, lists: &Lists) {
+    let session = lists.session(cli);
+    let pick = || lists.pick(cli);
+    let served = match command {
+        cli::Command::Serve { port, watch_port } => {
+            headless::serve(*port, *watch_port, &session, pick)
+        }
+        cli::Command::Http { port } => http::serve(*port, &session, pick),
+        _ => return,
+    };
+    if let Err(err) = served {
+        match command {
+            cli::Command::Http { .. } => eprintln!("Could not serve the HTTP API: {}", err),
+            _ => eprintln!("Could not serve games: {}", err),
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Show how the solver finds a word, or how well it does on all the answers
+fn solve(cli: &cli::Cli, lists: &Lists, word: Option<&str>, bench: bool) {
+    let words = lists.words();
+    if bench {
+        bench::run(
+            words,
+            &lists.answers,
+            cli.hard,
+            cli.strategy,
+            max_guesses(1),
+        );
+    } else if let Some(word) = word {
+        let word = lists
+            .language
+            .join_digraphs(&word::compose(&word.to_lowercase()));
+        if !lists.dictionary.contains(&word) {
+            eprintln!("The word {} is not in the word list", word);
+            std::process::exit(1);
+        }
+        bench::show(words, &word, cli.hard, cli.strategy, max_guesses(1));
+    }
+}
+
+fn simulate(command: &cli::Command, lists: &Lists) {
+    let cli::Command::Simulate {
+        games,
+        strategy,
+        guesses,
+        boards,
+        hard,
+        evil,
+        seed,
+    } = command
+    else {
+        return;
+    };
+    let rules = simulate::Rules {
+        boards: *boards as usize,
+        max_guesses: guesses.unwrap_or(max_guesses(*boards as usize)),
+        hard: *hard,
+        evil: *evil,
+        strategy: *strategy,
+    };
+    if let Err(err) = simulate::run(
+        &lists.dictionary,
+        &lists.answers,
+        &lists.alphabet,
+        &rules,
+        *games,
+        *seed,
+    ) {
+        eprintln!("The simulation stopped: {}", err);
+        std::process::exit(1);
+    }
+}
+
+/// Print the code of a challenge on the word, or on a word that is picked
+fn create_challenge(cli: &cli::Cli, lists: &Lists, word: Option<&str>) {
+    let language = lists.language;
+    let word = match word {
+        Some(word) => {
+            let word = language.join_digraphs(&word::compose(&word.to_lowercase()));
+            if !lists.answers.contains(&word.as_str()) {
+                eprintln!("The word {} is not in the word list", word);
+                std::process::exit(1);
+            }
+            word
+        }
+        None => lists.pick(cli).to_string(),
+    };
+    match challenge::Challenge::new(&word, language, lists.words()) {
+        Ok(challenge) => println!("Send a friend: lingo play {}", challenge),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// What the games on the screen are played with besides the words, from the config file and the
+/// arguments
+struct Setup {
+    theme: &'static Theme,
+    /// The first guess that is suggested
+    opener: Option<&'static str>,
+    announcer: Announcer,
+    events: EventSink,
+    definitions: &'static Definitions,
+    scripts: &'static Scripts,
+    keys: &'static Bindings,
+    /// Start the games without the assistance that can be turned off
+    zen: bool,
+}
+
+impl Setup {
+    fn load(
+        cli: &cli::Cli,
+        config: &Config,
+        settings: &Settings,
+        lists: &Lists,
+        theme: &'static Theme,
+    ) -> Setup {
+        // Working out the best first guess takes a moment, so only when it is asked for
+        let opener = cli
+            .suggest_opener
+            .then(|| analyze::best_opener(lists.words(), &lists.answers))
+            .flatten();
+
+        // Open the announcement stream before ncurses takes over the terminal so problems can
+        // still be reported
+        let announcer = match &cli.announce {
+            None => Announcer::disabled(),
+            Some(path) => Announcer::open(path).unwrap_or_else(|err| {
+                eprintln!("Could not open {}: {}", path.display(), err);
+                std::process::exit(1);
+            }),
+        };
+        let events = cli
+            .events
+            .clone()
+            .map_or_else(EventSink::disabled, EventSink::open);
+
+        let definitions = Definitions::load(
+            cli.definitions.as_deref(),
+            &config.definition_command,
+            lists.language,
+        )
+        .unwrap_or_else(|err| {
+            eprintln!("Could not load the definitions: {}", err);
+            std::process::exit(1);
+        });
+        // The definitions are kept for as long as the game runs
+        let definitions: &'static Definitions = Box::leak(Box::new(definitions));
+
+        let script_paths = config
+            .scripts
             .iter()
-            .flat_map(|word| word.chars())
-            .unique()
+            .chain(&cli.script)
+            .cloned()
             .collect_vec();
-        // TODO Sort alphabet?
+        let scripts = Scripts::load(&script_paths).unwrap_or_else(|err| {
+            eprintln!("Could not load the scripts: {}", err);
+            std::process::exit(1);
+        });
+        let scripts: &'static Scripts = Box::leak(Box::new(scripts));
+        let keys = config.keys.bindings().unwrap_or_else(|err| {
+            eprintln!("Could not use the keys of the config file: {}", err);
+            std::process::exit(1);
+        });
+        let keys: &'static Bindings = Box::leak(Box::new(keys));
+
+        Setup {
+            theme,
+            opener,
+            announcer,
+            events,
+            definitions,
+            scripts,
+            keys,
+            zen: cli.no_assist || settings.zen,
+        }
     }
 
-    play_game(words, alphabet);
+    /// The options of a game of the mode on a single word, a game that doesn't count in the
+    /// statistics
+    fn options(
+        &self,
+        cli: &cli::Cli,
+        language: &'static Language,
+        mode: Mode,
+    ) -> GameOptions<'static> {
+        GameOptions {
+            time_bonus: cli.time_bonus,
+            row_timer: cli.row_timer.map(Duration::from_secs),
+            evil: None,
+            hard: cli.hard,
+            language,
+            boards: 1,
+            theme: self.theme,
+            mode,
+            definitions: self.definitions,
+            define_guesses: cli.define_guesses,
+            opener: self.opener,
+            review: cli.review,
+            streak: None,
+            lives: None,
+            learn: cli.learn,
+            scripts: self.scripts,
+            zen: self.zen,
+            a11y: cli.a11y,
+            keys: self.keys,
+            turn: None,
+            bingo: None,
+        }
+    }
 }
 
-fn play_game(words: Vec<&'static str>, alphabet: Vec<char>) {
-    // Pick a random word
-    let word;
+/// Stop with the message when a mode that plays single words is combined with another mode
+fn single_words_only(cli: &cli::Cli, message: &str) {
+    if cli.evil
+        || cli.versus
+        || cli.host
+        || cli.join.is_some()
+        || cli.boards != 1
+        || cli.is_practice()
     {
-        let mut rng = rand::thread_rng();
-        let index = rng.gen_range(0..words.len());
-        word = words[index];
+        eprintln!("{}", message);
+        std::process::exit(1);
+    }
+}
+
+fn play_math(setup: &Setup) {
+    if let Err(err) = math::run(setup.keys, setup.theme) {
+        eprintln!("The sum stopped: {}", err);
+        std::process::exit(1);
     }
+}
 
-    // Do some ncurses initialization
-    ncurses::initscr();
-    ncurses::start_color();
-    ncurses::use_default_colors();
-    ncurses::init_pair(
-        COLOR_PAIR_CORRECT,
-        ncurses::COLOR_GREEN,
-        ncurses::COLOR_BLACK,
+fn play_crossword(lists: &Lists, setup: &Setup) {
+    let played = crossword::run(
+        &lists.dictionary,
+        &lists.answers,
+        &lists.alphabet,
+        lists.language,
+        setup.keys,
+        setup.theme,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("The crossword stopped: {}", err);
+        std::process::exit(1);
+    });
+    if let Some((words, solved)) = played {
+        let display = |word: &str| word.chars().map(word::display).collect::<String>();
+        println!(
+            "{}",
+            fill(
+                strings().crossword_result,
+                &[
+                    &solved.iter().filter(|solved| **solved).count(),
+                    &display(words[0]),
+                    &display(words[1]),
+                ]
+            )
+        );
+    }
+}
+
+fn play_anagrams(lists: &Lists, setup: &Setup) {
+    match anagram::run(
+        &lists.dictionary,
+        &lists.answers,
+        lists.language,
+        setup.keys,
+        setup.theme,
+    ) {
+        Ok((_, 0)) => (),
+        Ok((solved, played)) => println!("{}", fill(strings().anagram_score, &[&solved, &played])),
+        Err(err) => {
+            eprintln!("The anagrams stopped: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn play_drill(cli: &cli::Cli, lists: &Lists, setup: Setup) {
+    single_words_only(
+        cli,
+        "A drill is always a single word against the clock, it can't be combined with other modes",
     );
-    ncurses::init_pair(
-        COLOR_PAIR_WRONG_PLACE,
-        ncurses::COLOR_YELLOW,
-        ncurses::COLOR_BLACK,
+    let options = setup.options(cli, lists.language, Mode::Drill);
+    drill::drill(
+        &lists.dictionary,
+        &lists.answers,
+        lists.alphabet.clone(),
+        setup.announcer,
+        &setup.events,
+        &options,
     );
-    ncurses::raw();
-    ncurses::noecho();
-    ncurses::curs_set(ncurses::CURSOR_VISIBILITY::CURSOR_INVISIBLE);
-    // endwin always needs to get called
-    let _window_ender = defer(|| {
-        ncurses::endwin();
+}
+
+/// Host a group, join it or compare the results of its players
+fn group_command(cli: &cli::Cli, command: &cli::GroupCommand, lists: &Lists, mut setup: Setup) {
+    single_words_only(
+        cli,
+        "Everyone in a group plays the same single words, it can't be combined with other modes",
+    );
+    if cli.allow_offensive {
+        eprintln!("Everyone in a group plays the same words, the offensive words can't be allowed");
+        std::process::exit(1);
+    }
+    let words = lists.words();
+    let parse = |code: &str| {
+        group::GroupCode::parse(code, words).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        })
+    };
+    match command {
+        cli::GroupCommand::Host { rounds } => group::host(*rounds, words),
+        cli::GroupCommand::Join { code, name } => {
+            let code = parse(code);
+            let options = setup.options(cli, lists.language, Mode::Group);
+            if let Err(err) = group::join(
+                &code,
+                name,
+                &lists.dictionary,
+                lists.alphabet.clone(),
+                &mut setup.announcer,
+                &setup.events,
+                &options,
+            ) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+        cli::GroupCommand::Results { code, results } => {
+            group::results(&parse(code), results, words, lists.language)
+        }
+    }
+}
+
+fn play_team(cli: &cli::Cli, rounds: u8, lists: &Lists, mut setup: Setup) {
+    single_words_only(
+        cli,
+        "The teams take turns on single words, it can't be combined with other modes",
+    );
+    let options = GameOptions {
+        time_bonus: false,
+        ..setup.options(cli, lists.language, Mode::Team)
+    };
+    match team::play(
+        rounds,
+        &lists.dictionary,
+        &lists.answers,
+        &lists.alphabet,
+        &mut setup.announcer,
+        &setup.events,
+        &options,
+    ) {
+        Ok([first, second]) => println!("Team 1: {} points, team 2: {} points", first, second),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn play_endless(cli: &cli::Cli, lists: &Lists, mut setup: Setup) {
+    single_words_only(
+        cli,
+        "An endless run is played on single words, it can't be combined with other modes",
+    );
+    let options = setup.options(cli, lists.language, Mode::Endless);
+    let run = endless::play(
+        &lists.dictionary,
+        &lists.answers,
+        &lists.alphabet,
+        &mut setup.announcer,
+        &setup.events,
+        &options,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let solved = match run.solved {
+        1 => "1 word".to_string(),
+        solved => format!("{} words", solved),
+    };
+    println!(
+        "Solved {} for {} points, and {} points from the bingo card",
+        solved, run.points, run.prizes
+    );
+    if !run.missed.is_empty() {
+        println!(
+            "Missed {}, {} of {} lives left",
+            run.missed.join(", "),
+            run.lives,
+            endless::LIVES
+        );
+    }
+    let mut stats = Stats::load().unwrap_or_else(|err| {
+        eprintln!("Could not load the statistics, starting over: {}", err);
+        Stats::default()
     });
+    stats.record_run(run);
+    if let Err(err) = stats.save() {
+        eprintln!("Could not save the statistics: {}", err);
+    }
+}
 
-    let mut board_state: BoardState = Default::default();
-    let mut guess_num = 0;
-
-    /// The information known about a specific letter because of the guesses
-    #[derive(PartialEq, Debug)]
-    enum LetterKnowledge {
-        /// If the letter is not in the word
-        NotInWord,
-        /// If there is no information about the letter
-        NoInformation,
-        /// If there is knowledge about places where the letter isn't
-        InWordPlaces {
-            // The places in which this letter is still possible
-            possible: [bool; WORD_LENGTH],
-            // The places in which this letter is mandatory
-            confirmed: [bool; WORD_LENGTH],
-        },
+fn play_weekly(cli: &cli::Cli, count: usize, week: Option<&str>, lists: &Lists, mut setup: Setup) {
+    single_words_only(
+        cli,
+        "The puzzle of the week is played on single words, it can't be combined with other modes",
+    );
+    let week = week
+        .map_or(Ok(weekly::IsoWeek::now()), weekly::IsoWeek::parse)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+    if lists.answers.len() < count {
+        eprintln!("There are not enough words in the word list for the week");
+        std::process::exit(1);
+    }
+    let options = setup.options(cli, lists.language, Mode::Weekly);
+    let results = weekly::play(
+        week,
+        &week.words(&lists.answers, count),
+        &lists.dictionary,
+        &lists.alphabet,
+        &mut setup.announcer,
+        &setup.events,
+        &options,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    // The share text gives away no words, it is printed to paste the week in a chat
+    if !results.is_empty() {
+        println!(
+            "{}",
+            weekly::share_text(lists.language.code, week, &results)
+        );
     }
-    let mut letter_knowledge = HashMap::<char, LetterKnowledge>::new();
+}
 
-    // Start with no knowledge about each letter
-    for letter in alphabet.iter() {
-        letter_knowledge.insert(*letter, LetterKnowledge::NoInformation);
+/// The word of the challenge that is played, it is looked up in the word list so it has to be the
+/// same language
+fn challenged_word(cli: &cli::Cli, code: &str, lists: &Lists) -> &'static str {
+    if cli.evil || cli.host || cli.join.is_some() || cli.boards != 1 || cli.is_practice() {
+        eprintln!("A challenge is a single word, it can't be combined with other modes");
+        std::process::exit(1);
     }
+    let challenge = challenge::Challenge::parse(code).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let found = if challenge.language().code != lists.language.code {
+        Err(format!(
+            "The challenge is a {} word, play it with a {} word list",
+            challenge.language().name,
+            challenge.language().name
+        ))
+    } else if challenge.length != WORD_LENGTH {
+        Err(format!(
+            "The challenge is a word of {} letters, the game plays words of {} letters",
+            challenge.length, WORD_LENGTH
+        ))
+    } else {
+        challenge
+            .find(lists.words())
+            .ok_or_else(|| "The word of the challenge is not in the word list".to_string())
+    };
+    found.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    })
+}
 
-    // Loop over all the guesses
-    loop {
-        // Get the guess this round
-        let mut guess = String::new();
-        // Loop over the characters
-        loop {
-            // Copy the guess string into the board
-            for i in 0..WORD_LENGTH {
-                board_state.board[guess_num][i] = match guess.chars().nth(i) {
-                    None => GuessedLetter::NoLetter,
-                    Some(x) => GuessedLetter::Letter(x),
-                };
+/// The words a practice game picks from: the words that match the filters, or the given word. The
+/// arguments are written like the words in the list.
+fn practice_pool(cli: &cli::Cli, lists: &Lists) -> Vec<&'static str> {
+    let language = lists.language;
+    let words = lists.words();
+    let spell = |text: &str| language.join_digraphs(&word::compose(&text.to_lowercase()));
+    // The difficulty limits the words to a tier of the list, the guesses can be any word
+    // Kid mode picks from the easy words
+    let tier = cli
+        .difficulty
+        .or(cli.kids.then_some(dict::Difficulty::Easy))
+        .map(|difficulty| dict::tier(words, &lists.frequencies, difficulty));
+    match &cli.word {
+        Some(word) => {
+            let word = spell(word);
+            match words.iter().find(|candidate| **candidate == word) {
+                None => {
+                    eprintln!("The word {} is not in the word list", word);
+                    std::process::exit(1);
+                }
+                Some(word) => {
+                    eprintln!("The word to practice on is visible in the shell history");
+                    vec![*word]
+                }
             }
-
-            // Create the list of still possible words
-            board_state.possible_words = words
-                .iter()
-                // Only consider words the fit the currently typed (partial) guess
-                .filter(|word| word.chars().take(guess.len()).eq(guess.chars()))
-                // Remove words that contain letters that aren't in the word or are known to not be
-                // in a specific place
-                .filter(|word| {
-                    word.chars()
-                        .enumerate()
-                        .all(|(index, chr)| match letter_knowledge[&chr] {
-                            LetterKnowledge::NotInWord => false,
-                            LetterKnowledge::NoInformation => true,
-                            LetterKnowledge::InWordPlaces {
-                                possible,
-                                confirmed: _,
-                            } => possible[index],
-                        })
-                })
-                // Remove words that don't contain letters that are known to be in the word or that
-                // have a letter confirmed on a specific spot that clashes
-                .filter(|word| {
-                    letter_knowledge
-                        .iter()
-                        .all(|(chr, knowledge)| match knowledge {
-                            LetterKnowledge::InWordPlaces {
-                                possible: _,
-                                confirmed,
-                            } => {
-                                word.chars().contains(chr)
-                                    && confirmed.iter().enumerate().all(|(index, confirmed)| {
-                                        !confirmed
-                                            || word.chars().enumerate().any(|(index2, chr2)| {
-                                                index == index2 && *chr == chr2
-                                            })
-                                    })
-                            }
-                            _ => true,
-                        })
+        }
+        None => lists
+            .answers
+            .iter()
+            .filter(|word| tier.as_ref().is_none_or(|tier| tier.contains(*word)))
+            .filter(|word| {
+                cli.starts_with
+                    .as_ref()
+                    .is_none_or(|start| word.starts_with(&spell(start)))
+            })
+            .filter(|word| {
+                cli.contains.as_ref().is_none_or(|letters| {
+                    spell(letters).chars().all(|letter| word.contains(letter))
                 })
-                .take(3 + 2 * GUESSES as usize)
-                .map(|word| *word)
-                .collect();
-
-            // Render the current guess on the screen
-            render_game(&board_state);
-
-            // Get input from the user
-            let input = ncurses::getch();
-
-            // Act on the input
-            if [27].contains(&input) {
-                // On escape close down the application
-                return;
-            } else if [ncurses::KEY_ENTER, '\n' as i32].contains(&input) {
-                // On a enter or newline if the current guess is the correct amount of characters
-                // process the guess
-                if guess.len() == WORD_LENGTH {
-                    break;
-                }
-            } else if [ncurses::KEY_BACKSPACE, ncurses::KEY_DC, 127].contains(&input) {
-                // On a backspace remove the last entered letter, if there is one
-                if !guess.is_empty() {
-                    guess.pop();
+            })
+            .copied()
+            .collect(),
+    }
+}
+
+/// Play a game on the screen: a casual game, a practice game, a challenge or a game over the
+/// network. A finished game is recorded in the statistics and the leaderboard.
+fn play(cli: &cli::Cli, config: &Config, lists: &Lists, mut setup: Setup) {
+    let words = lists.words();
+    let language = lists.language;
+    let challenged = match &cli.command {
+        Some(cli::Command::Play { code }) => Some(challenged_word(cli, code, lists)),
+        _ => None,
+    };
+
+    // In a network game both sides pick the word with the seed of the host
+    let connection = if cli.host {
+        let seed = rand::thread_rng().gen();
+        Some(net::host(
+            cli.port,
+            seed,
+            words.len(),
+            cli.room.as_deref(),
+            cli.coop,
+        ))
+    } else {
+        cli.join
+            .as_ref()
+            .map(|address| net::join(address.as_str(), words.len(), cli.room.as_deref()))
+    };
+    let connection = connection.transpose().unwrap_or_else(|err| {
+        eprintln!("Could not set up the network game: {}", err);
+        std::process::exit(1);
+    });
+
+    // A challenge is played on its word, also when the game is started over
+    let pool = challenged.map_or_else(|| practice_pool(cli, lists), |word| vec![word]);
+    if pool.len() < cli.boards as usize {
+        eprintln!("Not enough words in the word list match the practice filters");
+        std::process::exit(1);
+    }
+
+    // In a network game both sides pick the same words with the seed of the host, otherwise the
+    // common words are preferred
+    let targets = match &connection {
+        Some(connection) => pick_words(&pool, cli.boards as usize, Some(connection.seed)),
+        None => obscurity::pick(
+            &pool,
+            cli.boards as usize,
+            &lists.frequencies,
+            cli.obscurity,
+        ),
+    };
+
+    if cli.headless {
+        let played = lists.session(cli).play(
+            &mut io::stdin().lock(),
+            &mut io::stdout(),
+            targets[0],
+            &|_| {},
+        );
+        if let Err(err) = played {
+            eprintln!("Could not play the headless game: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mode = if connection.is_some() {
+        Mode::Network
+    } else if cli.kids {
+        Mode::Kids
+    } else if cli.is_practice() {
+        Mode::Practice
+    } else {
+        Mode::Casual
+    };
+
+    let computer_opponent = |target| -> Box<dyn Opponent> {
+        Box::new(ComputerOpponent::new(
+            words,
+            target,
+            Duration::from_secs(cli.versus_delay),
+            cli.hard,
+            setup.scripts.strategy(cli.strategy.strategy()),
+        ))
+    };
+    let opponent: Option<Box<dyn Opponent>> = match connection {
+        Some(connection) => {
+            // In a co-op game the other player is a partner on the same board
+            let remote = match connection.coop {
+                true => net::RemotePartner::new(connection)
+                    .map(|partner| Box::new(partner) as Box<dyn Opponent>),
+                false => net::RemoteOpponent::new(connection)
+                    .map(|opponent| Box::new(opponent) as Box<dyn Opponent>),
+            };
+            match remote {
+                Ok(opponent) => Some(opponent),
+                Err(err) => {
+                    eprintln!("Could not set up the network game: {}", err);
+                    std::process::exit(1);
                 }
-            } else if ('a' as i32..='z' as i32).contains(&input) {
-                // If the input is a letter add it to the guess, if more letters are allowed in the
-                // guess
-                if guess.len() < WORD_LENGTH {
-                    guess.push(char::from_u32(input as u32).unwrap());
+            }
+        }
+        None if cli.versus => Some(computer_opponent(targets[0])),
+        None => None,
+    };
+
+    let options = GameOptions {
+        time_bonus: cli.time_bonus,
+        row_timer: cli.row_timer.map(Duration::from_secs),
+        evil: cli.evil.then_some(&lists.answers[..]),
+        hard: cli.hard,
+        language,
+        boards: cli.boards as usize,
+        theme: setup.theme,
+        mode,
+        definitions: setup.definitions,
+        define_guesses: cli.define_guesses,
+        opener: setup.opener,
+        review: cli.review,
+        // Practice games and kid mode don't count in the statistics
+        streak: (!cli.is_practice() && !cli.kids)
+            .then(|| Stats::load().map_or(0, |stats| stats.streak())),
+        lives: None,
+        learn: cli.learn,
+        scripts: setup.scripts,
+        zen: setup.zen && !cli.kids,
+        a11y: cli.a11y,
+        keys: setup.keys,
+        turn: None,
+        bingo: None,
+    };
+
+    let ratatui = cli.ui == Ui::Ratatui
+        && cli.boards == 1
+        && opponent.is_none()
+        && cli.row_timer.is_none()
+        && !cli.kids
+        && cli.phrases.is_none();
+    if cli.ui == Ui::Ratatui && !ratatui {
+        eprintln!("The ratatui interface plays games on one board without an opponent, a row timer, kid mode or phrases, this game is shown with ncurses");
+    }
+
+    let mut game = (targets, opponent);
+    let result = loop {
+        let (targets, opponent) = game;
+        // The rows of a phrase only fit the phrases with the space on the same tile
+        let guesses;
+        let dictionary = match &lists.phrases {
+            Some(phrases) => {
+                guesses = phrases::guesses_for(phrases, targets[0]);
+                &guesses
+            }
+            None => &lists.dictionary,
+        };
+        let played = match ratatui {
+            true => tui::play_game(
+                dictionary,
+                &lists.alphabet,
+                targets[0],
+                &setup.events,
+                &options,
+            )
+            .inspect(|result| {
+                if let Err(err) = options.scripts.on_game_end(result) {
+                    eprintln!("The on_game_end script failed: {}", err);
                 }
+            }),
+            false => play::play_game(
+                dictionary,
+                lists.alphabet.clone(),
+                targets,
+                &mut setup.announcer,
+                &setup.events,
+                opponent,
+                &options,
+            ),
+        };
+        match played {
+            Ok(result) => break result,
+            // The game was quit before it ended
+            Err(Stopped::Quit) => return,
+            Err(Stopped::Failed(err)) => {
+                eprintln!("The game stopped: {}", err);
+                std::process::exit(1);
+            }
+            // Only casual and practice games are restarted, these never have a network opponent
+            Err(Stopped::Restart) => {
+                let targets = obscurity::pick(
+                    &pool,
+                    cli.boards as usize,
+                    &lists.frequencies,
+                    cli.obscurity,
+                );
+                let opponent = cli.versus.then(|| computer_opponent(targets[0]));
+                game = (targets, opponent);
             }
+        }
+    };
 
-            // Reset the board message
-            board_state.message = None;
+    if let Some(path) = &cli.record {
+        if let Err(err) = result
+            .replay
+            .as_ref()
+            .map_or(Ok(()), |replay| replay.save(path))
+        {
+            eprintln!("Could not save the replay to {}: {}", path.display(), err);
         }
+    }
 
-        debug_assert!(
-            guess.len() == WORD_LENGTH,
-            "The guessed word length was wrong"
-        );
+    // Overlays get the result as it would be shared without the word
+    setup.events.emit(&Event::GameFinished {
+        result: &share::Share::new(&result, language.code, Privacy::Standard),
+    });
 
-        // Process the guessed word
-        if !words.contains(&guess.as_str()) {
-            // If the word is not in the dictionary disallow the guess
-            board_state.message = Some(format!("The word {} is not in the dictionary", guess));
-            continue;
-        } else {
-            // If the word is in the dictionary process each character
-            for (index, chr) in guess.chars().enumerate() {
-                let knowledge = letter_knowledge.get_mut(&chr).unwrap();
-                if word.chars().nth(index).unwrap() == chr {
-                    debug_assert!(
-                        *knowledge != LetterKnowledge::NotInWord,
-                        "A letter in the correct place was marked as NotInWord"
-                    );
-
-                    // Update what is known about a letter
-                    *knowledge = match *knowledge {
-                        LetterKnowledge::NoInformation => {
-                            let mut confirmed = [false; WORD_LENGTH];
-                            confirmed[index] = true;
-                            LetterKnowledge::InWordPlaces {
-                                possible: [true; WORD_LENGTH],
-                                confirmed,
-                            }
-                        }
-                        LetterKnowledge::InWordPlaces {
-                            possible,
-                            mut confirmed,
-                        } => {
-                            confirmed[index] = true;
-                            LetterKnowledge::InWordPlaces {
-                                possible,
-                                confirmed,
-                            }
-                        }
-                        LetterKnowledge::NotInWord => LetterKnowledge::NotInWord,
-                    };
-
-                    board_state.board[guess_num][index] = GuessedLetter::Correct(chr);
-                } else if word.contains(chr) {
-                    debug_assert!(
-                        *knowledge != LetterKnowledge::NotInWord,
-                        "A letter in the word but in the wrong place was marked as NotInWord"
-                    );
-
-                    // If the letter is in the wrong place set or update the knowledge about this
-                    // letter
-                    *knowledge = match *knowledge {
-                        LetterKnowledge::NoInformation => {
-                            let mut possible = [true; WORD_LENGTH];
-                            possible[index] = false;
-                            LetterKnowledge::InWordPlaces {
-                                possible,
-                                confirmed: [false; WORD_LENGTH],
-                            }
-                        }
-                        LetterKnowledge::InWordPlaces {
-                            mut possible,
-                            confirmed,
-                        } => {
-                            possible[index] = false;
-                            LetterKnowledge::InWordPlaces {
-                                possible,
-                                confirmed,
-                            }
-                        }
-                        LetterKnowledge::NotInWord => LetterKnowledge::NotInWord,
-                    };
-
-                    board_state.board[guess_num][index] = GuessedLetter::WrongPlace(chr);
-                } else {
-                    debug_assert!(
-                        [LetterKnowledge::NoInformation, LetterKnowledge::NotInWord]
-                            .contains(knowledge),
-                        "A letter that isn't in the word was marked as InWord"
-                    );
-
-                    *knowledge = LetterKnowledge::NotInWord;
-                    board_state.board[guess_num][index] = GuessedLetter::Wrong(chr);
-                }
+    if let Some(provenance) = &lists.provenance {
+        for word in result.words.iter() {
+            if let Some(path) = provenance.source(word) {
+                println!("The word {} is from {}", word, path.display());
+            }
+        }
+    }
+
+    // Practice games aren't recorded or shared, the word was known or picked from a small part of
+    // the list. Kid mode has no limit on the guesses, and the statistics are of games on words.
+    if !cli.is_practice() && !cli.kids && cli.phrases.is_none() {
+        record_result(cli, config, language, &result);
+    }
+}
+
+/// Keep the result of a game in the statistics and the leaderboard, and share it
+fn record_result(
+    cli: &cli::Cli,
+    config: &Config,
+    language: &'static Language,
+    result: &GameResult,
+) {
+    // The terminal has been restored at this point so errors can be printed
+    let mut stats = Stats::load().unwrap_or_else(|err| {
+        eprintln!("Could not load the statistics, starting over: {}", err);
+        Stats::default()
+    });
+    stats.record(result, language.code, cli.hard);
+    if let Err(err) = stats.save() {
+        eprintln!("Could not save the statistics: {}", err);
+    }
+
+    let share = share::Share::new(
+        result,
+        language.code,
+        cli.share_privacy.unwrap_or(config.share_privacy),
+    );
+    if cli.share {
+        println!("{}", share.text());
+    }
+    share::post_to_webhooks(&config.webhooks, &share);
+
+    let mut leaderboard = Leaderboard::load().unwrap_or_else(|err| {
+        eprintln!("Could not load the leaderboard, starting over: {}", err);
+        Leaderboard::default()
+    });
+    leaderboard.record(language.code, WORD_LENGTH, result);
+    if let Err(err) = leaderboard.save() {
+        eprintln!("Could not save the leaderboard: {}", err);
+    }
+}
+
+/// The kinds of entries of the word lists that are played besides the words
+fn allowed_kinds(cli: &cli::Cli) -> Vec<word::Kind> {
+    [
+        (cli.allow_proper_nouns, word::Kind::ProperNoun),
+        (cli.allow_abbreviations, word::Kind::Abbreviation),
+    ]
+    .into_iter()
+    .filter_map(|(allow, kind)| allow.then_some(kind))
+    .collect()
+}
+
+/// The frequencies in the frequency column of the word lists, the first count of a word is used
+fn collect_frequencies(word_strings: &[&'static str]) -> HashMap<&'static str, u64> {
+    let mut frequencies = HashMap::new();
+    for line in word_strings
+        .iter()
+        .flat_map(|word_string| word_string.lines())
+    {
+        if let (word, Some(count)) = word::split_entry(line) {
+            if let Ok(count) = count.parse() {
+                frequencies.entry(word).or_insert(count);
             }
-            guess_num += 1;
         }
+    }
+    frequencies
+}
+
+/// Pick different random words from the list, with a seed the same words are picked each time
+fn pick_words(words: &[&'static str], count: usize, seed: Option<u64>) -> Vec<&'static str> {
+    let mut rng = match seed {
+        None => rand::rngs::StdRng::from_entropy(),
+        Some(seed) => rand::rngs::StdRng::seed_from_u64(seed),
+    };
+    rand::seq::index::sample(&mut rng, words.len(), count)
+        .into_iter()
+        .map(|index| words[index])
+        .collect()
+}
+
+/// Read a shared result and show its grid with the guesses behind it. The answer is given or looked
+/// up in the list of daily answers by the number of the puzzle.
+fn import_shared(
+    file: Option<&Path>,
+    answer: Option<&str>,
+    answers: Option<&Path>,
+    words: &[&'static str],
+    language: &'static Language,
+    theme: &Theme,
+) {
+    let text = match file {
+        Some(path) => std::fs::read_to_string(path),
+        None => {
+            if io::stdin().is_terminal() {
+                eprintln!("Paste the shared result and end it with Ctrl-D");
+            }
+            io::read_to_string(io::stdin())
+        }
+    };
+    let text = text.unwrap_or_else(|err| {
+        eprintln!("Could not read the shared result: {}", err);
+        std::process::exit(1);
+    });
+    let imported = import_share::parse(&text).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    let answer = match (answer, answers) {
+        (Some(answer), _) => Some(answer.to_string()),
+        (None, Some(path)) => {
+            let Some(puzzle) = imported.puzzle else {
+                eprintln!("The shared result has no puzzle number to pick the answer with");
+                std::process::exit(1);
+            };
+            let list = std::fs::read_to_string(path).unwrap_or_else(|err| {
+                eprintln!("Could not read the answers {}: {}", path.display(), err);
+                std::process::exit(1);
+            });
+            let Some(answer) = list.lines().nth(puzzle) else {
+                eprintln!("The answers have no answer for puzzle {}", puzzle);
+                std::process::exit(1);
+            };
+            Some(answer.trim().to_string())
+        }
+        (None, None) => None,
+    };
+    // The answer is written like the words of the word list
+    let answer =
+        answer.map(|answer| language.join_digraphs(&word::compose(&answer.to_lowercase())));
+    if let Some(answer) = &answer {
+        if word::length(answer) != WORD_LENGTH {
+            eprintln!("The answer {} is not {} letters", answer, WORD_LENGTH);
+            std::process::exit(1);
+        }
+    }
+
+    if io::stdin().is_terminal() && io::stdout().is_terminal() {
+        import_share::show(&imported, answer.as_deref(), words, theme);
+    } else {
+        import_share::print(&imported, answer.as_deref(), words);
+    }
+}
+
+/// The amount of guesses for a game, every extra board gives one extra guess
+fn max_guesses(boards: usize) -> u32 {
+    GUESSES + boards as u32 - 1
+}
+
+/// Do the ncurses initialization, the terminal is restored when the returned value is dropped or
+/// when the process is stopped by a signal
+fn init_ncurses(theme: &Theme) -> impl Drop {
+    restore_on_signal();
+    restore_on_panic();
+    // Take the character set from the environment so letters outside ASCII are shown
+    ncurses::setlocale(ncurses::LcCategory::all, "");
+    ncurses::initscr();
+    ncurses::start_color();
+    ncurses::use_default_colors();
+    theme.apply();
+    ncurses::raw();
+    ncurses::noecho();
+    // Let ncurses decode the arrow keys, with a short delay to still recognize a lone escape
+    ncurses::keypad(ncurses::stdscr(), true);
+    ncurses::set_escdelay(25);
+    ncurses::curs_set(ncurses::CURSOR_VISIBILITY::CURSOR_INVISIBLE);
+    // endwin always needs to get called
+    defer(|| {
+        ncurses::endwin();
+    })
+}
+
+/// Restore the terminal and stop when a signal asks the process to stop. In raw mode Ctrl-C is read
+/// as a key, these signals come from outside the game. The signals are handled on a thread of their
+/// own so the terminal can be restored outside the signal handler.
+#[cfg(unix)]
+fn restore_on_signal() {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
 
-        // The game end conditions
-        if word.eq_ignore_ascii_case(guess.as_str()) {
-            // If the guess is equal to the selected word the player wins and the game ends
-            board_state.message = Some("You win! Press any key to quit".to_string());
-            break;
-        } else if guess_num as u32 == GUESSES {
-            // If the maximum amount of guesses has been reached the player loses and the game ends
-            board_state.message = Some(format!("The word was {}! Press any key to quit.", word));
-            break;
+    static LISTENING: std::sync::Once = std::sync::Once::new();
+    LISTENING.call_once(|| {
+        let signals = signal_hook::iterator::Signals::new([SIGINT, SIGTERM, SIGHUP]);
+        match signals {
+            Ok(mut signals) => {
+                std::thread::spawn(move || {
+                    if let Some(signal) = signals.forever().next() {
+                        ncurses::endwin();
+                        eprintln!("Stopped by a signal, the game wasn't finished");
+                        std::process::exit(128 + signal);
+                    }
+                });
+            }
+            // The game can still be played, only the terminal isn't restored on a signal
+            Err(err) => eprintln!("Could not listen for signals: {}", err),
         }
+    });
+}
+
+/// The Windows console has no signals like these, closing it ends the process
+#[cfg(not(unix))]
+fn restore_on_signal() {}
+
+/// Restore the terminal before a panic is reported, otherwise the report is written on the game
+/// screen and cleared with it
+fn restore_on_panic() {
+    static HOOKED: std::sync::Once = std::sync::Once::new();
+    HOOKED.call_once(|| {
+        let report = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            ncurses::endwin();
+            report(info);
+        }));
+    });
+}
+
+/// The words that are still possible with what is known about the letters and that start with the
+/// (partial) guess that is being typed. The words are checked with their masks, those only have
+/// the places of a word so a longer phrase is checked on the knowledge as well.
+fn possible_words<'a>(
+    dictionary: &'a Dictionary,
+    length: usize,
+    knowledge: &'a Knowledge,
+    guess: &str,
+) -> impl Iterator<Item = &'static str> + 'a {
+    let range = dictionary.starting_with(length, guess);
+    let (index, masks) = dictionary.masks(length);
+    let constraint = Constraint::from_knowledge(index, knowledge);
+    dictionary.words(length)[range.clone()]
+        .iter()
+        .zip(&masks[range])
+        .filter(move |(word, mask)| {
+            constraint.matches(mask) && (length <= WORD_LENGTH || knowledge.allows(word))
+        })
+        .map(|(word, _)| *word)
+}
+
+/// The feedback that is shown on the keyboard for each letter that something is known about
+fn key_feedback(knowledge: &Knowledge) -> HashMap<char, Feedback> {
+    knowledge
+        .letters()
+        .filter_map(|(letter, _)| Some((letter, knowledge.feedback(letter)?)))
+        .collect()
+}
+
+/// Where the boards go on a screen of this size: the left and top, and the width and height of all
+/// the boards together
+fn board_area(board_state: &BoardState, max_x: i32, max_y: i32) -> (i32, i32, i32, i32) {
+    let board_width: i32 = 1 + 4 * board_state.tiles() as i32;
+    let guesses = board_state
+        .boards
+        .first()
+        .map_or(GUESSES as usize, |board| board.rows.len());
+    let win_height: i32 = 3 + 2 * guesses as i32;
+
+    // The boards are placed next to each other, win_width is the width of all of them together
+    let board_count = board_state.boards.len() as i32;
+    let win_width = board_count * board_width + (board_count - 1) * 2;
+
+    // The keyboard takes the bottom rows of the screen, with an empty row above it
+    let keyboard_height = match board_state.keyboard.is_empty() {
+        true => 0,
+        false => keyboard::height(&board_state.keyboard) + 1,
+    };
+    let win_x = (max_x - win_width) / 2;
+    let win_y = (max_y - keyboard_height - win_height) / 2;
+    (win_x, win_y, win_width, win_height)
+}
+
+/// The panel with the possible words to the right of the boards
+struct WordPanel {
+    x: i32,
+    y: i32,
+    /// The width of a column, a word with the space after it
+    column_width: usize,
+    columns: usize,
+    /// The rows of words, the footer goes below them
+    rows: usize,
+}
+
+impl WordPanel {
+    /// The most words that are shown at once
+    fn capacity(&self) -> usize {
+        self.columns * self.rows
+    }
+}
+
+/// The panel for the possible words on the screen as it is now, None when the words are hidden or
+/// don't fit
+fn word_panel(board_state: &BoardState, (max_x, max_y): (i32, i32)) -> Option<WordPanel> {
+    if board_state.hide_possible_words || board_state.possible_words.is_empty() {
+        return None;
     }
+    let (win_x, win_y, win_width, win_height) = board_area(board_state, max_x, max_y);
 
-    // Render the last message and quit
-    render_game(&board_state);
-    ncurses::getch();
+    // The panel is as high as the boards, the messages and hints go below them, and takes as many
+    // columns as fit up to a few. In learning mode the frequencies of the letters go above the
+    // words.
+    let x = win_x + win_width + 1;
+    let column_width = board_state.tiles() + 2;
+    let learning = match board_state.frequencies.is_empty() {
+        true => 0,
+        false => board_state.frequencies.len() as i32 + 1,
+    };
+    let rows = usize::try_from(win_height.min(max_y - win_y) - 1 - learning).ok()?;
+    let columns = usize::try_from(max_x - x).ok()? / column_width;
+    if rows == 0 || columns == 0 {
+        return None;
+    }
+    Some(WordPanel {
+        x,
+        y: win_y + learning,
+        column_width,
+        columns: columns.min(4),
+        rows,
+    })
 }
 
 fn render_game(board_state: &BoardState) {
+    let started = Instant::now();
+    draw_game(&mut Terminal, board_state);
+    log::debug!("Drew the screen in {:?}", started.elapsed());
+}
+
+/// Draw the game on the screen
+fn draw_game(screen: &mut impl Renderer, board_state: &BoardState) {
     // First clear whatever was there before
-    ncurses::clear();
+    screen.clear();
+    let (max_x, max_y) = screen.size();
+
+    let board_width: i32 = 1 + 4 * board_state.tiles() as i32;
+    let (win_x, win_y, win_width, win_height) = board_area(board_state, max_x, max_y);
 
-    // Use ncurses examples from https://lib.rs/crates/ncurses
-    let mut max_x = 0;
-    let mut max_y = 0;
-    ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+    // Print the messages below the window, the ones that need attention stand out
+    let mut message_lines = 0;
+    for (line, severity) in board_state.messages.lines() {
+        let attribute = match severity {
+            Severity::Info => 0,
+            Severity::Warning => ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_WARNING),
+            Severity::Error => ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_ERROR),
+        };
+        screen.print(
+            win_y + win_height + 1 + message_lines,
+            (max_x - line.chars().count() as i32) / 2,
+            line,
+            attribute,
+        );
+        message_lines += 1;
+    }
 
-    let win_width: i32 = 1 + 4 * WORD_LENGTH as i32;
-    let win_height: i32 = 3 + 2 * GUESSES as i32;
+    // Print the score between the board and the message. With a row timer the score is on a bar
+    // that shrinks with the time that is left.
+    if let Some(score) = &board_state.score {
+        match board_state.row_timer {
+            None => screen.print(
+                win_y + win_height,
+                win_x + (win_width - score.len() as i32) / 2,
+                score.as_str(),
+                0,
+            ),
+            Some(left) => {
+                let line = format!("{:^1$}", score, win_width as usize)
+                    .chars()
+                    .collect_vec();
+                let filled = ((left * line.len() as f64).ceil() as usize).min(line.len());
+                let attribute = match left < 0.25 {
+                    true => ncurses::A_REVERSE() | ncurses::COLOR_PAIR(COLOR_PAIR_WARNING),
+                    false => ncurses::A_REVERSE(),
+                };
+                let (bar, rest) = line.split_at(filled);
+                screen.print(
+                    win_y + win_height,
+                    win_x,
+                    &String::from_iter(bar),
+                    attribute,
+                );
+                screen.print(
+                    win_y + win_height,
+                    win_x + filled as i32,
+                    &String::from_iter(rest),
+                    0,
+                );
+            }
+        }
+    }
 
-    let win_x = (max_x - win_width) / 2;
-    let win_y = (max_y - win_height) / 2;
+    // Print the hint bar for letters that aren't on the keyboard below the message
+    if !board_state.compose_hints.is_empty() {
+        let hints = fill(
+            strings().compose_hints,
+            &[&board_state.compose_hints.join("  ")],
+        );
+        screen.print(
+            win_y + win_height + 2 + message_lines.max(1),
+            (max_x - hints.chars().count() as i32) / 2,
+            &hints,
+            0,
+        );
+    }
+
+    // The status line goes above the boards
+    if let Some(info) = &board_state.info {
+        let status = info.status();
+        screen.print(
+            win_y - 1,
+            (max_x - status.chars().count() as i32) / 2,
+            &status,
+            0,
+        );
+    }
+
+    for (index, board) in board_state.boards.iter().enumerate() {
+        let title = match (
+            &board_state.crossword,
+            board_state.boards.len(),
+            board.solved,
+        ) {
+            (Some(grid), _, _) => crossword::title(grid, index),
+            (None, 1, _) => board_state
+                .info
+                .as_ref()
+                .map_or("LINGO".to_string(), GameInfo::title),
+            (None, _, true) => strings().solved_title.to_string(),
+            (None, _, false) => fill(strings().board_title, &[&(index + 1)]),
+        };
+        let active = board_state
+            .crossword
+            .as_ref()
+            .is_none_or(|grid| grid.active == index);
+        render_board(
+            screen,
+            (win_x + index as i32 * (board_width + 2), win_y),
+            &title,
+            &board.rows,
+            board_state.cursor.filter(|_| !board.solved && active),
+            board_state.shake.filter(|_| !board.solved),
+            Tiles {
+                masked: false,
+                marked: board_state.marked,
+            },
+        );
+    }
+
+    // The board of the opponent goes on the left of the board of the player
+    if let Some(opponent_board) = &board_state.opponent_board {
+        render_board(
+            screen,
+            (win_x - board_width - 2, win_y),
+            &board_state.opponent_name,
+            opponent_board,
+            None,
+            None,
+            Tiles {
+                masked: board_state.opponent_masked,
+                marked: board_state.marked,
+            },
+        );
+    }
+
+    // The bingo card goes on the left of the boards too, there is no opponent in an endless run
+    if let Some(card) = &board_state.bingo {
+        bingo::render(
+            screen,
+            (win_x - bingo::WIDTH - 2, win_y),
+            card,
+            board_state.marked,
+        );
+    }
 
-    let print_horizontal_line = |y: i32| {
-        for i in 0..(WORD_LENGTH) {
-            ncurses::mvaddstr(win_y + y, win_x + 4 * i as i32, &"+---".to_string());
+    // The grid of a crossword goes on the left of the boards
+    if let Some(grid) = &board_state.crossword {
+        crossword::render(screen, (win_x - crossword::WIDTH - 2, win_y), grid);
+    }
+
+    // Print the possible words in columns from the scroll position, with the amount that doesn't
+    // fit below them
+    if let Some(panel) = word_panel(board_state, (max_x, max_y)) {
+        // The most common letters of each place, as far as they fit
+        for (place, frequencies) in board_state.frequencies.iter().enumerate() {
+            let odds = frequencies
+                .iter()
+                .take(3)
+                .map(|(letter, part)| format!("{} {:.0}%", word::display(*letter), part * 100.0))
+                .join(" ");
+            let line = format!("{}: {}", place + 1, odds);
+            screen.print(
+                panel.y - (board_state.frequencies.len() - place) as i32 - 1,
+                panel.x,
+                &line
+                    .chars()
+                    .take((max_x - panel.x) as usize)
+                    .collect::<String>(),
+                0,
+            );
+        }
+        let words = board_state
+            .possible_words
+            .iter()
+            .skip(board_state.possible_scroll)
+            .take(panel.capacity());
+        for (index, word) in words.enumerate() {
+            screen.print(
+                panel.y + (index % panel.rows) as i32,
+                panel.x + (index / panel.rows * panel.column_width) as i32,
+                word,
+                0,
+            );
         }
-        ncurses::mvaddch(win_y + y, win_x + win_width - 1, '+' as ncurses::chtype);
+        let more = board_state
+            .possible_words
+            .len()
+            .saturating_sub(board_state.possible_scroll + panel.capacity());
+        if more > 0 {
+            screen.print(
+                panel.y + panel.rows as i32,
+                panel.x,
+                &format!("{} more\u{2026}", more),
+                0,
+            );
+        }
+    }
+
+    if !board_state.keyboard.is_empty() {
+        keyboard::render(
+            screen,
+            &keyboard::layout(&board_state.keyboard, max_x, max_y),
+            &board_state.key_feedback,
+            board_state.marked,
+        );
+    }
+
+    screen.refresh();
+}
+
+/// Draw a board with the title in the header at the given position. When the board is masked the
+/// colors of the verified letters are shown but not the letters themselves.
+fn render_board(
+    screen: &mut impl Renderer,
+    (win_x, win_y): (i32, i32),
+    title: &str,
+    board: &[GuessedWord],
+    cursor: Option<(usize, usize)>,
+    shake: Option<(usize, i32)>,
+    tiles: Tiles,
+) {
+    // A row of a phrase can be longer than a word
+    let places = board.first().map_or(WORD_LENGTH, |row| row.len());
+    let win_width: i32 = 1 + 4 * places as i32;
+    let borders = borders::borders();
+
+    let print_horizontal_line = |screen: &mut _, y: i32, line: &Line| {
+        Renderer::print(screen, win_y + y, win_x, &line.across(places), 0);
     };
-    let print_guess = |y: i32, guess: &GuessedWord| {
-        for i in 0..WORD_LENGTH {
-            ncurses::mvaddstr(win_y + y, win_x + 4 * i as i32, &"|   ".to_string());
+    let print_guess = |screen: &mut _, y: i32, guess: &GuessedWord, cursor, shake: i32| {
+        for (i, letter) in guess.iter().enumerate() {
+            Renderer::print(
+                screen,
+                win_y + y,
+                win_x + 4 * i as i32,
+                &format!("{}   ", borders.vertical),
+                0,
+            );
 
             // Resolve the guess into a (char, attribute) tuple
-            let (character, attribute) = match guess[i as usize] {
+            let (character, attribute) = match *letter {
                 GuessedLetter::NoLetter => (' ', 0),
                 GuessedLetter::Letter(x) => (x, 0),
-                GuessedLetter::Wrong(x) => (x, ncurses::A_BOLD()),
+                GuessedLetter::Wrong(x) => (
+                    x,
+                    ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_WRONG_TILE),
+                ),
                 GuessedLetter::WrongPlace(x) => (
                     x,
-                    ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_WRONG_PLACE),
+                    ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_WRONG_PLACE_TILE),
                 ),
                 GuessedLetter::Correct(x) => (
                     x,
-                    ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_CORRECT),
+                    ncurses::A_BOLD() | ncurses::COLOR_PAIR(COLOR_PAIR_CORRECT_TILE),
                 ),
+                GuessedLetter::Separator => (borders.between.horizontal(), 0),
+            };
+            let character = match letter {
+                GuessedLetter::NoLetter | GuessedLetter::Separator => character,
+                _ if tiles.masked => '*',
+                _ => character,
+            };
+
+            // The terminal may draw the verified tiles as images, marks need the text cells
+            let image = letter.feedback().is_some_and(|feedback| {
+                !tiles.marked
+                    && Renderer::tile(
+                        screen,
+                        win_y + y,
+                        win_x + 1 + 4 * i as i32,
+                        feedback,
+                        &word::display(character),
+                    )
+            });
+            if image {
+                continue;
+            }
+
+            // Fill the verified tiles, the letter goes on top
+            if let Some(feedback) = letter.feedback() {
+                let [open, close] = match tiles.marked {
+                    true => announce::marks(feedback),
+                    false => [' ', ' '],
+                };
+                Renderer::print(
+                    screen,
+                    win_y + y,
+                    win_x + 1 + 4 * i as i32,
+                    &format!("{} {}", open, close),
+                    attribute,
+                );
+            }
+
+            // Show the cursor by reversing the tile it is on
+            let attribute = if cursor == Some(i) {
+                attribute | ncurses::A_REVERSE()
+            } else {
+                attribute
             };
 
-            ncurses::attron(attribute);
-            ncurses::mvaddch(
+            Renderer::print(
+                screen,
                 win_y + y,
-                win_x + 2 + 4 * i as i32,
-                character.to_ascii_uppercase() as ncurses::chtype,
+                win_x + 2 + shake + 4 * i as i32,
+                &word::display(character),
+                attribute,
             );
-            ncurses::attroff(attribute);
         }
-        ncurses::mvaddch(win_y + y, win_x + win_width - 1, '|' as ncurses::chtype);
+        Renderer::print(
+            screen,
+            win_y + y,
+            win_x + win_width - 1,
+            borders.vertical,
+            0,
+        );
     };
 
     // Print the header
+    print_horizontal_line(screen, 0, &borders.top);
     {
-        // Print the top line
-        for i in 0..(WORD_LENGTH) {
-            ncurses::mvaddstr(win_y, win_x + 4 * i as i32, &"----".to_string());
-        }
-        ncurses::mvaddch(win_y, win_x, '+' as ncurses::chtype);
-        ncurses::mvaddch(win_y, win_x + win_width - 1, '+' as ncurses::chtype);
-    }
-    {
-        // Print the line with LINGO in it
-        ncurses::mvaddstr(win_y + 1, win_x + (win_width - 5) / 2, &"LINGO".to_string());
-        ncurses::mvaddch(win_y + 1, win_x, '|' as ncurses::chtype);
-        ncurses::mvaddch(win_y + 1, win_x + win_width - 1, '|' as ncurses::chtype);
+        // Print the line with the title in it
+        screen.print(
+            win_y + 1,
+            win_x + (win_width - title.len() as i32) / 2,
+            title,
+            0,
+        );
+        screen.print(win_y + 1, win_x, borders.vertical, 0);
+        screen.print(win_y + 1, win_x + win_width - 1, borders.vertical, 0);
     }
-    // The line below LINGO
-    print_horizontal_line(2);
+    // The line below the title
+    print_horizontal_line(screen, 2, &borders.below_title);
 
     // Print the guesses
-    for i in 0..GUESSES {
-        print_guess(3 + (i as i32 * 2), &board_state.board[i as usize]);
-        print_horizontal_line(4 + 2 * i as i32);
-    }
-
-    // Print the possible words
-    for (index, word) in board_state.possible_words.iter().enumerate() {
-        ncurses::mvaddstr(win_y + index as i32, win_x + win_width + 1, word);
+    for (i, row) in board.iter().enumerate() {
+        let cursor = cursor
+            .filter(|(cursor_row, _)| *cursor_row == i)
+            .map(|(_, tile)| tile);
+        let shake = shake
+            .filter(|(shake_row, _)| *shake_row == i)
+            .map_or(0, |(_, offset)| offset);
+        print_guess(screen, 3 + (i as i32 * 2), row, cursor, shake);
+        let line = match i + 1 == board.len() {
+            true => &borders.bottom,
+            false => &borders.between,
+        };
+        print_horizontal_line(screen, 4 + 2 * i as i32, line);
     }
-
-    // Print the message below the window if there is one
-    match &board_state.message {
-        None => (),
-        Some(msg) => {
-            ncurses::mvaddstr(
-                win_y + win_height + 1,
-                (max_x - msg.len() as i32) / 2,
-                msg.as_str(),
-            );
-        }
-    }
-
-    ncurses::refresh();
 }
 
 /*
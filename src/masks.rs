@@ -0,0 +1,222 @@
+//! Words as bitmasks of their letters. Every letter of the alphabet gets a bit, a word has a mask
+//! with the bits of all its letters and a mask for each place with the bit of the letter there.
+//! What is known about the hidden word is kept in the same way, so checking a word takes a few
+//! bitwise operations and no allocations.
+//!
+//! An alphabet can have 127 letters with their own bit, the letters after that share the last
+//! bit. Nothing is known about those letters, a constraint lets the words with them through.
+
+use crate::WORD_LENGTH;
+use lingo_core::knowledge::Knowledge;
+use std::collections::HashMap;
+
+/// The bit of the letters that don't have a bit of their own
+const OTHER: u128 = 1 << 127;
+
+/// The bit of each letter of the alphabet
+#[derive(Clone)]
+pub struct LetterIndex {
+    bits: HashMap<char, u128>,
+}
+
+/// The letters of a word as bits
+#[derive(Clone, Copy)]
+pub struct WordMask {
+    letters: u128,
+    places: [u128; WORD_LENGTH],
+}
+
+impl LetterIndex {
+    /// Give the letters of the words a bit, in the order they come up
+    pub fn new(words: &[&str]) -> LetterIndex {
+        let mut bits = HashMap::new();
+        for letter in words.iter().flat_map(|word| word.chars()) {
+            let next = bits.len();
+            bits.entry(letter)
+                .or_insert_with(|| if next < 127 { 1 << next } else { OTHER });
+        }
+        LetterIndex { bits }
+    }
+
+    fn bit(&self, letter: char) -> u128 {
+        self.bits.get(&letter).copied().unwrap_or(OTHER)
+    }
+
+    /// The masks of a word of the word length
+    pub fn mask(&self, word: &str) -> WordMask {
+        let mut mask = WordMask {
+            letters: 0,
+            places: [0; WORD_LENGTH],
+        };
+        for (index, letter) in word.chars().enumerate() {
+            let bit = self.bit(letter);
+            mask.letters |= bit;
+            if let Some(place) = mask.places.get_mut(index) {
+                *place = bit;
+            }
+        }
+        mask
+    }
+}
+
+/// What is known about the letters of the hidden word
+pub struct Constraint<'a> {
+    index: &'a LetterIndex,
+    /// The letters that are in the word
+    required: u128,
+    /// The letters that aren't in the word
+    forbidden: u128,
+    /// The letters that can still be on each place
+    places: [u128; WORD_LENGTH],
+}
+
+impl<'a> Constraint<'a> {
+    /// Nothing known yet, every word matches
+    fn new(index: &'a LetterIndex) -> Constraint<'a> {
+        Constraint {
+            index,
+            required: 0,
+            forbidden: 0,
+            places: [u128::MAX; WORD_LENGTH],
+        }
+    }
+
+    /// The letters of the alphabet that are known, the shared bit is never known
+    fn known(&self, letter: char) -> Option<u128> {
+        Some(self.index.bit(letter)).filter(|bit| *bit != OTHER)
+    }
+
+    /// The letter isn't in the word
+    fn exclude(&mut self, letter: char) {
+        if let Some(bit) = self.known(letter) {
+            self.forbidden |= bit;
+        }
+    }
+
+    /// The letter is somewhere in the word
+    fn require(&mut self, letter: char) {
+        if let Some(bit) = self.known(letter) {
+            self.required |= bit;
+        }
+    }
+
+    /// The letter isn't on the place
+    fn forbid_at(&mut self, letter: char, place: usize) {
+        if let Some(bit) = self.known(letter) {
+            self.places[place] &= !bit;
+        }
+    }
+
+    /// The letter is on the place
+    fn fix(&mut self, letter: char, place: usize) {
+        self.places[place] &= self.index.bit(letter);
+    }
+
+    /// What the verified guesses revealed about the word, as bits. The letters without a bit of
+    /// their own aren't checked, `Knowledge::allows` has the final say on those.
+    pub fn from_knowledge(index: &'a LetterIndex, knowledge: &Knowledge) -> Constraint<'a> {
+        let mut constraint = Constraint::new(index);
+        for (letter, count) in knowledge.letters() {
+            if count.max == Some(0) {
+                constraint.exclude(letter);
+            } else if count.min > 0 {
+                constraint.require(letter);
+            }
+        }
+        for place in 0..WORD_LENGTH {
+            if let Some(letter) = knowledge.confirmed(place) {
+                constraint.fix(letter, place);
+            }
+            for letter in knowledge.excluded(place) {
+                constraint.forbid_at(*letter, place);
+            }
+        }
+        constraint
+    }
+
+    pub fn matches(&self, mask: &WordMask) -> bool {
+        mask.letters & self.required == self.required
+            && mask.letters & self.forbidden == 0
+            && mask
+                .places
+                .iter()
+                .zip(self.places.iter())
+                .all(|(letter, allowed)| letter & allowed != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{score, Feedback};
+
+    const WORDS: [&str; 16] = [
+        "kaart", "tabak", "taken", "tafel", "stoel", "appel", "lepel", "kakel", "kraak", "staat",
+        "tante", "otter", "ratel", "later", "kater", "tarot",
+    ];
+
+    /// The constraint of the pattern the guess gets against the target
+    fn learned<'a>(index: &'a LetterIndex, guess: &str, target: &str) -> Constraint<'a> {
+        let mut knowledge = Knowledge::default();
+        knowledge.record(guess, &score(guess, target));
+        Constraint::from_knowledge(index, &knowledge)
+    }
+
+    #[test]
+    fn constraints_match_the_words_with_the_same_pattern() {
+        let index = LetterIndex::new(&WORDS);
+        for guess in WORDS {
+            for target in WORDS {
+                let constraint = learned(&index, guess, target);
+                for word in WORDS {
+                    assert_eq!(
+                        constraint.matches(&index.mask(word)),
+                        score(guess, word) == score(guess, target),
+                        "{} against {} with {}",
+                        guess,
+                        target,
+                        word
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn letters_without_a_bit_let_the_words_through() {
+        // Other letters take all but one of the bits, after the k the letters share the last bit
+        let others = (0..126)
+            .filter_map(|offset| char::from_u32(0x100 + offset))
+            .collect::<String>();
+        let mut words = vec![others.as_str()];
+        words.extend(WORDS);
+        let index = LetterIndex::new(&words);
+        assert_ne!(index.bit('k'), OTHER);
+        assert_eq!(index.bit('a'), OTHER);
+
+        for guess in WORDS {
+            for target in WORDS {
+                let constraint = learned(&index, guess, target);
+                for word in WORDS {
+                    if score(guess, word) == score(guess, target) {
+                        assert!(
+                            constraint.matches(&index.mask(word)),
+                            "{} against {} with {}",
+                            guess,
+                            target,
+                            word
+                        );
+                    }
+                }
+                // What is known about the k still counts
+                if score(guess, target)
+                    .iter()
+                    .zip(guess.chars())
+                    .any(|(feedback, letter)| letter == 'k' && *feedback == Feedback::Wrong)
+                {
+                    assert!(!constraint.matches(&index.mask("kaart")));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Numbers instead of letters, like Mathler: the hidden word is a sum like `12+34` and the player
+//! knows what it makes. Every guess has to be a sum that makes the same number, it is scored like
+//! a word with the digits and the operators as the letters.
+
+use crate::dictionary::Dictionary;
+use crate::editor::GuessEditor;
+use crate::engine::{Event, Game, GameState, Transition};
+use crate::error::Error;
+use crate::input::{InputEvent, Poller};
+use crate::keymap::Bindings;
+use crate::messages::Notice;
+use crate::pause;
+use crate::quick_settings::QuickSettings;
+use crate::rules;
+use crate::script::Scripts;
+use crate::strings::{fill, strings};
+use crate::theme::Theme;
+use crate::{init_ncurses, key_feedback, render_game, Board, BoardState, GuessedLetter};
+use crate::{word, GUESSES, WORD_LENGTH};
+use rand::Rng;
+
+/// The characters of the sums, they are the keys of the keyboard
+const ALPHABET: [char; 14] = [
+    '1', '2', '3', '4', '5', '6', '7', '8', '9', '0', '+', '-', '*', '/',
+];
+const OPERATORS: [char; 4] = ['+', '-', '*', '/'];
+/// The renders a rejected guess stays on the screen
+const REJECTION_RENDERS: u32 = 3;
+
+/// What the sum makes, with multiplication and division before addition and subtraction. `None`
+/// when it isn't a sum of whole numbers: numbers don't start with a zero, there is a number
+/// between every two operators and a division has to come out even.
+fn evaluate(sum: &str) -> Option<i64> {
+    // Split the sum in numbers and the operators between them
+    let mut numbers = Vec::new();
+    let mut operators = Vec::new();
+    let mut number = String::new();
+    for chr in sum.chars() {
+        if OPERATORS.contains(&chr) {
+            operators.push(chr);
+            numbers.push(std::mem::take(&mut number));
+        } else if chr.is_ascii_digit() {
+            number.push(chr);
+        } else {
+            return None;
+        }
+    }
+    numbers.push(number);
+    if numbers
+        .iter()
+        .any(|number| number.is_empty() || (number.len() > 1 && number.starts_with('0')))
+    {
+        return None;
+    }
+    let numbers = numbers
+        .iter()
+        .map(|number| number.parse::<i64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    // Multiply and divide into terms, the terms are added up
+    let mut terms = vec![numbers[0]];
+    let mut signs = vec![1];
+    for (operator, number) in operators.into_iter().zip(&numbers[1..]) {
+        let term = terms.last_mut()?;
+        match operator {
+            '*' => *term = term.checked_mul(*number)?,
+            '/' if *number != 0 && *term % number == 0 => *term /= number,
+            '/' => return None,
+            sign => {
+                terms.push(*number);
+                signs.push(if sign == '-' { -1 } else { 1 });
+            }
+        }
+    }
+    Some(
+        terms
+            .iter()
+            .zip(signs)
+            .map(|(term, sign)| term * sign)
+            .sum(),
+    )
+}
+
+/// If the guess is a sum with an operator in it, a number on its own doesn't count
+fn is_sum(guess: &str) -> bool {
+    guess.contains(OPERATORS) && evaluate(guess).is_some()
+}
+
+/// A sum of the length of a word that makes a positive number, a sum that makes zero is too easy
+/// with a multiplication by zero
+fn pick(rng: &mut impl Rng) -> String {
+    loop {
+        let sum = (0..WORD_LENGTH)
+            .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())])
+            .collect::<String>();
+        if is_sum(&sum) && evaluate(&sum).is_some_and(|value| value > 0) {
+            return sum;
+        }
+    }
+}
+
+/// Check that the guess is a sum that makes the number, the error tells why it isn't
+fn check(guess: &str, value: i64) -> Result<(), String> {
+    match evaluate(guess).filter(|_| is_sum(guess)) {
+        None => Err(fill(strings().math_not_a_sum, &[&guess])),
+        Some(made) if made != value => Err(fill(strings().math_other_value, &[&guess, &made])),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Play a game on a sum until it is found or the guesses run out
+pub fn run(keys: &'static Bindings, theme: &'static Theme) -> Result<(), Error> {
+    let sum = pick(&mut rand::thread_rng());
+    let value = evaluate(&sum).expect("The sum was picked because it makes a number");
+    let _window_ender = init_ncurses(theme);
+    let poller = Poller::new(keys, -1);
+    let mut board_state = BoardState {
+        boards: vec![Board::new(GUESSES)],
+        keyboard: ALPHABET.to_vec(),
+        score: Some(fill(strings().math_target, &[&WORD_LENGTH, &value])),
+        ..Default::default()
+    };
+    // The sums aren't in a dictionary, a guess has to make the same number instead
+    let dictionary = Dictionary::default();
+    let scripts = Scripts::default();
+    let target = Box::leak(sum.into_boxed_str());
+    let mut game = Game::new(
+        &dictionary,
+        &scripts,
+        &ALPHABET,
+        vec![target],
+        GUESSES,
+        None,
+        false,
+    )
+    .with_check(|guess| check(guess, value).err().map(Notice::warning));
+    // There are no possible words to show, only the theme can be changed
+    let mut quick = QuickSettings {
+        theme,
+        suggestions: None,
+    };
+    let mut editor = GuessEditor::default();
+    // The pause menu opened with Ctrl-C asks to quit right away
+    let mut quitting = false;
+    loop {
+        let row = game.guesses() as usize;
+        match game.state() {
+            GameState::Typing => {
+                let text = editor.text();
+                for (index, letter) in board_state.boards[0].rows[row].iter_mut().enumerate() {
+                    *letter = match word::letter(&text, index) {
+                        None => GuessedLetter::NoLetter,
+                        Some(chr) => GuessedLetter::Letter(chr),
+                    };
+                }
+                board_state.cursor = editor.cursor().map(|tile| (row, tile));
+                board_state.key_feedback = key_feedback(game.knowledge(0));
+                render_game(&board_state);
+
+                match poller.poll() {
+                    event @ (InputEvent::Quit | InputEvent::Interrupt) => {
+                        quitting = event == InputEvent::Interrupt;
+                        game.handle_event(Event::Pause)?;
+                        continue;
+                    }
+                    InputEvent::Char(chr) if ALPHABET.contains(&chr) => editor.type_letter(chr),
+                    InputEvent::Backspace => editor.backspace(),
+                    InputEvent::Delete => editor.delete(),
+                    InputEvent::ClearRow => editor.clear(),
+                    InputEvent::Left => editor.move_left(),
+                    InputEvent::Right => editor.move_right(),
+                    InputEvent::Home => editor.move_home(),
+                    InputEvent::End => editor.move_end(),
+                    InputEvent::Submit if editor.is_full() => {
+                        if let Transition::Rejected(notice) =
+                            game.handle_event(Event::Submit(text))?
+                        {
+                            board_state
+                                .messages
+                                .push(notice.expire_after(REJECTION_RENDERS));
+                            continue;
+                        }
+                    }
+                    _ => (),
+                }
+                board_state.messages.tick();
+            }
+            GameState::Scoring => {
+                if let Transition::Scored(scored) = game.handle_event(Event::Score)? {
+                    for (_, pattern) in scored.patterns {
+                        board_state.boards[0].rows[row] =
+                            rules::guessed_word(&scored.guess, &pattern);
+                    }
+                    editor.clear();
+                }
+            }
+            GameState::Menu => {
+                // Quitting after a guess has to be confirmed
+                if pause::pause_or_quit(&mut quick, row > 0, quitting, || render_game(&board_state))
+                {
+                    return Ok(());
+                }
+                game.handle_event(Event::Resume)?;
+            }
+            GameState::Won | GameState::Lost => break,
+        }
+    }
+
+    // The sum is shown until a key is pressed
+    let message = match game.state() {
+        GameState::Won => fill(strings().math_found, &[&target, &game.guesses()]),
+        _ => fill(strings().math_lost, &[&target]),
+    };
+    board_state.cursor = None;
+    board_state.key_feedback = key_feedback(game.knowledge(0));
+    board_state.messages.clear();
+    board_state.messages.push(Notice::info(message));
+    render_game(&board_state);
+    ncurses::getch();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn sums_make_whole_numbers() {
+        assert_eq!(evaluate("12+34"), Some(46));
+        assert_eq!(evaluate("3*4-2"), Some(10));
+        assert_eq!(evaluate("2-3*4"), Some(-10));
+        assert_eq!(evaluate("8/4/2"), Some(1));
+        assert_eq!(evaluate("8/3+1"), None);
+        assert_eq!(evaluate("01+23"), None);
+        assert_eq!(evaluate("+12+3"), None);
+        assert_eq!(evaluate("1+/23"), None);
+        assert!(!is_sum("12345"));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let sum = pick(&mut rng);
+        assert!(check(&sum, evaluate(&sum).unwrap()).is_ok());
+        assert!(check("12+34", 45).is_err());
+    }
+}
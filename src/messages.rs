@@ -0,0 +1,117 @@
+//! The messages below the board. Several messages can be shown at once, so a rejected guess doesn't
+//! hide a hint, and a message can go away by itself after a few renders. A render is counted each
+//! time the screen is drawn again after a key.
+
+/// How important a message is, this decides how it stands out
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct Notice {
+    /// The text, a line break starts a new line on the screen
+    pub text: String,
+    pub severity: Severity,
+    /// The renders left before the message goes away, None to keep it until the queue is cleared
+    renders: Option<u32>,
+}
+
+impl Notice {
+    pub fn info(text: impl Into<String>) -> Notice {
+        Notice::new(text.into(), Severity::Info)
+    }
+
+    pub fn warning(text: impl Into<String>) -> Notice {
+        Notice::new(text.into(), Severity::Warning)
+    }
+
+    pub fn error(text: impl Into<String>) -> Notice {
+        Notice::new(text.into(), Severity::Error)
+    }
+
+    fn new(text: String, severity: Severity) -> Notice {
+        Notice {
+            text,
+            severity,
+            renders: None,
+        }
+    }
+
+    /// Let the message go away after it has been shown this many times
+    pub fn expire_after(mut self, renders: u32) -> Notice {
+        self.renders = Some(renders);
+        self
+    }
+}
+
+/// The messages that are shown, oldest first
+#[derive(Default)]
+pub struct MessageQueue {
+    notices: Vec<Notice>,
+}
+
+impl MessageQueue {
+    /// The most messages that are shown at once, the oldest go first
+    const MAX_NOTICES: usize = 3;
+
+    /// Show a message, the same text that is already shown is moved to the end instead
+    pub fn push(&mut self, notice: Notice) {
+        self.notices.retain(|shown| shown.text != notice.text);
+        self.notices.push(notice);
+        let overflow = self.notices.len().saturating_sub(Self::MAX_NOTICES);
+        self.notices.drain(..overflow);
+    }
+
+    /// Take away the message with this text, if it is shown
+    pub fn remove(&mut self, text: &str) {
+        self.notices.retain(|notice| notice.text != text);
+    }
+
+    pub fn clear(&mut self) {
+        self.notices.clear();
+    }
+
+    /// Count a render, the messages that have been shown long enough go away
+    pub fn tick(&mut self) {
+        for notice in &mut self.notices {
+            if let Some(renders) = &mut notice.renders {
+                *renders = renders.saturating_sub(1);
+            }
+        }
+        self.notices.retain(|notice| notice.renders != Some(0));
+    }
+
+    /// The lines of the messages with their severity
+    pub fn lines(&self) -> impl Iterator<Item = (&str, Severity)> {
+        self.notices
+            .iter()
+            .flat_map(|notice| notice.text.lines().map(|line| (line, notice.severity)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_expire_and_do_not_overwrite_each_other() {
+        let mut queue = MessageQueue::default();
+        queue.push(Notice::info("A good first guess is RATEL"));
+        queue.push(Notice::error("The word zzzzz is not\nin the dictionary").expire_after(2));
+        assert_eq!(queue.lines().count(), 3);
+
+        queue.tick();
+        assert_eq!(queue.lines().count(), 3);
+        queue.tick();
+        assert_eq!(
+            queue.lines().collect::<Vec<_>>(),
+            [("A good first guess is RATEL", Severity::Info)]
+        );
+
+        queue.push(Notice::info("A good first guess is RATEL"));
+        assert_eq!(queue.lines().count(), 1);
+    }
+}
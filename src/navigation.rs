@@ -0,0 +1,80 @@
+//! Moving through the lists of the menus with the arrow keys, or with the keys of vim: j and k,
+//! gg for the top, G for the bottom and Ctrl-F and Ctrl-B for a page.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Move {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+}
+
+impl Move {
+    /// The position after the move, in a list where `last` is the last position and a page is
+    /// `page` positions
+    pub fn apply(self, position: usize, last: usize, page: usize) -> usize {
+        match self {
+            Move::Up => position.saturating_sub(1),
+            Move::Down => (position + 1).min(last),
+            Move::PageUp => position.saturating_sub(page),
+            Move::PageDown => (position + page).min(last),
+            Move::Top => 0,
+            Move::Bottom => last,
+        }
+    }
+}
+
+/// Turns the keys into moves, it remembers the first g of gg
+#[derive(Default)]
+pub struct Navigator {
+    pending_g: bool,
+}
+
+impl Navigator {
+    /// The move of the key, None for the keys that don't move
+    pub fn read(&mut self, input: i32) -> Option<Move> {
+        let pending_g = std::mem::take(&mut self.pending_g);
+        let is = |keys: &[i32]| keys.contains(&input);
+        if is(&[ncurses::KEY_UP, 'k' as i32]) {
+            Some(Move::Up)
+        } else if is(&[ncurses::KEY_DOWN, 'j' as i32]) {
+            Some(Move::Down)
+        } else if is(&[ncurses::KEY_PPAGE, 0x02]) {
+            Some(Move::PageUp)
+        } else if is(&[ncurses::KEY_NPAGE, 0x06]) {
+            Some(Move::PageDown)
+        } else if is(&[ncurses::KEY_HOME]) || (pending_g && input == 'g' as i32) {
+            Some(Move::Top)
+        } else if is(&[ncurses::KEY_END, 'G' as i32]) {
+            Some(Move::Bottom)
+        } else {
+            self.pending_g = input == 'g' as i32;
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vim_keys_move_through_the_list() {
+        let mut navigator = Navigator::default();
+        let mut position = 0;
+        for key in "jjjkG".chars() {
+            if let Some(movement) = navigator.read(key as i32) {
+                position = movement.apply(position, 9, 5);
+            }
+        }
+        assert_eq!(position, 9);
+
+        // A single g does nothing, and another key in between breaks gg up
+        assert_eq!(navigator.read('g' as i32), None);
+        assert_eq!(navigator.read('x' as i32), None);
+        assert_eq!(navigator.read('g' as i32), None);
+        assert_eq!(navigator.read('g' as i32), Some(Move::Top));
+    }
+}
@@ -0,0 +1,389 @@
+//! Two players race on the same word over TCP. The host picks a seed that both sides use to pick
+//! the word, after that each side sends the patterns of its verified guesses so the other side
+//! can show the progress of the opponent without the letters. In a co-op game the players take
+//! turns on the rows of a single board instead, the keys of the player whose turn it is are sent
+//! to the other side.
+//!
+//! Every message is a json line of the `protocol` module prefixed by its length as a big endian
+//! u32. In a room the json is sealed with the keys from the handshake in the `crypto` module, both
+//! sides first send each other a sealed confirmation so a wrong room code is noticed before the
+//! game starts.
+
+use crate::crypto::{self, Opener, Role, Sealer};
+use crate::opponent::{Opponent, PartnerMove};
+use crate::protocol::{self, Message};
+use crate::rules::{self, Pattern};
+use crate::{GuessedWord, GUESSES, WORD_LENGTH};
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// Messages larger than this are refused, no valid message comes close
+const MAX_MESSAGE_LENGTH: u32 = 64 * 1024;
+/// The payload both sides send first in a room to show they derived the same keys
+const ROOM_CONFIRMATION: &[u8] = b"rust-lingo room";
+
+fn write_frame(stream: &mut TcpStream, bytes: &[u8]) -> io::Result<()> {
+    stream.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    stream.write_all(bytes)?;
+    stream.flush()
+}
+
+fn read_frame(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut length = [0; 4];
+    stream.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length);
+    if length > MAX_MESSAGE_LENGTH {
+        return Err(io::Error::new(ErrorKind::InvalidData, "Message too large"));
+    }
+    let mut bytes = vec![0; length as usize];
+    stream.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn send(stream: &mut TcpStream, sealer: &mut Option<Sealer>, message: &Message) -> io::Result<()> {
+    let bytes = protocol::encode(message).into_bytes();
+    match sealer {
+        None => write_frame(stream, &bytes),
+        Some(sealer) => write_frame(stream, &sealer.seal(&bytes)),
+    }
+}
+
+fn receive(stream: &mut TcpStream, opener: &mut Option<Opener>) -> io::Result<Message> {
+    let bytes = read_frame(stream)?;
+    let bytes = match opener {
+        None => bytes,
+        Some(opener) => opener.open(&bytes)?,
+    };
+    let line =
+        String::from_utf8(bytes).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    protocol::decode(&line)
+}
+
+/// Set up the keys of a room and check that the other side has the same room code
+fn secure(
+    stream: &mut TcpStream,
+    room: Option<&str>,
+    role: Role,
+) -> io::Result<(Option<Sealer>, Option<Opener>)> {
+    let room = match room {
+        None => return Ok((None, None)),
+        Some(room) => room,
+    };
+
+    let (mut sealer, mut opener) = crypto::handshake(stream, room, role)?;
+    write_frame(stream, &sealer.seal(ROOM_CONFIRMATION))?;
+    let confirmation = read_frame(stream)?;
+    match opener.open(&confirmation) {
+        Ok(confirmation) if confirmation == ROOM_CONFIRMATION => Ok((Some(sealer), Some(opener))),
+        _ => Err(io::Error::new(
+            ErrorKind::PermissionDenied,
+            "The other player is in a different room, check the room code",
+        )),
+    }
+}
+
+/// A connection to the other player that has agreed on the seed
+pub struct Connection {
+    stream: TcpStream,
+    sealer: Option<Sealer>,
+    opener: Option<Opener>,
+    pub seed: u64,
+    /// If the players play together on one board, the host decides
+    pub coop: bool,
+    host: bool,
+}
+
+impl Connection {
+    /// Read the messages of the other player on a background thread
+    fn listen(self) -> io::Result<(TcpStream, Option<Sealer>, Receiver<Message>)> {
+        let mut reader = self.stream.try_clone()?;
+        let mut opener = self.opener;
+        let (sender, messages) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(message) = receive(&mut reader, &mut opener) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok((self.stream, self.sealer, messages))
+    }
+}
+
+/// Wait for the other player to connect and send them the seed, in a room the connection is
+/// encrypted first
+pub fn host(
+    port: u16,
+    seed: u64,
+    words: usize,
+    room: Option<&str>,
+    coop: bool,
+) -> io::Result<Connection> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    eprintln!("Waiting for an opponent to join on port {}", port);
+    let (mut stream, address) = listener.accept()?;
+    eprintln!("{} joined", address);
+
+    let (mut sealer, opener) = secure(&mut stream, room, Role::Host)?;
+    send(
+        &mut stream,
+        &mut sealer,
+        &Message::Hello { seed, words, coop },
+    )?;
+    Ok(Connection {
+        stream,
+        sealer,
+        opener,
+        seed,
+        coop,
+        host: true,
+    })
+}
+
+/// Connect to a hosting player and receive the seed, in a room the connection is encrypted first
+pub fn join(
+    address: impl ToSocketAddrs,
+    words: usize,
+    room: Option<&str>,
+) -> io::Result<Connection> {
+    let mut stream = TcpStream::connect(address)?;
+    let (sealer, mut opener) = secure(&mut stream, room, Role::Join)?;
+    match receive(&mut stream, &mut opener)? {
+        Message::Hello {
+            seed,
+            words: host_words,
+            coop,
+        } => {
+            if host_words != words {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "The host plays with {} words and you with {}, use the same word list",
+                        host_words, words
+                    ),
+                ));
+            }
+            Ok(Connection {
+                stream,
+                sealer,
+                opener,
+                seed,
+                coop,
+                host: false,
+            })
+        }
+        _ => Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "Expected a hello from the host",
+        )),
+    }
+}
+
+/// The other player of a network game
+pub struct RemoteOpponent {
+    stream: TcpStream,
+    sealer: Option<Sealer>,
+    /// The messages read from the other player by a background thread
+    messages: Receiver<Message>,
+    board: [GuessedWord; GUESSES as usize],
+    guesses: usize,
+    solved: bool,
+    disconnected: bool,
+}
+
+impl RemoteOpponent {
+    pub fn new(connection: Connection) -> io::Result<RemoteOpponent> {
+        let (stream, sealer, messages) = connection.listen()?;
+        Ok(RemoteOpponent {
+            stream,
+            sealer,
+            messages,
+            board: Default::default(),
+            guesses: 0,
+            solved: false,
+            disconnected: false,
+        })
+    }
+
+    fn send(&mut self, message: &Message) {
+        if !self.disconnected && send(&mut self.stream, &mut self.sealer, message).is_err() {
+            self.disconnected = true;
+        }
+    }
+}
+
+impl Opponent for RemoteOpponent {
+    fn name(&self) -> &str {
+        if self.disconnected {
+            "DISCONNECTED"
+        } else {
+            "OPPONENT"
+        }
+    }
+
+    fn board(&self) -> &[GuessedWord; GUESSES as usize] {
+        &self.board
+    }
+
+    fn solved(&self) -> bool {
+        self.solved
+    }
+
+    fn reveals_letters(&self) -> bool {
+        // The letters are never sent
+        false
+    }
+
+    fn poll(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            match self.messages.try_recv() {
+                Ok(Message::Result { pattern, .. }) if self.guesses < GUESSES as usize => {
+                    let hidden = "*".repeat(WORD_LENGTH);
+                    self.board[self.guesses] = rules::guessed_word(&hidden, &pattern);
+                    self.guesses += 1;
+                    changed = true;
+                }
+                Ok(Message::Finished { won, .. }) => {
+                    self.solved = won;
+                    changed = true;
+                }
+                Ok(_) => (),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    changed |= !self.disconnected;
+                    self.disconnected = true;
+                    break;
+                }
+            }
+        }
+        changed
+    }
+
+    fn player_guessed(&mut self, pattern: &Pattern) {
+        // Only the pattern is sent, never the letters
+        self.send(&Message::Result {
+            guess: None,
+            pattern: *pattern,
+        });
+    }
+
+    fn player_finished(&mut self, won: bool) {
+        self.send(&Message::Finished {
+            won,
+            word: None,
+            message: None,
+        });
+    }
+}
+
+/// The other player of a co-op game, the host plays the first row and after that the players take
+/// turns. When the other player is gone the player plays the rest of the rows.
+pub struct RemotePartner {
+    stream: TcpStream,
+    sealer: Option<Sealer>,
+    messages: Receiver<Message>,
+    /// The rows of the partner are never shown on a board of their own
+    board: [GuessedWord; GUESSES as usize],
+    /// What the partner did on their turns, that isn't on the board yet
+    moves: VecDeque<PartnerMove>,
+    host: bool,
+    disconnected: bool,
+}
+
+impl RemotePartner {
+    pub fn new(connection: Connection) -> io::Result<RemotePartner> {
+        let host = connection.host;
+        let (stream, sealer, messages) = connection.listen()?;
+        Ok(RemotePartner {
+            stream,
+            sealer,
+            messages,
+            board: Default::default(),
+            moves: VecDeque::new(),
+            host,
+            disconnected: false,
+        })
+    }
+
+    fn send(&mut self, message: &Message) {
+        if !self.disconnected && send(&mut self.stream, &mut self.sealer, message).is_err() {
+            self.disconnected = true;
+        }
+    }
+}
+
+impl Opponent for RemotePartner {
+    fn name(&self) -> &str {
+        if self.disconnected {
+            "DISCONNECTED"
+        } else {
+            "PARTNER"
+        }
+    }
+
+    fn board(&self) -> &[GuessedWord; GUESSES as usize] {
+        &self.board
+    }
+
+    fn solved(&self) -> bool {
+        // The word is solved together
+        false
+    }
+
+    fn reveals_letters(&self) -> bool {
+        true
+    }
+
+    fn poll(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            match self.messages.try_recv() {
+                Ok(Message::Typed { text, cursor }) => {
+                    self.moves.push_back(PartnerMove::Typed { text, cursor });
+                    changed = true;
+                }
+                Ok(Message::Guess { word }) => {
+                    self.moves.push_back(PartnerMove::Submitted(word));
+                    changed = true;
+                }
+                Ok(_) => (),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    changed |= !self.disconnected;
+                    self.disconnected = true;
+                    break;
+                }
+            }
+        }
+        changed
+    }
+
+    fn shares_board(&self) -> bool {
+        true
+    }
+
+    fn plays_row(&self, row: usize) -> bool {
+        !self.disconnected && row.is_multiple_of(2) != self.host
+    }
+
+    fn take_move(&mut self) -> Option<PartnerMove> {
+        self.moves.pop_front()
+    }
+
+    fn player_typed(&mut self, text: &str, cursor: usize) {
+        self.send(&Message::Typed {
+            text: text.to_string(),
+            cursor,
+        });
+    }
+
+    fn player_submitted(&mut self, guess: &str) {
+        self.send(&Message::Guess {
+            word: guess.to_string(),
+        });
+    }
+}
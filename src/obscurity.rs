@@ -0,0 +1,67 @@
+//! Picking the word with a preference for common words. A word list can have a frequency column,
+//! `word<TAB>count`, and the obscurity decides how deep into the uncommon words the word can come
+//! from. Lists without frequencies have no common words, the word is picked from all of them.
+
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+
+/// How uncommon the word of the game can be
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Obscurity {
+    /// Only the most common words, the more common the more likely
+    Easy,
+    /// Most words, common words are more likely
+    #[default]
+    Normal,
+    /// Any word, all words are as likely
+    Hard,
+}
+
+impl Obscurity {
+    /// The part of the words, starting at the most common, the word can come from
+    fn depth(self) -> f64 {
+        match self {
+            Obscurity::Easy => 0.25,
+            Obscurity::Normal => 0.6,
+            Obscurity::Hard => 1.0,
+        }
+    }
+
+    /// How strongly the frequency counts, the weight of a word is its count to this power
+    fn preference(self) -> f64 {
+        match self {
+            Obscurity::Easy => 1.0,
+            Obscurity::Normal => 0.5,
+            Obscurity::Hard => 0.0,
+        }
+    }
+}
+
+/// Pick `count` different words from the pool, weighted toward the common words by the
+/// frequencies of the word list
+pub fn pick(
+    pool: &[&'static str],
+    count: usize,
+    frequencies: &HashMap<&str, u64>,
+    obscurity: Obscurity,
+) -> Vec<&'static str> {
+    if frequencies.is_empty() {
+        return crate::pick_words(pool, count, None);
+    }
+
+    let frequency = |word: &str| frequencies.get(word).copied().unwrap_or(0);
+    let mut common = pool.to_vec();
+    common.sort_by_key(|word| std::cmp::Reverse(frequency(word)));
+    let depth = (common.len() as f64 * obscurity.depth()).ceil() as usize;
+    common.truncate(depth.max(count));
+
+    // Words without a count still have a small chance
+    common
+        .choose_multiple_weighted(&mut rand::thread_rng(), count, |word| {
+            ((frequency(word) + 1) as f64).powf(obscurity.preference())
+        })
+        .expect("The weights are positive and finite")
+        .copied()
+        .collect()
+}
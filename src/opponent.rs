@@ -0,0 +1,127 @@
+use crate::rules::{self, Pattern};
+use crate::solver::Solver;
+use crate::strategy::Strategy;
+use crate::{GuessedWord, GUESSES};
+use std::time::{Duration, Instant};
+
+/// What the other player of a co-op game did on their turn
+pub enum PartnerMove {
+    /// The letters on the row changed
+    Typed { text: String, cursor: usize },
+    /// The row was submitted as a guess
+    Submitted(String),
+}
+
+/// Someone racing the player on the same word, their board is shown next to the board of the
+/// player. In a co-op game it is the partner taking turns on the board of the player instead.
+pub trait Opponent {
+    /// The title shown above the board of the opponent
+    fn name(&self) -> &str;
+
+    fn board(&self) -> &[GuessedWord; GUESSES as usize];
+
+    /// If the opponent found the word
+    fn solved(&self) -> bool;
+
+    /// If the letters on the board of the opponent can be shown at the end of the game
+    fn reveals_letters(&self) -> bool;
+
+    /// Let the opponent make progress, returns if anything changed
+    fn poll(&mut self) -> bool;
+
+    /// The player made a verified guess
+    fn player_guessed(&mut self, _pattern: &Pattern) {}
+
+    /// The game of the player ended
+    fn player_finished(&mut self, _won: bool) {}
+
+    /// If the other player guesses on the board of the player, taking turns on the rows
+    fn shares_board(&self) -> bool {
+        false
+    }
+
+    /// If the row of a shared board is the turn of the other player
+    fn plays_row(&self, _row: usize) -> bool {
+        false
+    }
+
+    /// The next thing the other player did on a shared board
+    fn take_move(&mut self) -> Option<PartnerMove> {
+        None
+    }
+
+    /// The letters on the row of the player changed, on a shared board
+    fn player_typed(&mut self, _text: &str, _cursor: usize) {}
+
+    /// The player submitted the row as a guess, on a shared board
+    fn player_submitted(&mut self, _guess: &str) {}
+}
+
+/// A computer player that makes a guess at a fixed interval
+pub struct ComputerOpponent {
+    solver: Solver,
+    word: &'static str,
+    board: [GuessedWord; GUESSES as usize],
+    guesses: usize,
+    solved: bool,
+    /// The time between two guesses of the computer
+    delay: Duration,
+    next_guess_at: Instant,
+}
+
+impl ComputerOpponent {
+    pub fn new(
+        words: &[&'static str],
+        word: &'static str,
+        delay: Duration,
+        hard: bool,
+        strategy: &'static dyn Strategy,
+    ) -> ComputerOpponent {
+        ComputerOpponent {
+            solver: Solver::new(words, hard, strategy),
+            word,
+            board: Default::default(),
+            guesses: 0,
+            solved: false,
+            delay,
+            next_guess_at: Instant::now() + delay,
+        }
+    }
+}
+
+impl Opponent for ComputerOpponent {
+    fn name(&self) -> &str {
+        "COMPUTER"
+    }
+
+    fn board(&self) -> &[GuessedWord; GUESSES as usize] {
+        &self.board
+    }
+
+    fn solved(&self) -> bool {
+        self.solved
+    }
+
+    fn reveals_letters(&self) -> bool {
+        true
+    }
+
+    /// Make a guess if it is time for it
+    fn poll(&mut self) -> bool {
+        if self.solved || self.guesses == GUESSES as usize || Instant::now() < self.next_guess_at {
+            return false;
+        }
+
+        let guess = match self.solver.next_guess() {
+            None => return false,
+            Some(guess) => guess,
+        };
+        let pattern = rules::score(guess, self.word);
+        self.solver.update(guess, &pattern);
+        self.board[self.guesses] = rules::guessed_word(guess, &pattern);
+        self.guesses += 1;
+        self.solved = guess == self.word;
+        self.next_guess_at = Instant::now() + self.delay;
+        true
+    }
+}
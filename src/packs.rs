@@ -0,0 +1,206 @@
+//! Word packs are word lists that are downloaded from a url and cached in the data directory. The
+//! player subscribes to them in the config file:
+//!
+//! ```toml
+//! [[packs]]
+//! name = "animals"
+//! url = "https://example.com/animals.txt"
+//! kind = "words"       # or "denylist" for words that should be removed
+//! refresh_hours = 24
+//! ```
+//!
+//! Downloading is only compiled in with the `word-packs` feature, without it the packs that are
+//! already in the cache are still used.
+
+use crate::language::Language;
+use crate::storage;
+use crate::word;
+use serde::Deserialize;
+#[cfg(feature = "word-packs")]
+use serde::Serialize;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "word-packs")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn default_refresh_hours() -> u64 {
+    24
+}
+
+/// What the words in a pack are used for
+#[derive(Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackKind {
+    /// Words that are added to the dictionary
+    #[default]
+    Words,
+    /// Words that are removed from the dictionary
+    Denylist,
+}
+
+/// A word pack the player subscribed to in the config file
+#[derive(Clone, Deserialize)]
+#[cfg_attr(not(feature = "word-packs"), allow(dead_code))]
+pub struct PackSubscription {
+    /// The name of the pack, also used as the name of the cache file
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub kind: PackKind,
+    /// How long a downloaded pack is used before checking for a new version
+    #[serde(default = "default_refresh_hours")]
+    pub refresh_hours: u64,
+}
+
+/// What is known about the cached version of a pack
+#[cfg(feature = "word-packs")]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct PackMeta {
+    /// The ETag the server sent with the cached version
+    etag: Option<String>,
+    /// When the pack was last checked, in seconds since the unix epoch
+    checked_at: u64,
+}
+
+/// The words from all the cached packs
+#[derive(Default)]
+pub struct PackWords {
+    pub words: Vec<&'static str>,
+    pub denied: Vec<&'static str>,
+}
+
+#[cfg(feature = "word-packs")]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// The path of the cached word list of a pack, the metadata is stored next to it
+fn cache_path(subscription: &PackSubscription) -> io::Result<PathBuf> {
+    // The name ends up in a path so only allow simple names
+    let valid = !subscription.name.is_empty()
+        && subscription
+            .name
+            .chars()
+            .all(|chr| chr.is_ascii_alphanumeric() || chr == '-' || chr == '_');
+    if !valid {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid pack name '{}'", subscription.name),
+        ));
+    }
+    storage::data_path(&format!("packs/{}.txt", subscription.name))
+}
+
+#[cfg(feature = "word-packs")]
+fn load_meta(path: &Path) -> PackMeta {
+    fs::read_to_string(path.with_extension("json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "word-packs")]
+fn save_meta(path: &Path, meta: &PackMeta) -> io::Result<()> {
+    let content = serde_json::to_string_pretty(meta).map_err(io::Error::other)?;
+    fs::write(path.with_extension("json"), content)
+}
+
+/// Check the server for a new version of the pack, the ETag of the cached version is sent along
+/// so the server only has to send the pack when it changed
+#[cfg(feature = "word-packs")]
+fn refresh(subscription: &PackSubscription, path: &Path, meta: &mut PackMeta) -> io::Result<()> {
+    let mut request = ureq::get(&subscription.url);
+    if let Some(etag) = &meta.etag {
+        // Only use the ETag when the cached file is still there
+        if path.exists() {
+            request = request.set("If-None-Match", etag);
+        }
+    }
+
+    let response = request
+        .call()
+        .map_err(|err| io::Error::other(err.to_string()))?;
+    if response.status() != 304 {
+        let etag = response.header("ETag").map(str::to_string);
+        let content = response.into_string()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, content)?;
+        meta.etag = etag;
+    }
+
+    meta.checked_at = now();
+    save_meta(path, meta)
+}
+
+/// Refresh the pack if it was never downloaded or if the refresh interval has passed
+#[cfg(feature = "word-packs")]
+fn refresh_if_due(subscription: &PackSubscription, path: &Path) {
+    let mut meta = load_meta(path);
+    let due = now() >= meta.checked_at + subscription.refresh_hours * 60 * 60;
+    if due || !path.exists() {
+        if let Err(err) = refresh(subscription, path, &mut meta) {
+            eprintln!("Could not refresh word pack {}: {}", subscription.name, err);
+        }
+    }
+}
+
+/// Without the `word-packs` feature nothing is ever downloaded
+#[cfg(not(feature = "word-packs"))]
+fn refresh_if_due(_subscription: &PackSubscription, _path: &Path) {}
+
+/// Load the words of all the subscribed packs from the cache. When `refresh` is set the packs that
+/// are due are downloaded first, if that fails the cached version is used. Problems are reported
+/// on stderr, so this should be called before ncurses is started.
+pub fn load_packs(
+    subscriptions: &[PackSubscription],
+    refresh: bool,
+    language: &Language,
+) -> PackWords {
+    let mut pack_words = PackWords::default();
+
+    for subscription in subscriptions {
+        let path = match cache_path(subscription) {
+            Ok(path) => path,
+            Err(err) => {
+                eprintln!("Skipping word pack: {}", err);
+                continue;
+            }
+        };
+
+        if refresh {
+            refresh_if_due(subscription, &path);
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            // Packs that were never downloaded are skipped silently
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => {
+                eprintln!("Could not read word pack {}: {}", subscription.name, err);
+                continue;
+            }
+        };
+
+        // The words have to live as long as the bundled words, they are written like the bundled
+        // words are
+        let content = language.join_digraphs(&word::compose(&content));
+        let content: &'static str = Box::leak(content.into_boxed_str());
+        let words = content
+            .lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty());
+        match subscription.kind {
+            PackKind::Words => pack_words.words.extend(words),
+            PackKind::Denylist => pack_words.denied.extend(words),
+        }
+    }
+
+    pack_words
+}
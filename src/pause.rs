@@ -0,0 +1,123 @@
+//! The menu that pauses a game, so a game isn't lost to a stray escape
+
+use crate::keymap::{ENTER, ESCAPE};
+use crate::navigation::Navigator;
+use crate::quick_settings::{self, QuickSettings};
+use crate::strings::strings;
+
+/// The width of the popup, including the border
+const POPUP_WIDTH: i32 = 40;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Choice {
+    Resume,
+    /// Start over with new words
+    Restart,
+    /// Open the quick settings
+    Settings,
+    Quit,
+}
+
+/// Show the pause menu on top of the game until the player picks what to do. Restart is only
+/// offered when the game can be started over, quitting a game in progress has to be confirmed.
+/// With `quitting` the menu starts with that question, for Ctrl-C. `background` draws the game
+/// behind the popup.
+pub fn pause_menu(
+    restart: bool,
+    in_progress: bool,
+    quitting: bool,
+    mut background: impl FnMut(),
+) -> Choice {
+    let strings = strings();
+    let choices = [
+        (Choice::Resume, strings.resume),
+        (Choice::Restart, strings.restart),
+        (Choice::Settings, strings.settings),
+        (Choice::Quit, strings.quit),
+    ]
+    .into_iter()
+    .filter(|(choice, _)| restart || *choice != Choice::Restart)
+    .collect::<Vec<_>>();
+
+    if quitting && !in_progress {
+        return Choice::Quit;
+    }
+    let mut selected = match quitting {
+        true => choices.len() - 1,
+        false => 0,
+    };
+    let mut navigator = Navigator::default();
+    let mut confirming = quitting;
+    loop {
+        background();
+
+        let mut max_x = 0;
+        let mut max_y = 0;
+        ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+        let win_height = choices.len() as i32 + 6;
+        let win_x = (max_x - POPUP_WIDTH) / 2;
+        let win_y = (max_y - win_height) / 2;
+
+        // Clear the space of the popup and draw the border
+        for y in 0..win_height {
+            let border = if y == 0 || y == win_height - 1 {
+                format!("+{}+", "-".repeat(POPUP_WIDTH as usize - 2))
+            } else {
+                format!("|{}|", " ".repeat(POPUP_WIDTH as usize - 2))
+            };
+            ncurses::mvaddstr(win_y + y, win_x, &border);
+        }
+        ncurses::mvaddstr(win_y + 1, win_x + 2, strings.paused);
+        for (index, (_, name)) in choices.iter().enumerate() {
+            let marker = if index == selected { "> " } else { "  " };
+            ncurses::mvaddstr(
+                win_y + 3 + index as i32,
+                win_x + 2,
+                &format!("{}{}", marker, name),
+            );
+        }
+        if confirming {
+            ncurses::mvaddstr(win_y + win_height - 2, win_x + 2, strings.confirm_quit);
+        }
+        ncurses::refresh();
+
+        let input = ncurses::getch();
+        if input == ncurses::ERR {
+            continue;
+        }
+        if confirming {
+            confirming = false;
+            // The Dutch question is answered with j
+            if ['y' as i32, 'Y' as i32, 'j' as i32, 'J' as i32].contains(&input) {
+                return Choice::Quit;
+            }
+        } else if input == ESCAPE {
+            return Choice::Resume;
+        } else if let Some(movement) = navigator.read(input) {
+            selected = movement.apply(selected, choices.len() - 1, choices.len());
+        } else if input == ' ' as i32 || ENTER.contains(&input) {
+            match choices[selected].0 {
+                Choice::Quit if in_progress => confirming = true,
+                choice => return choice,
+            }
+        }
+    }
+}
+
+/// The pause menu of a mode that can't be started over, the settings are opened from it as in a
+/// game. Returns if the player quits.
+pub fn pause_or_quit(
+    quick: &mut QuickSettings,
+    in_progress: bool,
+    quitting: bool,
+    mut background: impl FnMut(),
+) -> bool {
+    match pause_menu(false, in_progress, quitting, &mut background) {
+        Choice::Quit => true,
+        Choice::Settings => {
+            quick_settings::quick_settings(quick, |_| background());
+            false
+        }
+        Choice::Resume | Choice::Restart => false,
+    }
+}
@@ -0,0 +1,107 @@
+//! The phrases of phrase mode, two short words with a space between them like "ik ga". The space
+//! is a fixed tile on the board, the rows have a tile for each letter and one for the space.
+//! The phrases take the place of the word list: they are the answers and the guesses.
+
+use crate::dictionary::Dictionary;
+use crate::language::Language;
+use crate::word::{self, SEPARATOR};
+use crate::MAX_TILES;
+use std::fs;
+use std::path::Path;
+
+/// The phrase of a line written like the words of the word lists, `None` when it isn't two words
+/// that fit on a row
+fn parse(line: &str, language: &Language) -> Option<String> {
+    let phrase = language.join_digraphs(&word::compose(&line.trim().to_lowercase()));
+    let parts = phrase.split(SEPARATOR).collect::<Vec<_>>();
+    let fits = parts.len() == 2
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && word::has_only_letters(part))
+        && word::length(&phrase) <= MAX_TILES;
+    fits.then_some(phrase)
+}
+
+/// Read the phrases from a file with a phrase on each line, empty lines are skipped
+pub fn load(path: &Path, language: &Language) -> Result<Vec<&'static str>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let mut phrases = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse(line, language) {
+            Some(phrase) => phrases.push(&*Box::leak(phrase.into_boxed_str())),
+            None => {
+                return Err(format!(
+                    "Line {} isn't two words that fit on {} tiles with the space: {}",
+                    number + 1,
+                    MAX_TILES,
+                    line
+                ))
+            }
+        }
+    }
+    phrases.sort_unstable();
+    phrases.dedup();
+    match phrases.is_empty() {
+        true => Err("There are no phrases in the file".to_string()),
+        false => Ok(phrases),
+    }
+}
+
+/// The phrases that can be guessed in a game on the phrase, those of the same length with the space
+/// on the same tile. The rows of the game only fit those.
+pub fn guesses_for(phrases: &[&'static str], phrase: &str) -> Dictionary {
+    let length = word::length(phrase);
+    let separator = word::separator(phrase);
+    phrases
+        .iter()
+        .filter(|guess| word::length(guess) == length && word::separator(guess) == separator)
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::GuessEditor;
+    use crate::language;
+
+    #[test]
+    fn phrases_are_two_words_that_fit_on_a_row() {
+        assert_eq!(
+            parse(" Ik Ga ", &language::DUTCH),
+            Some("ik ga".to_string())
+        );
+        assert_eq!(parse("a ben", &language::DUTCH), Some("a ben".to_string()));
+        assert_eq!(parse("kaart", &language::DUTCH), None);
+        assert_eq!(parse("ik  ga", &language::DUTCH), None);
+        assert_eq!(parse("op de", &language::DUTCH), Some("op de".to_string()));
+        assert_eq!(
+            parse("het huis", &language::DUTCH),
+            Some("het huis".to_string())
+        );
+        assert_eq!(parse("lange zinnen", &language::DUTCH), None);
+
+        // The space is skipped while the phrase is typed
+        let mut editor = GuessEditor::new(5, word::separator("ik ga"));
+        for letter in "ikga".chars() {
+            editor.type_letter(letter);
+            if editor.text() == "ik" {
+                assert_eq!(editor.cursor(), Some(3));
+            }
+        }
+        assert!(editor.is_full());
+        assert_eq!(editor.text(), "ik ga");
+    }
+    #[test]
+    fn only_phrases_of_the_same_shape_are_guessed() {
+        let phrases = ["de kat", "ik ga", "op de", "wat nu", "zo is"];
+        let guesses = guesses_for(&phrases, "ik ga");
+        assert_eq!(guesses.words(5), ["ik ga", "op de", "zo is"]);
+        assert!(!guesses.contains("de kat"));
+        assert!(guesses_for(&phrases, "wat nu").contains("wat nu"));
+        assert!(!guesses_for(&phrases, "wat nu").contains("de kat"));
+    }
+}
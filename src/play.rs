@@ -0,0 +1,919 @@
+//! Playing a game on the terminal. The rules are kept by `engine::Game`, this is the loop that
+//! reads the keys, shows the boards and tells the opponent, the recording and the announcements
+//! what happened.
+
+use crate::announce::{self, Announcement, Announcer};
+use crate::definitions;
+use crate::dictionary::Dictionary;
+use crate::editor::GuessEditor;
+use crate::engine::{self, Game, GameState, Transition};
+use crate::events::{Event, EventSink};
+use crate::input::{self, InputEvent, Poller};
+use crate::integrity::{Assistance, Mode};
+use crate::keyboard::{self, KeyAction};
+use crate::keymap::Action;
+use crate::messages::Notice;
+use crate::opponent::{Opponent, PartnerMove};
+use crate::pause::{self, Choice};
+use crate::protocol::Message;
+use crate::quick_settings::{self, QuickSettings};
+use crate::render::{Renderer, Terminal};
+use crate::replay::Recorder;
+use crate::review;
+use crate::rules::{self, Feedback, Pattern};
+use crate::score::Score;
+use crate::settings::Settings;
+use crate::sound::{self, Sound};
+use crate::strings::{fill, strings};
+use crate::word;
+use crate::{
+    candidates, init_ncurses, key_feedback, max_guesses, possible_words, render_game, word_panel,
+    Board, BoardState, GameInfo, GameOptions, GameResult, GuessedLetter, GuessedWord, Stopped,
+    GUESSES, UNLIMITED, WORD_LENGTH,
+};
+use itertools::Itertools;
+use lingo_core::knowledge::Knowledge;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The renders a disallowed guess is explained for
+const REJECTION_RENDERS: u32 = 4;
+/// The renders a hint is shown for
+const HINT_RENDERS: u32 = 6;
+
+/// Play a single game, returns the result if the game was played until the end. Every board has
+/// its own hidden word in `targets`. Casual and practice games can be restarted from the pause
+/// menu, the caller picks the new words.
+pub fn play_game(
+    dictionary: &Dictionary,
+    alphabet: Vec<char>,
+    targets: Vec<&'static str>,
+    announcer: &mut Announcer,
+    events: &EventSink,
+    mut opponent: Option<Box<dyn Opponent>>,
+    options: &GameOptions,
+) -> Result<GameResult, Stopped> {
+    // The rows are as long as the words, or as the phrase with its space in phrase mode
+    let tiles = word::length(targets[0]);
+    let words = dictionary.words(tiles);
+    let _window_ender = init_ncurses(options.theme);
+    let _paste_ender = input::enable_bracketed_paste();
+
+    // The opponent plays and the row timer runs while the player is typing, so don't wait for keys
+    // forever
+    let input_timeout = match opponent.is_some() || options.row_timer.is_some() {
+        true => 100,
+        false => -1,
+    };
+    let poller = Poller::new(options.keys, input_timeout);
+
+    // The keys of the keyboard below the board can be clicked
+    keyboard::enable_mouse();
+
+    // A word that is passed on in a team game gets one guess after those of the other team
+    let max_guesses = match &options.turn {
+        Some(turn) if !turn.earlier.is_empty() => turn.earlier.len() as u32 + 1,
+        _ if options.mode == Mode::Kids => UNLIMITED,
+        _ => max_guesses(options.boards),
+    };
+    // A board without a limit on the guesses shows the last rows, the points are given as if the
+    // word was solved in the last of them
+    let board_rows = match max_guesses {
+        UNLIMITED => GUESSES,
+        _ => max_guesses,
+    };
+    let solved_score =
+        |guesses: u32, elapsed| Score::solved(guesses.min(board_rows), board_rows, elapsed);
+    // The space between the words of a phrase is on the same tile in every row
+    let separator = word::separator(targets[0]);
+    let mut board_state = BoardState {
+        boards: vec![Board::with_tiles(board_rows, tiles); options.boards],
+        keyboard: alphabet.clone(),
+        compose_hints: options.language.compose_hints(&alphabet),
+        // A partner in a co-op game plays on the board of the player
+        opponent_board: opponent
+            .as_ref()
+            .filter(|opponent| !opponent.shares_board())
+            .map(|opponent| *opponent.board()),
+        opponent_name: opponent
+            .as_ref()
+            .map(|opponent| opponent.name().to_string())
+            .unwrap_or_default(),
+        opponent_masked: true,
+        bingo: options.bingo,
+        marked: options.a11y,
+        info: Some(GameInfo {
+            language: options.language.code,
+            letters: tiles - separator.is_some() as usize,
+            mode: options.mode,
+            hard: options.hard,
+            evil: options.evil.is_some(),
+            timed: options.time_bonus,
+            guess: 1,
+            max_guesses,
+            streak: options.streak,
+            lives: options.lives,
+        }),
+        ..Default::default()
+    };
+    let start = Instant::now();
+    let elapsed = || options.time_bonus.then(|| start.elapsed());
+    let mut game = Game::new(
+        dictionary,
+        options.scripts,
+        &alphabet,
+        targets,
+        max_guesses,
+        options.evil,
+        options.hard,
+    );
+    // The guesses of the other team are on the board already
+    for guess in options.turn.iter().flat_map(|turn| &turn.earlier) {
+        game.handle_event(engine::Event::Submit(guess.clone()))
+            .map_err(Stopped::Failed)?;
+        let scored = game
+            .handle_event(engine::Event::Score)
+            .map_err(Stopped::Failed)?;
+        if let Transition::Scored(scored) = scored {
+            let row = game.guesses() as usize - 1;
+            for (index, pattern) in scored.patterns {
+                board_state.boards[index].rows[row] = rules::guessed_word(&scored.guess, &pattern);
+            }
+        }
+    }
+    // In a team game the first letters of the word are typed in every row
+    let revealed = options.turn.as_ref().map_or(String::new(), |turn| {
+        game.targets()[0].chars().take(turn.revealed).collect()
+    });
+    let new_row = || {
+        let mut editor = GuessEditor::new(tiles, separator);
+        editor.set_text(&revealed);
+        editor
+    };
+    events.emit(&Event::RoundStarted {
+        language: options.language.code,
+        boards: options.boards,
+        max_guesses,
+    });
+    log::info!(
+        "Started a {:?} game with {} boards and {} guesses",
+        options.mode,
+        options.boards,
+        max_guesses
+    );
+
+    let assistance = Assistance::for_mode(options.mode);
+    // Zen mode hides the assistance that can be turned off, tab turns it on and off
+    let mut zen = options.zen;
+    let shown = |zen: bool| match zen {
+        true => assistance.zen(),
+        false => assistance,
+    };
+    board_state.hide_possible_words = !shown(zen).suggestions;
+    // The suggestion stays while the first guess is typed
+    if let Some(opener) = options.opener.filter(|_| shown(zen).suggestions) {
+        board_state.messages.push(
+            Notice::info(fill(
+                strings().opener,
+                &[&opener.chars().map(word::display).collect::<String>()],
+            ))
+            .expire_after(HINT_RENDERS),
+        );
+    }
+    let mut quick = QuickSettings::new(options.theme, shown(zen));
+
+    // Replays only have room for a single board of words
+    let mut recorder = (options.boards == 1 && tiles == WORD_LENGTH).then(Recorder::new);
+    let mut record = |message: Message| {
+        if let Some(recorder) = &mut recorder {
+            recorder.record(message);
+        }
+    };
+
+    // The guess that is being typed, it is kept when the guess is disallowed
+    let mut editor = new_row();
+    // The row as it was last recorded, it is recorded again after a guess
+    let mut recorded = None;
+    // The feedback on the last guess that is written out with --a11y
+    let mut written_feedback: Option<String> = None;
+    // The pause menu opened with Ctrl-C asks to quit right away
+    let mut quitting = false;
+    // If the player won, the score and the last message, once the game has ended
+    let mut ending = None;
+    // When the row that is being typed was started, for the row timer
+    let mut row_started = Instant::now();
+    // The row the frequencies of the letters were counted for in learning mode
+    let mut learned = None;
+
+    loop {
+        // The row that is being typed, and where it is on the board
+        let guess_num = game.guesses() as usize;
+        let row = guess_num.min(board_rows as usize - 1);
+        match game.state() {
+            GameState::Typing => {
+                let guess = editor.text();
+                // In a co-op game the players take turns on the rows
+                let partner_turn = opponent
+                    .as_ref()
+                    .is_some_and(|opponent| opponent.plays_row(guess_num));
+
+                // Record the row every time the letters or the cursor change, the partner of a
+                // co-op game sees the row of the player being typed
+                let typed = (guess.clone(), editor.cursor().unwrap_or(tiles));
+                if recorded.as_ref() != Some(&typed) {
+                    if let Some(opponent) = opponent.as_mut().filter(|_| !partner_turn) {
+                        opponent.player_typed(&typed.0, typed.1);
+                    }
+                    record(Message::Typed {
+                        text: typed.0.clone(),
+                        cursor: typed.1,
+                    });
+                    recorded = Some(typed);
+                }
+
+                // Copy the guess string into the boards that are still being played
+                for board in board_state.boards.iter_mut().filter(|board| !board.solved) {
+                    for i in 0..tiles {
+                        // The space of a phrase is on its tile before the letters after it are typed
+                        board.rows[row][i] = match separator == Some(i) {
+                            true => GuessedLetter::Separator,
+                            false => GuessedLetter::typed(word::letter(&guess, i)),
+                        };
+                    }
+                }
+                board_state.cursor = editor.cursor().map(|tile| (row, tile));
+                if let Some(info) = &mut board_state.info {
+                    info.guess = guess_num + 1;
+                }
+
+                // The keyboard and the possible words are for the first board that isn't solved
+                let focus = board_state
+                    .boards
+                    .iter()
+                    .position(|board| !board.solved)
+                    .unwrap_or(0);
+                board_state.key_feedback = match shown(zen).keyboard_hints {
+                    true => key_feedback(game.knowledge(focus)),
+                    false => HashMap::new(),
+                };
+
+                // Create the list of still possible words, games without assistance never have them
+                if assistance.suggestions || assistance.adjustable {
+                    let possible = possible_words(dictionary, tiles, game.knowledge(focus), &guess)
+                        // Only suggest guesses that are legal in hard mode
+                        .filter(|word| game.allows(word))
+                        .collect::<Vec<_>>();
+                    // A new list starts at the top again
+                    if possible != board_state.possible_words {
+                        board_state.possible_words = possible;
+                        board_state.possible_scroll = 0;
+                    }
+                    // In learning mode the letters of all the possible words are counted, they
+                    // only change with a guess
+                    if options.learn && learned != Some(guess_num) {
+                        let words = possible_words(dictionary, tiles, game.knowledge(focus), "")
+                            .filter(|word| game.allows(word))
+                            .collect_vec();
+                        board_state.frequencies = candidates::position_frequencies(&words);
+                        learned = Some(guess_num);
+                    }
+                }
+
+                // Show the points the word is worth when it is solved with this guess
+                board_state.score = Some(match partner_turn {
+                    true => strings().partner_turn.to_string(),
+                    false => fill(
+                        strings().worth,
+                        &[&solved_score(guess_num as u32 + 1, elapsed()).total()],
+                    ),
+                });
+
+                // The time that is left for the row, the row of a co-op partner isn't timed
+                let row_timer = options.row_timer.filter(|_| !partner_turn);
+                let time_left = row_timer.map(|limit| limit.saturating_sub(row_started.elapsed()));
+                board_state.row_timer = row_timer
+                    .zip(time_left)
+                    .map(|(limit, left)| left.as_secs_f64() / limit.as_secs_f64());
+
+                // Render the current guess on the screen
+                render_game(&board_state);
+
+                // When the time is up a full row is guessed, a row that isn't full or that isn't
+                // accepted is lost
+                if time_left == Some(Duration::ZERO) {
+                    let submission = match editor.is_full() {
+                        true => Some(
+                            game.handle_event(engine::Event::Submit(editor.text()))
+                                .map_err(Stopped::Failed)?,
+                        ),
+                        false => None,
+                    };
+                    if let Some(Transition::Accepted) = submission {
+                        if let Some(opponent) = &mut opponent {
+                            opponent.player_submitted(&editor.text());
+                        }
+                        continue;
+                    }
+                    log::info!("The time for guess {} ran out", guess_num + 1);
+                    sound::play(Sound::Invalid);
+                    let transition = game
+                        .handle_event(engine::Event::TimeUp)
+                        .map_err(Stopped::Failed)?;
+                    record(Message::Rejected {
+                        message: strings().time_up.to_string(),
+                    });
+                    for board in board_state.boards.iter_mut().filter(|board| !board.solved) {
+                        board.rows[row] = GuessedWord::empty(tiles);
+                    }
+                    editor = new_row();
+                    recorded = None;
+                    row_started = Instant::now();
+                    let message = match transition {
+                        Transition::Ended => {
+                            sound::play(Sound::Lost);
+                            let message = lost_message(options, game.targets());
+                            ending = Some((false, Score::lost(), Some(message.clone())));
+                            board_state.messages.clear();
+                            board_state.messages.push(Notice::info(message.clone()));
+                            message
+                        }
+                        _ => {
+                            let message = strings().time_up.to_string();
+                            board_state
+                                .messages
+                                .push(Notice::warning(message.clone()).expire_after(HINT_RENDERS));
+                            message
+                        }
+                    };
+                    announcer.announce(&Announcement {
+                        message: Some(message),
+                        ..Default::default()
+                    });
+                    continue;
+                }
+
+                // Wait for what the player does
+                let event = poller.poll();
+                if event != InputEvent::Tick {
+                    log::debug!("Read {:?}", event);
+                }
+                // The row of the partner is typed on the other side
+                if partner_turn && event.edits_row() {
+                    continue;
+                }
+
+                // Submitting the row goes through the game after the event
+                let mut submitted = false;
+                match event {
+                    // Without input only the opponent can have made progress
+                    InputEvent::Tick => {
+                        let Some(opponent) = &mut opponent else {
+                            continue;
+                        };
+                        let changed = opponent.poll();
+                        // The row of a co-op partner is typed and submitted as they do it
+                        if opponent.shares_board() {
+                            while let Some(partner_move) = opponent.take_move() {
+                                match partner_move {
+                                    PartnerMove::Typed { text, cursor } => {
+                                        editor.set_text(&text);
+                                        editor.move_to(cursor);
+                                    }
+                                    PartnerMove::Submitted(guess) => {
+                                        editor.set_text(&guess);
+                                        submitted = true;
+                                        break;
+                                    }
+                                }
+                            }
+                        } else if changed {
+                            board_state.opponent_board = Some(*opponent.board());
+                            board_state.opponent_name = opponent.name().to_string();
+                            if opponent.solved() {
+                                log::info!("The opponent solved the word");
+                                game.handle_event(engine::Event::OpponentSolved)
+                                    .map_err(Stopped::Failed)?;
+                                let message = fill(strings().opponent_won, &[&game.targets()[0]]);
+                                board_state.messages.clear();
+                                board_state.messages.push(Notice::info(message.clone()));
+                                announcer.announce(&Announcement {
+                                    message: Some(message.clone()),
+                                    ..Default::default()
+                                });
+                                ending = Some((false, Score::lost(), Some(message)));
+                            }
+                        }
+                        if !submitted {
+                            continue;
+                        }
+                    }
+                    // The screen is drawn again for the new size
+                    InputEvent::Resize => continue,
+                    InputEvent::Paste(pasted) => {
+                        // A pasted word is taken as a whole guess, only keep the letters, a copied
+                        // word often has spaces or a newline around it
+                        let pasted = pasted
+                            .chars()
+                            .filter(|chr| !chr.is_whitespace())
+                            .collect::<String>();
+                        let letters = pasted
+                            .chars()
+                            .map(|chr| input::normalize(chr, &alphabet))
+                            .collect::<Option<String>>()
+                            .map(|letters| options.language.join_digraphs(&letters));
+                        let capacity = editor.capacity();
+                        match letters.filter(|letters| letters.chars().count() == capacity) {
+                            Some(letters) => {
+                                editor.set_text(&letters);
+                                submitted = true;
+                            }
+                            None => {
+                                if !pasted.is_empty() {
+                                    board_state.messages.push(
+                                        Notice::warning(fill(
+                                            strings().pasted,
+                                            &[&pasted, &capacity],
+                                        ))
+                                        .expire_after(REJECTION_RENDERS),
+                                    );
+                                }
+                                continue;
+                            }
+                        }
+                    }
+                    InputEvent::Quit | InputEvent::Interrupt => {
+                        // Pause the game, giving up a game after a guess has to be confirmed.
+                        // Ctrl-C asks for that right away.
+                        quitting = event == InputEvent::Interrupt;
+                        game.handle_event(engine::Event::Pause)
+                            .map_err(Stopped::Failed)?;
+                        continue;
+                    }
+                    // If the current guess is the correct amount of characters process the guess
+                    InputEvent::Submit => submitted = editor.is_full(),
+                    // Remove the letter before the cursor, if there is one
+                    InputEvent::Backspace => editor.backspace(),
+                    InputEvent::Hint => {
+                        // A hint is one of the words that are still possible, only in games with
+                        // assistance
+                        let hint = board_state
+                            .possible_words
+                            .first()
+                            .filter(|_| assistance.adjustable);
+                        let notice = match hint {
+                            Some(hint) => Notice::info(fill(
+                                strings().hint,
+                                &[&hint.chars().map(word::display).collect::<String>()],
+                            )),
+                            None => Notice::warning(strings().no_hint),
+                        };
+                        board_state.messages.push(notice.expire_after(HINT_RENDERS));
+                    }
+                    InputEvent::Help => {
+                        let keys = options.keys;
+                        let help = fill(
+                            strings().help,
+                            &[
+                                &keys.name(Action::Quit),
+                                &keys.name(Action::Submit),
+                                &keys.name(Action::Backspace),
+                                &keys.name(Action::Hint),
+                                &keys.name(Action::TogglePanel),
+                                &keys.name(Action::Help),
+                            ],
+                        );
+                        board_state
+                            .messages
+                            .push(Notice::info(help).expire_after(HINT_RENDERS));
+                    }
+                    // On a delete remove the letter under the cursor
+                    InputEvent::Delete => editor.delete(),
+                    InputEvent::Settings => {
+                        // The row timer stops while the settings are open
+                        let opened = Instant::now();
+                        quick_settings::quick_settings(&mut quick, |settings| {
+                            board_state.hide_possible_words = settings.suggestions != Some(true);
+                            render_game(&board_state);
+                        });
+                        row_started += opened.elapsed();
+                    }
+                    // Kid mode always shows the possible words
+                    InputEvent::TogglePanel
+                        if assistance.adjustable && options.mode != Mode::Kids =>
+                    {
+                        // Turn zen mode on and off, the choice is kept for the next games
+                        zen = !zen;
+                        board_state.hide_possible_words = !shown(zen).suggestions;
+                        quick.suggestions = Some(shown(zen).suggestions);
+                        let saved = Settings::load().and_then(|mut settings| {
+                            settings.zen = zen;
+                            settings.save()
+                        });
+                        let notice = match saved {
+                            Err(err) => Notice::warning(fill(strings().zen_not_saved, &[&err])),
+                            Ok(()) if zen => Notice::info(strings().zen_on),
+                            Ok(()) => Notice::info(strings().zen_off),
+                        };
+                        board_state.messages.push(notice.expire_after(HINT_RENDERS));
+                    }
+                    InputEvent::PageUp | InputEvent::PageDown => {
+                        // Scroll the possible words a panel at a time
+                        if let Some(panel) = word_panel(&board_state, Terminal.size()) {
+                            let page = panel.capacity();
+                            let scroll = board_state.possible_scroll;
+                            board_state.possible_scroll = match event {
+                                InputEvent::PageDown
+                                    if scroll + page < board_state.possible_words.len() =>
+                                {
+                                    scroll + page
+                                }
+                                InputEvent::PageDown => scroll,
+                                _ => scroll.saturating_sub(page),
+                            };
+                        }
+                    }
+                    InputEvent::ClearRow => editor.clear(),
+                    InputEvent::DeleteWord => editor.delete_word(),
+                    InputEvent::Left => editor.move_left(),
+                    InputEvent::Right => editor.move_right(),
+                    InputEvent::Home => editor.move_home(),
+                    InputEvent::End => editor.move_end(),
+                    InputEvent::Insert => editor.toggle_overwrite(),
+                    InputEvent::Mouse { x, y } => {
+                        // A click on the keyboard works like pressing the key
+                        match keyboard::key_at(&board_state.keyboard, x, y) {
+                            Some(KeyAction::Letter(letter)) => {
+                                let joined = editor.join_digraph(options.language, letter);
+                                if !joined {
+                                    editor.type_letter(letter);
+                                }
+                            }
+                            Some(KeyAction::Enter) if editor.is_full() => submitted = true,
+                            Some(KeyAction::Backspace) => editor.backspace(),
+                            _ => (),
+                        }
+                    }
+                    InputEvent::Char(chr) => {
+                        if let Some(letter) = input::normalize(chr, &alphabet) {
+                            // If the input is a letter add it to the guess at the cursor, if more
+                            // letters are allowed in the guess. A letter that forms a pair with
+                            // the letter before it that is written on a single tile joins that
+                            // letter instead.
+                            if !editor.join_digraph(options.language, letter) {
+                                editor.type_letter(letter);
+                            }
+                            sound::play(Sound::Key);
+                        } else if chr.is_ascii_punctuation() {
+                            // Accents after a letter can compose letters that aren't on the
+                            // keyboard
+                            editor.compose(options.language, chr, &alphabet);
+                        }
+                    }
+                    InputEvent::TogglePanel | InputEvent::Other => (),
+                }
+                if submitted {
+                    let submission = game
+                        .handle_event(engine::Event::Submit(editor.text()))
+                        .map_err(Stopped::Failed)?;
+                    // The other side only hears of guesses that were accepted, a co-op partner
+                    // with other lists would play a row the player couldn't
+                    if let Transition::Accepted = submission {
+                        if let Some(opponent) = opponent.as_mut().filter(|_| !partner_turn) {
+                            opponent.player_submitted(&editor.text());
+                        }
+                    }
+                    if let Transition::Rejected(notice) = submission {
+                        log::info!("Rejected guess {}: {}", guess_num + 1, notice.text);
+                        let notice = match options.mode {
+                            Mode::Kids => Notice::info(fill(
+                                strings().kids_unknown_word,
+                                &[&editor.text().chars().map(word::display).collect::<String>()],
+                            )),
+                            _ => notice,
+                        };
+                        sound::play(Sound::Invalid);
+                        announcer.announce(&Announcement {
+                            message: Some(notice.text.clone()),
+                            ..Default::default()
+                        });
+                        record(Message::Rejected {
+                            message: notice.text.clone(),
+                        });
+                        recorded = None;
+                        board_state
+                            .messages
+                            .push(notice.expire_after(REJECTION_RENDERS));
+                        // The letters stay in the row so the guess can be corrected
+                        shake_row(&mut board_state, row);
+                    }
+                    continue;
+                }
+
+                // The messages that were shown long enough go away
+                board_state.messages.tick();
+            }
+            GameState::Menu => {
+                let restart = matches!(options.mode, Mode::Casual | Mode::Practice | Mode::Kids);
+                // The row timer stops while the game is paused
+                let paused = Instant::now();
+                let choice = pause::pause_menu(restart, guess_num > 0, quitting, || {
+                    render_game(&board_state)
+                });
+                log::info!("Picked {:?} in the pause menu", choice);
+                match choice {
+                    Choice::Resume => (),
+                    Choice::Restart => return Err(Stopped::Restart),
+                    Choice::Quit => return Err(Stopped::Quit),
+                    Choice::Settings => {
+                        quick_settings::quick_settings(&mut quick, |settings| {
+                            board_state.hide_possible_words = settings.suggestions != Some(true);
+                            render_game(&board_state);
+                        });
+                    }
+                }
+                row_started += paused.elapsed();
+                game.handle_event(engine::Event::Resume)
+                    .map_err(Stopped::Failed)?;
+                board_state.messages.tick();
+            }
+            GameState::Scoring => {
+                let Transition::Scored(scored) = game
+                    .handle_event(engine::Event::Score)
+                    .map_err(Stopped::Failed)?
+                else {
+                    continue;
+                };
+                editor = new_row();
+                recorded = None;
+                row_started = Instant::now();
+                board_state.cursor = None;
+
+                // Show the feedback on every board that is still being played
+                let mut rows = Vec::new();
+                let mut keyboards = Vec::new();
+                for (index, pattern) in scored.patterns.iter() {
+                    events.emit(&Event::GuessVerified {
+                        guess: guess_num as u32 + 1,
+                        board: *index,
+                        pattern,
+                    });
+                    let board = &mut board_state.boards[*index];
+                    board.rows[row] = rules::guessed_word(&scored.guess, pattern);
+                    board.solved = game.is_solved(*index);
+
+                    // The announcements name the board when there is more than one
+                    let label = |text: String| match options.boards {
+                        1 => text,
+                        _ => fill(strings().board_label, &[&(index + 1), &text]),
+                    };
+                    rows.push(label(announce::describe_row(&board.rows[row])));
+                    keyboards.push(label(describe_keyboard(&alphabet, game.knowledge(*index))));
+
+                    // The replays and the opponents follow a single board of words
+                    if let (1, Ok(pattern)) = (options.boards, Pattern::try_from(&pattern[..])) {
+                        record(Message::Result {
+                            guess: Some(scored.guess.clone()),
+                            pattern,
+                        });
+                        if let Some(opponent) = &mut opponent {
+                            opponent.player_guessed(&pattern);
+                        }
+                    }
+                }
+                let guesses = game.guesses();
+                log::info!(
+                    "Verified guess {}, {} of {} boards solved",
+                    guesses,
+                    board_state
+                        .boards
+                        .iter()
+                        .filter(|board| board.solved)
+                        .count(),
+                    options.boards
+                );
+
+                // The game end conditions
+                let mut message = None;
+                let result = match game.state() {
+                    GameState::Won => {
+                        // If the guess is equal to the selected word on every board the player
+                        // wins and the game ends
+                        let score = solved_score(guesses, elapsed());
+                        message = Some(match options.mode {
+                            Mode::Kids => fill(
+                                strings().kids_win,
+                                &[
+                                    &game.targets()[0]
+                                        .chars()
+                                        .map(word::display)
+                                        .collect::<String>(),
+                                    &guesses,
+                                ],
+                            ),
+                            _ => fill(strings().win, &[&score.total()]),
+                        });
+                        Some((true, score))
+                    }
+                    GameState::Lost => {
+                        // If the maximum amount of guesses has been reached the player loses and
+                        // the game ends
+                        message = Some(lost_message(options, game.targets()));
+                        Some((false, Score::lost()))
+                    }
+                    _ => {
+                        // Tell which word was found when there are more to find
+                        if !scored.found.is_empty() {
+                            let and = format!(" {} ", strings().and);
+                            message = Some(fill(strings().found, &[&scored.found.join(&and)]));
+                        }
+                        None
+                    }
+                };
+                let correct = scored
+                    .patterns
+                    .iter()
+                    .any(|(_, pattern)| pattern.contains(&Feedback::Correct));
+                match result {
+                    Some((true, _)) => sound::play(Sound::Won),
+                    Some((false, _)) => sound::play(Sound::Lost),
+                    None if correct => sound::play(Sound::Correct),
+                    None => (),
+                }
+
+                // For screen readers the feedback is written out below the board until the next
+                // guess
+                if options.a11y {
+                    let feedback = format!("{}\n{}", rows.join("\n"), keyboards.join("\n"));
+                    if let Some(previous) = written_feedback.replace(feedback.clone()) {
+                        board_state.messages.remove(&previous);
+                    }
+                    board_state.messages.push(Notice::info(feedback));
+                }
+                if let Some(message) = &message {
+                    match result {
+                        // Only the end of the game is shown when it ends
+                        Some(_) => {
+                            board_state.messages.clear();
+                            board_state.messages.push(Notice::info(message.clone()));
+                        }
+                        None => board_state
+                            .messages
+                            .push(Notice::info(message.clone()).expire_after(HINT_RENDERS)),
+                    }
+                }
+
+                // Announce the verified guess only after the end conditions have been checked, so
+                // the message, row and keyboard always go out together and in the same order
+                announcer.announce(&Announcement {
+                    message: message.clone(),
+                    row: Some(rows.join("\n")),
+                    keyboard: Some(keyboards.join("\n")),
+                });
+
+                if let Some((won, score)) = result {
+                    ending = Some((won, score, message));
+                }
+
+                // Without a limit on the guesses the rows move up when the last row is used
+                if game.state() == GameState::Typing && row + 1 == board_rows as usize {
+                    for board in &mut board_state.boards {
+                        board.rows.rotate_left(1);
+                        board.rows[row] = GuessedWord::empty(tiles);
+                    }
+                }
+            }
+            GameState::Won | GameState::Lost => {
+                let (won, score, message) = ending.take().unwrap_or((false, Score::lost(), None));
+                log::info!("The game ended, won {} with {} points", won, score.total());
+                board_state.cursor = None;
+                record(Message::Finished {
+                    won,
+                    word: None,
+                    message,
+                });
+                board_state.score = Some(score.describe());
+                board_state.row_timer = None;
+                if let Some(opponent) = &mut opponent {
+                    opponent.player_finished(won);
+                    board_state.opponent_masked = !opponent.reveals_letters();
+                }
+
+                // Render the last message and quit
+                ncurses::timeout(-1);
+                render_game(&board_state);
+                ncurses::getch();
+                // A word that goes on to the other team isn't given away yet
+                if won || !options.turn.as_ref().is_some_and(|turn| turn.passes_on) {
+                    // Only the guesses on words are reviewed, not those on a phrase
+                    if tiles == WORD_LENGTH {
+                        show_review(options, words, game.guessed(), game.patterns());
+                    }
+                    show_definitions(options, game.targets(), game.guessed());
+                }
+
+                let result = GameResult {
+                    replay: recorder
+                        .map(|recorder| recorder.finish(options.language.code, game.targets()[0])),
+                    words: game.targets().to_vec(),
+                    won,
+                    guesses: game.guesses(),
+                    max_guesses,
+                    guessed: game.guessed().to_vec(),
+                    patterns: game.patterns().to_vec(),
+                    score,
+                    elapsed: start.elapsed(),
+                };
+                script_game_end(options, &result);
+                return Ok(result);
+            }
+        }
+    }
+}
+
+/// The message when the guesses ran out. In a team game the word can go on to the other team, it
+/// stays hidden.
+fn lost_message(options: &GameOptions, targets: &[&'static str]) -> String {
+    let passes_on = options.turn.as_ref().is_some_and(|turn| turn.passes_on);
+    match options.boards {
+        _ if passes_on => strings().passed_on.to_string(),
+        1 => fill(strings().lost_word, &[&targets[0]]),
+        _ => fill(strings().lost_words, &[&targets.join(", ")]),
+    }
+}
+
+/// Hand the finished game to the scripts, a script that fails is shown before the game closes
+fn script_game_end(options: &GameOptions, result: &GameResult) {
+    if let Err(err) = options.scripts.on_game_end(result) {
+        ncurses::clear();
+        ncurses::mvaddstr(0, 2, &fill(strings().script_failed, &[&err]));
+        ncurses::mvaddstr(2, 2, strings().press_to_quit);
+        ncurses::refresh();
+        ncurses::getch();
+    }
+}
+
+/// Shake the letters of a row that was disallowed from side to side
+fn shake_row(board_state: &mut BoardState, row: usize) {
+    for offset in [-1, 1, -1, 1] {
+        board_state.shake = Some((row, offset));
+        render_game(board_state);
+        ncurses::napms(50);
+    }
+    board_state.shake = None;
+}
+
+/// Review the guesses of a game with a single board after the game, when asked for
+fn show_review(
+    options: &GameOptions,
+    words: &[&'static str],
+    guessed: &[String],
+    patterns: &[Pattern],
+) {
+    if !options.review || options.boards != 1 {
+        return;
+    }
+    ncurses::clear();
+    ncurses::mvaddstr(0, 2, strings().reviewing);
+    ncurses::refresh();
+    review::show(&review::review(words, guessed, patterns, options.hard));
+}
+
+/// Show the definitions of the hidden words after the game, and of the guesses when asked for
+fn show_definitions(options: &GameOptions, targets: &[&'static str], guessed: &[String]) {
+    if options.definitions.is_empty() {
+        return;
+    }
+    let mut words = targets.iter().map(|word| word.to_string()).collect_vec();
+    if options.define_guesses {
+        words.extend(guessed.iter().cloned());
+    }
+    let words = words.into_iter().unique().collect_vec();
+    definitions::show(
+        options.definitions,
+        &words.iter().map(String::as_str).collect_vec(),
+    );
+}
+
+/// Describe what is known about the letters of the alphabet in words
+fn describe_keyboard(alphabet: &[char], knowledge: &Knowledge) -> String {
+    let mut correct = Vec::new();
+    let mut in_word = Vec::new();
+    let mut not_in_word = Vec::new();
+    for letter in alphabet.iter().sorted() {
+        match knowledge.feedback(*letter) {
+            None => (),
+            Some(Feedback::Correct) => correct.push(word::display(*letter)),
+            Some(Feedback::WrongPlace) => in_word.push(word::display(*letter)),
+            Some(Feedback::Wrong) => not_in_word.push(word::display(*letter)),
+        }
+    }
+
+    fill(
+        strings().keyboard,
+        &[
+            &correct.iter().join(" "),
+            &in_word.iter().join(" "),
+            &not_in_word.iter().join(" "),
+        ],
+    )
+}
@@ -0,0 +1,80 @@
+//! Profiles for the players that share a machine. Every profile keeps its own statistics, settings,
+//! leaderboard and drills, the config file and the downloaded word packs are shared. Without a
+//! profile the data is kept where it always was.
+
+use crate::keymap::{ENTER, ESCAPE};
+use crate::navigation::Navigator;
+use crate::storage;
+use crate::strings::strings;
+use crate::theme::Theme;
+use std::fs;
+use std::io::{self, ErrorKind};
+
+/// The directory in the data directory with a directory for each profile
+pub const PROFILES_DIR: &str = "profiles";
+
+/// If the name can be used for a profile, it becomes a directory name
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|chr| chr.is_alphanumeric() || chr == '-' || chr == '_')
+}
+
+/// The names of the profiles that have been played with, sorted
+pub fn list() -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(storage::data_path(PROFILES_DIR)?) {
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        entries => entries?,
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            if let Some(name) = entry.file_name().to_str().filter(|name| valid_name(name)) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Let the player pick their profile before the game starts. Returns None for the data without a
+/// profile, and quits the game on escape.
+pub fn pick(profiles: &[String], theme: &Theme) -> Option<String> {
+    let window_ender = crate::init_ncurses(theme);
+    ncurses::timeout(-1);
+
+    // The first line is the data without a profile
+    let mut selected = 0;
+    let mut navigator = Navigator::default();
+    loop {
+        ncurses::clear();
+        ncurses::mvaddstr(0, 2, strings().profile_title);
+        let lines = [strings().without_profile]
+            .into_iter()
+            .chain(profiles.iter().map(String::as_str));
+        for (row, line) in lines.enumerate() {
+            if row == selected {
+                ncurses::attron(ncurses::A_REVERSE());
+            }
+            ncurses::mvaddstr(2 + row as i32, 4, line);
+            ncurses::attroff(ncurses::A_REVERSE());
+        }
+        ncurses::mvaddstr(4 + profiles.len() as i32, 2, strings().profile_help);
+        ncurses::refresh();
+
+        let input = ncurses::getch();
+        if input == ESCAPE {
+            // Restore the terminal before quitting
+            drop(window_ender);
+            std::process::exit(0);
+        } else if let Some(movement) = navigator.read(input) {
+            selected = movement.apply(selected, profiles.len(), profiles.len() + 1);
+        } else if ENTER.contains(&input) {
+            return selected.checked_sub(1).map(|index| profiles[index].clone());
+        }
+    }
+}
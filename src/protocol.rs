@@ -0,0 +1,144 @@
+//! The messages of the game as json lines. Headless mode, network play and replays all speak this
+//! protocol, each with the messages it needs. Every message is a json object on a line of its own,
+//! the kind of message is in `type`:
+//!
+//! ```text
+//! {"type":"hello","seed":1234,"words":5045,"coop":false}
+//! {"type":"start","language":"nl","letters":5,"guesses":5}
+//! {"type":"typed","text":"sto","cursor":3}
+//! {"type":"guess","word":"stoel"}
+//! {"type":"result","pattern":"GYXXG"}
+//! {"type":"rejected","message":"The word stoep is not in the dictionary"}
+//! {"type":"finished","won":false,"word":"kaart"}
+//! {"type":"watched","player":1,"message":{"type":"result","guess":"stoel","pattern":"XXGXG"}}
+//! ```
+//!
+//! A pattern has a letter for each tile: `G` for a letter in the right place, `Y` for a letter
+//! in the wrong place and `X` for a letter that isn't in the word. The optional fields are left
+//! out when they aren't known or may not be told.
+//!
+//! - Headless mode, `lingo --headless`, writes `start`, reads a `guess` on every line and answers
+//!   with `result` or `rejected`. At the end of the game it writes `finished` with the word.
+//! - In network play the host sends `hello`, after that each side sends a `result` without the
+//!   guess for its verified guesses and `finished` when its game ends. In a co-op game the players
+//!   take turns on the rows of one board, the player whose turn it is sends `typed` when the row
+//!   changes and `guess` when it is submitted.
+//! - A replay holds `typed`, `result` with the guess, `rejected` and `finished` with the message
+//!   that was shown, each with the milliseconds since the start of the game in `at`.
+//! - Spectators of a server get `watched` with the `start`, `result` with the guess and `finished`
+//!   without the word of every game, and the player it is about.
+
+use crate::rules::Pattern;
+use crate::WORD_LENGTH;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::io::{self, ErrorKind};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// The host of a network game agrees on the word with the player that joined
+    Hello {
+        /// The seed both sides use to pick the word
+        seed: u64,
+        /// The size of the word list of the host, picking the same word only works when both
+        /// sides have the same word list
+        words: usize,
+        /// If the players play together on one board
+        #[serde(default)]
+        coop: bool,
+    },
+    /// A headless game started
+    Start {
+        language: String,
+        /// The letters of the word
+        letters: usize,
+        /// The guesses there are to find the word
+        guesses: u32,
+    },
+    /// The letters on the current row changed
+    Typed { text: String, cursor: usize },
+    /// A guess of the player
+    Guess { word: String },
+    /// A guess was verified
+    Result {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        guess: Option<String>,
+        #[serde(with = "pattern_code")]
+        pattern: Pattern,
+    },
+    /// A guess wasn't accepted, or the line wasn't understood
+    Rejected { message: String },
+    /// The game ended
+    Finished {
+        won: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        word: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+    /// What happened in the game of a player, for a spectator
+    Watched {
+        /// The number of the player on the server
+        player: usize,
+        message: Box<Message>,
+    },
+}
+
+/// The message as a line of json, without the line ending
+pub fn encode(message: &Message) -> String {
+    serde_json::to_string(message).expect("A message can always be written as json")
+}
+
+/// Read a message from a line of json
+pub fn decode(line: &str) -> io::Result<Message> {
+    serde_json::from_str(line.trim()).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Patterns are written as their code
+mod pattern_code {
+    use super::*;
+    use lingo_core::rules::{pattern_from_code, pattern_to_code};
+
+    pub fn serialize<S: Serializer>(pattern: &Pattern, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&pattern_to_code(pattern))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pattern, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        pattern_from_code(&code).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "{} is not a pattern of {} G, Y or X",
+                code, WORD_LENGTH
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules;
+
+    #[test]
+    fn result_is_written_with_the_pattern_code() {
+        let message = Message::Result {
+            guess: None,
+            pattern: rules::score("salet", "kaart"),
+        };
+        let line = encode(&message);
+        assert_eq!(line, r#"{"type":"result","pattern":"XGXXG"}"#);
+        assert_eq!(decode(&line).unwrap(), message);
+    }
+
+    #[test]
+    fn hello_without_coop_is_a_race() {
+        let hello = decode(r#"{"type":"hello","seed":1,"words":5045}"#).unwrap();
+        assert!(matches!(hello, Message::Hello { coop: false, .. }));
+    }
+
+    #[test]
+    fn invalid_patterns_are_refused() {
+        assert!(decode(r#"{"type":"result","pattern":"GGGG"}"#).is_err());
+        assert!(decode(r#"{"type":"result","pattern":"GGGGB"}"#).is_err());
+    }
+}
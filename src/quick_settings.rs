@@ -0,0 +1,146 @@
+use crate::integrity::Assistance;
+use crate::keymap::{ENTER, ESCAPE};
+use crate::navigation::Navigator;
+use crate::strings::strings;
+use crate::theme::{Theme, THEMES};
+
+/// The width of the popup, including the border
+const POPUP_WIDTH: i32 = 36;
+
+/// The settings that can be changed during a game, they only last for the session
+pub struct QuickSettings {
+    pub theme: &'static Theme,
+    /// If the panel with the words that are still possible is shown, `None` when the game doesn't
+    /// let the player change it
+    pub suggestions: Option<bool>,
+}
+
+impl QuickSettings {
+    pub fn new(theme: &'static Theme, assistance: Assistance) -> QuickSettings {
+        QuickSettings {
+            theme,
+            suggestions: assistance.adjustable.then_some(assistance.suggestions),
+        }
+    }
+
+    /// The lines of the popup, one for each setting
+    fn lines(&self) -> Vec<String> {
+        let strings = strings();
+        // The values line up behind the longest name
+        let width = strings
+            .theme
+            .chars()
+            .count()
+            .max(strings.suggestions.chars().count());
+        let mut lines = vec![format!(
+            "{:<width$}  < {} >",
+            strings.theme, self.theme.name
+        )];
+        if let Some(suggestions) = self.suggestions {
+            lines.push(format!(
+                "{:<width$}  < {} >",
+                strings.suggestions,
+                if suggestions { strings.on } else { strings.off }
+            ));
+        }
+        lines
+    }
+
+    /// Change the setting on the line, `forward` picks the next value instead of the previous
+    fn change(&mut self, line: usize, forward: bool) {
+        match line {
+            0 => {
+                let index = THEMES
+                    .iter()
+                    .position(|theme| theme.name == self.theme.name)
+                    .unwrap_or(0);
+                let index = if forward {
+                    (index + 1) % THEMES.len()
+                } else {
+                    (index + THEMES.len() - 1) % THEMES.len()
+                };
+                self.theme = THEMES[index];
+                // Recolor the board right away
+                self.theme.apply();
+            }
+            _ => {
+                if let Some(suggestions) = &mut self.suggestions {
+                    *suggestions = !*suggestions;
+                }
+            }
+        }
+    }
+}
+
+/// Show the quick settings popup on top of the game until it is closed. Changes are applied right
+/// away, `background` draws the game behind the popup so they can be seen.
+pub fn quick_settings(settings: &mut QuickSettings, mut background: impl FnMut(&QuickSettings)) {
+    let mut selected = 0;
+    let mut navigator = Navigator::default();
+    loop {
+        background(settings);
+
+        let mut max_x = 0;
+        let mut max_y = 0;
+        ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+        let lines = settings.lines();
+        let win_height = lines.len() as i32 + 6;
+        let win_x = (max_x - POPUP_WIDTH) / 2;
+        let win_y = (max_y - win_height) / 2;
+
+        // Clear the space of the popup and draw the border
+        for y in 0..win_height {
+            let border = if y == 0 || y == win_height - 1 {
+                format!("+{}+", "-".repeat(POPUP_WIDTH as usize - 2))
+            } else {
+                format!("|{}|", " ".repeat(POPUP_WIDTH as usize - 2))
+            };
+            ncurses::mvaddstr(win_y + y, win_x, &border);
+        }
+        ncurses::mvaddstr(win_y + 1, win_x + 2, strings().quick_settings);
+        for (index, line) in lines.iter().enumerate() {
+            let marker = if index == selected { "> " } else { "  " };
+            ncurses::mvaddstr(
+                win_y + 3 + index as i32,
+                win_x + 2,
+                &format!("{}{}", marker, line),
+            );
+        }
+        ncurses::mvaddstr(
+            win_y + win_height - 2,
+            win_x + 2,
+            strings().quick_settings_help,
+        );
+        ncurses::refresh();
+
+        let input = ncurses::getch();
+        if let Some(movement) = navigator.read(input) {
+            selected = movement.apply(selected, lines.len() - 1, lines.len());
+        } else if [ncurses::KEY_LEFT, 'h' as i32].contains(&input) {
+            settings.change(selected, false);
+        } else if [ncurses::KEY_RIGHT, 'l' as i32, ' ' as i32].contains(&input)
+            || ENTER.contains(&input)
+        {
+            settings.change(selected, true);
+        } else if [ESCAPE, 0x13].contains(&input) {
+            // Escape or Ctrl-S again closes the popup
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrity::Mode;
+
+    #[test]
+    fn competitive_modes_offer_no_suggestions() {
+        let settings = QuickSettings::new(THEMES[0], Assistance::for_mode(Mode::Network));
+        assert_eq!(settings.suggestions, None);
+        assert!(!settings
+            .lines()
+            .iter()
+            .any(|line| line.starts_with(strings().suggestions)));
+    }
+}
@@ -0,0 +1,259 @@
+//! Where the game screen is drawn. The game draws on a `Renderer`, which is the terminal through
+//! ncurses when playing and a text buffer in the tests, so the layout can be checked without a
+//! terminal.
+
+use crate::graphics::{self, Protocol};
+use crate::rules::Feedback;
+use ncurses::attr_t;
+
+pub trait Renderer {
+    /// The width and height of the screen
+    fn size(&self) -> (i32, i32);
+    fn clear(&mut self);
+    /// Write the text at a row and column with the attributes, like the colors
+    fn print(&mut self, y: i32, x: i32, text: &str, attributes: attr_t);
+    /// Show what was drawn
+    fn refresh(&mut self);
+    /// Draw a verified tile with the text as an image on the three cells from the row and column.
+    /// Returns false when the tile has to be drawn as text.
+    fn tile(&mut self, _y: i32, _x: i32, _feedback: Feedback, _text: &str) -> bool {
+        false
+    }
+}
+
+/// The terminal ncurses draws on
+pub struct Terminal;
+
+impl Renderer for Terminal {
+    fn size(&self) -> (i32, i32) {
+        let mut max_x = 0;
+        let mut max_y = 0;
+        ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+        (max_x, max_y)
+    }
+
+    fn clear(&mut self) {
+        ncurses::clear();
+        graphics::clear();
+    }
+
+    fn print(&mut self, y: i32, x: i32, text: &str, attributes: attr_t) {
+        ncurses::attron(attributes);
+        ncurses::mvaddstr(y, x, text);
+        ncurses::attroff(attributes);
+    }
+
+    fn refresh(&mut self) {
+        ncurses::refresh();
+        graphics::flush();
+    }
+
+    fn tile(&mut self, y: i32, x: i32, feedback: Feedback, text: &str) -> bool {
+        match graphics::queue(y, x, feedback, text) {
+            // The kitty image goes under the letter
+            Some(Protocol::Kitty) => {
+                self.print(y, x + 1, text, ncurses::A_BOLD());
+                true
+            }
+            Some(Protocol::Sixel) => true,
+            None => false,
+        }
+    }
+}
+
+/// Show the lines in the middle of the terminal with an empty line between them, and wait for a key
+pub fn show(lines: &[String], wait: bool) {
+    let mut screen = Terminal;
+    let (max_x, max_y) = screen.size();
+    screen.clear();
+    let top = (max_y - 2 * lines.len() as i32) / 2;
+    for (index, line) in lines.iter().enumerate() {
+        screen.print(
+            top + 2 * index as i32,
+            (max_x - line.chars().count() as i32) / 2,
+            line,
+            0,
+        );
+    }
+    screen.refresh();
+    if wait {
+        ncurses::getch();
+    }
+}
+
+/// A screen of text in memory, the attributes are left out
+#[cfg(test)]
+pub struct TestRenderer {
+    rows: Vec<Vec<char>>,
+}
+
+#[cfg(test)]
+impl TestRenderer {
+    pub fn new(width: usize, height: usize) -> TestRenderer {
+        TestRenderer {
+            rows: vec![vec![' '; width]; height],
+        }
+    }
+
+    /// The screen as lines without the spaces at their end
+    pub fn text(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+impl Renderer for TestRenderer {
+    fn size(&self) -> (i32, i32) {
+        let width = self.rows.first().map_or(0, Vec::len);
+        (width as i32, self.rows.len() as i32)
+    }
+
+    fn clear(&mut self) {
+        for row in self.rows.iter_mut() {
+            row.fill(' ');
+        }
+    }
+
+    /// Text outside the screen is cut off
+    fn print(&mut self, y: i32, x: i32, text: &str, _: attr_t) {
+        let Some(row) = usize::try_from(y).ok().and_then(|y| self.rows.get_mut(y)) else {
+            return;
+        };
+        for (index, chr) in text.chars().enumerate() {
+            if let Some(cell) = usize::try_from(x + index as i32)
+                .ok()
+                .and_then(|x| row.get_mut(x))
+            {
+                *cell = chr;
+            }
+        }
+    }
+
+    fn refresh(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borders::{self, BorderStyle};
+    use crate::integrity::Mode;
+    use crate::messages::Notice;
+    use crate::rules::Feedback;
+    use crate::{draw_game, Board, BoardState, GameInfo, GuessedLetter, GuessedWord, WORD_LENGTH};
+    use std::collections::HashMap;
+
+    /// The screen of a game on a terminal of 120 by 40
+    fn screen(board_state: &BoardState) -> String {
+        borders::use_style(BorderStyle::Unicode);
+        let mut screen = TestRenderer::new(120, 40);
+        draw_game(&mut screen, board_state);
+        screen.text()
+    }
+
+    fn game() -> BoardState {
+        BoardState {
+            boards: vec![Board::new(5)],
+            keyboard: ('a'..='z').collect(),
+            info: Some(GameInfo {
+                language: "en",
+                letters: WORD_LENGTH,
+                mode: Mode::Practice,
+                hard: false,
+                evil: false,
+                timed: false,
+                guess: 1,
+                max_guesses: 5,
+                streak: None,
+                lives: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// The guess of "tabak" for "taart"
+    fn guess() -> GuessedWord {
+        let mut row = GuessedWord::default();
+        row.copy_from_slice(&[
+            GuessedLetter::Correct('t'),
+            GuessedLetter::Correct('a'),
+            GuessedLetter::Wrong('b'),
+            GuessedLetter::WrongPlace('a'),
+            GuessedLetter::Wrong('k'),
+        ]);
+        row
+    }
+
+    fn typed(word: &str) -> GuessedWord {
+        let mut row = GuessedWord::default();
+        for (tile, letter) in row.iter_mut().zip(word.chars()) {
+            *tile = GuessedLetter::Letter(letter);
+        }
+        row
+    }
+
+    #[test]
+    fn start_of_a_game() {
+        let board_state = BoardState {
+            cursor: Some((0, 0)),
+            ..game()
+        };
+        insta::assert_snapshot!(screen(&board_state));
+    }
+
+    #[test]
+    fn typing_after_a_guess() {
+        let mut board_state = game();
+        board_state.boards[0].rows[0] = guess();
+        board_state.boards[0].rows[1] = typed("ta");
+        board_state.cursor = Some((1, 2));
+        board_state.possible_words = vec!["taart", "taats", "tamme", "tante"];
+        board_state.key_feedback = HashMap::from([
+            ('t', Feedback::Correct),
+            ('a', Feedback::Correct),
+            ('b', Feedback::Wrong),
+            ('k', Feedback::Wrong),
+        ]);
+        insta::assert_snapshot!(screen(&board_state));
+    }
+
+    #[test]
+    fn end_of_a_game() {
+        let mut board_state = game();
+        board_state.boards[0].rows[0] = guess();
+        board_state.boards[0].rows[1].fill(GuessedLetter::Correct('t'));
+        board_state.boards[0].solved = true;
+        board_state.score = Some("Score: 120".to_string());
+        board_state.messages.push(Notice::info("You won!"));
+        board_state
+            .messages
+            .push(Notice::warning("Press escape to quit"));
+        insta::assert_snapshot!(screen(&board_state));
+    }
+
+    #[test]
+    fn two_boards() {
+        let mut board_state = BoardState {
+            boards: vec![Board::new(6); 2],
+            ..game()
+        };
+        board_state.boards[0].rows[0] = guess();
+        board_state.boards[1].rows[0] = guess();
+        board_state.boards[1].solved = true;
+        board_state.cursor = Some((1, 0));
+        insta::assert_snapshot!(screen(&board_state));
+    }
+
+    #[test]
+    fn marked_tiles_and_keys() {
+        let mut board_state = game();
+        board_state.boards[0].rows[0] = guess();
+        board_state.marked = true;
+        board_state.key_feedback =
+            HashMap::from([('t', Feedback::Correct), ('b', Feedback::Wrong)]);
+        insta::assert_snapshot!(screen(&board_state));
+    }
+}
@@ -0,0 +1,453 @@
+//! Replays of games. A replay file is a json header on the first line followed by one message of
+//! the `protocol` module per line, with the time it happened. The header holds the amount of
+//! events and the events at which guesses were verified, so a viewer can jump to any event or
+//! guess without reading the file as a stream.
+
+use crate::messages::Notice;
+use crate::protocol::Message;
+use crate::rules;
+use crate::strings::strings;
+use crate::theme::Theme;
+use crate::word;
+use crate::{render_game, Board, BoardState, GuessedLetter, GUESSES, WORD_LENGTH};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::time::Instant;
+
+/// The version of the replay format that is written, version 2 moved to the messages of the
+/// `protocol` module
+const VERSION: u32 = 2;
+/// The playback speeds that can be picked
+const SPEEDS: [f64; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+
+#[derive(Serialize, Deserialize)]
+pub struct Header {
+    pub version: u32,
+    pub language: String,
+    pub word: String,
+    /// The amount of events in the replay
+    pub events: usize,
+    /// The indices of the events at which a guess was verified
+    pub guesses: Vec<usize>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// Milliseconds since the start of the game
+    pub at: u64,
+    #[serde(flatten)]
+    pub message: Message,
+}
+
+pub struct Replay {
+    pub header: Header,
+    pub events: Vec<Event>,
+}
+
+/// Keeps the events of a game while it is played
+pub struct Recorder {
+    start: Instant,
+    events: Vec<Event>,
+    guesses: Vec<usize>,
+}
+
+impl Recorder {
+    pub fn new() -> Recorder {
+        Recorder {
+            start: Instant::now(),
+            events: Vec::new(),
+            guesses: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, message: Message) {
+        self.record_at(self.start.elapsed().as_millis() as u64, message);
+    }
+
+    /// Record the message at a time of its own, in milliseconds since the start
+    pub fn record_at(&mut self, at: u64, message: Message) {
+        if let Message::Result { .. } = message {
+            self.guesses.push(self.events.len());
+        }
+        self.events.push(Event { at, message });
+    }
+
+    /// The replay of the recorded game, the word is only known at the end in evil mode
+    pub fn finish(self, language: &str, word: &str) -> Replay {
+        Replay {
+            header: Header {
+                version: VERSION,
+                language: language.to_string(),
+                word: word.to_string(),
+                events: self.events.len(),
+                guesses: self.guesses,
+            },
+            events: self.events,
+        }
+    }
+}
+
+fn invalid(err: impl ToString) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, err.to_string())
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> io::Result<Replay> {
+        let content = fs::read_to_string(path)?;
+        let mut lines = content.lines();
+        let header: Header =
+            serde_json::from_str(lines.next().ok_or_else(|| invalid("Empty replay file"))?)
+                .map_err(invalid)?;
+        if header.version != VERSION {
+            return Err(invalid(format!(
+                "Unsupported replay version {}",
+                header.version
+            )));
+        }
+        let events = lines
+            .map(|line| serde_json::from_str(line).map_err(invalid))
+            .collect::<io::Result<Vec<Event>>>()?;
+        if events.len() != header.events {
+            return Err(invalid("The replay file is incomplete"));
+        }
+        Ok(Replay { header, events })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut content = serde_json::to_string(&self.header).map_err(io::Error::other)?;
+        for event in self.events.iter() {
+            content.push('\n');
+            content.push_str(&serde_json::to_string(event).map_err(io::Error::other)?);
+        }
+        content.push('\n');
+        fs::write(path, content)
+    }
+
+    /// The state of the board after the first `position` events
+    pub(crate) fn board_at(&self, position: usize) -> BoardState {
+        let mut board_state = BoardState {
+            boards: vec![Board::new(GUESSES)],
+            ..Default::default()
+        };
+        let mut row = 0;
+        for event in self.events[..position].iter() {
+            match &event.message {
+                Message::Typed { text, cursor } => {
+                    board_state.messages.clear();
+                    if row < GUESSES as usize {
+                        for (index, letter) in
+                            board_state.boards[0].rows[row].iter_mut().enumerate()
+                        {
+                            *letter = GuessedLetter::typed(word::letter(text, index));
+                        }
+                        board_state.cursor = (*cursor < WORD_LENGTH).then_some((row, *cursor));
+                    }
+                }
+                Message::Result {
+                    guess: Some(guess),
+                    pattern,
+                } => {
+                    if row < GUESSES as usize {
+                        board_state.boards[0].rows[row] = rules::guessed_word(guess, pattern);
+                        row += 1;
+                    }
+                    board_state.cursor = None;
+                }
+                Message::Rejected { message } => {
+                    board_state.messages.push(Notice::error(message.clone()));
+                }
+                Message::Finished {
+                    message: Some(message),
+                    ..
+                } => {
+                    board_state.messages.push(Notice::info(message.clone()));
+                }
+                _ => (),
+            }
+        }
+        board_state
+    }
+}
+
+/// The position in a replay that is being watched
+struct Playback {
+    /// The amount of events that have been shown
+    position: usize,
+    /// The time in the replay in milliseconds
+    clock: f64,
+    playing: bool,
+    /// The index into `SPEEDS`
+    speed: usize,
+}
+
+impl Playback {
+    /// Move to right after the event at `position`, the clock jumps along
+    fn seek(&mut self, replay: &Replay, position: usize) {
+        self.position = position.min(replay.events.len());
+        self.clock = match self.position {
+            0 => 0.0,
+            position => replay.events[position - 1].at as f64,
+        };
+    }
+}
+
+/// Draw the controls and a bar with the position in the replay at the bottom of the screen
+fn render_progress(replay: &Replay, playback: &Playback) {
+    let mut max_x = 0;
+    let mut max_y = 0;
+    ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+
+    let status = format!(
+        " {}/{} {}x {}",
+        playback.position,
+        replay.events.len(),
+        SPEEDS[playback.speed],
+        if playback.playing {
+            strings().replay_playing
+        } else {
+            strings().replay_paused
+        }
+    );
+    let bar_width = (max_x - status.len() as i32 - 2).max(10) as usize;
+    let filled = match replay.events.len() {
+        0 => bar_width,
+        events => bar_width * playback.position / events,
+    };
+    let bar = format!(
+        "[{}{}]{}",
+        "=".repeat(filled),
+        " ".repeat(bar_width - filled),
+        status
+    );
+    ncurses::mvaddstr(max_y - 2, 0, &bar);
+
+    let help = strings().replay_help;
+    ncurses::mvaddstr(max_y - 1, (max_x - help.len() as i32) / 2, help);
+    ncurses::refresh();
+}
+
+/// Watch a replay, with controls to pause, step through the events or guesses and change the speed
+pub fn play_replay(replay: &Replay, theme: &Theme) {
+    let _window_ender = crate::init_ncurses(theme);
+    ncurses::timeout(50);
+
+    let mut playback = Playback {
+        position: 0,
+        clock: 0.0,
+        playing: true,
+        speed: 1,
+    };
+    let mut last_tick = Instant::now();
+    let mut shown = None;
+
+    loop {
+        // Advance the clock while playing and show the events that have passed
+        let now = Instant::now();
+        if playback.playing {
+            playback.clock +=
+                now.duration_since(last_tick).as_secs_f64() * 1000.0 * SPEEDS[playback.speed];
+            while playback.position < replay.events.len()
+                && replay.events[playback.position].at as f64 <= playback.clock
+            {
+                playback.position += 1;
+            }
+            if playback.position == replay.events.len() {
+                playback.playing = false;
+            }
+        }
+        last_tick = now;
+
+        let state = (playback.position, playback.playing, playback.speed);
+        if shown != Some(state) {
+            render_game(&replay.board_at(playback.position));
+            render_progress(replay, &playback);
+            shown = Some(state);
+        }
+
+        let input = ncurses::getch();
+        if input == ncurses::ERR {
+            continue;
+        }
+        match char::from_u32(input as u32) {
+            Some('q') | Some('\u{1b}') => return,
+            Some(' ') => {
+                if playback.position == replay.events.len() {
+                    playback.seek(replay, 0);
+                }
+                playback.playing = !playback.playing;
+            }
+            Some('+') | Some('=') => playback.speed = (playback.speed + 1).min(SPEEDS.len() - 1),
+            Some('-') => playback.speed = playback.speed.saturating_sub(1),
+            Some(']') => {
+                // Jump to right after the next verified guess
+                let next = replay
+                    .header
+                    .guesses
+                    .iter()
+                    .find(|index| **index >= playback.position)
+                    .map(|index| index + 1)
+                    .unwrap_or(replay.events.len());
+                playback.playing = false;
+                playback.seek(replay, next);
+            }
+            Some('[') => {
+                // Jump to right after the verified guess before the current position
+                let previous = replay
+                    .header
+                    .guesses
+                    .iter()
+                    .rev()
+                    .find(|index| **index + 1 < playback.position)
+                    .map(|index| index + 1)
+                    .unwrap_or(0);
+                playback.playing = false;
+                playback.seek(replay, previous);
+            }
+            _ if input == ncurses::KEY_RIGHT => {
+                playback.playing = false;
+                playback.seek(replay, playback.position + 1);
+            }
+            _ if input == ncurses::KEY_LEFT => {
+                playback.playing = false;
+                playback.seek(replay, playback.position.saturating_sub(1));
+            }
+            _ if input == ncurses::KEY_HOME => playback.seek(replay, 0),
+            _ if input == ncurses::KEY_END => {
+                playback.playing = false;
+                playback.seek(replay, replay.events.len());
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::score;
+
+    /// A game of two guesses, the second one finds the word
+    fn recorded() -> Replay {
+        let mut recorder = Recorder::new();
+        let typed = |text: &str, cursor| Message::Typed {
+            text: text.to_string(),
+            cursor,
+        };
+        let result = |guess: &str| Message::Result {
+            guess: Some(guess.to_string()),
+            pattern: score(guess, "kaars"),
+        };
+        recorder.record_at(0, typed("k", 1));
+        recorder.record_at(400, typed("kaart", 5));
+        recorder.record_at(500, result("kaart"));
+        recorder.record_at(900, typed("kaa", 3));
+        recorder.record_at(1200, typed("kaars", 5));
+        recorder.record_at(1300, result("kaars"));
+        recorder.record_at(
+            1300,
+            Message::Finished {
+                won: true,
+                word: Some("kaars".to_string()),
+                message: None,
+            },
+        );
+        recorder.finish("nl", "kaars")
+    }
+
+    fn path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("lingo-replay-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn a_saved_replay_loads_the_same() {
+        let replay = recorded();
+        let path = path("saved");
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path);
+        let _ = fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.header.version, VERSION);
+        assert_eq!(loaded.header.language, "nl");
+        assert_eq!(loaded.header.word, "kaars");
+        assert_eq!(loaded.header.events, replay.header.events);
+        assert_eq!(loaded.header.guesses, replay.header.guesses);
+        let events = |replay: &Replay| {
+            replay
+                .events
+                .iter()
+                .map(|event| (event.at, event.message.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(events(&loaded), events(&replay));
+    }
+
+    #[test]
+    fn the_header_points_at_the_verified_guesses() {
+        let replay = recorded();
+        assert_eq!(replay.header.events, 7);
+        assert_eq!(replay.header.guesses, [2, 5]);
+        for guess in replay.header.guesses {
+            assert!(matches!(
+                replay.events[guess].message,
+                Message::Result { .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn the_board_is_rebuilt_up_to_a_guess() {
+        let replay = recorded();
+        let feedback = |board_state: &BoardState, row: usize| {
+            board_state.boards[0].rows[row]
+                .iter()
+                .map(|letter| letter.feedback())
+                .collect::<Vec<_>>()
+        };
+
+        // Right after the first guess was verified
+        let board_state = replay.board_at(replay.header.guesses[0] + 1);
+        assert_eq!(feedback(&board_state, 0), score("kaart", "kaars").map(Some));
+        assert_eq!(feedback(&board_state, 1), [None; WORD_LENGTH]);
+        assert_eq!(board_state.cursor, None);
+
+        // The second guess is being typed
+        let board_state = replay.board_at(replay.header.guesses[0] + 2);
+        assert!(matches!(
+            board_state.boards[0].rows[1][..3],
+            [
+                GuessedLetter::Letter('k'),
+                GuessedLetter::Letter('a'),
+                GuessedLetter::Letter('a')
+            ]
+        ));
+        assert_eq!(board_state.cursor, Some((1, 3)));
+
+        let board_state = replay.board_at(replay.events.len());
+        assert_eq!(feedback(&board_state, 1), score("kaars", "kaars").map(Some));
+    }
+
+    #[test]
+    fn a_truncated_replay_is_rejected() {
+        let path = path("truncated");
+        recorded().save(&path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+        fs::write(&path, lines[..lines.len() - 2].join("\n")).unwrap();
+        let truncated = Replay::load(&path);
+        fs::write(&path, "").unwrap();
+        let empty = Replay::load(&path);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            truncated.err().map(|err| err.kind()),
+            Some(ErrorKind::InvalidData)
+        );
+        assert_eq!(
+            empty.err().map(|err| err.kind()),
+            Some(ErrorKind::InvalidData)
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! A review of the guesses after the game. Every guess is compared with the best guess that was
+//! available at that point: the candidate that was expected to leave the fewest candidates.
+
+use crate::candidates::CandidateSet;
+use crate::rules::Pattern;
+use crate::strings::{fill, strings};
+use crate::word;
+use lingo_core::knowledge::Knowledge;
+use rayon::prelude::*;
+
+/// How one guess of the game did
+pub struct GuessReview {
+    pub guess: String,
+    /// The candidates before and after the guess
+    pub before: usize,
+    pub after: usize,
+    /// The candidates the guess was expected to leave
+    pub expected: f64,
+    /// The candidate that was expected to leave the fewest candidates, and how many
+    pub best: &'static str,
+    pub best_expected: f64,
+}
+
+impl GuessReview {
+    /// A grade of the guess compared with the best guess
+    pub fn grade(&self) -> &'static str {
+        if self.expected <= self.best_expected {
+            return strings().grade_best;
+        }
+        match self.best_expected / self.expected {
+            ratio if ratio >= 0.8 => strings().grade_great,
+            ratio if ratio >= 0.5 => strings().grade_good,
+            ratio if ratio >= 0.25 => strings().grade_fair,
+            _ => strings().grade_poor,
+        }
+    }
+}
+
+/// The candidates the guess is expected to leave when every candidate is equally likely
+fn expected(candidates: &CandidateSet, guess: &str) -> f64 {
+    let total = candidates.words().len().max(1) as f64;
+    candidates
+        .bucket_sizes(guess)
+        .iter()
+        .map(|size| *size as f64 * *size as f64)
+        .sum::<f64>()
+        / total
+}
+
+/// Review the guesses of a game with one board, with the patterns they got
+pub fn review(
+    words: &[&'static str],
+    guesses: &[String],
+    patterns: &[Pattern],
+    hard: bool,
+) -> Vec<GuessReview> {
+    let mut candidates = CandidateSet::new(words);
+    let mut knowledge = Knowledge::default();
+    let mut reviews = Vec::new();
+    for (guess, pattern) in guesses.iter().zip(patterns) {
+        let (best, best_expected) = candidates
+            .words()
+            .par_iter()
+            // In hard mode only the guesses that use all the revealed hints were available
+            .filter(|candidate| !hard || knowledge.check_hints(candidate).is_ok())
+            .map(|candidate| (*candidate, expected(&candidates, candidate)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or_default();
+        let before = candidates.words().len();
+        let guess_expected = expected(&candidates, guess);
+        knowledge.record(guess, pattern);
+        candidates.retain(&knowledge);
+        reviews.push(GuessReview {
+            guess: guess.clone(),
+            before,
+            after: candidates.words().len(),
+            expected: guess_expected,
+            best,
+            best_expected,
+        });
+    }
+    reviews
+}
+
+/// The word as it is shown on the board
+fn display(word: &str) -> String {
+    word.chars().map(word::display).collect()
+}
+
+/// Show the review until a key is pressed
+pub fn show(reviews: &[GuessReview]) {
+    if reviews.is_empty() {
+        return;
+    }
+    ncurses::timeout(-1);
+    ncurses::clear();
+    ncurses::mvaddstr(0, 2, strings().review_title);
+    for (row, review) in reviews.iter().enumerate() {
+        let mut line = fill(
+            strings().review_line,
+            &[
+                &(row + 1),
+                &display(&review.guess),
+                &review.before,
+                &review.after,
+                &if review.after == 1 {
+                    strings().candidate
+                } else {
+                    strings().candidates
+                },
+                &review.grade(),
+            ],
+        );
+        if review.expected > review.best_expected {
+            line.push_str(&fill(strings().best_available, &[&display(review.best)]));
+        }
+        ncurses::mvaddstr(2 + row as i32, 2, &line);
+    }
+    ncurses::mvaddstr(3 + reviews.len() as i32, 2, strings().press_to_continue);
+    ncurses::refresh();
+    ncurses::getch();
+}
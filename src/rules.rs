@@ -0,0 +1,18 @@
+use crate::word::SEPARATOR;
+use crate::{GuessedLetter, GuessedWord};
+
+pub use lingo_core::rules::{score, score_tiles, Feedback, HardMode, Pattern};
+
+/// Combine a guess with its pattern into a row of the board
+pub fn guessed_word(guess: &str, pattern: &[Feedback]) -> GuessedWord {
+    let mut row = GuessedWord::empty(pattern.len());
+    for ((letter, chr), feedback) in row.iter_mut().zip(guess.chars()).zip(pattern) {
+        *letter = match feedback {
+            _ if chr == SEPARATOR => GuessedLetter::Separator,
+            Feedback::Wrong => GuessedLetter::Wrong(chr),
+            Feedback::WrongPlace => GuessedLetter::WrongPlace(chr),
+            Feedback::Correct => GuessedLetter::Correct(chr),
+        };
+    }
+    row
+}
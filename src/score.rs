@@ -0,0 +1,71 @@
+use crate::strings::{fill, strings};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The points for each guess that is left when the word is solved, including the solving guess
+const POINTS_PER_GUESS: u32 = 20;
+/// The bonus points for each guess that wasn't needed to solve the word
+const UNUSED_GUESS_BONUS: u32 = 10;
+/// The time bonus starts at this many points and goes down by one point each second
+const TIME_BONUS_SECONDS: u64 = 60;
+
+/// The points scored in a game
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    /// Points for solving the word, fewer guesses give more points
+    pub guess_points: u32,
+    /// Bonus for the guesses that weren't used
+    pub unused_bonus: u32,
+    /// Bonus for solving the word quickly, only if the time bonus is enabled
+    pub time_bonus: u32,
+}
+
+impl Score {
+    /// The score for solving the word in `guesses` guesses out of `max_guesses`. The time bonus is
+    /// only given if the time it took is passed.
+    pub fn solved(guesses: u32, max_guesses: u32, elapsed: Option<Duration>) -> Score {
+        debug_assert!(
+            (1..=max_guesses).contains(&guesses),
+            "The amount of guesses is out of range"
+        );
+
+        let unused = max_guesses - guesses;
+        Score {
+            guess_points: (unused + 1) * POINTS_PER_GUESS,
+            unused_bonus: unused * UNUSED_GUESS_BONUS,
+            time_bonus: elapsed
+                .map(|elapsed| TIME_BONUS_SECONDS.saturating_sub(elapsed.as_secs()) as u32)
+                .unwrap_or(0),
+        }
+    }
+
+    /// The score when the word wasn't solved
+    pub fn lost() -> Score {
+        Score::default()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.guess_points + self.unused_bonus + self.time_bonus
+    }
+
+    /// A description of how the total score is built up
+    pub fn describe(&self) -> String {
+        let strings = strings();
+        if self.time_bonus > 0 {
+            fill(
+                strings.score_timed,
+                &[
+                    &self.total(),
+                    &self.guess_points,
+                    &self.unused_bonus,
+                    &self.time_bonus,
+                ],
+            )
+        } else {
+            fill(
+                strings.score,
+                &[&self.total(), &self.guess_points, &self.unused_bonus],
+            )
+        }
+    }
+}
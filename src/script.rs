@@ -0,0 +1,202 @@
+//! Lua scripts that hook into the game, for bots, house rules and logging. The scripts are listed
+//! in the config file or given with --script and need the `scripting` feature. A script defines
+//! any of these global functions, a later script replaces the functions of an earlier one:
+//!
+//! - `on_guess(guess, number)` is called before a guess is verified. Returning a message rejects
+//!   the guess with that message.
+//! - `on_game_end(result)` is called with a table of the finished game: `words`, `won`,
+//!   `guesses`, `max_guesses`, `seconds` and `patterns`, with the patterns as codes like `GYXXG`.
+//! - `suggest_word(candidates, guesses)` picks the next guess of the computer opponent out of
+//!   `guesses`, the `candidates` are the words that are still possible. Returning nil or a word that
+//!   isn't one of the guesses leaves the choice to the --strategy.
+//!
+//! The scripts run while the game is on the screen, so they should write to a file instead of
+//! printing.
+
+use crate::strategy::Strategy;
+use crate::GameResult;
+use std::io;
+use std::path::PathBuf;
+
+#[cfg(feature = "scripting")]
+use crate::candidates::CandidateSet;
+#[cfg(feature = "scripting")]
+use mlua::{Function, Lua, Table};
+#[cfg(feature = "scripting")]
+use std::sync::Mutex;
+
+/// The loaded scripts, without scripts every hook does nothing
+#[derive(Default)]
+pub struct Scripts {
+    #[cfg(feature = "scripting")]
+    lua: Option<Mutex<Lua>>,
+}
+
+#[cfg(feature = "scripting")]
+impl Scripts {
+    /// Run the scripts so they can define their hooks
+    pub fn load(paths: &[PathBuf]) -> io::Result<Scripts> {
+        if paths.is_empty() {
+            return Ok(Scripts::default());
+        }
+        let lua = Lua::new();
+        for path in paths {
+            let source = std::fs::read_to_string(path)?;
+            lua.load(source)
+                .set_name(path.display().to_string())
+                .exec()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        }
+        Ok(Scripts {
+            lua: Some(Mutex::new(lua)),
+        })
+    }
+
+    /// Call `hook` with the Lua state when one of the scripts defines it
+    fn call<T>(
+        &self,
+        hook: &str,
+        call: impl FnOnce(&Lua, Function) -> mlua::Result<T>,
+    ) -> Option<mlua::Result<T>> {
+        let lua = self
+            .lua
+            .as_ref()?
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let function = lua.globals().get::<_, Option<Function>>(hook).ok()??;
+        Some(call(&lua, function))
+    }
+
+    /// The message the guess is rejected with, if a script rejects it
+    pub fn on_guess(&self, guess: &str, number: u32) -> Option<String> {
+        match self.call("on_guess", |_, function| {
+            function.call::<_, Option<String>>((guess, number))
+        })? {
+            Ok(message) => message,
+            // A broken house rule doesn't let guesses through unchecked
+            Err(err) => Some(format!("The on_guess script failed: {}", err)),
+        }
+    }
+
+    /// Hand the finished game to the scripts
+    pub fn on_game_end(&self, result: &GameResult) -> Result<(), String> {
+        let called = self.call("on_game_end", |lua, function| {
+            let table = lua.create_table()?;
+            table.set("words", result.words.clone())?;
+            table.set("won", result.won)?;
+            table.set("guesses", result.guesses)?;
+            table.set("max_guesses", result.max_guesses)?;
+            table.set("seconds", result.elapsed.as_secs())?;
+            let patterns = result
+                .patterns
+                .iter()
+                .map(lingo_core::rules::pattern_to_code)
+                .collect::<Vec<_>>();
+            table.set("patterns", patterns)?;
+            function.call::<_, ()>(table)
+        });
+        called.unwrap_or(Ok(())).map_err(|err| err.to_string())
+    }
+
+    /// The strategy of the computer opponent, the scripts pick the guesses when they define
+    /// `suggest_word`
+    pub fn strategy(&'static self, fallback: &'static dyn Strategy) -> &'static dyn Strategy {
+        match self.call("suggest_word", |_, _| Ok(())) {
+            Some(_) => Box::leak(Box::new(ScriptStrategy {
+                scripts: self,
+                fallback,
+            })),
+            None => fallback,
+        }
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+impl Scripts {
+    /// Without the `scripting` feature the scripts never run
+    pub fn load(paths: &[PathBuf]) -> io::Result<Scripts> {
+        if !paths.is_empty() {
+            eprintln!("Scripts are configured but this build has no scripting support");
+        }
+        Ok(Scripts::default())
+    }
+
+    pub fn on_guess(&self, _guess: &str, _number: u32) -> Option<String> {
+        None
+    }
+
+    pub fn on_game_end(&self, _result: &GameResult) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn strategy(&'static self, fallback: &'static dyn Strategy) -> &'static dyn Strategy {
+        fallback
+    }
+}
+
+/// Asks `suggest_word` for the guess, the fallback picks when the script doesn't
+#[cfg(feature = "scripting")]
+struct ScriptStrategy {
+    scripts: &'static Scripts,
+    fallback: &'static dyn Strategy,
+}
+
+#[cfg(feature = "scripting")]
+impl Strategy for ScriptStrategy {
+    fn choose(&self, candidates: &CandidateSet, guesses: &[&'static str]) -> &'static str {
+        let suggested = self.scripts.call("suggest_word", |lua, function| {
+            let list = |words: &[&'static str]| -> mlua::Result<Table> {
+                lua.create_sequence_from(words.iter().copied())
+            };
+            function.call::<_, Option<String>>((list(candidates.words())?, list(guesses)?))
+        });
+        // A script that fails or suggests a word that can't be guessed doesn't stop the opponent
+        match suggested {
+            Some(Ok(Some(word))) => guesses.iter().find(|guess| **guess == word).copied(),
+            _ => None,
+        }
+        .unwrap_or_else(|| self.fallback.choose(candidates, guesses))
+    }
+}
+
+#[cfg(all(test, feature = "scripting"))]
+mod tests {
+    use super::*;
+    use crate::strategy::Builtin;
+    use std::io::Write;
+
+    fn scripts(source: &str) -> &'static Scripts {
+        let path = std::env::temp_dir().join(format!("lingo-script-{}.lua", std::process::id()));
+        std::fs::File::create(&path)
+            .and_then(|mut file| file.write_all(source.as_bytes()))
+            .unwrap();
+        let scripts = Scripts::load(std::slice::from_ref(&path)).unwrap();
+        std::fs::remove_file(path).unwrap();
+        Box::leak(Box::new(scripts))
+    }
+
+    #[test]
+    fn hooks_reject_guesses_and_pick_words() {
+        let scripts = scripts(
+            r#"
+            function on_guess(guess, number)
+                if number == 1 and guess:sub(1, 1) ~= "t" then
+                    return "Start with a T"
+                end
+            end
+            function suggest_word(candidates, guesses)
+                return guesses[#guesses]
+            end
+            "#,
+        );
+        assert_eq!(
+            scripts.on_guess("kaart", 1).as_deref(),
+            Some("Start with a T")
+        );
+        assert_eq!(scripts.on_guess("kaart", 2), None);
+
+        let words = ["kaart", "tabak", "taken"];
+        let strategy = scripts.strategy(Builtin::Frequency.strategy());
+        assert_eq!(strategy.choose(&CandidateSet::new(&words), &words), "taken");
+    }
+}
@@ -0,0 +1,25 @@
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// The settings that are changed from within the game, kept between runs
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The name of the color theme
+    pub theme: String,
+    /// Zen mode, the games are played without the assistance that can be turned off
+    pub zen: bool,
+}
+
+impl Settings {
+    pub fn load() -> io::Result<Settings> {
+        storage::load_json(SETTINGS_FILE)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save_json(SETTINGS_FILE, self)
+    }
+}
@@ -0,0 +1,218 @@
+use crate::rules::{Feedback, Pattern};
+use crate::GameResult;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How much of a game is shared, the levels are ordered from the least to the most detail
+#[derive(
+    Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Privacy {
+    /// Only the amount of guesses and the time
+    Minimal,
+    /// The colored grid without letters
+    #[default]
+    Standard,
+    /// The grid and the word, hidden behind a spoiler
+    Full,
+}
+
+/// The row of the grid as colored squares
+pub fn tiles(pattern: &Pattern) -> String {
+    pattern
+        .iter()
+        .map(|feedback| match feedback {
+            Feedback::Wrong => '⬛',
+            Feedback::WrongPlace => '🟨',
+            Feedback::Correct => '🟩',
+        })
+        .collect()
+}
+
+/// A shared result, the fields that the privacy level doesn't allow are left out
+#[derive(Serialize)]
+pub struct Share {
+    pub privacy: Privacy,
+    pub language: String,
+    pub won: bool,
+    /// The amount of words that were guessed at the same time
+    pub boards: usize,
+    pub guesses: u32,
+    pub max_guesses: u32,
+    pub seconds: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid: Option<Vec<Pattern>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word: Option<String>,
+}
+
+impl Share {
+    pub fn new(result: &GameResult, language: &str, privacy: Privacy) -> Share {
+        Share {
+            privacy,
+            language: language.to_string(),
+            won: result.won,
+            boards: result.words.len(),
+            guesses: result.guesses,
+            max_guesses: result.max_guesses,
+            seconds: result.elapsed.as_secs(),
+            // The patterns are only kept for a single board, a grid for every board doesn't fit in
+            // a chat message
+            grid: (privacy >= Privacy::Standard && result.words.len() == 1)
+                .then(|| result.patterns.clone()),
+            word: (privacy >= Privacy::Full).then(|| result.words.join(" ")),
+        }
+    }
+
+    /// The result as text that can be pasted in a chat, the word is put behind a spoiler tag
+    pub fn text(&self) -> String {
+        let guesses = if self.won {
+            self.guesses.to_string()
+        } else {
+            "X".to_string()
+        };
+        let boards = match self.boards {
+            1 => String::new(),
+            boards => format!(" x{}", boards),
+        };
+        let mut text = format!(
+            "Lingo {}{} {}/{} in {}:{:02}",
+            self.language,
+            boards,
+            guesses,
+            self.max_guesses,
+            self.seconds / 60,
+            self.seconds % 60
+        );
+
+        if let Some(grid) = &self.grid {
+            for pattern in grid {
+                text.push('\n');
+                text.push_str(&tiles(pattern));
+            }
+        }
+        if let Some(word) = &self.word {
+            text.push_str(&format!("\n||{}||", word));
+        }
+
+        text
+    }
+}
+
+/// A webhook the result of each game is posted to, configured in the config file:
+///
+/// ```toml
+/// [[webhooks]]
+/// url = "https://example.com/daily-group"
+/// require = "standard"
+/// ```
+#[derive(Clone, Deserialize)]
+#[cfg_attr(not(feature = "webhooks"), allow(dead_code))]
+pub struct Webhook {
+    pub url: String,
+    /// The least detail the receiving server accepts, results shared with less detail aren't
+    /// posted
+    #[serde(default = "Webhook::default_require")]
+    pub require: Privacy,
+}
+
+impl Webhook {
+    fn default_require() -> Privacy {
+        Privacy::Minimal
+    }
+
+    /// If the result is shared with enough detail for the receiving server
+    #[cfg_attr(not(feature = "webhooks"), allow(dead_code))]
+    fn accepts(&self, share: &Share) -> bool {
+        share.privacy >= self.require
+    }
+}
+
+/// Post the shared result to the webhooks that accept its privacy level. Problems are reported on
+/// stderr.
+#[cfg(feature = "webhooks")]
+pub fn post_to_webhooks(webhooks: &[Webhook], share: &Share) {
+    for webhook in webhooks {
+        if !webhook.accepts(share) {
+            eprintln!(
+                "Not posting to {}, it requires at least {:?} sharing",
+                webhook.url, webhook.require
+            );
+            continue;
+        }
+        if let Err(err) = ureq::post(&webhook.url).send_json(share) {
+            eprintln!("Could not post the result to {}: {}", webhook.url, err);
+        }
+    }
+}
+
+/// Without the `webhooks` feature nothing is ever posted
+#[cfg(not(feature = "webhooks"))]
+pub fn post_to_webhooks(webhooks: &[Webhook], _share: &Share) {
+    if !webhooks.is_empty() {
+        eprintln!("Webhooks are configured but this build has no webhook support");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules;
+    use crate::score::Score;
+    use std::time::Duration;
+
+    /// "tabak" solved in two guesses
+    fn share(privacy: Privacy) -> Share {
+        let result = GameResult {
+            words: vec!["tabak"],
+            won: true,
+            guesses: 2,
+            max_guesses: 5,
+            guessed: vec!["kaart".to_string(), "tabak".to_string()],
+            patterns: vec![
+                rules::score("kaart", "tabak"),
+                rules::score("tabak", "tabak"),
+            ],
+            replay: None,
+            score: Score::solved(2, 5, None),
+            elapsed: Duration::from_secs(75),
+        };
+        Share::new(&result, "nl", privacy)
+    }
+
+    #[test]
+    fn minimal_shares_leave_out_the_grid_and_the_word() {
+        let share = share(Privacy::Minimal);
+        assert_eq!(share.text(), "Lingo nl 2/5 in 1:15");
+        let json = serde_json::to_value(&share).unwrap();
+        assert!(json.get("grid").is_none() && json.get("word").is_none());
+    }
+
+    #[test]
+    fn standard_shares_have_the_grid_without_the_word() {
+        let share = share(Privacy::Standard);
+        assert_eq!(share.text(), "Lingo nl 2/5 in 1:15\n🟨🟩🟨⬛🟨\n🟩🟩🟩🟩🟩");
+        let json = serde_json::to_value(&share).unwrap();
+        assert!(json.get("grid").is_some() && json.get("word").is_none());
+    }
+
+    #[test]
+    fn full_shares_hide_the_word_behind_a_spoiler() {
+        let share = share(Privacy::Full);
+        assert!(share.text().ends_with("\n🟩🟩🟩🟩🟩\n||tabak||"));
+        let json = serde_json::to_value(&share).unwrap();
+        assert_eq!(json["word"], "tabak");
+    }
+
+    #[test]
+    fn webhooks_that_require_more_detail_are_skipped() {
+        let webhook = Webhook {
+            url: "https://example.com/daily-group".to_string(),
+            require: Privacy::Standard,
+        };
+        assert!(!webhook.accepts(&share(Privacy::Minimal)));
+        assert!(webhook.accepts(&share(Privacy::Standard)));
+        assert!(webhook.accepts(&share(Privacy::Full)));
+    }
+}
@@ -0,0 +1,228 @@
+//! Playing many games with the solver to see how difficult the rules make the game. Unlike `bench`
+//! the games are played through the same `Game` as the player, so the guesses run out, evil mode
+//! dodges and hard mode checks the guesses like they do in a real game.
+
+use crate::dictionary::Dictionary;
+use crate::engine::{Event, Game, GameState, Transition};
+use crate::error::Error;
+use crate::script::Scripts;
+use crate::solver::Solver;
+use crate::strategy::Builtin;
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::time::Instant;
+
+/// The rules the games are played with
+pub struct Rules {
+    pub boards: usize,
+    pub max_guesses: u32,
+    pub hard: bool,
+    pub evil: bool,
+    pub strategy: Builtin,
+}
+
+impl Rules {
+    fn describe(&self) -> String {
+        let mut parts = vec![
+            format!("{} strategy", self.strategy.name()),
+            format!("{} guesses", self.max_guesses),
+        ];
+        if self.boards > 1 {
+            parts.push(format!("{} boards", self.boards));
+        }
+        if self.hard {
+            parts.push("hard mode".to_string());
+        }
+        if self.evil {
+            parts.push("evil mode".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// Play a game on the words with the solver, the guesses it took when it was won. Every board has
+/// its own solver, a guess is made for the first board that isn't solved yet.
+fn play(
+    dictionary: &Dictionary,
+    answers: &[&'static str],
+    alphabet: &[char],
+    rules: &Rules,
+    (start, opener): (&Solver, &'static str),
+    targets: Vec<&'static str>,
+) -> Result<Option<u32>, Error> {
+    // The games of the simulation have no house rules
+    let scripts = Scripts::default();
+    let mut solvers = vec![start.clone(); targets.len()];
+    let mut game = Game::new(
+        dictionary,
+        &scripts,
+        alphabet,
+        targets,
+        rules.max_guesses,
+        rules.evil.then_some(answers),
+        rules.hard,
+    );
+    let mut guess = opener;
+    loop {
+        // The solver only guesses words that are allowed, a rejected guess loses the game
+        if let Transition::Rejected(_) = game.handle_event(Event::Submit(guess.to_string()))? {
+            return Ok(None);
+        }
+        if let Transition::Scored(scored) = game.handle_event(Event::Score)? {
+            for (board, _) in scored.patterns {
+                solvers[board].learn(game.knowledge(board));
+            }
+        }
+        match game.state() {
+            GameState::Won => return Ok(Some(game.guesses())),
+            GameState::Lost => return Ok(None),
+            _ => {}
+        }
+        let next = (0..solvers.len())
+            .find(|board| !game.is_solved(*board))
+            .and_then(|board| solvers[board].next_guess());
+        match next {
+            Some(next) => guess = next,
+            None => return Ok(None),
+        }
+    }
+}
+
+/// Play `games` games on random words from `answers` and print how many were won and the guesses
+/// they took. With a seed the same words are played each time.
+pub fn run(
+    dictionary: &Dictionary,
+    answers: &[&'static str],
+    alphabet: &[char],
+    rules: &Rules,
+    games: usize,
+    seed: Option<u64>,
+) -> Result<(), Error> {
+    let started = Instant::now();
+    let words = dictionary.playable(crate::WORD_LENGTH)?;
+    let solver = Solver::new(words, rules.hard, rules.strategy.strategy());
+    let Some(opener) = solver
+        .next_guess()
+        .filter(|_| answers.len() >= rules.boards)
+    else {
+        println!("There are not enough words to play");
+        return Ok(());
+    };
+    let targets = (0..games)
+        .map(|game| {
+            let seed = seed.map(|seed| seed.wrapping_add(game as u64));
+            crate::pick_words(answers, rules.boards, seed)
+        })
+        .collect_vec();
+    let results = targets
+        .into_par_iter()
+        .map(|targets| {
+            play(
+                dictionary,
+                answers,
+                alphabet,
+                rules,
+                (&solver, opener),
+                targets,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let elapsed = started.elapsed();
+
+    let won = results.into_iter().flatten().collect_vec();
+    println!(
+        "Played {} games with the {} in {:.1}s",
+        games,
+        rules.describe(),
+        elapsed.as_secs_f64()
+    );
+    println!(
+        "Won {} games ({:.1}%)",
+        won.len(),
+        100.0 * won.len() as f64 / games.max(1) as f64
+    );
+    if !won.is_empty() {
+        println!(
+            "Average guesses of the won games: {:.3}",
+            won.iter().sum::<u32>() as f64 / won.len() as f64
+        );
+    }
+
+    println!();
+    println!("Guesses  Games");
+    let counts = won.iter().copied().counts();
+    let rows = (1..=rules.max_guesses)
+        .map(|guesses| {
+            let count = counts.get(&guesses).copied().unwrap_or_default();
+            (guesses.to_string(), count)
+        })
+        .chain([("lost".to_string(), games - won.len())]);
+    for (guesses, count) in rows {
+        let line = format!(
+            "{:>7}  {:>5}  {}",
+            guesses,
+            count,
+            "#".repeat(bar(count, games))
+        );
+        println!("{}", line.trim_end());
+    }
+    Ok(())
+}
+
+/// The length of the bar of a count in the distribution, 40 for all the games
+fn bar(count: usize, games: usize) -> usize {
+    (40 * count).div_ceil(games.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_solver_wins_within_the_guesses() {
+        let words = ["kaart", "tabak", "taken", "tafel", "stoel"];
+        let dictionary: Dictionary = words.into_iter().collect();
+        let alphabet = words
+            .iter()
+            .flat_map(|word| word.chars())
+            .unique()
+            .collect_vec();
+        let rules = Rules {
+            boards: 1,
+            max_guesses: 5,
+            hard: false,
+            evil: false,
+            strategy: Builtin::Entropy,
+        };
+        let solver = Solver::new(&words, false, rules.strategy.strategy());
+        let opener = solver.next_guess().unwrap();
+        for word in words {
+            let guesses = play(
+                &dictionary,
+                &words,
+                &alphabet,
+                &rules,
+                (&solver, opener),
+                vec![word],
+            )
+            .unwrap()
+            .unwrap();
+            assert!((1..=5).contains(&guesses));
+        }
+
+        let rules = Rules {
+            max_guesses: 1,
+            ..rules
+        };
+        let target = words.into_iter().find(|word| *word != opener).unwrap();
+        let result = play(
+            &dictionary,
+            &words,
+            &alphabet,
+            &rules,
+            (&solver, opener),
+            vec![target],
+        );
+        assert_eq!(result.unwrap(), None);
+    }
+}
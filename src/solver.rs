@@ -0,0 +1,99 @@
+use crate::candidates::CandidateSet;
+use crate::rules::Pattern;
+use crate::strategy::Strategy;
+use lingo_core::knowledge::Knowledge;
+
+/// Finds the word by guessing, keeping track of the words that are still possible
+#[derive(Clone)]
+pub struct Solver {
+    candidates: CandidateSet,
+    /// What the guesses revealed about the word
+    knowledge: Knowledge,
+    /// If the guesses have to use all the revealed hints
+    hard: bool,
+    strategy: &'static dyn Strategy,
+}
+
+impl Solver {
+    pub fn new(words: &[&'static str], hard: bool, strategy: &'static dyn Strategy) -> Solver {
+        Solver {
+            candidates: CandidateSet::new(words),
+            knowledge: Knowledge::default(),
+            hard,
+            strategy,
+        }
+    }
+
+    /// The next guess out of the candidates, picked by the strategy
+    pub fn next_guess(&self) -> Option<&'static str> {
+        let guesses = self
+            .candidates
+            .words()
+            .iter()
+            .copied()
+            // In hard mode only the guesses that use all the revealed hints are legal
+            .filter(|candidate| !self.hard || self.knowledge.check_hints(candidate).is_ok())
+            .collect::<Vec<_>>();
+        (!guesses.is_empty()).then(|| self.strategy.choose(&self.candidates, &guesses))
+    }
+
+    /// Remove the candidates that would have given a different pattern for the guess
+    pub fn update(&mut self, guess: &str, pattern: &Pattern) {
+        self.knowledge.record(guess, pattern);
+        self.candidates.retain(&self.knowledge);
+    }
+
+    /// Take over what is known about the word from the game, like the knowledge of a board
+    pub fn learn(&mut self, knowledge: &Knowledge) {
+        self.knowledge = knowledge.clone();
+        self.candidates.retain(&self.knowledge);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{self, HardMode};
+    use crate::strategy::Builtin;
+    use clap::ValueEnum;
+
+    const WORDS: [&str; 8] = [
+        "kaart", "tabak", "taken", "tafel", "stoel", "totem", "kapel", "boten",
+    ];
+
+    /// Let the solver play until it finds the word, checking every guess against the hard mode
+    /// rules of the guesses before it
+    fn assert_legal_game(word: &str, strategy: Builtin) {
+        let mut solver = Solver::new(&WORDS, true, strategy.strategy());
+        let mut hard_mode = HardMode::default();
+        for _ in 0..WORDS.len() {
+            let guess = solver.next_guess().unwrap();
+            assert_eq!(hard_mode.check(guess), Ok(()), "{} after {:?}", guess, word);
+            if guess == word {
+                return;
+            }
+            let pattern = rules::score(guess, word);
+            hard_mode.record(guess, &pattern);
+            solver.update(guess, &pattern);
+        }
+        panic!("The solver didn't find {} with {:?}", word, strategy);
+    }
+
+    #[test]
+    fn hard_mode_guesses_are_legal() {
+        for strategy in Builtin::value_variants() {
+            for word in WORDS {
+                assert_legal_game(word, *strategy);
+            }
+        }
+    }
+
+    #[test]
+    fn hard_mode_keeps_revealed_places() {
+        let mut solver = Solver::new(&WORDS, true, Builtin::Frequency.strategy());
+        solver.update("kaart", &rules::score("kaart", "tabak"));
+        // Only tabak and taken keep the A on the second place and contain K, A and T
+        let guess = solver.next_guess().unwrap();
+        assert!(["tabak", "taken"].contains(&guess));
+    }
+}
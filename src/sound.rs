@@ -0,0 +1,205 @@
+//! Sound effects for the typed letters, rejected words, correct letters and the end of the game.
+//! They are off unless the `[sounds]` table of the config file picks an output:
+//!
+//! ```toml
+//! [sounds]
+//! output = "audio"  # or "bell"
+//! key = 0           # the volume of every sound from 0 for muted to 100
+//! won = 100
+//! ```
+//!
+//! The tones are played on the sound card with the `audio` feature, the terminal bell works in
+//! every build but only has one sound.
+
+use serde::Deserialize;
+use std::io::{self, Write};
+use std::sync::mpsc::Sender;
+use std::sync::OnceLock;
+
+/// Where the sounds are played
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    #[default]
+    Off,
+    Bell,
+    Audio,
+}
+
+/// The `[sounds]` table of the config file
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct SoundConfig {
+    pub output: Output,
+    pub key: u8,
+    pub invalid: u8,
+    pub correct: u8,
+    pub won: u8,
+    pub lost: u8,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        SoundConfig {
+            output: Output::Off,
+            key: 20,
+            invalid: 60,
+            correct: 60,
+            won: 80,
+            lost: 80,
+        }
+    }
+}
+
+impl SoundConfig {
+    /// The volume of the sound from 0 to 1
+    fn volume(&self, sound: Sound) -> f32 {
+        let volume = match sound {
+            Sound::Key => self.key,
+            Sound::Invalid => self.invalid,
+            Sound::Correct => self.correct,
+            Sound::Won => self.won,
+            Sound::Lost => self.lost,
+        };
+        volume.min(100) as f32 / 100.0
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sound {
+    /// A letter was typed
+    Key,
+    /// The guess wasn't accepted
+    Invalid,
+    /// A verified guess has letters in the right place
+    Correct,
+    Won,
+    Lost,
+}
+
+impl Sound {
+    /// The tones of the sound as their frequency and milliseconds
+    #[cfg_attr(not(feature = "audio"), allow(dead_code))]
+    fn notes(self) -> &'static [(f32, u64)] {
+        match self {
+            Sound::Key => &[(1200.0, 15)],
+            Sound::Invalid => &[(196.0, 90), (147.0, 160)],
+            Sound::Correct => &[(784.0, 70), (1047.0, 110)],
+            Sound::Won => &[(523.0, 110), (659.0, 110), (784.0, 110), (1047.0, 260)],
+            Sound::Lost => &[(392.0, 160), (330.0, 160), (262.0, 320)],
+        }
+    }
+}
+
+enum Player {
+    Off,
+    Bell,
+    /// The thread that plays the sounds with their volume
+    Audio(Sender<(Sound, f32)>),
+}
+
+static PLAYER: OnceLock<(Player, SoundConfig)> = OnceLock::new();
+
+/// Play the sounds like the config asks from now on, only the first call counts. Problems are
+/// reported on stderr, so this should be called before ncurses is started.
+pub fn use_sounds(config: &SoundConfig) {
+    let player = match config.output {
+        Output::Off => Player::Off,
+        Output::Bell => Player::Bell,
+        Output::Audio => match start_audio() {
+            Ok(sender) => Player::Audio(sender),
+            Err(err) => {
+                eprintln!("Could not play sounds, the terminal bell is used: {}", err);
+                Player::Bell
+            }
+        },
+    };
+    PLAYER.get_or_init(|| (player, config.clone()));
+}
+
+/// Play the sound, unless its volume is 0
+pub fn play(sound: Sound) {
+    let Some((player, config)) = PLAYER.get() else {
+        return;
+    };
+    let volume = config.volume(sound);
+    if volume == 0.0 {
+        return;
+    }
+    match player {
+        Player::Off => (),
+        // The bell goes past ncurses, it doesn't change the screen
+        Player::Bell => {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+        }
+        Player::Audio(sender) => {
+            let _ = sender.send((sound, volume));
+        }
+    }
+}
+
+/// Open the sound card on a thread of its own, the sounds are sent to it
+#[cfg(feature = "audio")]
+fn start_audio() -> Result<Sender<(Sound, f32)>, String> {
+    use rodio::source::{SineWave, Source};
+    use rodio::{OutputStream, Sink};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let (sender, receiver) = mpsc::channel::<(Sound, f32)>();
+    let (opened, result) = mpsc::channel();
+    std::thread::spawn(move || {
+        // The stream plays for as long as it is kept
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => {
+                let _ = opened.send(Ok(()));
+                output
+            }
+            Err(err) => {
+                let _ = opened.send(Err(err.to_string()));
+                return;
+            }
+        };
+        for (sound, volume) in receiver {
+            match Sink::try_new(&handle) {
+                Ok(sink) => {
+                    for (frequency, millis) in sound.notes() {
+                        let tone = SineWave::new(*frequency)
+                            .take_duration(Duration::from_millis(*millis))
+                            .amplify(volume);
+                        sink.append(tone);
+                    }
+                    // The sounds can overlap, each plays on until it is done
+                    sink.detach();
+                }
+                Err(err) => log::warn!("Could not play {:?}: {}", sound, err),
+            }
+        }
+    });
+    result
+        .recv()
+        .map_err(|err| err.to_string())?
+        .map(|_| sender)
+}
+
+/// Without the `audio` feature there is only the bell
+#[cfg(not(feature = "audio"))]
+fn start_audio() -> Result<Sender<(Sound, f32)>, String> {
+    Err("this build has no audio support, build it with the audio feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sounds_are_muted_one_by_one() {
+        let config: SoundConfig = toml::from_str("output = \"bell\"\nkey = 0\nwon = 250").unwrap();
+        assert_eq!(config.output, Output::Bell);
+        assert_eq!(config.volume(Sound::Key), 0.0);
+        assert_eq!(config.volume(Sound::Won), 1.0);
+        assert_eq!(config.volume(Sound::Lost), 0.8);
+        assert!(!Sound::Invalid.notes().is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::endless::Run;
+use crate::history::date;
+use crate::rules::Pattern;
+use crate::storage;
+use crate::GameResult;
+use clap::ValueEnum;
+use itertools::Itertools;
+use lingo_core::rules::pattern_to_code;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const STATS_FILE: &str = "stats.json";
+/// The amount of games kept in the history, older games are dropped
+const MAX_HISTORY: usize = 1000;
+
+/// Statistics over all the games played, kept between runs
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub played: u32,
+    pub won: u32,
+    pub total_score: u64,
+    pub best_score: u32,
+    /// The most recent single word games, oldest first
+    pub history: Vec<Played>,
+    /// The most recent endless runs, oldest first
+    pub runs: Vec<Run>,
+}
+
+/// A game in the history, used to find the kinds of words that are hard to guess and to browse
+/// the past games. The fields after `guesses` are missing in games from older versions.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Played {
+    pub word: String,
+    pub won: bool,
+    pub guesses: u32,
+    /// When the game ended, in seconds since the Unix epoch
+    #[serde(default)]
+    pub at: u64,
+    #[serde(default)]
+    pub language: String,
+    #[serde(default)]
+    pub hard: bool,
+    #[serde(default)]
+    pub max_guesses: u32,
+    /// The verified guesses and their patterns
+    #[serde(default)]
+    pub guessed: Vec<String>,
+    #[serde(default)]
+    pub patterns: Vec<Pattern>,
+}
+
+impl Stats {
+    pub fn load() -> io::Result<Stats> {
+        storage::load_json(STATS_FILE)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save_json(STATS_FILE, self)
+    }
+
+    /// The amount of games in a row that were won, up to the last game
+    pub fn streak(&self) -> u32 {
+        self.history
+            .iter()
+            .rev()
+            .take_while(|played| played.won)
+            .count() as u32
+    }
+
+    /// Add a finished game in `language` to the statistics
+    pub fn record(&mut self, result: &GameResult, language: &str, hard: bool) {
+        self.played += 1;
+        if result.won {
+            self.won += 1;
+        }
+        self.total_score += result.score.total() as u64;
+        self.best_score = self.best_score.max(result.score.total());
+
+        // Games on more boards don't tell which word took how many guesses
+        if let [word] = result.words[..] {
+            self.history.push(Played {
+                word: word.to_string(),
+                won: result.won,
+                guesses: result.guesses,
+                at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |since| since.as_secs()),
+                language: language.to_string(),
+                hard,
+                max_guesses: result.max_guesses,
+                guessed: result.guessed.clone(),
+                patterns: result.patterns.clone(),
+            });
+            let overflow = self.history.len().saturating_sub(MAX_HISTORY);
+            self.history.drain(..overflow);
+        }
+    }
+
+    /// Add a finished endless run
+    pub fn record_run(&mut self, run: Run) {
+        self.runs.push(run);
+        let overflow = self.runs.len().saturating_sub(MAX_HISTORY);
+        self.runs.drain(..overflow);
+    }
+}
+
+/// The file formats the statistics can be exported to
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum ExportFormat {
+    /// A row for each game, followed by a table with the totals
+    Csv,
+    Json,
+}
+
+/// The totals over all games, the averages and the distribution only count the games in the
+/// history
+#[derive(Serialize)]
+struct Aggregates {
+    played: u32,
+    won: u32,
+    win_rate: f64,
+    total_score: u64,
+    best_score: u32,
+    /// The average guesses of the won games
+    average_guesses: f64,
+    /// The amount of won games for each amount of guesses
+    guess_distribution: BTreeMap<u32, u32>,
+}
+
+#[derive(Serialize)]
+struct Export<'a> {
+    aggregates: Aggregates,
+    games: &'a [Played],
+}
+
+impl Stats {
+    fn aggregates(&self) -> Aggregates {
+        let won = self
+            .history
+            .iter()
+            .filter(|played| played.won)
+            .collect_vec();
+        let mut guess_distribution = BTreeMap::new();
+        for played in &won {
+            *guess_distribution.entry(played.guesses).or_default() += 1;
+        }
+        Aggregates {
+            played: self.played,
+            won: self.won,
+            win_rate: match self.played {
+                0 => 0.0,
+                played => self.won as f64 / played as f64,
+            },
+            total_score: self.total_score,
+            best_score: self.best_score,
+            average_guesses: match won.len() {
+                0 => 0.0,
+                count => won.iter().map(|played| played.guesses).sum::<u32>() as f64 / count as f64,
+            },
+            guess_distribution,
+        }
+    }
+
+    /// Write the history and the totals for spreadsheets or other programs
+    pub fn export(&self, format: ExportFormat, output: &mut impl Write) -> io::Result<()> {
+        match format {
+            ExportFormat::Json => {
+                let export = Export {
+                    aggregates: self.aggregates(),
+                    games: &self.history,
+                };
+                serde_json::to_writer_pretty(&mut *output, &export).map_err(io::Error::other)?;
+                writeln!(output)
+            }
+            ExportFormat::Csv => {
+                writeln!(
+                    output,
+                    "date,timestamp,language,word,won,guesses,max_guesses,hard,guessed,patterns"
+                )?;
+                for played in &self.history {
+                    writeln!(
+                        output,
+                        "{},{},{},{},{},{},{},{},{},{}",
+                        if played.at == 0 {
+                            String::new()
+                        } else {
+                            date(played.at)
+                        },
+                        played.at,
+                        played.language,
+                        played.word,
+                        played.won,
+                        played.guesses,
+                        played.max_guesses,
+                        played.hard,
+                        played.guessed.join(" "),
+                        played.patterns.iter().map(pattern_to_code).join(" ")
+                    )?;
+                }
+
+                // The totals are a second table below the games
+                let aggregates = self.aggregates();
+                writeln!(output)?;
+                writeln!(output, "statistic,value")?;
+                writeln!(output, "played,{}", aggregates.played)?;
+                writeln!(output, "won,{}", aggregates.won)?;
+                writeln!(output, "win_rate,{:.3}", aggregates.win_rate)?;
+                writeln!(output, "total_score,{}", aggregates.total_score)?;
+                writeln!(output, "best_score,{}", aggregates.best_score)?;
+                writeln!(output, "average_guesses,{:.3}", aggregates.average_guesses)?;
+                for (guesses, count) in &aggregates.guess_distribution {
+                    writeln!(output, "won_in_{},{}", guesses, count)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
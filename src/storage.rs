@@ -0,0 +1,69 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// The profile of the player, set once at the start
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Keep the data of the player in this profile from now on
+pub fn use_profile(name: String) {
+    PROFILE
+        .set(name)
+        .expect("The profile should only be picked once");
+}
+
+/// The path of a file in the directory where the game keeps its data between runs
+pub fn data_path(file_name: &str) -> io::Result<PathBuf> {
+    match dirs::data_dir() {
+        None => Err(io::Error::new(
+            ErrorKind::NotFound,
+            "Could not determine the data directory",
+        )),
+        Some(dir) => Ok(dir.join("rust-lingo").join(file_name)),
+    }
+}
+
+/// The path of a file in the directory with the configuration of the game
+pub fn config_path(file_name: &str) -> io::Result<PathBuf> {
+    match dirs::config_dir() {
+        None => Err(io::Error::new(
+            ErrorKind::NotFound,
+            "Could not determine the config directory",
+        )),
+        Some(dir) => Ok(dir.join("rust-lingo").join(file_name)),
+    }
+}
+
+/// The path of a data file of the player, in the directory of their profile if they have one
+fn profile_path(file_name: &str) -> io::Result<PathBuf> {
+    match PROFILE.get() {
+        None => data_path(file_name),
+        Some(name) => Ok(data_path(crate::profile::PROFILES_DIR)?
+            .join(name)
+            .join(file_name)),
+    }
+}
+
+/// Load a json data file of the player, a file that doesn't exist yet results in the default value
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> io::Result<T> {
+    let path = profile_path(file_name)?;
+    match fs::read_to_string(&path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(err),
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|err| io::Error::new(ErrorKind::InvalidData, err)),
+    }
+}
+
+/// Save a value into a json data file of the player, creating the data directory if needed
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> io::Result<()> {
+    let path = profile_path(file_name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let content = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    fs::write(path, content)
+}
@@ -0,0 +1,147 @@
+//! The ways the solver can pick its next guess. A strategy only sees the candidates and the guesses
+//! it may make, so a new strategy is a type that implements `Strategy` and a name in `Builtin`, the
+//! games don't change.
+
+use crate::candidates::CandidateSet;
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Picks the next guess of the solver
+pub trait Strategy: Sync {
+    /// The next guess out of `guesses`, the candidates that may be guessed. There is always at
+    /// least one.
+    fn choose(&self, candidates: &CandidateSet, guesses: &[&'static str]) -> &'static str;
+}
+
+/// Any of the guesses, picked at random
+pub struct RandomValid;
+
+impl Strategy for RandomValid {
+    fn choose(&self, _candidates: &CandidateSet, guesses: &[&'static str]) -> &'static str {
+        guesses
+            .choose(&mut rand::thread_rng())
+            .expect("There should always be a guess")
+    }
+}
+
+/// Each guess gets points for how common its letters are among the candidates, both anywhere in
+/// the word and on the same place, so the guess tells the most about the remaining candidates.
+/// With equal points the last guess wins.
+pub struct LetterFrequency;
+
+impl Strategy for LetterFrequency {
+    fn choose(&self, candidates: &CandidateSet, guesses: &[&'static str]) -> &'static str {
+        let mut anywhere = HashMap::<char, u32>::new();
+        let mut placed = HashMap::<(usize, char), u32>::new();
+        for candidate in candidates.words() {
+            for (index, chr) in candidate.chars().enumerate() {
+                *placed.entry((index, chr)).or_default() += 1;
+            }
+            let mut seen = Vec::new();
+            for chr in candidate.chars() {
+                if !seen.contains(&chr) {
+                    seen.push(chr);
+                    *anywhere.entry(chr).or_default() += 1;
+                }
+            }
+        }
+
+        guesses
+            .par_iter()
+            .copied()
+            .max_by_key(|guess| {
+                let mut seen = Vec::new();
+                let mut points = 0;
+                for (index, chr) in guess.chars().enumerate() {
+                    points += placed.get(&(index, chr)).copied().unwrap_or_default();
+                    // Repeated letters tell nothing new about which letters are in the word
+                    if !seen.contains(&chr) {
+                        seen.push(chr);
+                        points += anywhere.get(&chr).copied().unwrap_or_default();
+                    }
+                }
+                points
+            })
+            .expect("There should always be a guess")
+    }
+}
+
+/// The guess whose pattern tells the most on average, the candidates are spread out over the
+/// patterns as evenly as possible. With equal information the first guess wins.
+pub struct Entropy;
+
+impl Strategy for Entropy {
+    fn choose(&self, candidates: &CandidateSet, guesses: &[&'static str]) -> &'static str {
+        // The information of a guess is the entropy of its bucket sizes. Summing n log n instead
+        // gives the same order without dividing by the amount of candidates, the lowest sum wins.
+        guesses
+            .par_iter()
+            .copied()
+            .map(|guess| {
+                let spread = candidates
+                    .bucket_sizes(guess)
+                    .iter()
+                    .filter(|size| **size > 1)
+                    .map(|size| *size as f64 * (*size as f64).ln())
+                    .sum::<f64>();
+                (guess, spread)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(guess, _)| guess)
+            .expect("There should always be a guess")
+    }
+}
+
+/// The guess whose largest bucket of candidates is the smallest, the best guess when the pattern
+/// is as bad as it can be. With equal buckets the first guess wins.
+pub struct Minimax;
+
+impl Strategy for Minimax {
+    fn choose(&self, candidates: &CandidateSet, guesses: &[&'static str]) -> &'static str {
+        guesses
+            .par_iter()
+            .copied()
+            .min_by_key(|guess| {
+                candidates
+                    .bucket_sizes(guess)
+                    .into_iter()
+                    .max()
+                    .unwrap_or_default()
+            })
+            .expect("There should always be a guess")
+    }
+}
+
+/// The strategies that can be picked on the command line
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Builtin {
+    /// Any word that can still be the answer
+    Random,
+    /// The word with the most common letters
+    #[default]
+    Frequency,
+    /// The word that tells the most on average
+    Entropy,
+    /// The word that leaves the fewest words when the pattern is as bad as it can be
+    Minimax,
+}
+
+impl Builtin {
+    pub fn strategy(self) -> &'static dyn Strategy {
+        match self {
+            Builtin::Random => &RandomValid,
+            Builtin::Frequency => &LetterFrequency,
+            Builtin::Entropy => &Entropy,
+            Builtin::Minimax => &Minimax,
+        }
+    }
+
+    /// The name of the strategy on the command line
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+}
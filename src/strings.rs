@@ -0,0 +1,715 @@
+//! The text on the screens in the languages the game is played in. The language is the one
+//! picked with --language, or the language of the locale without it, and English when there are
+//! no strings in that language. The messages on the command line are always in English.
+//!
+//! A `{}` in a string is filled in with `fill`, in the same order in every language.
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+pub struct Strings {
+    /// The ISO 639-1 code of the language
+    pub code: &'static str,
+    pub win: &'static str,
+    pub lost_word: &'static str,
+    pub lost_words: &'static str,
+    pub opponent_won: &'static str,
+    pub partner_turn: &'static str,
+    pub found: &'static str,
+    /// Between the words that were found with the same guess
+    pub and: &'static str,
+    pub not_in_dictionary: &'static str,
+    pub pasted: &'static str,
+    /// The time for a row ran out before the row was full
+    pub time_up: &'static str,
+    pub opener: &'static str,
+    pub zen_on: &'static str,
+    pub zen_off: &'static str,
+    pub zen_not_saved: &'static str,
+    pub hint: &'static str,
+    pub no_hint: &'static str,
+    /// The keys of the actions, in the order pause, guess, erase, hint, zen mode and help
+    pub help: &'static str,
+    /// The pause menu
+    pub paused: &'static str,
+    pub resume: &'static str,
+    pub restart: &'static str,
+    pub settings: &'static str,
+    pub quit: &'static str,
+    pub confirm_quit: &'static str,
+    /// The quick settings popup
+    pub quick_settings: &'static str,
+    pub theme: &'static str,
+    pub suggestions: &'static str,
+    pub on: &'static str,
+    pub off: &'static str,
+    pub quick_settings_help: &'static str,
+    pub worth: &'static str,
+    pub score: &'static str,
+    pub score_timed: &'static str,
+    /// The status line above the board
+    pub letters: &'static str,
+    pub hard: &'static str,
+    pub evil: &'static str,
+    pub timed: &'static str,
+    pub streak: &'static str,
+    pub lives: &'static str,
+    pub casual: &'static str,
+    pub practice: &'static str,
+    pub drill: &'static str,
+    pub network: &'static str,
+    pub group: &'static str,
+    pub team: &'static str,
+    pub endless: &'static str,
+    pub weekly: &'static str,
+    pub kids: &'static str,
+    /// The titles of the boards
+    pub guess_title: &'static str,
+    /// The title of a board without a limit on the guesses
+    pub guess_title_unlimited: &'static str,
+    pub board_title: &'static str,
+    pub solved_title: &'static str,
+    pub compose_hints: &'static str,
+    /// The keys of the keyboard below the board
+    pub enter_key: &'static str,
+    pub back_key: &'static str,
+    /// The feedback written out for screen readers
+    pub board_label: &'static str,
+    pub keyboard: &'static str,
+    pub correct: &'static str,
+    pub wrong_place: &'static str,
+    pub wrong: &'static str,
+    /// The team games
+    pub passed_on: &'static str,
+    pub team_name: &'static str,
+    pub team_turn: &'static str,
+    pub draw_balls: &'static str,
+    pub draw_ball: &'static str,
+    pub bonus_ball: &'static str,
+    pub red_ball: &'static str,
+    pub red: &'static str,
+    pub balls_drawn: &'static str,
+    pub team_scores: &'static str,
+    pub round_of: &'static str,
+    pub team_won: &'static str,
+    pub teams_tied: &'static str,
+    pub go_on: &'static str,
+    /// The bingo card of an endless run
+    pub bingo: &'static str,
+    pub bingo_draw: &'static str,
+    pub bingo_hit: &'static str,
+    pub bingo_miss: &'static str,
+    pub bingo_line: &'static str,
+    pub bingo_full: &'static str,
+    /// The puzzle of the week
+    pub week_word: &'static str,
+    pub week_total: &'static str,
+    pub week_summary: &'static str,
+    pub week_solved: &'static str,
+    pub week_lost: &'static str,
+    /// Watching the games of a server
+    pub player_title: &'static str,
+    pub spectating: &'static str,
+    pub no_games: &'static str,
+    pub server_gone: &'static str,
+    /// The title of the panel with the possible words in the ratatui interface
+    #[cfg_attr(not(feature = "ratatui"), allow(dead_code))]
+    pub possible_title: &'static str,
+    /// The line below the demo games of the attract mode
+    pub demo_banner: &'static str,
+    pub demo_found: &'static str,
+    /// The friendlier messages of kid mode
+    pub kids_win: &'static str,
+    pub kids_unknown_word: &'static str,
+    /// The anagram training
+    pub anagram_letters: &'static str,
+    pub anagram_other_letters: &'static str,
+    pub anagram_found: &'static str,
+    pub anagram_missed: &'static str,
+    pub anagram_score: &'static str,
+    /// The crossword mode
+    pub crossword_title: &'static str,
+    pub across_title: &'static str,
+    pub down_title: &'static str,
+    pub crossword_switch: &'static str,
+    pub crossword_shared: &'static str,
+    pub crossword_result: &'static str,
+    /// The sums of the math mode
+    pub math_target: &'static str,
+    pub math_not_a_sum: &'static str,
+    pub math_other_value: &'static str,
+    pub math_found: &'static str,
+    pub math_lost: &'static str,
+    /// The screens outside the game
+    pub definitions_title: &'static str,
+    pub definitions_scroll: &'static str,
+    pub definitions_close: &'static str,
+    pub history_title: &'static str,
+    pub history_empty: &'static str,
+    pub history_help: &'static str,
+    pub history_board: &'static str,
+    pub history_not_kept: &'static str,
+    pub history_other_language: &'static str,
+    pub unknown_date: &'static str,
+    pub won_in: &'static str,
+    pub lost: &'static str,
+    pub leaderboard_title: &'static str,
+    pub best_scores: &'static str,
+    pub fastest_solves: &'static str,
+    pub no_solved_games: &'static str,
+    pub guesses: &'static str,
+    pub profile_title: &'static str,
+    pub without_profile: &'static str,
+    pub profile_help: &'static str,
+    pub themes_title: &'static str,
+    pub normal_vision: &'static str,
+    pub themes_help: &'static str,
+    pub replay_playing: &'static str,
+    pub replay_paused: &'static str,
+    pub replay_help: &'static str,
+    pub script_failed: &'static str,
+    pub press_to_quit: &'static str,
+    pub press_to_continue: &'static str,
+    /// The review of the guesses
+    pub reviewing: &'static str,
+    pub review_title: &'static str,
+    pub review_line: &'static str,
+    pub candidate: &'static str,
+    pub candidates: &'static str,
+    pub best_available: &'static str,
+    /// The grades of a guess compared with the best guess
+    pub grade_best: &'static str,
+    pub grade_great: &'static str,
+    pub grade_good: &'static str,
+    pub grade_fair: &'static str,
+    pub grade_poor: &'static str,
+}
+
+pub const ENGLISH: Strings = Strings {
+    code: "en",
+    win: "You win with {} points! Press any key to quit",
+    lost_word: "The word was {}! Press any key to quit.",
+    lost_words: "The words were {}! Press any key to quit.",
+    opponent_won: "Your opponent found {} first! Press any key to quit.",
+    partner_turn: "Your partner is typing",
+    found: "You found {}!",
+    and: "and",
+    not_in_dictionary: "The word {} is not in the dictionary",
+    pasted: "The pasted {} is not a word of {} letters",
+    time_up: "Time's up, the row is lost",
+    opener: "A good first guess is {}",
+    zen_on: "Zen mode, press tab for the assistance",
+    zen_off: "The assistance is back, press tab for zen mode",
+    zen_not_saved: "Could not save zen mode: {}",
+    hint: "Try {}",
+    no_hint: "There are no hints in this game",
+    help: "{} pause  {} guess  {} erase  {} hint  {} zen mode  {} help",
+    paused: "PAUSED",
+    resume: "Resume",
+    restart: "Restart with a new word",
+    settings: "Settings",
+    quit: "Quit",
+    confirm_quit: "Give up this game? y/n",
+    quick_settings: "QUICK SETTINGS",
+    theme: "Theme",
+    suggestions: "Suggestions",
+    on: "on",
+    off: "off",
+    quick_settings_help: "arrows change  esc close",
+    worth: "Worth {} points",
+    score: "Score {} ({} + {} unused)",
+    score_timed: "Score {} ({} + {} unused + {} time)",
+    letters: "{} letters",
+    hard: "hard",
+    evil: "evil",
+    timed: "timed",
+    streak: "streak {}",
+    lives: "lives {}",
+    casual: "casual",
+    practice: "practice",
+    drill: "drill",
+    network: "network",
+    group: "group",
+    team: "team",
+    endless: "endless",
+    weekly: "weekly",
+    kids: "kids",
+    guess_title: "GUESS {}/{}",
+    guess_title_unlimited: "GUESS {}",
+    board_title: "WORD {}",
+    solved_title: "SOLVED",
+    compose_hints: "Type {}",
+    enter_key: "ENTER",
+    back_key: "BACK",
+    board_label: "Word {}: {}",
+    keyboard: "Keyboard: correct {}; in word {}; not in word {}",
+    correct: "{} correct",
+    wrong_place: "{} wrong place",
+    wrong: "{} wrong",
+    passed_on: "The word goes to the other team! Press any key to go on.",
+    team_name: "Team {}",
+    team_turn: "{} plays this word",
+    draw_balls: "{} draws balls",
+    draw_ball: "Press any key to draw a ball",
+    bonus_ball: "{} bonus points",
+    red_ball: "A red ball, the drawing ends",
+    red: "RED",
+    balls_drawn: "{} bonus points from the balls. Press any key to go on.",
+    team_scores: "{}: {} points    {}: {} points",
+    round_of: "Round {} of {}",
+    team_won: "{} wins!",
+    teams_tied: "It's a tie!",
+    go_on: "Press any key to go on",
+    bingo: "BINGO",
+    bingo_draw: "Press any key to draw a number for the card",
+    bingo_hit: "{} is on the card",
+    bingo_miss: "{} is not on the card",
+    bingo_line: "Bingo! {} points for the full lines",
+    bingo_full: "The card is full, the next words fill a new card",
+    week_word: "Week {}, word {} of {}",
+    week_total: "{} points so far",
+    week_summary: "Week {}: {} points",
+    week_solved: "Word {}: {}/{}, {} points",
+    week_lost: "Word {}: {} was not solved",
+    player_title: "PLAYER {}",
+    spectating: "Watching the games on {}, press q to stop",
+    no_games: "Nobody is playing, press q to stop",
+    server_gone: "The server closed the connection, press q to stop",
+    possible_title: "POSSIBLE WORDS ({})",
+    demo_banner: "DEMO - press any key to stop",
+    demo_found: "The computer found {} in {} guesses",
+    kids_win: "Well done! You found {} in {} guesses. Press any key",
+    kids_unknown_word: "Hmm, I don't know the word {}. Try another one!",
+    anagram_letters: "Put the letters {} in order",
+    anagram_other_letters: "Use the letters {}",
+    anagram_found: "The word is {}! Press a key for the next word or Escape to stop",
+    anagram_missed: "The word was {}! Press a key for the next word or Escape to stop",
+    anagram_score: "Solved {} of {} anagrams",
+    crossword_title: "CROSSWORD",
+    across_title: "ACROSS",
+    down_title: "DOWN",
+    crossword_switch: "{} switches between the words",
+    crossword_shared: "The shared letter {} is given away in the other word",
+    crossword_result: "Solved {} of the 2 words, across {} and down {}",
+    math_target: "Find the sum of {} characters that makes {}",
+    math_not_a_sum: "{} is not a sum",
+    math_other_value: "{} makes {}",
+    math_found: "You found {} in {} guesses! Press any key to quit",
+    math_lost: "The sum was {}! Press any key to quit.",
+    definitions_title: "DEFINITIONS",
+    definitions_scroll: "arrows scroll  esc close  {}/{}",
+    definitions_close: "esc close",
+    history_title: "HISTORY",
+    history_empty: "No finished games yet",
+    history_help:
+        "Up and down or j and k to pick a game, enter to show its board, r to review it, q to quit",
+    history_board: "{}. Press r to review the guesses, any other key to go back",
+    history_not_kept: "The guesses of this game weren't kept",
+    history_other_language: "This game was played in another language, pick it with --language",
+    unknown_date: "unknown date",
+    won_in: "won in {}",
+    lost: "lost",
+    leaderboard_title: "LEADERBOARD {} {} LETTERS",
+    best_scores: "Best scores",
+    fastest_solves: "Fastest solves",
+    no_solved_games: "No solved games yet",
+    guesses: "guesses",
+    profile_title: "WHO IS PLAYING?",
+    without_profile: "Without a profile",
+    profile_help: "Up and down to pick, enter to play. Start a new profile with --profile NAME",
+    themes_title: "THEMES",
+    normal_vision: "Normal vision",
+    themes_help: "up/down pick  enter save  q quit",
+    replay_playing: "playing",
+    replay_paused: "paused",
+    replay_help: "space pause  left/right step  [/] guess  +/- speed  q quit",
+    script_failed: "The on_game_end script failed: {}",
+    press_to_quit: "Press any key to quit",
+    press_to_continue: "Press any key to continue",
+    reviewing: "Reviewing the guesses...",
+    review_title: "REVIEW",
+    review_line: "{}  {}  left {} \u{2192} {} {}  {}",
+    candidate: "candidate",
+    candidates: "candidates",
+    best_available: "; best available was {}",
+    grade_best: "best",
+    grade_great: "great",
+    grade_good: "good",
+    grade_fair: "fair",
+    grade_poor: "poor",
+};
+
+pub const DUTCH: Strings = Strings {
+    code: "nl",
+    win: "Gewonnen met {} punten! Druk op een toets om te stoppen",
+    lost_word: "Het woord was {}! Druk op een toets om te stoppen.",
+    lost_words: "De woorden waren {}! Druk op een toets om te stoppen.",
+    opponent_won: "Je tegenstander vond {} als eerste! Druk op een toets om te stoppen.",
+    partner_turn: "Je partner is aan het typen",
+    found: "Je hebt {} gevonden!",
+    and: "en",
+    not_in_dictionary: "Het woord {} staat niet in het woordenboek",
+    pasted: "Het geplakte {} is geen woord van {} letters",
+    time_up: "De tijd is om, de beurt is verloren",
+    opener: "Een goede eerste gok is {}",
+    zen_on: "Zen-modus, druk op tab voor de hulp",
+    zen_off: "De hulp is terug, druk op tab voor de zen-modus",
+    zen_not_saved: "Kon de zen-modus niet bewaren: {}",
+    hint: "Probeer {}",
+    no_hint: "Er zijn geen hints in dit spel",
+    help: "{} pauze  {} raden  {} wissen  {} hint  {} zen-modus  {} hulp",
+    paused: "PAUZE",
+    resume: "Verder spelen",
+    restart: "Opnieuw met een nieuw woord",
+    settings: "Instellingen",
+    quit: "Stoppen",
+    confirm_quit: "Dit spel opgeven? j/n",
+    quick_settings: "SNELLE INSTELLINGEN",
+    theme: "Thema",
+    suggestions: "Suggesties",
+    on: "aan",
+    off: "uit",
+    quick_settings_help: "pijltjes wijzigen  esc sluiten",
+    worth: "{} punten waard",
+    score: "Score {} ({} + {} ongebruikt)",
+    score_timed: "Score {} ({} + {} ongebruikt + {} tijd)",
+    letters: "{} letters",
+    hard: "moeilijk",
+    evil: "gemeen",
+    timed: "op tijd",
+    streak: "reeks {}",
+    lives: "levens {}",
+    casual: "vrij",
+    practice: "oefenen",
+    drill: "training",
+    network: "netwerk",
+    group: "groep",
+    team: "team",
+    endless: "eindeloos",
+    weekly: "week",
+    kids: "kids",
+    guess_title: "BEURT {}/{}",
+    guess_title_unlimited: "BEURT {}",
+    board_title: "WOORD {}",
+    solved_title: "GERADEN",
+    compose_hints: "Typ {}",
+    enter_key: "ENTER",
+    back_key: "TERUG",
+    board_label: "Woord {}: {}",
+    keyboard: "Toetsenbord: goed {}; in het woord {}; niet in het woord {}",
+    correct: "{} goed",
+    wrong_place: "{} verkeerde plek",
+    wrong: "{} fout",
+    passed_on: "Het woord gaat naar het andere team! Druk op een toets om verder te gaan.",
+    team_name: "Team {}",
+    team_turn: "{} speelt dit woord",
+    draw_balls: "{} trekt ballen",
+    draw_ball: "Druk op een toets om een bal te trekken",
+    bonus_ball: "{} bonuspunten",
+    red_ball: "Een rode bal, het trekken stopt",
+    red: "ROOD",
+    balls_drawn: "{} bonuspunten uit de ballen. Druk op een toets om verder te gaan.",
+    team_scores: "{}: {} punten    {}: {} punten",
+    round_of: "Ronde {} van {}",
+    team_won: "{} wint!",
+    teams_tied: "Het is gelijkspel!",
+    go_on: "Druk op een toets om verder te gaan",
+    bingo: "BINGO",
+    bingo_draw: "Druk op een toets om een getal voor de kaart te trekken",
+    bingo_hit: "{} staat op de kaart",
+    bingo_miss: "{} staat niet op de kaart",
+    bingo_line: "Bingo! {} punten voor de volle rijen",
+    bingo_full: "De kaart is vol, de volgende woorden vullen een nieuwe kaart",
+    week_word: "Week {}, woord {} van {}",
+    week_total: "{} punten tot nu toe",
+    week_summary: "Week {}: {} punten",
+    week_solved: "Woord {}: {}/{}, {} punten",
+    week_lost: "Woord {}: {} is niet geraden",
+    player_title: "SPELER {}",
+    spectating: "Je kijkt naar de spellen op {}, druk op q om te stoppen",
+    no_games: "Er speelt niemand, druk op q om te stoppen",
+    server_gone: "De server heeft de verbinding gesloten, druk op q om te stoppen",
+    possible_title: "MOGELIJKE WOORDEN ({})",
+    demo_banner: "DEMO - druk op een toets om te stoppen",
+    demo_found: "De computer vond {} in {} beurten",
+    kids_win: "Goed zo! Je vond {} in {} beurten. Druk op een toets",
+    kids_unknown_word: "Hmm, het woord {} ken ik niet. Probeer een ander woord!",
+    anagram_letters: "Zet de letters {} op volgorde",
+    anagram_other_letters: "Gebruik de letters {}",
+    anagram_found:
+        "Het woord is {}! Druk op een toets voor het volgende woord of Escape om te stoppen",
+    anagram_missed:
+        "Het woord was {}! Druk op een toets voor het volgende woord of Escape om te stoppen",
+    anagram_score: "{} van de {} anagrammen opgelost",
+    crossword_title: "KRUISWOORD",
+    across_title: "HORIZONTAAL",
+    down_title: "VERTICAAL",
+    crossword_switch: "{} wisselt tussen de woorden",
+    crossword_shared: "De gedeelde letter {} is weggegeven in het andere woord",
+    crossword_result: "{} van de 2 woorden opgelost, horizontaal {} en verticaal {}",
+    math_target: "Zoek de som van {} tekens die {} maakt",
+    math_not_a_sum: "{} is geen som",
+    math_other_value: "{} maakt {}",
+    math_found: "Je vond {} in {} beurten! Druk op een toets om te stoppen",
+    math_lost: "De som was {}! Druk op een toets om te stoppen.",    definitions_title: "BETEKENISSEN",
+    definitions_scroll: "pijltjes scrollen  esc sluit  {}/{}",
+    definitions_close: "esc sluit",
+    history_title: "GESCHIEDENIS",
+    history_empty: "Nog geen uitgespeelde spellen",
+    history_help:
+        "Omhoog en omlaag of j en k om een spel te kiezen, enter toont het bord, r bespreekt het, q stopt",
+    history_board: "{}. Druk op r om de beurten te bespreken, op een andere toets om terug te gaan",
+    history_not_kept: "De beurten van dit spel zijn niet bewaard",
+    history_other_language: "Dit spel is in een andere taal gespeeld, kies die met --language",
+    unknown_date: "onbekende datum",
+    won_in: "gewonnen in {}",
+    lost: "verloren",
+    leaderboard_title: "RANGLIJST {} {} LETTERS",
+    best_scores: "Hoogste scores",
+    fastest_solves: "Snelst opgelost",
+    no_solved_games: "Nog geen opgeloste spellen",
+    guesses: "beurten",
+    profile_title: "WIE SPEELT ER?",
+    without_profile: "Zonder profiel",
+    profile_help:
+        "Omhoog en omlaag om te kiezen, enter om te spelen. Maak een nieuw profiel met --profile NAAM",
+    themes_title: "THEMA'S",
+    normal_vision: "Normaal zicht",
+    themes_help: "omhoog/omlaag kies  enter bewaar  q stop",
+    replay_playing: "speelt",
+    replay_paused: "gepauzeerd",
+    replay_help: "spatie pauze  links/rechts stap  [/] beurt  +/- snelheid  q stop",
+    script_failed: "Het on_game_end-script is mislukt: {}",
+    press_to_quit: "Druk op een toets om te stoppen",
+    press_to_continue: "Druk op een toets om verder te gaan",
+    reviewing: "De beurten worden besproken...",
+    review_title: "BESPREKING",
+    review_line: "{}  {}  over {} \u{2192} {} {}  {}",
+    candidate: "kandidaat",
+    candidates: "kandidaten",
+    best_available: "; de beste was {}",
+    grade_best: "beste",
+    grade_great: "heel goed",
+    grade_good: "goed",
+    grade_fair: "redelijk",
+    grade_poor: "zwak",
+};
+
+pub const STRINGS: [&Strings; 2] = [&ENGLISH, &DUTCH];
+
+static SELECTED: OnceLock<&'static Strings> = OnceLock::new();
+
+/// Show the game in the language with this code, or in the language of the locale without one.
+/// Only the first call counts.
+pub fn select(code: Option<&str>) {
+    let by_code = |code: &str| STRINGS.iter().copied().find(|strings| strings.code == code);
+    let strings = code
+        .and_then(by_code)
+        .or_else(|| locale_language().as_deref().and_then(by_code))
+        .unwrap_or(&ENGLISH);
+    SELECTED.get_or_init(|| strings);
+}
+
+/// The strings of the language the game is shown in
+pub fn strings() -> &'static Strings {
+    SELECTED.get_or_init(|| &ENGLISH)
+}
+
+/// The language of the locale, like nl for nl_NL.UTF-8
+fn locale_language() -> Option<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())?;
+    let language = locale.split(['_', '.', '@']).next()?;
+    Some(language.to_lowercase())
+}
+
+/// Put the values in the `{}` of the string, in order
+pub fn fill(string: &str, values: &[&dyn Display]) -> String {
+    let mut parts = string.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(value) = values.get(index) {
+            filled.push_str(&value.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every string of a language, in the order of the fields
+    fn all(strings: &Strings) -> Vec<&'static str> {
+        vec![
+            strings.win,
+            strings.lost_word,
+            strings.lost_words,
+            strings.opponent_won,
+            strings.partner_turn,
+            strings.found,
+            strings.and,
+            strings.not_in_dictionary,
+            strings.pasted,
+            strings.time_up,
+            strings.opener,
+            strings.zen_on,
+            strings.zen_off,
+            strings.zen_not_saved,
+            strings.hint,
+            strings.no_hint,
+            strings.help,
+            strings.paused,
+            strings.resume,
+            strings.restart,
+            strings.settings,
+            strings.quit,
+            strings.confirm_quit,
+            strings.quick_settings,
+            strings.theme,
+            strings.suggestions,
+            strings.on,
+            strings.off,
+            strings.quick_settings_help,
+            strings.worth,
+            strings.score,
+            strings.score_timed,
+            strings.letters,
+            strings.hard,
+            strings.evil,
+            strings.timed,
+            strings.streak,
+            strings.lives,
+            strings.casual,
+            strings.practice,
+            strings.drill,
+            strings.network,
+            strings.group,
+            strings.team,
+            strings.endless,
+            strings.weekly,
+            strings.kids,
+            strings.guess_title,
+            strings.guess_title_unlimited,
+            strings.board_title,
+            strings.solved_title,
+            strings.compose_hints,
+            strings.enter_key,
+            strings.back_key,
+            strings.board_label,
+            strings.keyboard,
+            strings.correct,
+            strings.wrong_place,
+            strings.wrong,
+            strings.passed_on,
+            strings.team_name,
+            strings.team_turn,
+            strings.draw_balls,
+            strings.draw_ball,
+            strings.bonus_ball,
+            strings.red_ball,
+            strings.red,
+            strings.balls_drawn,
+            strings.team_scores,
+            strings.round_of,
+            strings.team_won,
+            strings.teams_tied,
+            strings.go_on,
+            strings.bingo,
+            strings.bingo_draw,
+            strings.bingo_hit,
+            strings.bingo_miss,
+            strings.bingo_line,
+            strings.bingo_full,
+            strings.week_word,
+            strings.week_total,
+            strings.week_summary,
+            strings.week_solved,
+            strings.week_lost,
+            strings.player_title,
+            strings.spectating,
+            strings.no_games,
+            strings.server_gone,
+            strings.possible_title,
+            strings.demo_banner,
+            strings.demo_found,
+            strings.kids_win,
+            strings.kids_unknown_word,
+            strings.anagram_letters,
+            strings.anagram_other_letters,
+            strings.anagram_found,
+            strings.anagram_missed,
+            strings.anagram_score,
+            strings.crossword_title,
+            strings.across_title,
+            strings.down_title,
+            strings.crossword_switch,
+            strings.crossword_shared,
+            strings.crossword_result,
+            strings.math_target,
+            strings.math_not_a_sum,
+            strings.math_other_value,
+            strings.math_found,
+            strings.math_lost,
+            strings.definitions_title,
+            strings.definitions_scroll,
+            strings.definitions_close,
+            strings.history_title,
+            strings.history_empty,
+            strings.history_help,
+            strings.history_board,
+            strings.history_not_kept,
+            strings.history_other_language,
+            strings.unknown_date,
+            strings.won_in,
+            strings.lost,
+            strings.leaderboard_title,
+            strings.best_scores,
+            strings.fastest_solves,
+            strings.no_solved_games,
+            strings.guesses,
+            strings.profile_title,
+            strings.without_profile,
+            strings.profile_help,
+            strings.themes_title,
+            strings.normal_vision,
+            strings.themes_help,
+            strings.replay_playing,
+            strings.replay_paused,
+            strings.replay_help,
+            strings.script_failed,
+            strings.press_to_quit,
+            strings.press_to_continue,
+            strings.reviewing,
+            strings.review_title,
+            strings.review_line,
+            strings.candidate,
+            strings.candidates,
+            strings.best_available,
+            strings.grade_best,
+            strings.grade_great,
+            strings.grade_good,
+            strings.grade_fair,
+            strings.grade_poor,
+        ]
+    }
+
+    #[test]
+    fn every_language_fills_in_the_same_values() {
+        for strings in STRINGS {
+            for (english, string) in all(&ENGLISH).into_iter().zip(all(strings)) {
+                assert_eq!(
+                    english.matches("{}").count(),
+                    string.matches("{}").count(),
+                    "{}: {}",
+                    strings.code,
+                    string
+                );
+            }
+        }
+        assert_eq!(fill(DUTCH.guess_title, &[&2, &5]), "BEURT 2/5".to_string());
+    }
+}
@@ -0,0 +1,212 @@
+//! Team games like the TV show. Two teams take turns on the words on one machine, the team gets
+//! the first letter of the word. A team that solves the word scores and draws balls from the bowl
+//! for bonus points. A word that isn't solved goes to the other team with one more letter and a
+//! single guess after the guesses that were made on it.
+
+use crate::announce::Announcer;
+use crate::dictionary::Dictionary;
+use crate::events::EventSink;
+use crate::play::play_game;
+use crate::render::show;
+use crate::strings::{fill, strings};
+use crate::{init_ncurses, pick_words, GameOptions, GameResult, Stopped};
+use rand::seq::SliceRandom;
+
+/// The points for solving a word
+const WORD_POINTS: u32 = 25;
+/// The balls in the bowl, the bonus points of a ball or `None` for a red ball
+const BOWL: [Option<u32>; 8] = [
+    Some(10),
+    Some(10),
+    Some(15),
+    Some(15),
+    Some(20),
+    Some(25),
+    None,
+    None,
+];
+/// The most balls a team draws after solving a word
+const DRAWS: usize = 2;
+
+/// The turn of a team on a word
+pub struct Turn {
+    /// The letters at the start of the word that are typed in every row
+    pub revealed: usize,
+    /// The guesses the other team made on the word before it was passed on
+    pub earlier: Vec<String>,
+    /// When the guesses run out the word goes to the other team, so it isn't shown
+    pub passes_on: bool,
+}
+
+/// Draw balls from the bowl after a solved word, until a red ball comes out or the team drew all
+/// its balls. Every ball is drawn with a key. Returns the bonus points of the balls.
+fn draw_balls(team: &str) -> u32 {
+    let strings = strings();
+    let mut rng = rand::thread_rng();
+    let mut bowl = BOWL.to_vec();
+    bowl.shuffle(&mut rng);
+    let label = |ball: Option<u32>| match ball {
+        Some(points) => format!("( {:>2} )", points),
+        None => format!("( {} )", strings.red),
+    };
+
+    let title = fill(strings.draw_balls, &[&team]).to_uppercase();
+    let mut drawn: Vec<String> = Vec::new();
+    let mut bonus = 0;
+    let mut message = strings.draw_ball.to_string();
+    for _ in 0..DRAWS {
+        show(&[title.clone(), drawn.join("  "), message.clone()], true);
+        // The balls roll around for a moment before one comes out
+        for _ in 0..8 {
+            let rolling = *BOWL.choose(&mut rng).expect("The bowl isn't empty");
+            show(&[title.clone(), drawn.join("  "), label(rolling)], false);
+            ncurses::napms(80);
+        }
+        let ball = bowl.pop().expect("There are more balls than draws");
+        drawn.push(label(ball));
+        match ball {
+            Some(points) => {
+                bonus += points;
+                message = fill(strings.bonus_ball, &[&points]);
+            }
+            None => {
+                message = strings.red_ball.to_string();
+                break;
+            }
+        }
+    }
+    show(
+        &[
+            title,
+            drawn.join("  "),
+            message,
+            fill(strings.balls_drawn, &[&bonus]),
+        ],
+        true,
+    );
+    bonus
+}
+
+/// The lines with the points of both teams
+fn scores(names: &[String; 2], points: [u32; 2]) -> String {
+    fill(
+        strings().team_scores,
+        &[&names[0], &points[0], &names[1], &points[1]],
+    )
+}
+
+/// Let a team play its turn on the word, `None` when the game was quit
+fn play_turn(
+    dictionary: &Dictionary,
+    alphabet: &[char],
+    word: &'static str,
+    announcer: &mut Announcer,
+    events: &EventSink,
+    options: &GameOptions,
+    turn: Turn,
+) -> Result<Option<GameResult>, String> {
+    let options = GameOptions {
+        turn: Some(turn),
+        ..*options
+    };
+    match play_game(
+        dictionary,
+        alphabet.to_vec(),
+        vec![word],
+        announcer,
+        events,
+        None,
+        &options,
+    ) {
+        Ok(result) => Ok(Some(result)),
+        Err(Stopped::Failed(err)) => Err(err.to_string()),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Play the rounds with two teams, the teams start a round in turns. Returns the points of the
+/// teams.
+pub fn play(
+    rounds: u8,
+    dictionary: &Dictionary,
+    answers: &[&'static str],
+    alphabet: &[char],
+    announcer: &mut Announcer,
+    events: &EventSink,
+    options: &GameOptions,
+) -> Result<[u32; 2], String> {
+    let strings = strings();
+    let names = [1, 2].map(|number| fill(strings.team_name, &[&number]));
+    let mut points = [0; 2];
+    // The screens between the turns
+    let announce = |round: u8, points: [u32; 2], text: String| {
+        let _window_ender = init_ncurses(options.theme);
+        ncurses::timeout(-1);
+        show(
+            &[
+                fill(strings.round_of, &[&(round + 1), &rounds]),
+                scores(&names, points),
+                text,
+                strings.go_on.to_string(),
+            ],
+            true,
+        );
+    };
+
+    for round in 0..rounds {
+        let team = round as usize % 2;
+        let word = pick_words(answers, 1, None)[0];
+        announce(round, points, fill(strings.team_turn, &[&names[team]]));
+        let first = Turn {
+            revealed: 1,
+            earlier: Vec::new(),
+            passes_on: true,
+        };
+        let turn = play_turn(
+            dictionary, alphabet, word, announcer, events, options, first,
+        )?;
+        let Some(result) = turn else {
+            break;
+        };
+
+        let solver = if result.won {
+            Some(team)
+        } else {
+            // The other team gets the next letter and one guess
+            let other = 1 - team;
+            announce(round, points, fill(strings.team_turn, &[&names[other]]));
+            let second = Turn {
+                revealed: 2,
+                earlier: result.guessed,
+                passes_on: false,
+            };
+            let turn = play_turn(
+                dictionary, alphabet, word, announcer, events, options, second,
+            )?;
+            let Some(result) = turn else {
+                break;
+            };
+            result.won.then_some(other)
+        };
+        if let Some(solver) = solver {
+            let _window_ender = init_ncurses(options.theme);
+            ncurses::timeout(-1);
+            points[solver] += WORD_POINTS + draw_balls(&names[solver]);
+        }
+    }
+
+    let outcome = match points[0].cmp(&points[1]) {
+        std::cmp::Ordering::Greater => fill(strings.team_won, &[&names[0]]),
+        std::cmp::Ordering::Less => fill(strings.team_won, &[&names[1]]),
+        std::cmp::Ordering::Equal => strings.teams_tied.to_string(),
+    };
+    {
+        let _window_ender = init_ncurses(options.theme);
+        ncurses::timeout(-1);
+        show(
+            &[scores(&names, points), outcome, strings.go_on.to_string()],
+            true,
+        );
+    }
+    Ok(points)
+}
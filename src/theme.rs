@@ -0,0 +1,396 @@
+use crate::graphics;
+use crate::keymap::{ENTER, ESCAPE};
+use crate::navigation::Navigator;
+use crate::settings::Settings;
+use crate::strings::strings;
+use crate::{
+    COLOR_PAIR_CORRECT, COLOR_PAIR_CORRECT_TILE, COLOR_PAIR_ERROR, COLOR_PAIR_WARNING,
+    COLOR_PAIR_WRONG_PLACE, COLOR_PAIR_WRONG_PLACE_TILE, COLOR_PAIR_WRONG_TILE,
+};
+
+/// A color as red, green and blue components
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// The colors used for the verified letters
+pub struct Theme {
+    pub name: &'static str,
+    pub correct: Rgb,
+    pub wrong_place: Rgb,
+    /// If the verified tiles are filled with their color, like the web game. Otherwise only the
+    /// letters are colored.
+    pub filled: bool,
+}
+
+/// The gray of the filled tiles with a letter that isn't in the word
+const WRONG: Rgb = Rgb(58, 58, 60);
+
+/// The green and yellow of the terminal
+pub const CLASSIC: Theme = Theme {
+    name: "classic",
+    correct: Rgb(0, 205, 0),
+    wrong_place: Rgb(205, 205, 0),
+    filled: true,
+};
+
+/// The classic colors on the letters only, on a black board
+pub const LETTERS: Theme = Theme {
+    name: "letters",
+    correct: Rgb(0, 205, 0),
+    wrong_place: Rgb(205, 205, 0),
+    filled: false,
+};
+
+/// Orange and blue, these stay apart for most kinds of color blindness
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    correct: Rgb(245, 121, 58),
+    wrong_place: Rgb(133, 192, 249),
+    filled: true,
+};
+
+/// Blue and yellow, which only get close with tritanopia
+pub const OCEAN: Theme = Theme {
+    name: "ocean",
+    correct: Rgb(0, 114, 178),
+    wrong_place: Rgb(230, 159, 0),
+    filled: true,
+};
+
+pub const THEMES: [&Theme; 4] = [&CLASSIC, &LETTERS, &HIGH_CONTRAST, &OCEAN];
+
+impl Theme {
+    pub fn by_name(name: &str) -> Option<&'static Theme> {
+        THEMES.iter().copied().find(|theme| theme.name == name)
+    }
+
+    /// The theme picked in the settings, the classic theme if none was picked
+    pub fn from_settings(settings: &Settings) -> &'static Theme {
+        Theme::by_name(&settings.theme).unwrap_or(&CLASSIC)
+    }
+
+    /// Set up the color pairs of the game for this theme, ncurses has to be initialized
+    pub fn apply(&self) {
+        graphics::use_colors(
+            self.filled
+                .then_some([self.correct, self.wrong_place, WRONG]),
+        );
+        let correct = exact_color(CUSTOM_COLORS, self.correct);
+        let wrong_place = exact_color(CUSTOM_COLORS + 1, self.wrong_place);
+        ncurses::init_pair(COLOR_PAIR_CORRECT, correct, ncurses::COLOR_BLACK);
+        ncurses::init_pair(COLOR_PAIR_WRONG_PLACE, wrong_place, ncurses::COLOR_BLACK);
+        if self.filled {
+            // Black letters on the color, white on the gray
+            let wrong = exact_color(CUSTOM_COLORS + 2, WRONG);
+            ncurses::init_pair(COLOR_PAIR_CORRECT_TILE, ncurses::COLOR_BLACK, correct);
+            ncurses::init_pair(
+                COLOR_PAIR_WRONG_PLACE_TILE,
+                ncurses::COLOR_BLACK,
+                wrong_place,
+            );
+            ncurses::init_pair(COLOR_PAIR_WRONG_TILE, ncurses::COLOR_WHITE, wrong);
+        } else {
+            ncurses::init_pair(COLOR_PAIR_CORRECT_TILE, correct, ncurses::COLOR_BLACK);
+            ncurses::init_pair(
+                COLOR_PAIR_WRONG_PLACE_TILE,
+                wrong_place,
+                ncurses::COLOR_BLACK,
+            );
+            ncurses::init_pair(COLOR_PAIR_WRONG_TILE, -1, -1);
+        }
+        // The messages keep the colors of the terminal in every theme
+        ncurses::init_pair(COLOR_PAIR_WARNING, ncurses::COLOR_YELLOW, -1);
+        ncurses::init_pair(COLOR_PAIR_ERROR, ncurses::COLOR_RED, -1);
+    }
+}
+
+/// How many colors the terminal can show
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    /// The 8 colors of a standard terminal
+    Basic,
+    /// The 256 colors of the xterm palette
+    Extended,
+    /// The colors of the palette can be changed to any color
+    True,
+}
+
+impl ColorDepth {
+    /// The color depth of the terminal, ncurses has to be initialized
+    pub fn detect() -> ColorDepth {
+        if ncurses::COLORS() < 256 {
+            ColorDepth::Basic
+        } else if ncurses::can_change_color() {
+            ColorDepth::True
+        } else {
+            ColorDepth::Extended
+        }
+    }
+}
+
+/// The first of the colors in the palette that are changed to the exact colors of the theme, on
+/// terminals that allow it. The grays here are not used otherwise.
+const CUSTOM_COLORS: i16 = 240;
+
+/// The colors of a standard terminal, as xterm shows them
+const BASIC_COLORS: [Rgb; 8] = [
+    Rgb(0, 0, 0),
+    Rgb(205, 0, 0),
+    Rgb(0, 205, 0),
+    Rgb(205, 205, 0),
+    Rgb(0, 0, 238),
+    Rgb(205, 0, 205),
+    Rgb(0, 205, 205),
+    Rgb(229, 229, 229),
+];
+
+/// The color of the xterm 256 color palette with the given index, only for the 6x6x6 cube and the
+/// grays that follow it
+fn extended_color(index: i16) -> Rgb {
+    let level = |value: i16| if value == 0 { 0 } else { 55 + 40 * value as u8 };
+    if index < 232 {
+        let index = index - 16;
+        Rgb(level(index / 36), level(index / 6 % 6), level(index % 6))
+    } else {
+        let gray = 8 + 10 * (index - 232) as u8;
+        Rgb(gray, gray, gray)
+    }
+}
+
+fn distance(a: Rgb, b: Rgb) -> u32 {
+    let square = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    square(a.0, b.0) + square(a.1, b.1) + square(a.2, b.2)
+}
+
+/// The closest color the terminal can show, the extended colors are only used if the terminal
+/// has at least 256 colors
+pub fn terminal_color(rgb: Rgb) -> i16 {
+    let extended = match ColorDepth::detect() {
+        ColorDepth::Basic => 0..0,
+        ColorDepth::Extended | ColorDepth::True => 16..256,
+    };
+    (0..8)
+        .map(|index| (index, BASIC_COLORS[index as usize]))
+        .chain(extended.map(|index| (index, extended_color(index))))
+        .min_by_key(|(_, color)| distance(rgb, *color))
+        .map(|(index, _)| index)
+        .unwrap_or(ncurses::COLOR_WHITE)
+}
+
+/// The color itself when the terminal can change its palette, `slot` is changed to it. Otherwise
+/// the closest color the terminal can show.
+fn exact_color(slot: i16, rgb: Rgb) -> i16 {
+    if ColorDepth::detect() != ColorDepth::True {
+        return terminal_color(rgb);
+    }
+    // ncurses takes the components from 0 to 1000
+    let scale = |value: u8| (value as i32 * 1000 / 255) as i16;
+    ncurses::init_color(slot, scale(rgb.0), scale(rgb.1), scale(rgb.2));
+    slot
+}
+
+/// Kinds of color blindness that can be simulated
+#[derive(Clone, Copy)]
+pub enum ColorBlindness {
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl ColorBlindness {
+    pub const ALL: [ColorBlindness; 3] = [
+        ColorBlindness::Deuteranopia,
+        ColorBlindness::Protanopia,
+        ColorBlindness::Tritanopia,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorBlindness::Deuteranopia => "Deuteranopia",
+            ColorBlindness::Protanopia => "Protanopia",
+            ColorBlindness::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// The matrices from Machado, Oliveira and Fernandes (2009) at full severity, these work on
+    /// linear rgb
+    fn matrix(&self) -> [[f64; 3]; 3] {
+        match self {
+            ColorBlindness::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorBlindness::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorBlindness::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    /// Approximately how the color looks with this kind of color blindness
+    pub fn simulate(&self, rgb: Rgb) -> Rgb {
+        let to_linear = |value: u8| {
+            let value = value as f64 / 255.0;
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let to_srgb = |value: f64| {
+            let value = value.clamp(0.0, 1.0);
+            let value = if value <= 0.0031308 {
+                value * 12.92
+            } else {
+                1.055 * value.powf(1.0 / 2.4) - 0.055
+            };
+            (value * 255.0).round() as u8
+        };
+
+        let linear = [to_linear(rgb.0), to_linear(rgb.1), to_linear(rgb.2)];
+        let matrix = self.matrix();
+        let channel = |row: usize| {
+            to_srgb(
+                matrix[row]
+                    .iter()
+                    .zip(linear.iter())
+                    .map(|(a, b)| a * b)
+                    .sum(),
+            )
+        };
+        Rgb(channel(0), channel(1), channel(2))
+    }
+}
+
+/// The first color pair used by the preview, the pairs below it are used by the game
+const PREVIEW_COLOR_PAIRS: i16 = 16;
+
+/// The theme settings screen, shows the themes with a preview of how each looks with the kinds of
+/// color blindness. The picked theme is saved in the settings.
+pub fn theme_settings(settings: &mut Settings) {
+    let _window_ender = crate::init_ncurses(Theme::from_settings(settings));
+
+    let mut selected = THEMES
+        .iter()
+        .position(|theme| theme.name == Theme::from_settings(settings).name)
+        .unwrap_or(0);
+    let mut navigator = Navigator::default();
+
+    loop {
+        ncurses::clear();
+        let mut max_x = 0;
+        let mut max_y = 0;
+        ncurses::getmaxyx(ncurses::stdscr(), &mut max_y, &mut max_x);
+        let win_x = (max_x - 44) / 2;
+        let win_y = (max_y - (THEMES.len() as i32 + 12)) / 2;
+
+        ncurses::mvaddstr(win_y, win_x, strings().themes_title);
+        for (index, theme) in THEMES.iter().enumerate() {
+            let marker = if index == selected { "> " } else { "  " };
+            ncurses::mvaddstr(
+                win_y + 2 + index as i32,
+                win_x,
+                &format!("{}{}", marker, theme.name),
+            );
+        }
+
+        // The preview of the selected theme, the tiles show how the correct and wrong place
+        // colors look next to each other
+        let theme = THEMES[selected];
+        let preview_y = win_y + 3 + THEMES.len() as i32;
+        let variants = std::iter::once((strings().normal_vision, theme.correct, theme.wrong_place))
+            .chain(ColorBlindness::ALL.iter().map(|blindness| {
+                (
+                    blindness.name(),
+                    blindness.simulate(theme.correct),
+                    blindness.simulate(theme.wrong_place),
+                )
+            }));
+        for (row, (name, correct, wrong_place)) in variants.enumerate() {
+            let y = preview_y + row as i32;
+            ncurses::mvaddstr(y, win_x, name);
+            for (column, color) in [correct, wrong_place].into_iter().enumerate() {
+                let pair = PREVIEW_COLOR_PAIRS + 2 * row as i16 + column as i16;
+                // The colors of the theme itself keep their own slots
+                let slot = CUSTOM_COLORS + 3 + pair - PREVIEW_COLOR_PAIRS;
+                ncurses::init_pair(pair, exact_color(slot, color), ncurses::COLOR_BLACK);
+                let x = win_x + 20 + 10 * column as i32;
+                // A reversed block shows the color itself, next to a letter in the color
+                let attribute = ncurses::A_REVERSE() | ncurses::COLOR_PAIR(pair);
+                ncurses::attron(attribute);
+                ncurses::mvaddstr(y, x, "    ");
+                ncurses::attroff(attribute);
+                let attribute = ncurses::A_BOLD() | ncurses::COLOR_PAIR(pair);
+                ncurses::attron(attribute);
+                ncurses::mvaddstr(y, x + 5, "A");
+                ncurses::attroff(attribute);
+            }
+        }
+
+        let help = strings().themes_help;
+        ncurses::mvaddstr(preview_y + 6, (max_x - help.len() as i32) / 2, help);
+        ncurses::refresh();
+
+        let input = ncurses::getch();
+        if let Some(movement) = navigator.read(input) {
+            selected = movement.apply(selected, THEMES.len() - 1, THEMES.len());
+        } else if ENTER.contains(&input) {
+            settings.theme = THEMES[selected].name.to_string();
+            return;
+        } else if ['q' as i32, ESCAPE].contains(&input) {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_color_blind_themes_keep_the_feedback_apart() {
+        // At least 100 apart in the rgb space, the classic green and yellow are 18 apart with
+        // protanopia
+        const MIN_DISTANCE: u32 = 100 * 100;
+        for theme in [&HIGH_CONTRAST, &OCEAN] {
+            let colors = [theme.correct, theme.wrong_place, WRONG];
+            let variants = std::iter::once(colors).chain(
+                ColorBlindness::ALL
+                    .iter()
+                    .map(|blindness| colors.map(|color| blindness.simulate(color))),
+            );
+            for (variant, [correct, wrong_place, wrong]) in variants.enumerate() {
+                for (a, b) in [
+                    (correct, wrong_place),
+                    (correct, wrong),
+                    (wrong_place, wrong),
+                ] {
+                    assert!(
+                        distance(a, b) >= MIN_DISTANCE,
+                        "{} in variant {}: {:?} and {:?}",
+                        theme.name,
+                        variant,
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simulating_keeps_black_and_white() {
+        for blindness in ColorBlindness::ALL {
+            assert_eq!(blindness.simulate(Rgb(0, 0, 0)), Rgb(0, 0, 0));
+            let white = blindness.simulate(Rgb(255, 255, 255));
+            assert!(distance(white, Rgb(255, 255, 255)) <= 3 * 2 * 2);
+        }
+    }
+}
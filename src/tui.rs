@@ -0,0 +1,662 @@
+//! The game in a ratatui interface, picked with `--ui ratatui`. The screen is built from widgets: a
+//! board, the keyboard, a side panel with the words that are still possible and popups for the
+//! help, the pause menu and the end of the game. Layout and styling are left to ratatui instead of
+//! placing every character. The rules come from the engine like in the ncurses interface. Only
+//! games on one board without an opponent are played this way, the other games use ncurses.
+
+use clap::ValueEnum;
+
+/// The interface the game is shown in
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Ui {
+    /// Drawn with ncurses, every game
+    #[default]
+    Ncurses,
+    /// Drawn with ratatui widgets, for games on one board without an opponent. Needs a build
+    /// with the ratatui feature.
+    Ratatui,
+}
+
+#[cfg(feature = "ratatui")]
+mod widgets {
+    use crate::dictionary::Dictionary;
+    use crate::editor::GuessEditor;
+    use crate::engine::{self, GameState, Transition};
+    use crate::error::Error;
+    use crate::events::{Event, EventSink};
+    use crate::input::{self, InputEvent};
+    use crate::integrity::{Assistance, Mode};
+    use crate::keyboard::{self, KeyAction};
+    use crate::keymap::{Action, Bindings, ESCAPE};
+    use crate::messages::{Notice, Severity};
+    use crate::pause::Choice;
+    use crate::rules::{self, Feedback};
+    use crate::score::Score;
+    use crate::sound::{self, Sound};
+    use crate::strings::{fill, strings};
+    use crate::theme::{Rgb, Theme};
+    use crate::{announce, word};
+    use crate::{
+        key_feedback, max_guesses, possible_words, GameInfo, GameOptions, GameResult,
+        GuessedLetter, GuessedWord, Stopped, WORD_LENGTH,
+    };
+    use defer::defer;
+    use ratatui::crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use ratatui::layout::{Constraint, Layout, Rect};
+    use ratatui::style::{Color, Style, Stylize};
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, Clear, List, Padding, Paragraph};
+    use ratatui::Frame;
+    use std::collections::HashMap;
+    use std::io;
+    use std::time::Instant;
+
+    /// The width of the panel with the possible words
+    const PANEL_WIDTH: u16 = 24;
+    /// The words that are scrolled with page up and page down
+    const PAGE: usize = 10;
+
+    /// What is shown on top of the game
+    enum Popup {
+        Help(String),
+        Pause {
+            selected: usize,
+            /// The question if the game should be given up is asked
+            confirming: bool,
+        },
+        /// The game ended, with the message of how it ended
+        End(String),
+    }
+
+    /// What the player sees of the game
+    struct View<'a> {
+        options: &'a GameOptions<'a>,
+        alphabet: &'a [char],
+        info: GameInfo,
+        rows: Vec<GuessedWord>,
+        editor: GuessEditor,
+        possible: Vec<&'static str>,
+        scroll: usize,
+        key_feedback: HashMap<char, Feedback>,
+        assistance: Assistance,
+        zen: bool,
+        message: Option<Notice>,
+        popup: Option<Popup>,
+    }
+
+    fn color(rgb: Rgb) -> Color {
+        Color::Rgb(rgb.0, rgb.1, rgb.2)
+    }
+
+    /// The style of a tile or key with the feedback, like the colors of the theme
+    fn feedback_style(feedback: Feedback, theme: &Theme) -> Style {
+        let color = match feedback {
+            Feedback::Correct => color(theme.correct),
+            Feedback::WrongPlace => color(theme.wrong_place),
+            Feedback::Wrong => Color::DarkGray,
+        };
+        match (theme.filled, feedback) {
+            (true, Feedback::Wrong) => Style::new().fg(Color::White).bg(color),
+            (true, _) => Style::new().fg(Color::Black).bg(color).bold(),
+            (false, _) => Style::new().fg(color).bold(),
+        }
+    }
+
+    /// A rectangle of the size in the middle of the area
+    fn centered(area: Rect, width: u16, height: u16) -> Rect {
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(width),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        area
+    }
+
+    impl View<'_> {
+        fn shown(&self) -> Assistance {
+            match self.zen {
+                true => self.assistance.zen(),
+                false => self.assistance,
+            }
+        }
+
+        /// The pause menu, restart is only offered when the game can be started over
+        fn choices(&self) -> Vec<(Choice, &'static str)> {
+            let strings = strings();
+            let restart = matches!(self.options.mode, Mode::Casual | Mode::Practice);
+            [
+                (Choice::Resume, strings.resume),
+                (Choice::Restart, strings.restart),
+                (Choice::Quit, strings.quit),
+            ]
+            .into_iter()
+            .filter(|(choice, _)| restart || *choice != Choice::Restart)
+            .collect()
+        }
+
+        /// A tile of a row, with a space on both sides of the letter
+        fn tile(&self, letter: GuessedLetter, cursor: bool) -> Span<'static> {
+            let label = |chr: char| format!("{:^3}", word::display(chr));
+            let span = match (letter, letter.feedback()) {
+                (GuessedLetter::NoLetter, _) => Span::styled(" \u{b7} ", Style::new().dark_gray()),
+                (GuessedLetter::Letter(chr), _) => Span::styled(label(chr), Style::new().bold()),
+                (GuessedLetter::Separator, _) => Span::raw(" - "),
+                (
+                    GuessedLetter::Wrong(chr)
+                    | GuessedLetter::WrongPlace(chr)
+                    | GuessedLetter::Correct(chr),
+                    Some(feedback),
+                ) => {
+                    let text = match self.options.a11y {
+                        true => {
+                            let [open, close] = announce::marks(feedback);
+                            format!("{}{}{}", open, word::display(chr), close)
+                        }
+                        false => label(chr),
+                    };
+                    Span::styled(text, feedback_style(feedback, self.options.theme))
+                }
+                _ => Span::raw("   "),
+            };
+            match cursor {
+                true => span.reversed(),
+                false => span,
+            }
+        }
+
+        /// The rows of the board with an empty line between them, the guess being typed on its row
+        fn board(&self, typing: Option<usize>) -> Vec<Line<'static>> {
+            let mut lines = Vec::new();
+            for (index, row) in self.rows.iter().enumerate() {
+                let mut row = *row;
+                let mut cursor = None;
+                if typing == Some(index) {
+                    for (letter, chr) in row.iter_mut().zip(self.editor.text().chars()) {
+                        *letter = GuessedLetter::Letter(chr);
+                    }
+                    cursor = self.editor.cursor();
+                }
+                let mut spans = Vec::new();
+                for (place, letter) in row.iter().enumerate() {
+                    if place > 0 {
+                        spans.push(Span::raw(" "));
+                    }
+                    spans.push(self.tile(*letter, cursor == Some(place)));
+                }
+                if index > 0 {
+                    lines.push(Line::default());
+                }
+                lines.push(Line::from(spans));
+            }
+            lines
+        }
+
+        /// The keys of the keyboard colored by what is known about their letters
+        fn keyboard(&self) -> Vec<Line<'static>> {
+            let strings = strings();
+            keyboard::rows(self.alphabet)
+                .into_iter()
+                .map(|row| {
+                    let mut spans = Vec::new();
+                    for (index, key) in row.into_iter().enumerate() {
+                        if index > 0 {
+                            spans.push(Span::raw(" "));
+                        }
+                        let (label, known) = match key {
+                            KeyAction::Letter(letter) => (
+                                word::display(letter),
+                                self.key_feedback
+                                    .get(&letter)
+                                    .filter(|_| self.shown().keyboard_hints)
+                                    .copied(),
+                            ),
+                            KeyAction::Enter => (strings.enter_key.to_string(), None),
+                            KeyAction::Backspace => (strings.back_key.to_string(), None),
+                        };
+                        let style = match known {
+                            Some(feedback) => feedback_style(feedback, self.options.theme),
+                            None => Style::new().reversed(),
+                        };
+                        spans.push(Span::styled(format!(" {} ", label), style));
+                    }
+                    Line::from(spans).centered()
+                })
+                .collect()
+        }
+
+        fn draw(&self, frame: &mut Frame, typing: Option<usize>) {
+            let keyboard = self.keyboard();
+            let [status, middle, message, keys] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(keyboard.len() as u16),
+            ])
+            .areas(frame.area());
+            frame.render_widget(Line::from(self.info.status()).centered(), status);
+
+            let panel = self.shown().suggestions;
+            let board = self.board(typing);
+            let board_width = 4 * WORD_LENGTH as u16 + 3;
+            // The board and the panel are in the middle, the panel as high as the board
+            let [_, middle, _] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(board.len() as u16 + 2),
+                Constraint::Fill(1),
+            ])
+            .areas(middle);
+            let [_, board_area, _, panel_area, _] = Layout::horizontal([
+                Constraint::Fill(1),
+                Constraint::Length(board_width),
+                Constraint::Length(if panel { 2 } else { 0 }),
+                Constraint::Length(if panel { PANEL_WIDTH } else { 0 }),
+                Constraint::Fill(1),
+            ])
+            .areas(middle);
+            frame.render_widget(
+                Paragraph::new(board)
+                    .centered()
+                    .block(Block::bordered().title(self.info.title())),
+                board_area,
+            );
+
+            if panel {
+                let words = self
+                    .possible
+                    .iter()
+                    .skip(self.scroll)
+                    .map(|word| word.chars().map(word::display).collect::<String>());
+                frame.render_widget(
+                    List::new(words).block(
+                        Block::bordered()
+                            .title(fill(strings().possible_title, &[&self.possible.len()])),
+                    ),
+                    panel_area,
+                );
+            }
+
+            if let Some(notice) = &self.message {
+                let style = match notice.severity {
+                    Severity::Info => Style::new(),
+                    Severity::Warning => Style::new().yellow(),
+                    Severity::Error => Style::new().red(),
+                };
+                frame.render_widget(Line::styled(notice.text.clone(), style).centered(), message);
+            }
+            frame.render_widget(Paragraph::new(keyboard), keys);
+
+            let strings = strings();
+            let (title, lines) = match &self.popup {
+                None => return,
+                Some(Popup::Help(help)) => (
+                    "LINGO".to_string(),
+                    help.split("  ").map(Line::from).collect::<Vec<_>>(),
+                ),
+                Some(Popup::Pause {
+                    selected,
+                    confirming,
+                }) => {
+                    let mut lines = self
+                        .choices()
+                        .iter()
+                        .enumerate()
+                        .map(|(index, (_, name))| match index == *selected {
+                            true => Line::from(format!("> {} <", name)).bold(),
+                            false => Line::from(name.to_string()),
+                        })
+                        .collect::<Vec<_>>();
+                    if *confirming {
+                        lines.push(Line::default());
+                        lines.push(Line::from(strings.confirm_quit).yellow());
+                    }
+                    (strings.paused.to_string(), lines)
+                }
+                Some(Popup::End(text)) => {
+                    ("LINGO".to_string(), vec![Line::from(text.clone()).bold()])
+                }
+            };
+            let width = lines
+                .iter()
+                .map(Line::width)
+                .chain([title.chars().count()])
+                .max()
+                .unwrap_or_default() as u16
+                + 6;
+            let area = centered(frame.area(), width, lines.len() as u16 + 4);
+            frame.render_widget(Clear, area);
+            frame.render_widget(
+                Paragraph::new(lines).centered().block(
+                    Block::bordered()
+                        .title(Line::from(format!(" {} ", title)).centered())
+                        .padding(Padding::vertical(1)),
+                ),
+                area,
+            );
+        }
+    }
+
+    /// The code ncurses reads for the key, so the keys from the config file work the same
+    fn code(key: &KeyEvent) -> Option<i32> {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        Some(match key.code {
+            KeyCode::Esc => ESCAPE,
+            KeyCode::Enter => '\n' as i32,
+            KeyCode::Backspace => 127,
+            KeyCode::Tab => '\t' as i32,
+            KeyCode::Delete => ncurses::KEY_DC,
+            KeyCode::Up => ncurses::KEY_UP,
+            KeyCode::Down => ncurses::KEY_DOWN,
+            KeyCode::F(number) => ncurses::KEY_F0 + number as i32,
+            KeyCode::Char(chr) if control && chr.is_ascii_alphabetic() => {
+                chr.to_ascii_lowercase() as i32 - 'a' as i32 + 1
+            }
+            KeyCode::Char(chr) => chr as i32,
+            _ => return None,
+        })
+    }
+
+    /// The event of the key in a game, like the poller of the ncurses interface makes them
+    fn event(key: &KeyEvent, keys: &Bindings) -> InputEvent {
+        let code = code(key);
+        if let Some(action) = code.and_then(|code| keys.action(code)) {
+            return match action {
+                Action::Quit => InputEvent::Quit,
+                Action::Submit => InputEvent::Submit,
+                Action::Backspace => InputEvent::Backspace,
+                Action::Hint => InputEvent::Hint,
+                Action::Help => InputEvent::Help,
+                Action::TogglePanel => InputEvent::TogglePanel,
+            };
+        }
+        match (key.code, code) {
+            (_, Some(0x03)) => InputEvent::Interrupt,
+            (_, Some(0x15)) => InputEvent::ClearRow,
+            (_, Some(0x17)) => InputEvent::DeleteWord,
+            (KeyCode::Char(chr), _) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                InputEvent::Char(chr)
+            }
+            (KeyCode::Delete, _) => InputEvent::Delete,
+            (KeyCode::Left, _) => InputEvent::Left,
+            (KeyCode::Right, _) => InputEvent::Right,
+            (KeyCode::Home, _) => InputEvent::Home,
+            (KeyCode::End, _) => InputEvent::End,
+            (KeyCode::Insert, _) => InputEvent::Insert,
+            (KeyCode::PageUp, _) => InputEvent::PageUp,
+            (KeyCode::PageDown, _) => InputEvent::PageDown,
+            _ => InputEvent::Other,
+        }
+    }
+
+    fn failed(err: io::Error) -> Stopped {
+        Stopped::Failed(Error::Terminal(err))
+    }
+
+    /// Play a game on the word in the ratatui interface
+    pub fn play_game(
+        dictionary: &Dictionary,
+        alphabet: &[char],
+        target: &'static str,
+        events: &EventSink,
+        options: &GameOptions,
+    ) -> Result<GameResult, Stopped> {
+        let strings = strings();
+        let max_guesses = max_guesses(1);
+        let mut game = engine::Game::new(
+            dictionary,
+            options.scripts,
+            alphabet,
+            vec![target],
+            max_guesses,
+            options.evil,
+            options.hard,
+        );
+        let mut view = View {
+            options,
+            alphabet,
+            info: GameInfo {
+                language: options.language.code,
+                letters: WORD_LENGTH,
+                mode: options.mode,
+                hard: options.hard,
+                evil: options.evil.is_some(),
+                timed: options.time_bonus,
+                guess: 1,
+                max_guesses,
+                streak: options.streak,
+                lives: options.lives,
+            },
+            rows: vec![GuessedWord::default(); max_guesses as usize],
+            editor: GuessEditor::default(),
+            possible: Vec::new(),
+            scroll: 0,
+            key_feedback: HashMap::new(),
+            assistance: Assistance::for_mode(options.mode),
+            zen: options.zen,
+            message: None,
+            popup: None,
+        };
+        if let Some(opener) = options.opener.filter(|_| view.shown().suggestions) {
+            view.message = Some(Notice::info(fill(
+                strings.opener,
+                &[&opener.chars().map(word::display).collect::<String>()],
+            )));
+        }
+        events.emit(&Event::RoundStarted {
+            language: options.language.code,
+            boards: 1,
+            max_guesses,
+        });
+
+        let mut terminal = ratatui::try_init().map_err(failed)?;
+        let _restorer = defer(ratatui::restore);
+        let start = Instant::now();
+        let mut result = None;
+        loop {
+            let typing = game.state() == GameState::Typing;
+            let row = game.guesses() as usize;
+            view.info.guess = row + 1;
+            view.possible =
+                possible_words(dictionary, WORD_LENGTH, game.knowledge(0), "").collect();
+            view.key_feedback = key_feedback(game.knowledge(0));
+            terminal
+                .draw(|frame| view.draw(frame, typing.then_some(row)))
+                .map_err(failed)?;
+
+            let key = match event::read().map_err(failed)? {
+                event::Event::Key(key) if key.kind != KeyEventKind::Release => key,
+                // A resize is drawn on the next round
+                _ => continue,
+            };
+            let choices = view.choices();
+            match &mut view.popup {
+                Some(Popup::End(_)) => return result.ok_or(Stopped::Quit),
+                Some(Popup::Help(_)) => {
+                    view.popup = None;
+                    continue;
+                }
+                Some(Popup::Pause {
+                    selected,
+                    confirming,
+                }) => {
+                    if *confirming {
+                        // The Dutch question is answered with j
+                        if matches!(key.code, KeyCode::Char('y' | 'Y' | 'j' | 'J')) {
+                            return Err(Stopped::Quit);
+                        }
+                        *confirming = false;
+                        continue;
+                    }
+                    // Matched on the codes of ncurses, a line feed comes in as Ctrl-J
+                    const UP: i32 = ncurses::KEY_UP;
+                    const DOWN: i32 = ncurses::KEY_DOWN;
+                    const K: i32 = 'k' as i32;
+                    const J: i32 = 'j' as i32;
+                    const ENTER: i32 = '\n' as i32;
+                    const SPACE: i32 = ' ' as i32;
+                    match code(&key) {
+                        Some(ESCAPE) => view.popup = None,
+                        Some(UP | K) => {
+                            *selected = (*selected + choices.len() - 1) % choices.len();
+                        }
+                        Some(DOWN | J) => {
+                            *selected = (*selected + 1) % choices.len();
+                        }
+                        Some(ENTER | SPACE) => match choices[*selected].0 {
+                            // Giving up a game after a guess has to be confirmed
+                            Choice::Quit if row > 0 => *confirming = true,
+                            Choice::Quit => return Err(Stopped::Quit),
+                            Choice::Restart => return Err(Stopped::Restart),
+                            Choice::Resume | Choice::Settings => view.popup = None,
+                        },
+                        _ => (),
+                    }
+                    continue;
+                }
+                None => (),
+            }
+
+            view.message = None;
+            match event(&key, options.keys) {
+                InputEvent::Quit | InputEvent::Interrupt => {
+                    view.popup = Some(Popup::Pause {
+                        selected: 0,
+                        confirming: false,
+                    });
+                }
+                InputEvent::Help => {
+                    let keys = options.keys;
+                    view.popup = Some(Popup::Help(fill(
+                        strings.help,
+                        &[
+                            &keys.name(Action::Quit),
+                            &keys.name(Action::Submit),
+                            &keys.name(Action::Backspace),
+                            &keys.name(Action::Hint),
+                            &keys.name(Action::TogglePanel),
+                            &keys.name(Action::Help),
+                        ],
+                    )));
+                }
+                InputEvent::Hint => {
+                    let hint = view.possible.first().filter(|_| view.assistance.adjustable);
+                    view.message = Some(match hint {
+                        Some(hint) => Notice::info(fill(
+                            strings.hint,
+                            &[&hint.chars().map(word::display).collect::<String>()],
+                        )),
+                        None => Notice::warning(strings.no_hint),
+                    });
+                }
+                InputEvent::TogglePanel if view.assistance.adjustable => view.zen = !view.zen,
+                InputEvent::PageUp => view.scroll = view.scroll.saturating_sub(PAGE),
+                InputEvent::PageDown => {
+                    view.scroll = (view.scroll + PAGE).min(view.possible.len().saturating_sub(1));
+                }
+                InputEvent::Backspace => view.editor.backspace(),
+                InputEvent::Delete => view.editor.delete(),
+                InputEvent::ClearRow => view.editor.clear(),
+                InputEvent::DeleteWord => view.editor.delete_word(),
+                InputEvent::Left => view.editor.move_left(),
+                InputEvent::Right => view.editor.move_right(),
+                InputEvent::Home => view.editor.move_home(),
+                InputEvent::End => view.editor.move_end(),
+                InputEvent::Insert => view.editor.toggle_overwrite(),
+                InputEvent::Char(chr) => {
+                    if let Some(letter) = input::normalize(chr, alphabet) {
+                        if !view.editor.join_digraph(options.language, letter) {
+                            view.editor.type_letter(letter);
+                        }
+                        sound::play(Sound::Key);
+                    } else if chr.is_ascii_punctuation() {
+                        view.editor.compose(options.language, chr, alphabet);
+                    }
+                }
+                InputEvent::Submit if view.editor.is_full() && typing => {
+                    let submission = game
+                        .handle_event(engine::Event::Submit(view.editor.text()))
+                        .map_err(Stopped::Failed)?;
+                    if let Transition::Rejected(notice) = submission {
+                        sound::play(Sound::Invalid);
+                        view.message = Some(notice);
+                        continue;
+                    }
+                    let Transition::Scored(scored) = game
+                        .handle_event(engine::Event::Score)
+                        .map_err(Stopped::Failed)?
+                    else {
+                        continue;
+                    };
+                    let pattern = &scored.patterns[0].1;
+                    view.rows[row] = rules::guessed_word(&scored.guess, pattern);
+                    view.editor = GuessEditor::default();
+                    view.scroll = 0;
+                    events.emit(&Event::GuessVerified {
+                        guess: row as u32 + 1,
+                        board: 0,
+                        pattern,
+                    });
+
+                    let guesses = game.guesses();
+                    match game.state() {
+                        GameState::Won => sound::play(Sound::Won),
+                        GameState::Lost => sound::play(Sound::Lost),
+                        _ if pattern.contains(&Feedback::Correct) => sound::play(Sound::Correct),
+                        _ => (),
+                    }
+                    let (won, score, text) = match game.state() {
+                        GameState::Won => {
+                            let elapsed = options.time_bonus.then(|| start.elapsed());
+                            let score = Score::solved(guesses, max_guesses, elapsed);
+                            (true, score, fill(strings.win, &[&score.total()]))
+                        }
+                        GameState::Lost => (
+                            false,
+                            Score::lost(),
+                            fill(strings.lost_word, &[&game.targets()[0]]),
+                        ),
+                        _ => continue,
+                    };
+                    result = Some(GameResult {
+                        words: game.targets().to_vec(),
+                        won,
+                        guesses,
+                        max_guesses,
+                        guessed: game.guessed().to_vec(),
+                        patterns: game.patterns().to_vec(),
+                        replay: None,
+                        score,
+                        elapsed: start.elapsed(),
+                    });
+                    view.popup = Some(Popup::End(text));
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ratatui")]
+pub use widgets::play_game;
+
+/// Without the ratatui feature there is no ratatui interface
+#[cfg(not(feature = "ratatui"))]
+pub fn play_game(
+    _dictionary: &crate::dictionary::Dictionary,
+    _alphabet: &[char],
+    _target: &'static str,
+    _events: &crate::events::EventSink,
+    _options: &crate::GameOptions,
+) -> Result<crate::GameResult, crate::Stopped> {
+    Err(crate::Stopped::Failed(crate::error::Error::Terminal(
+        std::io::Error::other(
+            "This build has no ratatui interface, build it with the ratatui feature",
+        ),
+    )))
+}
@@ -0,0 +1,203 @@
+//! Watching the games of a server as a spectator, with `lingo watch`. The server sends the guesses
+//! of every game but not the words, the boards of the players are drawn next to each other.
+
+use crate::keymap::ESCAPE;
+use crate::protocol::{self, Message};
+use crate::render::{Renderer, Terminal};
+use crate::rules;
+use crate::strings::{fill, strings};
+use crate::theme::Theme;
+use crate::{init_ncurses, render_board, GuessedWord, Tiles, WORD_LENGTH};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, TryRecvError};
+use std::thread;
+
+/// The board of a player as the spectator sees it
+struct Watched {
+    rows: Vec<GuessedWord>,
+    guesses: usize,
+    /// If the game was won, once it ended
+    won: Option<bool>,
+}
+
+/// Put what happened in the game of the player on its board
+fn update(games: &mut BTreeMap<usize, Watched>, player: usize, message: Message) {
+    match message {
+        Message::Start { guesses, .. } => {
+            games.insert(
+                player,
+                Watched {
+                    rows: vec![GuessedWord::default(); guesses as usize],
+                    guesses: 0,
+                    won: None,
+                },
+            );
+        }
+        Message::Result {
+            guess: Some(guess),
+            pattern,
+        } => {
+            if let Some(game) = games.get_mut(&player) {
+                if let Some(row) = game.rows.get_mut(game.guesses) {
+                    *row = rules::guessed_word(&guess, &pattern);
+                    game.guesses += 1;
+                }
+            }
+        }
+        Message::Finished { won, .. } => {
+            if let Some(game) = games.get_mut(&player) {
+                game.won = Some(won);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Draw the boards of the latest players that fit on the screen, with the line below them
+fn draw(screen: &mut impl Renderer, games: &BTreeMap<usize, Watched>, status: &str) {
+    screen.clear();
+    let (max_x, max_y) = screen.size();
+    let board_width = 1 + 4 * WORD_LENGTH as i32;
+    let fit = ((max_x + 2) / (board_width + 2)).max(1) as usize;
+    let shown = games.iter().skip(games.len().saturating_sub(fit));
+    let count = games.len().min(fit) as i32;
+    let height = games
+        .values()
+        .map(|game| 3 + 2 * game.rows.len() as i32)
+        .max()
+        .unwrap_or(0);
+    let left = (max_x - count * board_width - (count - 1).max(0) * 2) / 2;
+    let top = (max_y - height - 2) / 2;
+    for (index, (player, game)) in shown.enumerate() {
+        let title = match game.won {
+            Some(true) => strings().solved_title.to_string(),
+            _ => fill(strings().player_title, &[player]),
+        };
+        render_board(
+            screen,
+            (left + index as i32 * (board_width + 2), top),
+            &title,
+            &game.rows,
+            None,
+            None,
+            Tiles::default(),
+        );
+    }
+    screen.print(
+        top + height + 1,
+        (max_x - status.chars().count() as i32) / 2,
+        status,
+        0,
+    );
+    screen.refresh();
+}
+
+/// Watch the games of the server at the address until q is pressed
+pub fn watch(address: &str, theme: &Theme) -> io::Result<()> {
+    let stream = TcpStream::connect(address)?;
+    // The lines of the server are read on a thread of their own so the keys keep working
+    let (sender, messages) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Ok(message) = protocol::decode(&line) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let _window_ender = init_ncurses(theme);
+    ncurses::timeout(100);
+    let mut games = BTreeMap::new();
+    let mut connected = true;
+    // The screen is only drawn again when something changed
+    let mut changed = true;
+    loop {
+        loop {
+            match messages.try_recv() {
+                Ok(Message::Watched { player, message }) => {
+                    update(&mut games, player, *message);
+                    changed = true;
+                }
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    changed |= connected;
+                    connected = false;
+                    break;
+                }
+            }
+        }
+        if !changed {
+            match ncurses::getch() {
+                input if input == 'q' as i32 || input == ESCAPE => return Ok(()),
+                ncurses::KEY_RESIZE => changed = true,
+                _ => {}
+            }
+            continue;
+        }
+        changed = false;
+        let status = if !connected {
+            strings().server_gone.to_string()
+        } else if games.is_empty() {
+            strings().no_games.to_string()
+        } else {
+            fill(strings().spectating, &[&address])
+        };
+        draw(&mut Terminal, &games, &status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GuessedLetter;
+
+    #[test]
+    fn guesses_go_on_the_board_of_the_player() {
+        let mut games = BTreeMap::new();
+        let start = Message::Start {
+            language: "nl".to_string(),
+            letters: WORD_LENGTH,
+            guesses: 5,
+        };
+        update(&mut games, 2, start);
+        update(
+            &mut games,
+            2,
+            Message::Result {
+                guess: Some("taken".to_string()),
+                pattern: rules::score("taken", "tabak"),
+            },
+        );
+        // Results of players without a game are left out
+        update(
+            &mut games,
+            3,
+            Message::Result {
+                guess: Some("taken".to_string()),
+                pattern: rules::score("taken", "tabak"),
+            },
+        );
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[&2].guesses, 1);
+        assert!(matches!(games[&2].rows[0][3], GuessedLetter::Wrong('e')));
+
+        update(
+            &mut games,
+            2,
+            Message::Finished {
+                won: false,
+                word: None,
+                message: None,
+            },
+        );
+        assert_eq!(games[&2].won, Some(false));
+    }
+}
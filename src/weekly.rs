@@ -0,0 +1,216 @@
+//! The puzzle of the week, a tournament of words that are played back to back with the points of
+//! all of them added up. The words are picked with the ISO week number, so everyone playing with
+//! the same word list gets the same words that week.
+
+use crate::announce::Announcer;
+use crate::codes::fnv;
+use crate::dictionary::Dictionary;
+use crate::events::EventSink;
+use crate::play::play_game;
+use crate::render::show;
+use crate::strings::{fill, strings};
+use crate::{init_ncurses, pick_words, GameOptions, GameResult, Stopped};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The days since the unix epoch of a date, from Howard Hinnant's date algorithms
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The day of the week of the days since the unix epoch, 0 for monday
+fn weekday(days: i64) -> i64 {
+    // The epoch was on a thursday
+    (days + 3).rem_euclid(7)
+}
+
+/// A week as ISO 8601 counts them, the first week of a year is the one with its first thursday
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IsoWeek {
+    pub year: i64,
+    pub week: i64,
+}
+
+impl IsoWeek {
+    /// The week of the day, in days since the unix epoch
+    pub fn of_day(days: i64) -> IsoWeek {
+        // The week belongs to the year its thursday is in
+        let thursday = days - weekday(days) + 3;
+        let mut year = 1970 + thursday.div_euclid(365);
+        while days_from_civil(year, 1, 1) > thursday {
+            year -= 1;
+        }
+        while days_from_civil(year + 1, 1, 1) <= thursday {
+            year += 1;
+        }
+        IsoWeek {
+            year,
+            week: (thursday - days_from_civil(year, 1, 1)) / 7 + 1,
+        }
+    }
+
+    pub fn now() -> IsoWeek {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        IsoWeek::of_day((seconds / 86400) as i64)
+    }
+
+    /// Read a week written like 2026-W42
+    pub fn parse(text: &str) -> Result<IsoWeek, String> {
+        let invalid = || format!("{} is not a week like 2026-W42", text);
+        let (year, week) = text
+            .to_uppercase()
+            .split_once("-W")
+            .and_then(|(year, week)| Some((year.parse().ok()?, week.parse().ok()?)))
+            .ok_or_else(invalid)?;
+        // Only some years have a week 53, the monday of a week that doesn't exist falls in another
+        let january_4 = days_from_civil(year, 1, 4);
+        let monday = january_4 - weekday(january_4) + 7 * (week - 1);
+        let parsed = IsoWeek { year, week };
+        match week >= 1 && IsoWeek::of_day(monday) == parsed {
+            true => Ok(parsed),
+            false => Err(invalid()),
+        }
+    }
+
+    /// The words of the week, the same for everyone with the same list
+    pub fn words(&self, answers: &[&'static str], count: usize) -> Vec<&'static str> {
+        pick_words(answers, count, Some(fnv(&format!("weekly|{}", self))))
+    }
+}
+
+impl fmt::Display for IsoWeek {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-W{:02}", self.year, self.week)
+    }
+}
+
+/// The week as it can be pasted in a chat, a line for every word
+pub fn share_text(language: &str, week: IsoWeek, results: &[GameResult]) -> String {
+    let total: u32 = results.iter().map(|result| result.score.total()).sum();
+    let mut text = format!("Lingo {} {}: {} points", language, week, total);
+    for result in results {
+        let (tile, guesses) = match result.won {
+            true => ('🟩', result.guesses.to_string()),
+            false => ('⬛', "X".to_string()),
+        };
+        text.push_str(&format!(
+            "\n{} {}/{} {}",
+            tile,
+            guesses,
+            result.max_guesses,
+            result.score.total()
+        ));
+    }
+    text
+}
+
+/// Play the words of the week one after the other, the results of the words that were played are
+/// shown at the end. Quitting a word ends the tournament.
+pub fn play(
+    week: IsoWeek,
+    words: &[&'static str],
+    dictionary: &Dictionary,
+    alphabet: &[char],
+    announcer: &mut Announcer,
+    events: &EventSink,
+    options: &GameOptions,
+) -> Result<Vec<GameResult>, String> {
+    let strings = strings();
+    let mut results: Vec<GameResult> = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        {
+            let _window_ender = init_ncurses(options.theme);
+            ncurses::timeout(-1);
+            let total: u32 = results.iter().map(|result| result.score.total()).sum();
+            show(
+                &[
+                    fill(strings.week_word, &[&week, &(index + 1), &words.len()]),
+                    fill(strings.week_total, &[&total]),
+                    strings.go_on.to_string(),
+                ],
+                true,
+            );
+        }
+        match play_game(
+            dictionary,
+            alphabet.to_vec(),
+            vec![*word],
+            announcer,
+            events,
+            None,
+            options,
+        ) {
+            Ok(result) => results.push(result),
+            Err(Stopped::Failed(err)) => return Err(err.to_string()),
+            Err(_) => break,
+        }
+    }
+
+    if !results.is_empty() {
+        let _window_ender = init_ncurses(options.theme);
+        ncurses::timeout(-1);
+        let total: u32 = results.iter().map(|result| result.score.total()).sum();
+        let mut lines = vec![fill(strings.week_summary, &[&week, &total])];
+        for (index, result) in results.iter().enumerate() {
+            lines.push(match result.won {
+                true => fill(
+                    strings.week_solved,
+                    &[
+                        &(index + 1),
+                        &result.guesses,
+                        &result.max_guesses,
+                        &result.score.total(),
+                    ],
+                ),
+                false => fill(strings.week_lost, &[&(index + 1), &result.words[0]]),
+            });
+        }
+        lines.push(strings.go_on.to_string());
+        show(&lines, true);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weeks_are_counted_like_iso_8601() {
+        assert_eq!(
+            IsoWeek::of_day(days_from_civil(2026, 10, 16)),
+            IsoWeek {
+                year: 2026,
+                week: 42
+            }
+        );
+        // The first days of a year can be in the last week of the year before
+        assert_eq!(
+            IsoWeek::of_day(days_from_civil(2021, 1, 1)),
+            IsoWeek {
+                year: 2020,
+                week: 53
+            }
+        );
+        assert_eq!(
+            IsoWeek::of_day(days_from_civil(2024, 12, 30)).to_string(),
+            "2025-W01"
+        );
+
+        assert_eq!(IsoWeek::parse("2020-w53").unwrap().week, 53);
+        assert!(IsoWeek::parse("2021-W53").is_err());
+        assert!(IsoWeek::parse("2021-W00").is_err());
+        assert!(IsoWeek::parse("next week").is_err());
+
+        let answers = ["kaart", "tabak", "taken", "tafel", "stoel"];
+        let week = IsoWeek::parse("2026-W42").unwrap();
+        assert_eq!(week.words(&answers, 3), week.words(&answers, 3));
+    }
+}
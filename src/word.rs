@@ -0,0 +1,107 @@
+//! Words as letters instead of bytes. A letter like ö or ß takes more than one byte, and in a
+//! decomposed word list even more than one character, so the words are composed when they are
+//! loaded and everything after that counts and indexes characters.
+
+use crate::WORD_LENGTH;
+use unicode_normalization::UnicodeNormalization;
+
+pub use lingo_core::word::display;
+
+/// The text with every letter and its accents composed into a single character where Unicode has
+/// one, so "o" followed by a combining diaeresis becomes "ö"
+pub fn compose(text: &str) -> String {
+    text.nfc().collect()
+}
+
+/// The tag of an entry that is a proper noun, the tag is a column after the word
+const PROPER_NOUN_TAG: &str = "name";
+/// The tag of an entry that is an abbreviation
+const ABBREVIATION_TAG: &str = "abbr";
+
+/// Split a line of a word list into the word and the text of its frequency, a line can have a
+/// frequency column after a tab: `word<TAB>count`. The tags of the entry are left out.
+pub fn split_entry(line: &str) -> (&str, Option<&str>) {
+    let mut columns = line.split('\t').map(str::trim);
+    let word = columns.next().unwrap_or_default();
+    let count = columns.find(|column| ![PROPER_NOUN_TAG, ABBREVIATION_TAG].contains(column));
+    (word, count)
+}
+
+/// What kind of word an entry of a word list is
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Word,
+    /// A name, like a city or a person
+    ProperNoun,
+    Abbreviation,
+}
+
+/// The kind of an entry of a word list. A tag column after the word says so explicitly, `name` for
+/// a proper noun and `abbr` for an abbreviation. Otherwise it is told from how the word is written:
+/// abbreviations are written in capitals or with periods and proper nouns start with a capital.
+pub fn kind(line: &str) -> Kind {
+    let mut columns = line.split('\t').map(str::trim);
+    let word = columns.next().unwrap_or_default();
+    let tags = columns.collect::<Vec<_>>();
+    if tags.contains(&ABBREVIATION_TAG) {
+        Kind::Abbreviation
+    } else if tags.contains(&PROPER_NOUN_TAG) {
+        Kind::ProperNoun
+    } else if word.contains('.')
+        || (word.chars().count() > 1 && word.chars().all(|chr| chr.is_uppercase()))
+    {
+        Kind::Abbreviation
+    } else if word.chars().next().is_some_and(char::is_uppercase) {
+        Kind::ProperNoun
+    } else {
+        Kind::Word
+    }
+}
+
+/// Write the entries of the allowed kinds in the text of a word list like the other words, in
+/// lowercase letters without periods and tags, so they are played like any other word
+pub fn admit(text: &str, allowed: &[Kind]) -> String {
+    text.lines()
+        .map(|line| {
+            if kind(line) == Kind::Word || !allowed.contains(&kind(line)) {
+                return line.to_string();
+            }
+            let word = split_entry(line).0.replace('.', "").to_lowercase();
+            match split_entry(line).1 {
+                None => word,
+                Some(count) => format!("{}\t{}", word, count),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The number of letters in the word
+pub fn length(word: &str) -> usize {
+    word.chars().count()
+}
+
+/// If the word can be played, it has to have the right length and consist of lowercase letters
+/// only. Accents that couldn't be composed can't be shown on a tile.
+pub fn is_playable(word: &str) -> bool {
+    length(word) == WORD_LENGTH && has_only_letters(word)
+}
+
+/// If the word consists of lowercase letters only
+pub fn has_only_letters(word: &str) -> bool {
+    word.chars()
+        .all(|chr| chr.is_alphabetic() && chr.is_lowercase())
+}
+
+/// The space between the words of a phrase, it is a fixed tile on the board
+pub const SEPARATOR: char = ' ';
+
+/// The place of the space between the words of a phrase, `None` for a word
+pub fn separator(word: &str) -> Option<usize> {
+    word.chars().position(|chr| chr == SEPARATOR)
+}
+
+/// The letter at `index` of the word
+pub fn letter(word: &str, index: usize) -> Option<char> {
+    word.chars().nth(index)
+}